@@ -0,0 +1,610 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    Raydium,
+    error::RaydiumError,
+    liquidity::{
+        stable::DEFAULT_STABLE_AMP,
+        v4::{RaydiumLiquidityPoolData, Tvl},
+    },
+    monitor::MAX_MULTIPLE_ACCOUNTS,
+    rpc::parse_address,
+    typs::{MintSide, PoolData, PoolKind},
+};
+
+/// price of one pool in an aggregate
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolPrice {
+    pub address: Pubkey,
+    pub kind: PoolKind,
+    /// price of mint a in mint b
+    pub price: f64,
+    /// value locked in the pool, in mint b
+    pub liquidity: f64,
+    /// share of the pool in the aggregate, 0 for excluded pools
+    pub weight: f64,
+}
+
+impl PoolPrice {
+    /// deviation of the pool price from `price`, in basis points
+    pub fn deviation_bps(&self, price: f64) -> f64 {
+        (self.price - price) / price * 10_000.0
+    }
+}
+
+/// liquidity-weighted price of a pair across its pools
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregatePrice {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// price of mint a in mint b
+    pub price: f64,
+    /// value locked in the included pools, in mint b
+    pub liquidity: f64,
+    /// every pool of the pair, including the ones below the minimum liquidity
+    pub pools: Vec<PoolPrice>,
+}
+
+/// price difference between two pools of the same pair
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolSpread {
+    /// base mint of pool a, both prices are in the other mint of the pair
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub price_a: f64,
+    pub price_b: f64,
+    /// `(price_b - price_a) / price_a` in basis points, positive when pool b is more expensive
+    pub spread_bps: f64,
+    /// slot the state behind each price was observed at
+    pub slot_a: u64,
+    pub slot_b: u64,
+}
+
+/// a pool holding the ranked mint
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedPool {
+    pub address: Pubkey,
+    pub kind: PoolKind,
+    /// mint the ranked mint trades against in this pool
+    pub other_mint: Pubkey,
+    /// value locked in the pool, in the other mint
+    pub tvl_in_quote: f64,
+    /// price of the ranked mint in the other mint
+    pub price: f64,
+}
+
+/// a pool left out of a ranking
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedPool {
+    pub address: Pubkey,
+    pub kind: PoolKind,
+    pub reason: RaydiumError,
+}
+
+/// pools holding a mint, largest value locked first
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidityRanking {
+    pub mint: Pubkey,
+    pub pools: Vec<RankedPool>,
+    /// pools that failed to parse or hold no liquidity
+    pub skipped: Vec<SkippedPool>,
+    /// pools discovered past the evaluation cap
+    pub not_evaluated: usize,
+}
+
+/// signed difference of `price_b` from `price_a`, in basis points
+pub fn spread_bps(price_a: f64, price_b: f64) -> f64 {
+    (price_b - price_a) / price_a * 10_000.0
+}
+
+/// weight the pool prices by liquidity, leaving out pools below `min_liquidity`
+pub fn aggregate_prices(
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    mut pools: Vec<PoolPrice>,
+    min_liquidity: f64,
+) -> Result<AggregatePrice, RaydiumError> {
+    let liquidity: f64 = pools
+        .iter()
+        .filter(|p| p.liquidity >= min_liquidity)
+        .map(|p| p.liquidity)
+        .sum();
+    if liquidity <= 0.0 {
+        return Err(RaydiumError::InsufficientLiquidity);
+    }
+    for pool in pools.iter_mut() {
+        pool.weight = if pool.liquidity >= min_liquidity {
+            pool.liquidity / liquidity
+        } else {
+            0.0
+        };
+    }
+    let price = pools.iter().map(|p| p.price * p.weight).sum();
+    Ok(AggregatePrice {
+        mint_a,
+        mint_b,
+        price,
+        liquidity,
+        pools,
+    })
+}
+
+impl Raydium {
+    /// liquidity-weighted price of `mint_a` in `mint_b` across every v4, CPMM and CLMM pool
+    /// of the pair, with the per-pool breakdown.
+    /// pools below `RaydiumConfig::min_aggregate_liquidity` get no weight
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::{USDC_MINT, WSOL_MINT}};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let aggregate = raydium.get_aggregate_price(&WSOL_MINT, &USDC_MINT).await?;
+    /// for pool in &aggregate.pools {
+    ///     if pool.deviation_bps(aggregate.price).abs() > 50.0 {
+    ///         println!("{} deviates", pool.address);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_aggregate_price(
+        &self,
+        mint_a: &Pubkey,
+        mint_b: &Pubkey,
+    ) -> Result<AggregatePrice, RaydiumError> {
+        let pools = self.get_liquidity_pools_by_pair(mint_a, mint_b).await?;
+        let stable_amp = self.config.stable_amp.unwrap_or(DEFAULT_STABLE_AMP);
+        let vaults: Vec<Pubkey> = pools
+            .iter()
+            .flat_map(|(_, pool)| [pool.base_vault(), pool.quote_vault()])
+            .collect();
+        let mut amounts = Vec::with_capacity(vaults.len());
+        for chunk in vaults.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            amounts.extend(self.get_token_account_amounts(chunk).await?);
+        }
+        let prices = pools
+            .iter()
+            .zip(amounts.chunks(2))
+            .filter_map(|((address, pool), amounts)| {
+                pool_price(*address, pool, mint_a, amounts[0], amounts[1], stable_amp)
+            })
+            .collect();
+        aggregate_prices(
+            *mint_a,
+            *mint_b,
+            prices,
+            self.config.min_aggregate_liquidity,
+        )
+    }
+
+    /// rank the v4, CPMM and CLMM pools holding `mint` by value locked, largest first.
+    /// `max_pools` caps the pools whose vaults are fetched, the others are counted in
+    /// `not_evaluated`. pools that fail to parse or hold no liquidity are listed in `skipped`.
+    /// values are in the other mint of each pool, so compare pools of different quote mints
+    /// with care
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::WSOL_MINT};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let ranking = raydium.rank_pools_by_liquidity(&WSOL_MINT, Some(50)).await?;
+    /// for pool in ranking.pools.iter().take(5) {
+    ///     println!("{} {:?} {:.0} {}", pool.address, pool.kind, pool.tvl_in_quote, pool.other_mint);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn rank_pools_by_liquidity(
+        &self,
+        mint: &Pubkey,
+        max_pools: Option<usize>,
+    ) -> Result<LiquidityRanking, RaydiumError> {
+        let mut accounts = self.get_pool_accounts_of_mint(mint).await?;
+        let evaluated = max_pools.unwrap_or(accounts.len()).min(accounts.len());
+        let not_evaluated = accounts.len() - evaluated;
+        accounts.truncate(evaluated);
+        let mut skipped = Vec::new();
+        let mut pools = Vec::with_capacity(accounts.len());
+        for (address, kind, data) in accounts {
+            match self.parse_with_metrics(Some(kind), || {
+                PoolData::parse(kind, &data).map_err(RaydiumError::Parse)
+            }) {
+                Ok(pool) => pools.push((address, pool)),
+                Err(reason) => skipped.push(SkippedPool {
+                    address,
+                    kind,
+                    reason,
+                }),
+            }
+        }
+        let vaults: Vec<Pubkey> = pools
+            .iter()
+            .flat_map(|(_, pool)| [pool.base_vault(), pool.quote_vault()])
+            .collect();
+        let mut amounts = Vec::with_capacity(vaults.len());
+        for chunk in vaults.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            amounts.extend(self.get_token_account_amounts(chunk).await?);
+        }
+        let mut ranked = Vec::with_capacity(pools.len());
+        for ((address, pool), amounts) in pools.iter().zip(amounts.chunks(2)) {
+            let (Some(price), Some(other_mint)) = (
+                pool_price(
+                    *address,
+                    pool,
+                    mint,
+                    amounts[0],
+                    amounts[1],
+                    self.config.stable_amp.unwrap_or(DEFAULT_STABLE_AMP),
+                ),
+                pool.other_mint(mint),
+            ) else {
+                skipped.push(SkippedPool {
+                    address: *address,
+                    kind: pool.kind(),
+                    reason: RaydiumError::InsufficientLiquidity,
+                });
+                continue;
+            };
+            ranked.push(RankedPool {
+                address: *address,
+                kind: pool.kind(),
+                other_mint,
+                tvl_in_quote: price.liquidity,
+                price: price.price,
+            });
+        }
+        ranked.sort_by(|a, b| b.tvl_in_quote.total_cmp(&a.tvl_in_quote));
+        Ok(LiquidityRanking {
+            mint: *mint,
+            pools: ranked,
+            skipped,
+            not_evaluated,
+        })
+    }
+
+    /// value locked in each of `pools`, in order, fetching the vaults and open orders of
+    /// every pool in as few RPC calls as possible. a pool whose accounts are missing or
+    /// malformed gets an error without failing the others
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, liquidity::v4::RaydiumLiquidityPoolData};
+    /// # async fn run(raydium: Raydium, pools: Vec<RaydiumLiquidityPoolData>) -> Result<(), RaydiumError> {
+    /// let tvls = raydium.get_tvls_v4(&pools).await?;
+    /// for tvl in tvls.iter().flatten() {
+    ///     println!("{:.0}", tvl.tvl_in_quote);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_tvls_v4(
+        &self,
+        pools: &[RaydiumLiquidityPoolData],
+    ) -> Result<Vec<Result<Tvl, RaydiumError>>, RaydiumError> {
+        let mut tvls = Vec::with_capacity(pools.len());
+        // the three accounts of a pool stay in the same request
+        for chunk in pools.chunks(MAX_MULTIPLE_ACCOUNTS / 3) {
+            let addresses: Vec<Pubkey> = chunk
+                .iter()
+                .flat_map(|pool| [pool.base_vault, pool.quote_vault, pool.open_orders])
+                .collect();
+            let accounts = self.get_multiple_accounts_with_context(&addresses).await?;
+            for (pool, accounts) in chunk.iter().zip(accounts.value.chunks(3)) {
+                tvls.push(
+                    pool.reserves_from_accounts(accounts)
+                        .map(|reserves| pool.tvl(&reserves)),
+                );
+            }
+        }
+        Ok(tvls)
+    }
+
+    /// compare the prices of two pools of the same pair, of any types.
+    /// both pools are fetched in one call and both prices are expressed as the base mint
+    /// of pool a in the other mint, whatever the token order of pool b
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let spread = raydium
+    ///     .compare_pools(
+    ///         "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2",
+    ///         "3ucNos4NbumPLZNWztqGHNFFgkHeRMBQAVemeeomsUxv",
+    ///     )
+    ///     .await?;
+    /// println!("spread {:.1} bps", spread.spread_bps);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn compare_pools(
+        &self,
+        pool_a: &str,
+        pool_b: &str,
+    ) -> Result<PoolSpread, RaydiumError> {
+        let addresses = [parse_address(pool_a)?, parse_address(pool_b)?];
+        let accounts = self.get_multiple_accounts_with_context(&addresses).await?;
+        let pool_slot = accounts.slot;
+        let mut pools = Vec::with_capacity(2);
+        for (address, account) in addresses.iter().zip(accounts.value) {
+            pools.push(self.parse_pool_account(address, None, account)?);
+        }
+        let (a, b) = (&pools[0], &pools[1]);
+        let (base_mint, quote_mint) = (a.base_mint(), a.quote_mint());
+        if b.side_of(&base_mint).is_none() || b.side_of(&quote_mint).is_none() {
+            return Err(RaydiumError::PairMismatch {
+                pool_a: addresses[0],
+                pool_b: addresses[1],
+            });
+        }
+        // stable pools price off their open orders too, like their quotes
+        let (amounts_slot, amounts) = self.get_spot_amounts(&[a, b]).await?;
+        let slot_of = |pool: &PoolData| match amounts_slot {
+            Some(slot) if pool.is_priced_from_vaults() => slot,
+            _ => pool_slot,
+        };
+        let stable_amp = self.config.stable_amp.unwrap_or(DEFAULT_STABLE_AMP);
+        let mut amounts = amounts.into_iter();
+        let mut price = |address: Pubkey, pool: &PoolData| {
+            let (base, quote) = amounts.next().unwrap_or(Ok((0, 0)))?;
+            pool_price(address, pool, &base_mint, base, quote, stable_amp)
+                .map(|p| p.price)
+                .ok_or(RaydiumError::InsufficientLiquidity)
+        };
+        let price_a = price(addresses[0], a)?;
+        let price_b = price(addresses[1], b)?;
+        Ok(PoolSpread {
+            base_mint,
+            quote_mint,
+            price_a,
+            price_b,
+            spread_bps: spread_bps(price_a, price_b),
+            slot_a: slot_of(a),
+            slot_b: slot_of(b),
+        })
+    }
+}
+
+/// price of `mint_a` in the other mint of the pool and the value locked in that mint,
+/// stable pools priced at `stable_amp`
+fn pool_price(
+    address: Pubkey,
+    pool: &PoolData,
+    mint_a: &Pubkey,
+    base_amount: u64,
+    quote_amount: u64,
+    stable_amp: u64,
+) -> Option<PoolPrice> {
+    let base_price = pool.spot_price_with_amp(base_amount, quote_amount, stable_amp)?;
+    let base = base_amount as f64 / 10f64.powi(pool.base_decimals() as i32);
+    let quote = quote_amount as f64 / 10f64.powi(pool.quote_decimals() as i32);
+    let (price, liquidity) = match pool.side_of(mint_a)? {
+        MintSide::Base => (base_price, quote + base * base_price),
+        MintSide::Quote => (1.0 / base_price, base + quote / base_price),
+    };
+    Some(PoolPrice {
+        address,
+        kind: pool.kind(),
+        price,
+        liquidity,
+        weight: 0.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::pubkey::Pubkey;
+
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+
+    use super::*;
+    use crate::{
+        liquidity::v4::{
+            RAYDIUM_LIQUIDITY_POOL_V4_BASE_MINT_OFFSET, RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE,
+            RAYDIUM_LIQUIDITY_POOL_V4_QUOTE_MINT_OFFSET, RaydiumLiquidityPoolData,
+        },
+        programs::RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+        test_utils::{mock_raydium, multiple_accounts_response, ui_account},
+    };
+
+    fn pool(price: f64, liquidity: f64) -> PoolPrice {
+        PoolPrice {
+            address: Pubkey::new_unique(),
+            kind: PoolKind::Cpmm,
+            price,
+            liquidity,
+            weight: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_prices() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // a dust pool far off the market does not move the aggregate
+        let pools = vec![pool(100.0, 3_000.0), pool(104.0, 1_000.0), pool(1.0, 5.0)];
+        let aggregate = aggregate_prices(a, b, pools, 10.0).unwrap();
+        assert_eq!(aggregate.price, 101.0);
+        assert_eq!(aggregate.liquidity, 4_000.0);
+        assert_eq!(aggregate.pools[2].weight, 0.0);
+        assert_eq!(
+            aggregate.pools[1].deviation_bps(aggregate.price).round(),
+            297.0
+        );
+        assert_eq!(
+            aggregate_prices(a, b, vec![pool(1.0, 5.0)], 10.0),
+            Err(RaydiumError::InsufficientLiquidity)
+        );
+    }
+
+    #[test]
+    fn test_spread_bps() {
+        assert_eq!(spread_bps(100.0, 101.0), 100.0);
+        assert_eq!(spread_bps(100.0, 99.5), -50.0);
+    }
+
+    #[tokio::test]
+    async fn test_compare_pools_pair_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = |base_mint: &Pubkey, quote_mint: &Pubkey| {
+            let mut data = vec![0u8; RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE];
+            data[RAYDIUM_LIQUIDITY_POOL_V4_BASE_MINT_OFFSET..][..32]
+                .copy_from_slice(base_mint.as_ref());
+            data[RAYDIUM_LIQUIDITY_POOL_V4_QUOTE_MINT_OFFSET..][..32]
+                .copy_from_slice(quote_mint.as_ref());
+            Some(ui_account(&RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID, data))
+        };
+        let (wsol, usdc, usdt) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![pool(&wsol, &usdc), pool(&wsol, &usdt)]),
+        );
+        let raydium = mock_raydium(mocks);
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(
+            raydium.compare_pools(&a.to_string(), &b.to_string()).await,
+            Err(RaydiumError::PairMismatch {
+                pool_a: a,
+                pool_b: b
+            })
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rank_pools_by_liquidity() -> Result<(), Box<dyn std::error::Error>> {
+        let (mint, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let keyed = |address: &Pubkey, data: Vec<u8>| {
+            serde_json::json!({
+                "pubkey": address.to_string(),
+                "account": ui_account(&RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID, data),
+            })
+        };
+        let pool = |address: &Pubkey| {
+            let mut data = vec![0u8; RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE];
+            data[RAYDIUM_LIQUIDITY_POOL_V4_BASE_MINT_OFFSET..][..32].copy_from_slice(mint.as_ref());
+            data[RAYDIUM_LIQUIDITY_POOL_V4_QUOTE_MINT_OFFSET..][..32]
+                .copy_from_slice(usdc.as_ref());
+            keyed(address, data)
+        };
+        let token_account = |amount: u64| {
+            let mut data = vec![0u8; 165];
+            data[64..72].copy_from_slice(&amount.to_le_bytes());
+            Some(ui_account(&Pubkey::new_unique(), data))
+        };
+        let (small, large, broken) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut mocks = MocksMap::default();
+        // v4 pools with the mint as base, then every other filter finds nothing
+        mocks.insert(
+            RpcRequest::GetProgramAccounts,
+            serde_json::json!([pool(&small), pool(&large), keyed(&broken, vec![0u8; 10])]),
+        );
+        for _ in 0..5 {
+            mocks.insert(RpcRequest::GetProgramAccounts, serde_json::json!([]));
+        }
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![
+                token_account(10),
+                token_account(10),
+                token_account(100),
+                token_account(400),
+            ]),
+        );
+        let raydium = mock_raydium(mocks);
+        let ranking = raydium.rank_pools_by_liquidity(&mint, None).await?;
+        let order: Vec<(Pubkey, f64)> = ranking
+            .pools
+            .iter()
+            .map(|p| (p.address, p.tvl_in_quote))
+            .collect();
+        assert_eq!(order, vec![(large, 800.0), (small, 20.0)]);
+        assert_eq!(
+            (ranking.pools[0].price, ranking.pools[0].other_mint),
+            (4.0, usdc)
+        );
+        assert_eq!(ranking.skipped.len(), 1);
+        assert_eq!(ranking.skipped[0].address, broken);
+        assert_eq!(ranking.not_evaluated, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_tvls_v4() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::{market::open_orders_data, programs::TOKEN_PROGRAM_ID};
+
+        let vault = |amount: u64| {
+            let mut data = vec![0u8; 165];
+            data[64..72].copy_from_slice(&amount.to_le_bytes());
+            Some(ui_account(&TOKEN_PROGRAM_ID, data))
+        };
+        let pool = || RaydiumLiquidityPoolData {
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            open_orders: Pubkey::new_unique(),
+            base_decimal: 9,
+            quote_decimal: 6,
+            ..Default::default()
+        };
+        let pools = [pool(), pool()];
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![
+                vault(10_000_000_000),
+                vault(1_000_000_000),
+                Some(ui_account(
+                    &Pubkey::new_unique(),
+                    open_orders_data(0, 500_000_000),
+                )),
+                vault(1),
+                vault(1),
+                None,
+            ]),
+        );
+        let tvls = mock_raydium(mocks).get_tvls_v4(&pools).await?;
+        assert_eq!(tvls[0].as_ref().map(|tvl| tvl.tvl_in_quote), Ok(3_000.0));
+        // the missing open orders only fails its own pool
+        assert_eq!(
+            tvls[1],
+            Err(RaydiumError::AccountNotFound(pools[1].open_orders))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_pool_price_orientation() {
+        let data = PoolData::V4(RaydiumLiquidityPoolData {
+            base_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            base_decimal: 9,
+            quote_decimal: 6,
+            ..Default::default()
+        });
+        // 10 base at 150 quote each
+        let base = pool_price(
+            Pubkey::new_unique(),
+            &data,
+            &data.base_mint(),
+            10_000_000_000,
+            1_500_000_000,
+            DEFAULT_STABLE_AMP,
+        )
+        .unwrap();
+        assert_eq!((base.price, base.liquidity), (150.0, 3_000.0));
+        let quote = pool_price(
+            Pubkey::new_unique(),
+            &data,
+            &data.quote_mint(),
+            10_000_000_000,
+            1_500_000_000,
+            DEFAULT_STABLE_AMP,
+        )
+        .unwrap();
+        assert_eq!((quote.price * 150.0, quote.liquidity), (1.0, 20.0));
+    }
+}
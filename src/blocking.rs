@@ -0,0 +1,253 @@
+//! synchronous mirror of the `Raydium` fetchers, quotes and price helpers.
+//! every call runs the async method to completion on a runtime owned by the handle,
+//! so these methods must not be called from within an async context.
+//! subscriptions (`subscribe_pool`, `subscribe_new_pools`), `watch_price` and `PoolMonitor`
+//! deliver updates from background tasks and have no blocking equivalent
+
+use std::{sync::Arc, time::Duration};
+
+use solana_network_client::SolanaClient;
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signature::Signature, signer::Signer,
+    transaction::Transaction,
+};
+use tokio::runtime::Runtime;
+
+use crate::{
+    aggregate::{AggregatePrice, LiquidityRanking, PoolSpread},
+    cache::CacheStats,
+    config::RaydiumConfig,
+    endpoints::EndpointHealth,
+    error::RaydiumError,
+    history::PriceHistory,
+    launchpad::LaunchpadPoolData,
+    liquidity::{
+        clmm::{
+            ClmmConfig, ClmmSwapQuoteAccounts, PersonalPositionData, ProtocolPositionData,
+            RaydiumLiquidityPoolCLMMData, RewardOwed, TickArrayData,
+            bitmap_extension::TickArrayBitmapExtensionData,
+        },
+        cpmm::RaydiumLiquidityPoolCPMMData,
+        stable::RaydiumLiquidityPoolStableData,
+        v4::{
+            RaydiumLiquidityPoolData, Tvl, instructions::CreatePoolV4, position::LpPosition,
+            target_orders::TargetOrdersData,
+        },
+    },
+    market::{Divergence, Fill, MarketData, OpenOrdersData, Orderbook},
+    metadata::MetadataLookup,
+    mints::MintInfo,
+    pool_keys::PoolKeysV4,
+    quote::SwapQuote,
+    registry::PoolRegistry,
+    routing::{PoolCandidate, RouteQuote},
+    safety::{LpBurnReport, TokenSafetyReport},
+    swap::BuiltSwap,
+    token_accounts::TokenAccounts,
+    transaction::{ExpectedOutcome, SendOptions, SentTransaction, SimulationOutcome},
+    typs::{Contextual, PoolData, PoolKind, PoolPrices},
+};
+
+/// blocking raydium handle, cheap to clone
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::blocking::Raydium;
+/// # use solana_network_client::{Mode, SolanaClient};
+/// # use std::sync::Arc;
+/// let sol = SolanaClient::new(Mode::MAIN).unwrap();
+/// let raydium = Raydium::new(Arc::new(sol)).unwrap();
+/// let pool = raydium.get_liquidity_pool_v4("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2");
+/// ```
+#[derive(Clone)]
+pub struct Raydium {
+    inner: crate::Raydium,
+    runtime: Arc<Runtime>,
+}
+
+/// generate blocking methods running the async method of the same name
+macro_rules! blocking {
+    ($($(#[$attr:meta])* fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(&self $(, $arg: $ty)*) -> $ret {
+                self.runtime.block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+impl Raydium {
+    /// create a blocking raydium with its own runtime
+    pub fn new(solana_client: Arc<SolanaClient>) -> Result<Self, RaydiumError> {
+        Self::new_with_config(solana_client, RaydiumConfig::default())
+    }
+
+    /// create a blocking raydium with a custom configuration
+    pub fn new_with_config(
+        solana_client: Arc<SolanaClient>,
+        config: RaydiumConfig,
+    ) -> Result<Self, RaydiumError> {
+        Self::from_async(crate::Raydium::new_with_config(solana_client, config))
+    }
+
+    /// wrap an async handle, sharing its cache and rate limiter
+    pub fn from_async(inner: crate::Raydium) -> Result<Self, RaydiumError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| RaydiumError::Unsupported(format!("runtime: {:?}", e)))?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// the async handle, for subscriptions and monitors
+    pub fn as_async(&self) -> &crate::Raydium {
+        &self.inner
+    }
+
+    pub fn config(&self) -> &RaydiumConfig {
+        &self.inner.config
+    }
+
+    pub fn program_id(&self, kind: PoolKind) -> Pubkey {
+        self.inner.program_id(kind)
+    }
+
+    pub fn invalidate(&self, address: &Pubkey) {
+        self.inner.invalidate(address)
+    }
+
+    pub fn clear_cache(&self) {
+        self.inner.clear_cache()
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        self.inner.cache_stats()
+    }
+
+    pub fn endpoint_health(&self) -> Vec<EndpointHealth> {
+        self.inner.endpoint_health()
+    }
+
+    /// symbols of the base and quote mints of a pool, see `PoolData::resolve_symbols`
+    pub fn resolve_symbols(&self, pool: &PoolData) -> Result<(String, String), RaydiumError> {
+        self.runtime.block_on(pool.resolve_symbols(&self.inner))
+    }
+
+    /// safety reports of the base and quote mints of a pool, see `PoolData::safety_report`
+    pub fn safety_report(
+        &self,
+        pool: &PoolData,
+    ) -> Result<(TokenSafetyReport, TokenSafetyReport), RaydiumError> {
+        self.runtime.block_on(pool.safety_report(&self.inner))
+    }
+
+    blocking! {
+        fn get_liquidity_pool_v4(&self, address: &str) -> Result<RaydiumLiquidityPoolData, RaydiumError>;
+        fn get_liquidity_pool_cpmm(&self, address: &str) -> Result<RaydiumLiquidityPoolCPMMData, RaydiumError>;
+        fn get_liquidity_pool_clmm(&self, address: &str) -> Result<RaydiumLiquidityPoolCLMMData, RaydiumError>;
+        fn get_liquidity_pool_launchpad(&self, address: &str) -> Result<LaunchpadPoolData, RaydiumError>;
+        fn get_liquidity_pool_stable(&self, address: &str) -> Result<RaydiumLiquidityPoolStableData, RaydiumError>;
+        fn get_pool(&self, address: &str) -> Result<PoolData, RaydiumError>;
+        fn get_pool_with_context(&self, address: &str) -> Result<Contextual<PoolData>, RaydiumError>;
+        fn get_liquidity_pool_v4_timeout(&self, address: &str, timeout: Duration) -> Result<RaydiumLiquidityPoolData, RaydiumError>;
+        fn get_liquidity_pool_cpmm_timeout(&self, address: &str, timeout: Duration) -> Result<RaydiumLiquidityPoolCPMMData, RaydiumError>;
+        fn get_liquidity_pool_clmm_timeout(&self, address: &str, timeout: Duration) -> Result<RaydiumLiquidityPoolCLMMData, RaydiumError>;
+        fn get_liquidity_pool_launchpad_timeout(&self, address: &str, timeout: Duration) -> Result<LaunchpadPoolData, RaydiumError>;
+        fn get_liquidity_pool_v4_with_context(&self, address: &str) -> Result<Contextual<RaydiumLiquidityPoolData>, RaydiumError>;
+        fn get_liquidity_pool_cpmm_with_context(&self, address: &str) -> Result<Contextual<RaydiumLiquidityPoolCPMMData>, RaydiumError>;
+        fn get_liquidity_pool_clmm_with_context(&self, address: &str) -> Result<Contextual<RaydiumLiquidityPoolCLMMData>, RaydiumError>;
+        fn get_liquidity_pool_launchpad_with_context(&self, address: &str) -> Result<Contextual<LaunchpadPoolData>, RaydiumError>;
+        fn get_liquidity_pool_stable_with_context(&self, address: &str) -> Result<Contextual<RaydiumLiquidityPoolStableData>, RaydiumError>;
+        fn get_vault_amounts_with_context(&self, pool: &PoolData) -> Result<Contextual<(u64, u64)>, RaydiumError>;
+        fn get_price_v4(&self, pool: &RaydiumLiquidityPoolData) -> Result<f64, RaydiumError>;
+        fn wait_for_pool_open(&self, pool_address: &str) -> Result<RaydiumLiquidityPoolData, RaydiumError>;
+        fn get_pool_price(&self, pool: &PoolData) -> Result<f64, RaydiumError>;
+        fn get_pool_price_by_address(&self, address: &str) -> Result<f64, RaydiumError>;
+        fn get_prices(&self, addresses: &[Pubkey]) -> Result<Contextual<PoolPrices>, RaydiumError>;
+        fn get_multiple_accounts_with_context(&self, addresses: &[Pubkey]) -> Result<Contextual<Vec<Option<solana_sdk::account::Account>>>, RaydiumError>;
+        fn get_liquidity_pools_v4_by_mint(&self, mint: &Pubkey) -> Result<Vec<(Pubkey, RaydiumLiquidityPoolData)>, RaydiumError>;
+        fn get_liquidity_pools_cpmm_by_mint(&self, mint: &Pubkey) -> Result<Vec<(Pubkey, RaydiumLiquidityPoolCPMMData)>, RaydiumError>;
+        fn get_liquidity_pools_clmm_by_mint(&self, mint: &Pubkey) -> Result<Vec<(Pubkey, RaydiumLiquidityPoolCLMMData)>, RaydiumError>;
+        fn get_clmm_config(&self, address: &str) -> Result<ClmmConfig, RaydiumError>;
+        fn list_clmm_configs(&self) -> Result<Vec<(Pubkey, ClmmConfig)>, RaydiumError>;
+        fn get_personal_position(&self, address: &str) -> Result<PersonalPositionData, RaydiumError>;
+        fn get_protocol_position(&self, pool: &str, tick_lower: i32, tick_upper: i32) -> Result<ProtocolPositionData, RaydiumError>;
+        fn get_position_fees_owed(&self, address: &str) -> Result<(u64, u64), RaydiumError>;
+        fn get_position_rewards_owed(&self, address: &str, now: u64) -> Result<[RewardOwed; 3], RaydiumError>;
+        fn get_tick_array(&self, address: &str) -> Result<TickArrayData, RaydiumError>;
+        fn get_tick_array_bitmap_extension(&self, pool: &str) -> Result<TickArrayBitmapExtensionData, RaydiumError>;
+        fn get_liquidity_pools_by_mint(&self, mint: &Pubkey) -> Result<Vec<(Pubkey, PoolData)>, RaydiumError>;
+        fn get_liquidity_pools_by_pair(&self, mint_a: &Pubkey, mint_b: &Pubkey) -> Result<Vec<(Pubkey, PoolData)>, RaydiumError>;
+        fn find_clmm_pool(&self, mint_a: &Pubkey, mint_b: &Pubkey, fee_tier_index: u16) -> Result<(Pubkey, RaydiumLiquidityPoolCLMMData), RaydiumError>;
+        fn find_quote_pool(&self, mint: &Pubkey, preference: &[Pubkey]) -> Result<(Pubkey, PoolData), RaydiumError>;
+        fn get_liquidity_pools_by_mint_with_registry(&self, mint: &Pubkey, registry: &mut PoolRegistry) -> Result<Vec<(Pubkey, PoolData)>, RaydiumError>;
+        fn get_liquidity_pools_by_pair_with_registry(&self, mint_a: &Pubkey, mint_b: &Pubkey, registry: &mut PoolRegistry) -> Result<Vec<(Pubkey, PoolData)>, RaydiumError>;
+        fn get_pool_keys_v4(&self, pool_address: &str) -> Result<PoolKeysV4, RaydiumError>;
+        fn get_pool_keys_v4_checked(&self, pool_address: &str) -> Result<PoolKeysV4, RaydiumError>;
+        fn get_market(&self, market_id: &str) -> Result<MarketData, RaydiumError>;
+        fn get_open_orders(&self, address: &str) -> Result<OpenOrdersData, RaydiumError>;
+        fn get_orderbook(&self, market_id: &str, depth: usize) -> Result<Orderbook, RaydiumError>;
+        fn get_recent_fills(&self, market_id: &str, limit: usize) -> Result<Vec<Fill>, RaydiumError>;
+        fn get_target_orders(&self, address: &str) -> Result<TargetOrdersData, RaydiumError>;
+        fn get_v4_price_divergence(&self, pool_address: &str) -> Result<Divergence, RaydiumError>;
+        fn quote_swap(&self, pool_address: &str, input_mint: &Pubkey, amount_in: u64) -> Result<SwapQuote, RaydiumError>;
+        fn quote_swap_pool(&self, pool: &PoolData, input_mint: &Pubkey, amount_in: u64) -> Result<SwapQuote, RaydiumError>;
+        fn quote_swap_clmm(&self, pool_address: &str, input_mint: &Pubkey, amount_in: u64) -> Result<ClmmSwapQuoteAccounts, RaydiumError>;
+        fn best_pool_for_pair(&self, mint_a: &Pubkey, mint_b: &Pubkey, amount_in: u64, input_mint: &Pubkey) -> Result<Vec<PoolCandidate>, RaydiumError>;
+        fn quote_route(&self, input_mint: &Pubkey, output_mint: &Pubkey, amount_in: u64, intermediates: &[Pubkey]) -> Result<RouteQuote, RaydiumError>;
+        fn get_aggregate_price(&self, mint_a: &Pubkey, mint_b: &Pubkey) -> Result<AggregatePrice, RaydiumError>;
+        fn compare_pools(&self, pool_a: &str, pool_b: &str) -> Result<PoolSpread, RaydiumError>;
+        fn fetch_mint_info(&self, mint: &Pubkey) -> Result<MintInfo, RaydiumError>;
+        fn fetch_mint_infos(&self, mints: &[Pubkey]) -> Result<Vec<MintInfo>, RaydiumError>;
+        fn fetch_token_metadata(&self, mint: &Pubkey) -> Result<MetadataLookup, RaydiumError>;
+        fn fetch_token_metadatas(&self, mints: &[Pubkey]) -> Result<Vec<MetadataLookup>, RaydiumError>;
+        fn check_token_safety(&self, mint: &Pubkey) -> Result<TokenSafetyReport, RaydiumError>;
+        fn check_tokens_safety(&self, mints: &[Pubkey]) -> Result<Vec<TokenSafetyReport>, RaydiumError>;
+        fn check_lp_burn_v4(&self, pool_address: &str) -> Result<LpBurnReport, RaydiumError>;
+        fn get_lp_position_v4(&self, pool_address: &str, owner: &Pubkey) -> Result<LpPosition, RaydiumError>;
+        fn get_price_history_v4(&self, pool: &str, limit: usize, until_signature: Option<Signature>, rpc_budget: usize) -> Result<PriceHistory, RaydiumError>;
+        fn send_transaction(&self, instructions: &[Instruction], payer: &Pubkey, signers: &[&dyn Signer], opts: &SendOptions) -> Result<SentTransaction, RaydiumError>;
+        #[allow(clippy::too_many_arguments)]
+        fn build_create_pool_v4(&self, market_id: &str, base_mint: &Pubkey, quote_mint: &Pubkey, user: &Pubkey, init_base_amount: u64, init_quote_amount: u64, open_time: u64) -> Result<CreatePoolV4, RaydiumError>;
+        fn build_swap_transaction(&self, pool_address: &str, input_mint: &Pubkey, amount_in: u64, slippage_bps: u16, user: &Pubkey) -> Result<BuiltSwap, RaydiumError>;
+        fn simulate_and_check(&self, transaction: &Transaction, expected: &ExpectedOutcome) -> Result<SimulationOutcome, RaydiumError>;
+        fn resolve_token_accounts(&self, owner: &Pubkey, mints: &[Pubkey], token_programs: &[Pubkey]) -> Result<TokenAccounts, RaydiumError>;
+        fn resolve_pool_token_accounts(&self, owner: &Pubkey, pool: &PoolData) -> Result<TokenAccounts, RaydiumError>;
+        fn rank_pools_by_liquidity(&self, mint: &Pubkey, max_pools: Option<usize>) -> Result<LiquidityRanking, RaydiumError>;
+        fn get_tvls_v4(&self, pools: &[RaydiumLiquidityPoolData]) -> Result<Vec<Result<Tvl, RaydiumError>>, RaydiumError>;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::Raydium;
+    use crate::{
+        launchpad::LAUNCHPAD_POOL_STATE_DATA_SIZE,
+        programs::RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+        test_utils::{account_info_response, mock_raydium},
+        typs::PoolKind,
+    };
+
+    #[test]
+    fn test_blocking_get_pool() -> Result<(), Box<dyn std::error::Error>> {
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            account_info_response(
+                &RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+                vec![0u8; LAUNCHPAD_POOL_STATE_DATA_SIZE],
+            ),
+        );
+        let raydium = Raydium::from_async(mock_raydium(mocks))?;
+        let pool = raydium.get_pool(&Pubkey::new_unique().to_string())?;
+        assert_eq!(pool.kind(), PoolKind::Launchpad);
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::{
+    Raydium, error::RaydiumError, metrics::MetricsHook, trace::trace_debug, typs::Contextual,
+};
+
+/// number of cache hits and misses since the cache was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// a cached value and the instant it was fetched at, `None` until the first fetch
+type Slot<V> = Arc<tokio::sync::Mutex<Option<(Instant, V)>>>;
+
+/// values keyed by address expiring after a fixed time.
+/// concurrent lookups of the same key wait for a single fetch
+pub(crate) struct TtlCache<V> {
+    ttl: Duration,
+    slots: Mutex<HashMap<Pubkey, Slot<V>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    metrics: Option<Arc<dyn MetricsHook>>,
+}
+
+impl<V: Clone> TtlCache<V> {
+    pub(crate) fn new(ttl: Duration, metrics: Option<Arc<dyn MetricsHook>>) -> Self {
+        Self {
+            ttl,
+            slots: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            metrics,
+        }
+    }
+
+    /// the cached value of `key`, or the result of `fetch` which is cached on success.
+    /// `fetch` is not polled on a hit
+    pub(crate) async fn get_or_fetch<F>(&self, key: Pubkey, fetch: F) -> Result<V, RaydiumError>
+    where
+        F: Future<Output = Result<V, RaydiumError>>,
+    {
+        let slot = self.slot(key);
+        let mut slot = slot.lock().await;
+        if let Some(value) = self.lookup(&key, &slot) {
+            return Ok(value);
+        }
+        let value = fetch.await?;
+        *slot = Some((Instant::now(), value.clone()));
+        Ok(value)
+    }
+
+    /// the cached value of `key` if it has not expired, counted as a hit or a miss
+    pub(crate) async fn cached(&self, key: &Pubkey) -> Option<V> {
+        let slot = self.slot(*key);
+        let slot = slot.lock().await;
+        self.lookup(key, &slot)
+    }
+
+    /// cache a value fetched by the caller
+    pub(crate) async fn insert(&self, key: Pubkey, value: V) {
+        *self.slot(key).lock().await = Some((Instant::now(), value));
+    }
+
+    fn slot(&self, key: Pubkey) -> Slot<V> {
+        self.slots.lock().unwrap().entry(key).or_default().clone()
+    }
+
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn lookup(&self, key: &Pubkey, slot: &Option<(Instant, V)>) -> Option<V> {
+        let hit = slot
+            .as_ref()
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < self.ttl)
+            .map(|(_, value)| value.clone());
+        if hit.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            trace_debug!(%key, "cache hit");
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            trace_debug!(%key, "cache miss");
+        }
+        if let Some(metrics) = &self.metrics {
+            metrics.on_cache(hit.is_some());
+        }
+        hit
+    }
+
+    pub(crate) fn invalidate(&self, key: &Pubkey) {
+        self.slots.lock().unwrap().remove(key);
+    }
+
+    pub(crate) fn clear(&self) {
+        self.slots.lock().unwrap().clear();
+    }
+
+    fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// pool accounts and prices cached by a `Raydium` handle and its clones
+pub(crate) struct PoolCache {
+    pub(crate) accounts: TtlCache<Contextual<Account>>,
+    pub(crate) prices: TtlCache<f64>,
+}
+
+impl PoolCache {
+    pub(crate) fn new(ttl: Duration, metrics: Option<Arc<dyn MetricsHook>>) -> Self {
+        Self {
+            accounts: TtlCache::new(ttl, metrics.clone()),
+            prices: TtlCache::new(ttl, metrics),
+        }
+    }
+}
+
+impl Raydium {
+    /// drop the cached account and price of a pool
+    pub fn invalidate(&self, address: &Pubkey) {
+        if let Some(cache) = &self.cache {
+            cache.accounts.invalidate(address);
+            cache.prices.invalidate(address);
+        }
+    }
+
+    /// drop every cached account, price, mint and CLMM amm config
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.accounts.clear();
+            cache.prices.clear();
+        }
+        self.mint_cache.clear();
+        self.clmm_config_cache.clear();
+    }
+
+    /// hits and misses of the pool and price caches, zero when caching is disabled
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache
+            .as_ref()
+            .map_or_else(CacheStats::default, |cache| {
+                let (accounts, prices) = (cache.accounts.stats(), cache.prices.stats());
+                CacheStats {
+                    hits: accounts.hits + prices.hits,
+                    misses: accounts.misses + prices.misses,
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicU64, Ordering},
+        time::Duration,
+    };
+
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+    use crate::{
+        config::RaydiumConfig,
+        launchpad::LAUNCHPAD_POOL_STATE_DATA_SIZE,
+        programs::RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+        test_utils::{account_info_response, mock_raydium_with_config},
+    };
+
+    #[tokio::test]
+    async fn test_ttl_cache_coalesces() {
+        let cache = TtlCache::new(Duration::from_secs(60), None);
+        let fetches = AtomicU64::new(0);
+        let key = Pubkey::new_unique();
+        let fetch = || async {
+            fetches.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(7u64)
+        };
+        let (a, b) = tokio::join!(
+            cache.get_or_fetch(key, fetch()),
+            cache.get_or_fetch(key, fetch())
+        );
+        assert_eq!((a, b), (Ok(7), Ok(7)));
+        assert_eq!(fetches.load(Ordering::Relaxed), 1);
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+        cache.invalidate(&key);
+        assert_eq!(cache.get_or_fetch(key, fetch()).await, Ok(7));
+        assert_eq!(fetches.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pool_cache() -> Result<(), Box<dyn std::error::Error>> {
+        // a single response is queued, further rpc calls would find no account
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            account_info_response(
+                &RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+                vec![0u8; LAUNCHPAD_POOL_STATE_DATA_SIZE],
+            ),
+        );
+        let config = RaydiumConfig::new().with_cache_ttl(Duration::from_secs(60));
+        let raydium = mock_raydium_with_config(mocks, config);
+        let address = Pubkey::new_unique();
+        raydium.get_pool(&address.to_string()).await?;
+        raydium
+            .clone()
+            .get_liquidity_pool_launchpad(&address.to_string())
+            .await?;
+        assert_eq!(raydium.cache_stats(), CacheStats { hits: 1, misses: 1 });
+        raydium.invalidate(&address);
+        assert!(raydium.get_pool(&address.to_string()).await.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use solana_network_client::Mode;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{metrics::MetricsHook, rate_limit::RateLimit, typs::PoolKind};
+
+/// raydium configuration
+#[derive(Debug, Clone, Default)]
+pub struct RaydiumConfig {
+    /// timeout applied to every rpc request, `None` waits forever
+    pub timeout: Option<Duration>,
+    /// websocket endpoint used by subscriptions, derived from the rpc url when `None`
+    pub ws_url: Option<String>,
+    /// accept pool accounts owned by any program, for forks of the raydium programs
+    pub skip_owner_check: bool,
+    /// network used to select the program ids, detected from the client when `None`
+    pub mode: Option<Mode>,
+    /// program ids overriding the ones of the network
+    pub program_ids: HashMap<PoolKind, Pubkey>,
+    /// minimum value locked, in the quote mint, for a pool to count in aggregate prices
+    pub min_aggregate_liquidity: f64,
+    /// time pool accounts and prices are cached for, `None` disables the cache
+    pub cache_ttl: Option<Duration>,
+    /// budget of rpc requests and subscription setups, `None` sends requests unthrottled
+    pub rate_limit: Option<RateLimit>,
+    /// time a failing rpc endpoint is skipped, `DEFAULT_ENDPOINT_COOLDOWN` when `None`
+    pub endpoint_cooldown: Option<Duration>,
+    /// consecutive reconnection attempts of a dropped subscription before it ends,
+    /// `None` retries forever
+    pub max_reconnects: Option<u32>,
+    /// time mint infos are cached for, `DEFAULT_MINT_CACHE_TTL` when `None`
+    pub mint_cache_ttl: Option<Duration>,
+    /// time the list of CLMM amm configs is cached for, `DEFAULT_CLMM_CONFIG_CACHE_TTL` when
+    /// `None`
+    pub clmm_config_cache_ttl: Option<Duration>,
+    /// hook observing rpc requests, parses and cache lookups, `None` observes nothing
+    pub metrics: Option<Arc<dyn MetricsHook>>,
+    /// amplification coefficient stable pools are quoted with, `DEFAULT_STABLE_AMP` when `None`
+    pub stable_amp: Option<u64>,
+    /// tick arrays a CLMM quote fetches before giving up, `DEFAULT_MAX_CLMM_TICK_ARRAYS` when
+    /// `None`
+    pub max_clmm_tick_arrays: Option<usize>,
+}
+
+impl RaydiumConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// set the timeout applied to every rpc request
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// set the websocket endpoint used by subscriptions
+    pub fn with_ws_url(mut self, ws_url: &str) -> Self {
+        self.ws_url = Some(ws_url.to_string());
+        self
+    }
+
+    /// disable the check that pool accounts are owned by the raydium programs
+    pub fn with_skip_owner_check(mut self, skip: bool) -> Self {
+        self.skip_owner_check = skip;
+        self
+    }
+
+    /// set the network used to select the program ids
+    pub fn with_mode(mut self, mode: Mode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// override the program id of a pool type, used for owner validation, discovery and PDAs
+    pub fn with_program_id(mut self, kind: PoolKind, program_id: Pubkey) -> Self {
+        self.program_ids.insert(kind, program_id);
+        self
+    }
+
+    /// set the minimum value locked for a pool to count in aggregate prices
+    pub fn with_min_aggregate_liquidity(mut self, min_liquidity: f64) -> Self {
+        self.min_aggregate_liquidity = min_liquidity;
+        self
+    }
+
+    /// cache pool accounts and prices for `ttl`, shared by every clone of the `Raydium` handle
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// limit rpc requests to `requests_per_second` with bursts of up to `burst` requests,
+    /// shared by every clone of the `Raydium` handle. a rate that is zero, negative or not
+    /// finite sets no limit
+    pub fn with_rate_limit(mut self, requests_per_second: f64, burst: u32) -> Self {
+        let limit = RateLimit {
+            requests_per_second,
+            burst,
+        };
+        self.rate_limit = limit.is_limited().then_some(limit);
+        self
+    }
+
+    /// set the time a failing rpc endpoint is skipped before being probed again
+    pub fn with_endpoint_cooldown(mut self, cooldown: Duration) -> Self {
+        self.endpoint_cooldown = Some(cooldown);
+        self
+    }
+
+    /// set the consecutive reconnection attempts of a dropped subscription before it ends
+    pub fn with_max_reconnects(mut self, max_reconnects: u32) -> Self {
+        self.max_reconnects = Some(max_reconnects);
+        self
+    }
+
+    /// set the time mint infos are cached for, zero disables the mint cache
+    pub fn with_mint_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.mint_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// set the time the list of CLMM amm configs is cached for
+    pub fn with_clmm_config_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.clmm_config_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// register a hook observing rpc requests, parses and cache lookups
+    pub fn with_metrics(mut self, metrics: Arc<dyn MetricsHook>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// set the amplification coefficient stable pools are quoted with
+    pub fn with_stable_amp(mut self, amp: u64) -> Self {
+        self.stable_amp = Some(amp);
+        self
+    }
+
+    /// set the tick arrays a CLMM quote fetches before giving up
+    pub fn with_max_clmm_tick_arrays(mut self, max_tick_arrays: usize) -> Self {
+        self.max_clmm_tick_arrays = Some(max_tick_arrays);
+        self
+    }
+}
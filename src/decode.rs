@@ -0,0 +1,449 @@
+//! swaps of the AMM v4 program decoded from confirmed transactions
+
+use solana_sdk::{bs58, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiInnerInstructions, UiInstruction,
+    UiLoadedAddresses,
+};
+
+use crate::{
+    liquidity::v4::instructions::{SWAP_BASE_IN_TAG, SWAP_BASE_OUT_TAG},
+    programs::{
+        DEVNET_RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID, RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+        TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    reader::r_u64,
+    typs::MintSide,
+};
+
+/// accounts of a v4 swap without the target orders account
+const SWAP_MIN_ACCOUNTS: usize = 17;
+/// token program instruction tags
+const TOKEN_TRANSFER_TAG: u8 = 3;
+const TOKEN_TRANSFER_CHECKED_TAG: u8 = 12;
+
+/// amounts requested by a v4 swap instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum V4SwapKind {
+    /// `swapBaseIn`, fixed input with a minimum output
+    BaseIn {
+        amount_in: u64,
+        minimum_amount_out: u64,
+    },
+    /// `swapBaseOut`, fixed output with a maximum input
+    BaseOut { max_amount_in: u64, amount_out: u64 },
+}
+
+/// one swap on an AMM v4 pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct V4SwapEvent {
+    pub signature: Signature,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// index of the top-level instruction the swap ran in
+    pub instruction_index: usize,
+    /// the swap was invoked by another program
+    pub cpi: bool,
+    pub pool: Pubkey,
+    /// signer owning the user token accounts
+    pub owner: Pubkey,
+    pub kind: V4SwapKind,
+    /// side of the pool the input was paid into
+    pub input_side: MintSide,
+    /// amount transferred into the pool, raw
+    pub amount_in: u64,
+    /// amount transferred out of the pool, raw
+    pub amount_out: u64,
+}
+
+impl V4SwapEvent {
+    /// base amount moved by the swap, raw
+    pub fn base_amount(&self) -> u64 {
+        match self.input_side {
+            MintSide::Base => self.amount_in,
+            MintSide::Quote => self.amount_out,
+        }
+    }
+
+    /// quote amount moved by the swap, raw
+    pub fn quote_amount(&self) -> u64 {
+        match self.input_side {
+            MintSide::Base => self.amount_out,
+            MintSide::Quote => self.amount_in,
+        }
+    }
+}
+
+/// an instruction of the transaction with its accounts resolved
+struct Invocation {
+    top_level: usize,
+    program: Pubkey,
+    accounts: Vec<Pubkey>,
+    data: Vec<u8>,
+    /// 1 for top-level instructions, unknown for inner instructions of old transactions
+    stack_height: Option<u32>,
+}
+
+/// a token transfer: source, destination and amount
+type Transfer = (Pubkey, Pubkey, u64);
+
+/// the v4 swaps of a transaction, top-level and invoked by other programs, in execution order.
+/// the moved amounts are read from the token transfers the swap made, so failed transactions
+/// and transactions without inner instructions yield no events
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::decode::decode_v4_instructions;
+/// # use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+/// # fn run(transaction: EncodedConfirmedTransactionWithStatusMeta) {
+/// for swap in decode_v4_instructions(&transaction) {
+///     println!(
+///         "{} swapped {} base for {} quote on {}",
+///         swap.owner,
+///         swap.base_amount(),
+///         swap.quote_amount(),
+///         swap.pool
+///     );
+/// }
+/// # }
+/// ```
+pub fn decode_v4_instructions(
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Vec<V4SwapEvent> {
+    let Some(meta) = &transaction.transaction.meta else {
+        return Vec::new();
+    };
+    if meta.err.is_some() {
+        return Vec::new();
+    }
+    let Some(decoded) = transaction.transaction.transaction.decode() else {
+        return Vec::new();
+    };
+    let Some(signature) = decoded.signatures.first().copied() else {
+        return Vec::new();
+    };
+    let mut keys = decoded.message.static_account_keys().to_vec();
+    if let Some(loaded) = Option::<&UiLoadedAddresses>::from(meta.loaded_addresses.as_ref()) {
+        for address in loaded.writable.iter().chain(loaded.readonly.iter()) {
+            match address.parse() {
+                Ok(key) => keys.push(key),
+                Err(_) => return Vec::new(),
+            }
+        }
+    }
+    let inner = Option::<&Vec<UiInnerInstructions>>::from(meta.inner_instructions.as_ref());
+    let resolve = |indexes: &[u8]| {
+        indexes
+            .iter()
+            .map(|i| keys.get(*i as usize).copied())
+            .collect::<Option<Vec<_>>>()
+    };
+    let mut invocations = Vec::new();
+    for (index, instruction) in decoded.message.instructions().iter().enumerate() {
+        if let (Some(program), Some(accounts)) = (
+            keys.get(instruction.program_id_index as usize),
+            resolve(&instruction.accounts),
+        ) {
+            invocations.push(Invocation {
+                top_level: index,
+                program: *program,
+                accounts,
+                data: instruction.data.clone(),
+                stack_height: Some(1),
+            });
+        }
+        let nested = inner
+            .into_iter()
+            .flatten()
+            .filter(|i| i.index as usize == index)
+            .flat_map(|i| i.instructions.iter());
+        for instruction in nested {
+            // base64 responses carry compiled inner instructions only
+            let UiInstruction::Compiled(instruction) = instruction else {
+                continue;
+            };
+            let (Some(program), Some(accounts), Ok(data)) = (
+                keys.get(instruction.program_id_index as usize),
+                resolve(&instruction.accounts),
+                bs58::decode(&instruction.data).into_vec(),
+            ) else {
+                continue;
+            };
+            invocations.push(Invocation {
+                top_level: index,
+                program: *program,
+                accounts,
+                data,
+                stack_height: instruction.stack_height,
+            });
+        }
+    }
+    let mut events = Vec::new();
+    for (position, invocation) in invocations.iter().enumerate() {
+        let Some(kind) = swap_kind(invocation) else {
+            continue;
+        };
+        let transfers = swap_transfers(invocation, &invocations[position + 1..]);
+        let accounts = &invocation.accounts;
+        let count = accounts.len();
+        let (base_vault, quote_vault) = (accounts[count - 13], accounts[count - 12]);
+        let vaults = [base_vault, quote_vault];
+        let (Some(paid), Some(received)) = (
+            transfers.iter().find(|t| vaults.contains(&t.1)),
+            transfers.iter().find(|t| vaults.contains(&t.0)),
+        ) else {
+            continue;
+        };
+        events.push(V4SwapEvent {
+            signature,
+            slot: transaction.slot,
+            block_time: transaction.block_time,
+            instruction_index: invocation.top_level,
+            cpi: invocation.stack_height != Some(1),
+            pool: accounts[1],
+            owner: accounts[count - 1],
+            kind,
+            input_side: if paid.1 == base_vault {
+                MintSide::Base
+            } else {
+                MintSide::Quote
+            },
+            amount_in: paid.2,
+            amount_out: received.2,
+        });
+    }
+    events
+}
+
+/// the requested amounts when the invocation is a v4 swap
+fn swap_kind(invocation: &Invocation) -> Option<V4SwapKind> {
+    if invocation.program != RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID
+        && invocation.program != DEVNET_RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID
+    {
+        return None;
+    }
+    if invocation.data.len() < 17 || invocation.accounts.len() < SWAP_MIN_ACCOUNTS {
+        return None;
+    }
+    let (first, second) = (r_u64(&invocation.data, 1), r_u64(&invocation.data, 9));
+    match invocation.data[0] {
+        SWAP_BASE_IN_TAG => Some(V4SwapKind::BaseIn {
+            amount_in: first,
+            minimum_amount_out: second,
+        }),
+        SWAP_BASE_OUT_TAG => Some(V4SwapKind::BaseOut {
+            max_amount_in: first,
+            amount_out: second,
+        }),
+        _ => None,
+    }
+}
+
+/// the token transfers made directly by `swap` among the invocations following it
+fn swap_transfers(swap: &Invocation, following: &[Invocation]) -> Vec<Transfer> {
+    let mut transfers = Vec::new();
+    for invocation in following {
+        if invocation.top_level != swap.top_level || transfers.len() == 2 {
+            break;
+        }
+        match (swap.stack_height, invocation.stack_height) {
+            // left the swap, back in its caller
+            (Some(swap), Some(height)) if height <= swap => break,
+            // deeper than the swap's own invocations
+            (Some(swap), Some(height)) if height > swap + 1 => continue,
+            // heights unknown, the next swap starts its own transfers
+            _ if swap_kind(invocation).is_some() => break,
+            _ => {}
+        }
+        if let Some(transfer) = token_transfer(invocation) {
+            transfers.push(transfer);
+        }
+    }
+    transfers
+}
+
+/// source, destination and amount of a token program transfer
+fn token_transfer(invocation: &Invocation) -> Option<Transfer> {
+    if invocation.program != TOKEN_PROGRAM_ID && invocation.program != TOKEN_2022_PROGRAM_ID {
+        return None;
+    }
+    if invocation.data.len() < 9 {
+        return None;
+    }
+    let amount = r_u64(&invocation.data, 1);
+    let accounts = &invocation.accounts;
+    match invocation.data[0] {
+        TOKEN_TRANSFER_TAG if accounts.len() >= 3 => Some((accounts[0], accounts[1], amount)),
+        TOKEN_TRANSFER_CHECKED_TAG if accounts.len() >= 4 => {
+            Some((accounts[0], accounts[2], amount))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        transaction::Transaction,
+    };
+
+    use super::*;
+    use crate::{test_utils::transaction_response, transaction::COMPUTE_BUDGET_PROGRAM_ID};
+
+    /// accounts of a v4 swap, with or without the target orders account
+    fn swap_accounts(pool: &[Pubkey; 3], user: &[Pubkey; 3], target_orders: bool) -> Vec<Pubkey> {
+        let mut accounts = vec![TOKEN_PROGRAM_ID, Pubkey::new_unique(), Pubkey::new_unique()];
+        accounts.push(Pubkey::new_unique());
+        if target_orders {
+            accounts.push(Pubkey::new_unique());
+        }
+        accounts.extend_from_slice(&pool[1..]);
+        accounts.extend((0..8).map(|_| Pubkey::new_unique()));
+        accounts.extend_from_slice(user);
+        accounts[1] = pool[0];
+        accounts
+    }
+
+    fn instruction(program: Pubkey, data: Vec<u8>, accounts: &[Pubkey]) -> Instruction {
+        let metas = accounts
+            .iter()
+            .map(|a| AccountMeta::new(*a, false))
+            .collect();
+        Instruction::new_with_bytes(program, &data, metas)
+    }
+
+    fn tagged(tag: u8, amounts: &[u64]) -> Vec<u8> {
+        let mut data = vec![tag];
+        for amount in amounts {
+            data.extend_from_slice(&amount.to_le_bytes());
+        }
+        data
+    }
+
+    /// recorded shape of a `getTransaction` response with the inner instructions of the
+    /// top-level instruction `top_level` and their stack heights
+    fn fixture(
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        top_level: u8,
+        inner: Vec<(Instruction, Option<u32>)>,
+    ) -> EncodedConfirmedTransactionWithStatusMeta {
+        let mut transaction = Transaction::new_with_payer(instructions, Some(payer));
+        transaction.message.recent_blockhash = Hash::default();
+        let keys = transaction.message.account_keys.clone();
+        let index = |key: &Pubkey| keys.iter().position(|k| k == key).unwrap();
+        let instructions: Vec<_> = inner
+            .iter()
+            .map(|(instruction, stack_height)| {
+                let accounts: Vec<_> = instruction
+                    .accounts
+                    .iter()
+                    .map(|a| index(&a.pubkey))
+                    .collect();
+                serde_json::json!({
+                    "programIdIndex": index(&instruction.program_id),
+                    "accounts": accounts,
+                    "data": bs58::encode(&instruction.data).into_string(),
+                    "stackHeight": stack_height,
+                })
+            })
+            .collect();
+        let meta = serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "innerInstructions": [{ "index": top_level, "instructions": instructions }],
+        });
+        serde_json::from_value(transaction_response(42, &transaction, meta)).unwrap()
+    }
+
+    #[test]
+    fn test_decode_v4_instructions() {
+        let pool = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let (pool_id, base_vault, quote_vault) = (pool[0], pool[1], pool[2]);
+        let owner = Pubkey::new_unique();
+        let user = [Pubkey::new_unique(), Pubkey::new_unique(), owner];
+        let transfer = |from: Pubkey, to: Pubkey, amount: u64| {
+            instruction(TOKEN_PROGRAM_ID, tagged(3, &[amount]), &[from, to, owner])
+        };
+
+        // top-level swapBaseIn selling base
+        let accounts = swap_accounts(&pool, &user, true);
+        let swap = instruction(
+            RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+            tagged(9, &[1_000, 450]),
+            &accounts,
+        );
+        let transaction = fixture(
+            &[swap],
+            &owner,
+            0,
+            vec![
+                (transfer(user[0], base_vault, 1_000), Some(2)),
+                (transfer(quote_vault, user[1], 500), Some(2)),
+            ],
+        );
+        let events = decode_v4_instructions(&transaction);
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!((event.pool, event.owner, event.slot), (pool_id, owner, 42));
+        assert_eq!(event.block_time, Some(1_700_000_042));
+        assert_eq!(
+            event.kind,
+            V4SwapKind::BaseIn {
+                amount_in: 1_000,
+                minimum_amount_out: 450
+            }
+        );
+        assert_eq!((event.input_side, event.cpi), (MintSide::Base, false));
+        assert_eq!((event.base_amount(), event.quote_amount()), (1_000, 500));
+
+        // swapBaseOut buying base through an aggregator, without the target orders account,
+        // followed by a fee transfer of the aggregator which is not part of the swap
+        let aggregator = Pubkey::new_unique();
+        let accounts = swap_accounts(&pool, &user, false);
+        let swap = instruction(
+            RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+            tagged(11, &[300, 2_000]),
+            &accounts,
+        );
+        let mut routed = accounts.clone();
+        routed.push(RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID);
+        let fee_account = Pubkey::new_unique();
+        routed.push(fee_account);
+        let transaction = fixture(
+            &[
+                instruction(COMPUTE_BUDGET_PROGRAM_ID, tagged(2, &[]), &[]),
+                instruction(aggregator, tagged(1, &[]), &routed),
+            ],
+            &owner,
+            1,
+            vec![
+                (swap, Some(2)),
+                (transfer(user[1], quote_vault, 280), Some(3)),
+                (transfer(base_vault, user[0], 2_000), Some(3)),
+                (transfer(user[1], fee_account, 5), Some(2)),
+            ],
+        );
+        let events = decode_v4_instructions(&transaction);
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!((event.instruction_index, event.cpi), (1, true));
+        assert_eq!(
+            event.kind,
+            V4SwapKind::BaseOut {
+                max_amount_in: 300,
+                amount_out: 2_000
+            }
+        );
+        assert_eq!(event.input_side, MintSide::Quote);
+        assert_eq!((event.base_amount(), event.quote_amount()), (2_000, 280));
+    }
+}
@@ -0,0 +1,409 @@
+use solana_sdk::pubkey::Pubkey;
+use tokio::join;
+
+use crate::{
+    Raydium,
+    error::RaydiumError,
+    liquidity::{
+        clmm::{
+            RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE, RAYDIUM_LIQUIDITY_POOL_CLMM_TOKEN_MINT_0_OFFSET,
+            RAYDIUM_LIQUIDITY_POOL_CLMM_TOKEN_MINT_1_OFFSET, RaydiumLiquidityPoolCLMM,
+            RaydiumLiquidityPoolCLMMData,
+            pda::{amm_config_address, pool_address},
+        },
+        cpmm::{
+            RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE, RAYDIUM_LIQUIDITY_POOL_CPMM_TOKEN_0_MINT_OFFSET,
+            RAYDIUM_LIQUIDITY_POOL_CPMM_TOKEN_1_MINT_OFFSET, RaydiumLiquidityPoolCPMM,
+            RaydiumLiquidityPoolCPMMData,
+        },
+        stable::{
+            RAYDIUM_LIQUIDITY_POOL_STABLE_BASE_MINT_OFFSET,
+            RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE,
+            RAYDIUM_LIQUIDITY_POOL_STABLE_QUOTE_MINT_OFFSET,
+        },
+        v4::{
+            RAYDIUM_LIQUIDITY_POOL_V4_BASE_MINT_OFFSET, RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE,
+            RAYDIUM_LIQUIDITY_POOL_V4_QUOTE_MINT_OFFSET, RaydiumLiquidityPoolData,
+            RaydiumLiquidityPoolV4,
+        },
+    },
+    mints::DEFAULT_QUOTE_PREFERENCE,
+    typs::{PoolData, PoolKind},
+};
+
+impl Raydium {
+    /// get all v4 liquidity pools that contain the mint on either side
+    pub async fn get_liquidity_pools_v4_by_mint(
+        &self,
+        mint: &Pubkey,
+    ) -> Result<Vec<(Pubkey, RaydiumLiquidityPoolData)>, RaydiumError> {
+        let accounts = self
+            .get_pool_accounts_by_mint(
+                &self.program_id(PoolKind::V4),
+                RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE,
+                [
+                    RAYDIUM_LIQUIDITY_POOL_V4_BASE_MINT_OFFSET,
+                    RAYDIUM_LIQUIDITY_POOL_V4_QUOTE_MINT_OFFSET,
+                ],
+                mint,
+            )
+            .await?;
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(address, data)| {
+                self.parse_with_metrics(Some(PoolKind::V4), || {
+                    RaydiumLiquidityPoolV4::get_liquidity_pool_info(&data)
+                        .map_err(RaydiumError::Parse)
+                })
+                .ok()
+                .map(|pool| (address, pool))
+            })
+            .collect())
+    }
+
+    /// get all CPMM liquidity pools that contain the mint on either side
+    pub async fn get_liquidity_pools_cpmm_by_mint(
+        &self,
+        mint: &Pubkey,
+    ) -> Result<Vec<(Pubkey, RaydiumLiquidityPoolCPMMData)>, RaydiumError> {
+        let accounts = self
+            .get_pool_accounts_by_mint(
+                &self.program_id(PoolKind::Cpmm),
+                RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE,
+                [
+                    RAYDIUM_LIQUIDITY_POOL_CPMM_TOKEN_0_MINT_OFFSET,
+                    RAYDIUM_LIQUIDITY_POOL_CPMM_TOKEN_1_MINT_OFFSET,
+                ],
+                mint,
+            )
+            .await?;
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(address, data)| {
+                self.parse_with_metrics(Some(PoolKind::Cpmm), || {
+                    RaydiumLiquidityPoolCPMM::get_liquidity_pool_info(&data)
+                        .map_err(RaydiumError::Parse)
+                })
+                .ok()
+                .map(|pool| (address, pool))
+            })
+            .collect())
+    }
+
+    /// get all CLMM liquidity pools that contain the mint on either side
+    pub async fn get_liquidity_pools_clmm_by_mint(
+        &self,
+        mint: &Pubkey,
+    ) -> Result<Vec<(Pubkey, RaydiumLiquidityPoolCLMMData)>, RaydiumError> {
+        let accounts = self
+            .get_pool_accounts_by_mint(
+                &self.program_id(PoolKind::Clmm),
+                RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE,
+                [
+                    RAYDIUM_LIQUIDITY_POOL_CLMM_TOKEN_MINT_0_OFFSET,
+                    RAYDIUM_LIQUIDITY_POOL_CLMM_TOKEN_MINT_1_OFFSET,
+                ],
+                mint,
+            )
+            .await?;
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(address, data)| {
+                self.parse_with_metrics(Some(PoolKind::Clmm), || {
+                    RaydiumLiquidityPoolCLMM::get_liquidity_pool_info(&data)
+                })
+                .ok()
+                .map(|pool| (address, pool))
+            })
+            .collect())
+    }
+
+    /// get all v4, CPMM and CLMM liquidity pools that contain the mint
+    pub async fn get_liquidity_pools_by_mint(
+        &self,
+        mint: &Pubkey,
+    ) -> Result<Vec<(Pubkey, PoolData)>, RaydiumError> {
+        let (v4, cpmm, clmm) = join!(
+            self.get_liquidity_pools_v4_by_mint(mint),
+            self.get_liquidity_pools_cpmm_by_mint(mint),
+            self.get_liquidity_pools_clmm_by_mint(mint)
+        );
+        let mut pools: Vec<(Pubkey, PoolData)> = Vec::new();
+        pools.extend(v4?.into_iter().map(|(a, p)| (a, PoolData::V4(p))));
+        pools.extend(cpmm?.into_iter().map(|(a, p)| (a, PoolData::Cpmm(p))));
+        pools.extend(clmm?.into_iter().map(|(a, p)| (a, PoolData::Clmm(p))));
+        Ok(pools)
+    }
+
+    /// get all v4, CPMM and CLMM liquidity pools trading `mint_a` against `mint_b`
+    pub async fn get_liquidity_pools_by_pair(
+        &self,
+        mint_a: &Pubkey,
+        mint_b: &Pubkey,
+    ) -> Result<Vec<(Pubkey, PoolData)>, RaydiumError> {
+        let (v4, cpmm, clmm) = join!(
+            self.get_pools_by_pair(PoolKind::V4, mint_a, mint_b),
+            self.get_pools_by_pair(PoolKind::Cpmm, mint_a, mint_b),
+            self.get_pools_by_pair(PoolKind::Clmm, mint_a, mint_b)
+        );
+        let mut pools = v4?;
+        pools.extend(cpmm?);
+        pools.extend(clmm?);
+        Ok(pools)
+    }
+
+    /// get the CLMM pool of a pair in the fee tier of the amm config `fee_tier_index`, its
+    /// address derived from the mints without scanning the program, see `list_clmm_configs`
+    /// for the tiers
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::{USDC_MINT, WSOL_MINT}};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let (address, pool) = raydium.find_clmm_pool(&WSOL_MINT, &USDC_MINT, 8).await?;
+    /// println!("{} tick {}", address, pool.tick_current);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn find_clmm_pool(
+        &self,
+        mint_a: &Pubkey,
+        mint_b: &Pubkey,
+        fee_tier_index: u16,
+    ) -> Result<(Pubkey, RaydiumLiquidityPoolCLMMData), RaydiumError> {
+        let program = self.program_id(PoolKind::Clmm);
+        let amm_config = amm_config_address(fee_tier_index, &program);
+        let address = pool_address(&amm_config, mint_a, mint_b, &program);
+        match self.get_liquidity_pool_clmm(&address.to_string()).await {
+            Ok(pool) => Ok((address, pool)),
+            Err(RaydiumError::AccountNotFound(_)) => Err(RaydiumError::PoolNotFound(format!(
+                "no CLMM pool pairs {} and {} at config index {}",
+                mint_a, mint_b, fee_tier_index
+            ))),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// find the pool to price `mint` against
+    /// picks the first pool whose other side matches the preference list (USDC, then WSOL
+    /// when the list is empty), breaking ties by the balance of the other side's vault.
+    /// use `PoolData::kind` and `PoolData::side_of` on the result to know whether to invert the price.
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::{USDC_MINT, WSOL_MINT}, typs::MintSide};
+    /// # use solana_sdk::pubkey::Pubkey;
+    /// # async fn run(raydium: Raydium, mint: Pubkey) -> Result<(), RaydiumError> {
+    /// let (address, pool) = raydium.find_quote_pool(&mint, &[USDC_MINT, WSOL_MINT]).await?;
+    /// let invert = pool.side_of(&mint) == Some(MintSide::Quote);
+    /// println!("{} {:?} invert: {}", address, pool.kind(), invert);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn find_quote_pool(
+        &self,
+        mint: &Pubkey,
+        preference: &[Pubkey],
+    ) -> Result<(Pubkey, PoolData), RaydiumError> {
+        let preference = if preference.is_empty() {
+            &DEFAULT_QUOTE_PREFERENCE[..]
+        } else {
+            preference
+        };
+        let candidates = self.get_liquidity_pools_by_mint(mint).await?;
+        for quote_mint in preference {
+            let matches: Vec<&(Pubkey, PoolData)> = candidates
+                .iter()
+                .filter(|(_, pool)| pool.other_mint(mint) == Some(*quote_mint))
+                .collect();
+            if matches.is_empty() {
+                continue;
+            }
+            let vaults: Vec<Pubkey> = matches
+                .iter()
+                .filter_map(|(_, pool)| pool.vault_of(quote_mint))
+                .collect();
+            let balances = self.get_token_account_amounts(&vaults).await?;
+            let best = matches
+                .iter()
+                .zip(balances)
+                .max_by_key(|(_, balance)| *balance)
+                .map(|(pool, _)| (*pool).clone());
+            if let Some(best) = best {
+                return Ok(best);
+            }
+        }
+        Err(RaydiumError::PoolNotFound(format!(
+            "no pool pairs {} with a preferred quote mint",
+            mint
+        )))
+    }
+
+    /// get the pools of one type holding both mints, in either order
+    async fn get_pools_by_pair(
+        &self,
+        kind: PoolKind,
+        mint_a: &Pubkey,
+        mint_b: &Pubkey,
+    ) -> Result<Vec<(Pubkey, PoolData)>, RaydiumError> {
+        let (data_size, [first_offset, second_offset]) = mint_filter(kind).ok_or_else(|| {
+            RaydiumError::Unsupported("pair discovery of launchpad pools".to_string())
+        })?;
+        let program = self.program_id(kind);
+        let forward = [(first_offset, *mint_a), (second_offset, *mint_b)];
+        let backward = [(first_offset, *mint_b), (second_offset, *mint_a)];
+        let (forward, backward) = join!(
+            self.get_program_accounts_filtered(&program, data_size, &forward),
+            self.get_program_accounts_filtered(&program, data_size, &backward)
+        );
+        let mut accounts = forward?;
+        accounts.extend(backward?);
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(address, data)| {
+                self.parse_with_metrics(Some(kind), || {
+                    PoolData::parse(kind, &data).map_err(RaydiumError::Parse)
+                })
+                .ok()
+                .map(|pool| (address, pool))
+            })
+            .collect())
+    }
+
+    /// get the unparsed v4, CPMM and CLMM pool accounts that contain the mint
+    pub(crate) async fn get_pool_accounts_of_mint(
+        &self,
+        mint: &Pubkey,
+    ) -> Result<Vec<(Pubkey, PoolKind, Vec<u8>)>, RaydiumError> {
+        let accounts_of = |kind: PoolKind| async move {
+            let (data_size, offsets) = mint_filter(kind).unwrap_or_default();
+            let accounts = self
+                .get_pool_accounts_by_mint(&self.program_id(kind), data_size, offsets, mint)
+                .await?;
+            Ok::<_, RaydiumError>(
+                accounts
+                    .into_iter()
+                    .map(move |(address, data)| (address, kind, data)),
+            )
+        };
+        let (v4, cpmm, clmm) = join!(
+            accounts_of(PoolKind::V4),
+            accounts_of(PoolKind::Cpmm),
+            accounts_of(PoolKind::Clmm)
+        );
+        Ok(v4?.chain(cpmm?).chain(clmm?).collect())
+    }
+
+    /// get the accounts of a pool program with `mint` at either of the two offsets
+    async fn get_pool_accounts_by_mint(
+        &self,
+        program: &Pubkey,
+        data_size: usize,
+        mint_offsets: [usize; 2],
+        mint: &Pubkey,
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>, RaydiumError> {
+        let first_filter = [(mint_offsets[0], *mint)];
+        let second_filter = [(mint_offsets[1], *mint)];
+        let (first, second) = join!(
+            self.get_program_accounts_filtered(program, data_size, &first_filter),
+            self.get_program_accounts_filtered(program, data_size, &second_filter)
+        );
+        let mut accounts = first?;
+        accounts.extend(second?);
+        Ok(accounts)
+    }
+}
+
+/// data size and mint offsets filtering the pools of a type, `None` for launchpad pools
+fn mint_filter(kind: PoolKind) -> Option<(usize, [usize; 2])> {
+    match kind {
+        PoolKind::V4 => Some((
+            RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE,
+            [
+                RAYDIUM_LIQUIDITY_POOL_V4_BASE_MINT_OFFSET,
+                RAYDIUM_LIQUIDITY_POOL_V4_QUOTE_MINT_OFFSET,
+            ],
+        )),
+        PoolKind::Cpmm => Some((
+            RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE,
+            [
+                RAYDIUM_LIQUIDITY_POOL_CPMM_TOKEN_0_MINT_OFFSET,
+                RAYDIUM_LIQUIDITY_POOL_CPMM_TOKEN_1_MINT_OFFSET,
+            ],
+        )),
+        PoolKind::Clmm => Some((
+            RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE,
+            [
+                RAYDIUM_LIQUIDITY_POOL_CLMM_TOKEN_MINT_0_OFFSET,
+                RAYDIUM_LIQUIDITY_POOL_CLMM_TOKEN_MINT_1_OFFSET,
+            ],
+        )),
+        PoolKind::Stable => Some((
+            RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE,
+            [
+                RAYDIUM_LIQUIDITY_POOL_STABLE_BASE_MINT_OFFSET,
+                RAYDIUM_LIQUIDITY_POOL_STABLE_QUOTE_MINT_OFFSET,
+            ],
+        )),
+        PoolKind::Launchpad => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_network_client::SolanaClient;
+
+    use crate::{Raydium, mints::WSOL_MINT};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_find_quote_pool() -> Result<(), Box<dyn std::error::Error>> {
+        let solana_client = SolanaClient::new(solana_network_client::Mode::MAIN).unwrap();
+        let raydium = Raydium::new(Arc::new(solana_client));
+        let pool = raydium.find_quote_pool(&WSOL_MINT, &[]).await;
+        println!("Quote Pool: {:?}", pool);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_clmm_pool() -> Result<(), Box<dyn std::error::Error>> {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+        use solana_sdk::pubkey::Pubkey;
+
+        use crate::{
+            error::RaydiumError,
+            liquidity::clmm::{
+                RAYDIUM_CLMM_POOL_STATE_DISCRIMINATOR, RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE,
+                RAYDIUM_LIQUIDITY_POOL_CLMM_TOKEN_MINT_0_OFFSET,
+            },
+            mints::USDC_MINT,
+            programs::RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+            test_utils::{account_info_response, mock_raydium},
+        };
+
+        let mut data = vec![0u8; RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE];
+        data[..8].copy_from_slice(&RAYDIUM_CLMM_POOL_STATE_DISCRIMINATOR);
+        data[RAYDIUM_LIQUIDITY_POOL_CLMM_TOKEN_MINT_0_OFFSET..][..32]
+            .copy_from_slice(WSOL_MINT.as_ref());
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            account_info_response(&RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID, data),
+        );
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            serde_json::json!({"context": {"slot": 1}, "value": null}),
+        );
+        let raydium = mock_raydium(mocks);
+        let (address, pool) = raydium.find_clmm_pool(&USDC_MINT, &WSOL_MINT, 8).await?;
+        assert_eq!(
+            address,
+            Pubkey::from_str_const("3ucNos4NbumPLZNWztqGHNFFgkHeRMBQAVemeeomsUxv")
+        );
+        assert_eq!(pool.token_mint_0, WSOL_MINT);
+        assert!(matches!(
+            raydium.find_clmm_pool(&USDC_MINT, &WSOL_MINT, 3).await,
+            Err(RaydiumError::PoolNotFound(_))
+        ));
+        Ok(())
+    }
+}
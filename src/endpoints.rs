@@ -0,0 +1,197 @@
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_network_client::SolanaClient;
+
+use crate::{Raydium, error::RaydiumError, metrics::RpcCall, trace::trace_debug};
+
+/// time an endpoint is skipped after a retryable failure, unless configured
+pub const DEFAULT_ENDPOINT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// observed state of an rpc endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointHealth {
+    pub url: String,
+    /// false while the endpoint is cooling down after a failure
+    pub healthy: bool,
+    pub successes: u64,
+    pub errors: u64,
+}
+
+struct Endpoint {
+    client: Arc<RpcClient>,
+    successes: u64,
+    errors: u64,
+    /// the endpoint is skipped until this instant
+    unhealthy_until: Option<Instant>,
+}
+
+/// rpc clients tried in order, the first one being the primary
+pub(crate) struct Endpoints {
+    endpoints: Mutex<Vec<Endpoint>>,
+}
+
+impl Endpoints {
+    pub(crate) fn new(clients: &[Arc<SolanaClient>]) -> Self {
+        let endpoints = clients
+            .iter()
+            .map(|client| Endpoint {
+                client: client.client_arc(),
+                successes: 0,
+                errors: 0,
+                unhealthy_until: None,
+            })
+            .collect();
+        Self {
+            endpoints: Mutex::new(endpoints),
+        }
+    }
+
+    /// endpoint indexes to try: healthy ones in order, then the ones cooling down
+    fn order(&self) -> Vec<(usize, Arc<RpcClient>)> {
+        let now = Instant::now();
+        let endpoints = self.endpoints.lock().unwrap();
+        let (mut healthy, cooling): (Vec<_>, Vec<_>) = endpoints
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (i, e.client.clone(), e.unhealthy_until))
+            .partition(|(_, _, until)| until.is_none_or(|until| until <= now));
+        healthy.extend(cooling);
+        healthy
+            .into_iter()
+            .map(|(i, client, _)| (i, client))
+            .collect()
+    }
+
+    fn record(&self, index: usize, failed: bool, cooldown: Duration) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let endpoint = &mut endpoints[index];
+        if failed {
+            endpoint.errors += 1;
+            endpoint.unhealthy_until = Some(Instant::now() + cooldown);
+        } else {
+            endpoint.successes += 1;
+            endpoint.unhealthy_until = None;
+        }
+    }
+
+    fn health(&self) -> Vec<EndpointHealth> {
+        let now = Instant::now();
+        self.endpoints
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| EndpointHealth {
+                url: e.client.url(),
+                healthy: e.unhealthy_until.is_none_or(|until| until <= now),
+                successes: e.successes,
+                errors: e.errors,
+            })
+            .collect()
+    }
+}
+
+impl Raydium {
+    /// state of every rpc endpoint, primary first
+    pub fn endpoint_health(&self) -> Vec<EndpointHealth> {
+        self.endpoints.health()
+    }
+
+    /// run an rpc request on the first healthy endpoint, failing over to the next ones
+    /// on retryable errors. each attempt is rate limited, runs under the timeout and
+    /// is reported to the metrics hook as `call`
+    pub(crate) async fn with_rpc<T, F, Fut>(
+        &self,
+        call: RpcCall,
+        timeout: Option<Duration>,
+        request: F,
+    ) -> Result<T, RaydiumError>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T, RaydiumError>>,
+    {
+        let cooldown = self
+            .config
+            .endpoint_cooldown
+            .unwrap_or(DEFAULT_ENDPOINT_COOLDOWN);
+        let mut last_error = None;
+        for (index, client) in self.endpoints.order() {
+            let started = Instant::now();
+            let result = self.with_timeout(timeout, request(client)).await;
+            if let Some(metrics) = &self.config.metrics {
+                metrics.on_rpc_call(call, started.elapsed(), result.as_ref().map(|_| ()));
+            }
+            trace_debug!(
+                endpoint = index,
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                ok = result.is_ok(),
+                "rpc request"
+            );
+            match result {
+                Err(e) if e.is_retryable() => {
+                    trace_debug!(endpoint = index, error = %e, "retrying on the next rpc endpoint");
+                    self.endpoints.record(index, true, cooldown);
+                    last_error = Some(e);
+                }
+                result => {
+                    self.endpoints.record(index, false, cooldown);
+                    return result;
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| RaydiumError::Rpc("no rpc endpoint".to_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::RpcRequest};
+    use solana_network_client::SolanaClient;
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::{Raydium, config::RaydiumConfig, test_utils::multiple_accounts_response};
+
+    /// solana client backed by a mock rpc client
+    fn mock_client(url: &str, mocks: MocksMap) -> Arc<SolanaClient> {
+        let mut solana_client = SolanaClient::new(solana_network_client::Mode::MAIN).unwrap();
+        solana_client.client = Some(Arc::new(RpcClient::new_mock_with_mocks_map(url, mocks)));
+        Arc::new(solana_client)
+    }
+
+    #[tokio::test]
+    async fn test_failover() -> Result<(), Box<dyn std::error::Error>> {
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![None]),
+        );
+        // the mock sender fails every request sent to "fails"
+        let raydium = Raydium::new_with_endpoints(
+            vec![
+                mock_client("fails", MocksMap::default()),
+                mock_client("succeeds", mocks),
+            ],
+            RaydiumConfig::new(),
+        );
+        let accounts = raydium
+            .get_multiple_accounts_with_context(&[Pubkey::new_unique()])
+            .await?;
+        assert_eq!(accounts.value, vec![None]);
+        let health = raydium.endpoint_health();
+        assert_eq!((health[0].healthy, health[0].errors), (false, 1));
+        assert_eq!((health[1].healthy, health[1].successes), (true, 1));
+        // the primary is skipped while cooling down
+        raydium
+            .get_multiple_accounts_with_context(&[Pubkey::new_unique()])
+            .await?;
+        assert_eq!(raydium.endpoint_health()[0].errors, 1);
+        Ok(())
+    }
+}
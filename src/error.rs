@@ -0,0 +1,164 @@
+use std::{fmt, time::Duration};
+
+use solana_sdk::pubkey::Pubkey;
+
+/// raydium sdk error
+#[derive(Debug, Clone, PartialEq)]
+pub enum RaydiumError {
+    /// the address string is not a valid pubkey
+    InvalidAddress(String),
+    /// the rpc request failed
+    Rpc(String),
+    /// the rpc request did not complete within the timeout
+    Timeout(Duration),
+    /// the account does not exist
+    AccountNotFound(Pubkey),
+    /// the account data could not be parsed
+    Parse(String),
+    /// no pool matched the search
+    PoolNotFound(String),
+    /// no route connects the two mints
+    NoRoute(String),
+    /// the mint is not one of the pool's mints
+    MintNotInPool(Pubkey),
+    /// the pool has no liquidity to trade against
+    InsufficientLiquidity,
+    /// the pool uses a feature the sdk does not support
+    Unsupported(String),
+    /// the account is not owned by the expected program
+    WrongOwner {
+        address: Pubkey,
+        expected: Pubkey,
+        owner: Pubkey,
+    },
+    /// the two pools do not trade the same pair
+    PairMismatch { pool_a: Pubkey, pool_b: Pubkey },
+    /// reading or writing a file failed
+    Io(String),
+    /// an account does not match the one derived or referenced elsewhere
+    KeyMismatch {
+        account: String,
+        expected: Pubkey,
+        found: Pubkey,
+    },
+    /// the transaction could not be signed
+    Signing(String),
+    /// the transaction was rejected or failed on chain
+    TransactionFailed(String),
+    /// the blockhash of the transaction expired before it landed
+    BlockhashExpired,
+    /// the simulation of the transaction failed
+    SimulationFailed { error: String, logs: Vec<String> },
+    /// the transaction would receive less than the minimum output
+    SlippageExceeded {
+        min_amount_out: u64,
+        amount_out: u64,
+    },
+    /// the pool does not accept swaps now
+    PoolNotTradable(Pubkey),
+    /// the amount is too small to receive any output
+    AmountTooSmall(u64),
+    /// a cumulative swap counter decreased between two snapshots, the pool was reset or migrated
+    CountersReset,
+    /// the account does not start with the anchor discriminator of the expected account type
+    WrongDiscriminator { expected: [u8; 8], got: [u8; 8] },
+    /// a CLMM swap reaches a tick array that was not provided, fetch the one at `start_index`
+    TickArrayMissing { start_index: i32 },
+    /// a CLMM position range is inverted, outside the tick range or not aligned to the tick
+    /// spacing of the pool
+    InvalidTickRange {
+        tick_lower: i32,
+        tick_upper: i32,
+        tick_spacing: u16,
+    },
+    /// the oracle of a CLMM pool does not reach back over the requested window, `max_window` is
+    /// the longest it covers
+    TwapWindowTooLong { max_window: Duration },
+}
+
+impl fmt::Display for RaydiumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RaydiumError::InvalidAddress(e) => write!(f, "invalid address: {}", e),
+            RaydiumError::Rpc(e) => write!(f, "rpc error: {}", e),
+            RaydiumError::Timeout(d) => write!(f, "rpc request timed out after {:?}", d),
+            RaydiumError::AccountNotFound(address) => write!(f, "account not found: {}", address),
+            RaydiumError::Parse(e) => write!(f, "parse error: {}", e),
+            RaydiumError::PoolNotFound(e) => write!(f, "pool not found: {}", e),
+            RaydiumError::NoRoute(e) => write!(f, "no route: {}", e),
+            RaydiumError::MintNotInPool(mint) => write!(f, "mint {} is not in the pool", mint),
+            RaydiumError::InsufficientLiquidity => write!(f, "insufficient liquidity"),
+            RaydiumError::Unsupported(e) => write!(f, "unsupported: {}", e),
+            RaydiumError::WrongOwner {
+                address,
+                expected,
+                owner,
+            } => write!(
+                f,
+                "account {} is owned by {}, expected {}",
+                address, owner, expected
+            ),
+            RaydiumError::PairMismatch { pool_a, pool_b } => write!(
+                f,
+                "pools {} and {} do not trade the same pair",
+                pool_a, pool_b
+            ),
+            RaydiumError::Io(e) => write!(f, "io error: {}", e),
+            RaydiumError::KeyMismatch {
+                account,
+                expected,
+                found,
+            } => write!(f, "{} is {}, expected {}", account, found, expected),
+            RaydiumError::Signing(e) => write!(f, "signing error: {}", e),
+            RaydiumError::TransactionFailed(e) => write!(f, "transaction failed: {}", e),
+            RaydiumError::BlockhashExpired => write!(f, "blockhash expired"),
+            RaydiumError::SimulationFailed { error, .. } => {
+                write!(f, "simulation failed: {}", error)
+            }
+            RaydiumError::SlippageExceeded {
+                min_amount_out,
+                amount_out,
+            } => write!(
+                f,
+                "output {} is below the minimum {}",
+                amount_out, min_amount_out
+            ),
+            RaydiumError::PoolNotTradable(pool) => write!(f, "pool {} is not tradable", pool),
+            RaydiumError::AmountTooSmall(amount) => {
+                write!(f, "amount {} is too small to receive any output", amount)
+            }
+            RaydiumError::CountersReset => write!(f, "swap counters decreased between snapshots"),
+            RaydiumError::WrongDiscriminator { expected, got } => write!(
+                f,
+                "account discriminator {:?} does not match {:?}",
+                got, expected
+            ),
+            RaydiumError::TickArrayMissing { start_index } => {
+                write!(f, "tick array starting at {} was not provided", start_index)
+            }
+            RaydiumError::InvalidTickRange {
+                tick_lower,
+                tick_upper,
+                tick_spacing,
+            } => write!(
+                f,
+                "ticks {}..{} are not a range aligned to tick spacing {}",
+                tick_lower, tick_upper, tick_spacing
+            ),
+            RaydiumError::TwapWindowTooLong { max_window } => write!(
+                f,
+                "twap window exceeds the {:?} of recorded observations",
+                max_window
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RaydiumError {}
+
+impl RaydiumError {
+    /// whether retrying the request (possibly on another endpoint) may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, RaydiumError::Rpc(_) | RaydiumError::Timeout(_))
+    }
+}
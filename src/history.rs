@@ -0,0 +1,357 @@
+//! price history rebuilt from the vault balances recorded in the transactions of a pool
+
+use std::str::FromStr;
+
+use solana_client::{
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_config::{CommitmentConfig, RpcTransactionConfig, UiTransactionEncoding},
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiLoadedAddresses, UiTransactionTokenBalance,
+};
+
+use crate::{
+    Raydium,
+    error::RaydiumError,
+    metrics::RpcCall,
+    rpc::parse_address,
+    typs::{PoolData, PoolKind},
+};
+
+/// most signatures returned by one `getSignaturesForAddress` request
+const MAX_SIGNATURES_PAGE: usize = 1000;
+/// vote program id, vote transactions carry no swaps
+const VOTE_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("Vote111111111111111111111111111111111111111");
+
+/// vault balances of a pool after one of its transactions
+#[derive(Debug, Clone, PartialEq)]
+pub struct PricePoint {
+    pub signature: Signature,
+    pub slot: u64,
+    /// unix time of the block, when the node recorded it
+    pub block_time: Option<i64>,
+    /// base vault balance, raw
+    pub base_reserve: u64,
+    /// quote vault balance, raw
+    pub quote_reserve: u64,
+    /// price of the base token in quote token implied by the vault balances, decimals applied
+    pub price: f64,
+}
+
+/// price points of a pool, oldest first
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceHistory {
+    pub points: Vec<PricePoint>,
+    /// the rpc budget ran out before `limit` points or the end of the history were reached
+    pub budget_exhausted: bool,
+}
+
+impl Raydium {
+    /// rebuild up to `limit` price points of a v4 or CPMM pool, newest transactions first,
+    /// from the post balances of its vaults. paging stops at `until_signature` and at most
+    /// `rpc_budget` rpc requests are sent, counting the pool fetch. failed and vote transactions
+    /// are skipped. v4 prices include the pnl owed to the protocol, which history does not record
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let history = raydium
+    ///     .get_price_history_v4("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2", 100, None, 200)
+    ///     .await?;
+    /// for point in history.points {
+    ///     println!("{:?} {}", point.block_time, point.price);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_price_history_v4(
+        &self,
+        pool: &str,
+        limit: usize,
+        until_signature: Option<Signature>,
+        rpc_budget: usize,
+    ) -> Result<PriceHistory, RaydiumError> {
+        let address = parse_address(pool)?;
+        let pool = self.get_pool(pool).await?;
+        if !matches!(pool.kind(), PoolKind::V4 | PoolKind::Cpmm) {
+            return Err(RaydiumError::Unsupported(format!(
+                "price history of {:?} pools",
+                pool.kind()
+            )));
+        }
+        let mut calls = 1;
+        let mut points = Vec::new();
+        let mut budget_exhausted = false;
+        let mut before = None;
+        let page_size = limit.clamp(1, MAX_SIGNATURES_PAGE);
+        'paging: while points.len() < limit {
+            if calls >= rpc_budget {
+                budget_exhausted = true;
+                break;
+            }
+            let page = self
+                .with_rpc(
+                    RpcCall::GetSignaturesForAddress,
+                    None,
+                    |client| async move {
+                        let config = GetConfirmedSignaturesForAddress2Config {
+                            before,
+                            until: until_signature,
+                            limit: Some(page_size),
+                            commitment: Some(CommitmentConfig::confirmed()),
+                        };
+                        client
+                            .get_signatures_for_address_with_config(&address, config)
+                            .await
+                            .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))
+                    },
+                )
+                .await?;
+            calls += 1;
+            let page_len = page.len();
+            for status in page {
+                let signature = Signature::from_str(&status.signature)
+                    .map_err(|e| RaydiumError::Parse(format!("signature: {}", e)))?;
+                before = Some(signature);
+                if status.err.is_some() {
+                    continue;
+                }
+                if points.len() >= limit {
+                    break 'paging;
+                }
+                if calls >= rpc_budget {
+                    budget_exhausted = true;
+                    break 'paging;
+                }
+                let transaction = self.get_transaction(&signature).await?;
+                calls += 1;
+                if let Some(point) = price_point(signature, &transaction, &pool)? {
+                    points.push(point);
+                }
+            }
+            if page_len < page_size {
+                break;
+            }
+        }
+        points.reverse();
+        Ok(PriceHistory {
+            points,
+            budget_exhausted,
+        })
+    }
+
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, RaydiumError> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        self.with_rpc(RpcCall::GetTransaction, None, |client| async move {
+            client
+                .get_transaction_with_config(signature, config)
+                .await
+                .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))
+        })
+        .await
+    }
+}
+
+/// the vault balances of `pool` after a transaction, `None` for failed or vote transactions
+/// and transactions leaving a vault untouched
+pub fn price_point(
+    signature: Signature,
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+    pool: &PoolData,
+) -> Result<Option<PricePoint>, RaydiumError> {
+    let Some(meta) = &transaction.transaction.meta else {
+        return Ok(None);
+    };
+    if meta.err.is_some() {
+        return Ok(None);
+    }
+    let decoded = transaction
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| RaydiumError::Parse("undecodable transaction".to_string()))?;
+    let mut keys: Vec<Pubkey> = decoded.message.static_account_keys().to_vec();
+    if keys.contains(&VOTE_PROGRAM_ID) {
+        return Ok(None);
+    }
+    if let Some(loaded) = Option::<&UiLoadedAddresses>::from(meta.loaded_addresses.as_ref()) {
+        for address in loaded.writable.iter().chain(loaded.readonly.iter()) {
+            keys.push(parse_address(address)?);
+        }
+    }
+    let Some(balances) =
+        Option::<&Vec<UiTransactionTokenBalance>>::from(meta.post_token_balances.as_ref())
+    else {
+        return Ok(None);
+    };
+    let balance_of = |vault: Pubkey| {
+        balances
+            .iter()
+            .find(|b| keys.get(b.account_index as usize) == Some(&vault))
+            .and_then(|b| b.ui_token_amount.amount.parse::<u64>().ok())
+    };
+    let (Some(base_reserve), Some(quote_reserve)) = (
+        balance_of(pool.base_vault()),
+        balance_of(pool.quote_vault()),
+    ) else {
+        return Ok(None);
+    };
+    if base_reserve == 0 {
+        return Ok(None);
+    }
+    let decimals = 10f64.powi(pool.base_decimals() as i32 - pool.quote_decimals() as i32);
+    Ok(Some(PricePoint {
+        signature,
+        slot: transaction.slot,
+        block_time: transaction.block_time,
+        base_reserve,
+        quote_reserve,
+        price: quote_reserve as f64 / base_reserve as f64 * decimals,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_sdk::{hash::Hash, instruction::Instruction, transaction::Transaction};
+
+    use super::*;
+    use crate::{
+        liquidity::cpmm::RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE,
+        programs::RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID,
+        test_utils::{account_info_response, mock_raydium, transaction_response},
+    };
+
+    /// recorded shape of a `getTransaction` response touching both vaults
+    fn transaction_fixture(
+        slot: u64,
+        keys: &[Pubkey],
+        balances: [u64; 2],
+        vote: bool,
+    ) -> serde_json::Value {
+        let mut accounts: Vec<_> = keys
+            .iter()
+            .map(|key| solana_sdk::instruction::AccountMeta::new(*key, false))
+            .collect();
+        accounts[0].is_signer = true;
+        let program = if vote {
+            VOTE_PROGRAM_ID
+        } else {
+            RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID
+        };
+        let instruction = Instruction::new_with_bytes(program, &[], accounts[1..].to_vec());
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&keys[0]));
+        transaction.message.recent_blockhash = Hash::default();
+        let index = |key: &Pubkey| {
+            transaction
+                .message
+                .account_keys
+                .iter()
+                .position(|k| k == key)
+                .unwrap()
+        };
+        let token_balance = |vault: &Pubkey, amount: u64| {
+            serde_json::json!({
+                "accountIndex": index(vault),
+                "mint": Pubkey::new_unique().to_string(),
+                "uiTokenAmount": {
+                    "amount": amount.to_string(),
+                    "decimals": 6,
+                    "uiAmount": null,
+                    "uiAmountString": "0",
+                },
+            })
+        };
+        let meta = serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "preTokenBalances": [],
+            "postTokenBalances": [
+                token_balance(&keys[1], balances[0]),
+                token_balance(&keys[2], balances[1]),
+            ],
+        });
+        transaction_response(slot, &transaction, meta)
+    }
+
+    #[tokio::test]
+    async fn test_get_price_history() -> Result<(), Box<dyn std::error::Error>> {
+        let (base_vault, quote_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // vaults after the discriminator, config and creator, decimals after the ten keys
+        let mut pool = vec![0u8; RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE];
+        pool[72..104].copy_from_slice(base_vault.as_ref());
+        pool[104..136].copy_from_slice(quote_vault.as_ref());
+        pool[331] = 9;
+        pool[332] = 6;
+        let keys = [Pubkey::new_unique(), base_vault, quote_vault];
+        let signatures: Vec<Signature> = (0..4).map(|_| Signature::new_unique()).collect();
+        let status = |signature: &Signature, slot: u64, failed: bool| {
+            serde_json::json!({
+                "signature": signature.to_string(),
+                "slot": slot,
+                "err": if failed { serde_json::json!({ "InstructionError": [0, "InvalidArgument"] }) } else { serde_json::Value::Null },
+                "memo": null,
+                "blockTime": null,
+                "confirmationStatus": "confirmed",
+            })
+        };
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            account_info_response(&RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID, pool),
+        );
+        // newest first: a swap, a failed transaction which is not fetched, a vote, a swap
+        mocks.insert(
+            RpcRequest::GetSignaturesForAddress,
+            serde_json::json!([
+                status(&signatures[0], 13, false),
+                status(&signatures[1], 12, true),
+                status(&signatures[2], 11, false),
+                status(&signatures[3], 10, false),
+            ]),
+        );
+        mocks.insert(
+            RpcRequest::GetTransaction,
+            transaction_fixture(13, &keys, [2_000_000_000, 300_000_000], false),
+        );
+        mocks.insert(
+            RpcRequest::GetTransaction,
+            transaction_fixture(11, &keys, [1, 1], true),
+        );
+        mocks.insert(
+            RpcRequest::GetTransaction,
+            transaction_fixture(10, &keys, [1_000_000_000, 100_000_000], false),
+        );
+        let raydium = mock_raydium(mocks);
+        let address = Pubkey::new_unique().to_string();
+        let history = raydium.get_price_history_v4(&address, 10, None, 10).await?;
+        assert!(!history.budget_exhausted);
+        let prices: Vec<_> = history
+            .points
+            .iter()
+            .map(|p| (p.slot, p.block_time, p.price))
+            .collect();
+        assert_eq!(
+            prices,
+            [
+                (10, Some(1_700_000_010), 100.0),
+                (13, Some(1_700_000_013), 150.0)
+            ]
+        );
+        assert_eq!(history.points[1].signature, signatures[0]);
+        Ok(())
+    }
+}
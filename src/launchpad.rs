@@ -1,8 +1,23 @@
+pub mod instructions;
+
 use bytemuck::{Pod, Zeroable};
 use solana_sdk::pubkey::Pubkey;
-use std::convert::TryInto;
+use std::{convert::TryInto, fmt};
+
+use crate::{
+    programs::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID},
+    reader::{group_digits, write_fields},
+    trace::trace_warn,
+    typs::Lenient,
+};
 
 pub const LAUNCHPAD_POOL_STATE_DATA_SIZE: usize = 429;
+/// offset of the curve type (u8) in the launchpad global config account
+pub const LAUNCHPAD_GLOBAL_CONFIG_CURVE_TYPE_OFFSET: usize = 16;
+/// offset of the trade fee rate (u64) in the launchpad global config account
+pub const LAUNCHPAD_GLOBAL_CONFIG_TRADE_FEE_RATE_OFFSET: usize = 27;
+/// denominator of the launchpad fee rates
+pub const LAUNCHPAD_FEE_RATE_DENOMINATOR: u64 = 1_000_000;
 const DISCRIMINATOR_LEN: usize = 8;
 
 unsafe impl Pod for LaunchpadPool {}
@@ -46,7 +61,7 @@ pub struct LaunchpadPool {
     pub padding: [u8; 59],           // 362-420: Padding
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct LaunchpadPoolData {
     pub epoch: u64,
     pub auth_bump: u8,
@@ -76,38 +91,52 @@ pub struct LaunchpadPoolData {
     pub amm_creator_fee_on: AmmCreatorFeeOn,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum PoolStatus {
+    #[default]
     Fund,
     Migrate,
     Trade,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum MigrateType {
+    #[default]
     AMM,
     CPSWAP,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TokenProgramFlagBits {
     pub base_token_program: TokenProgramFlag,
     pub quote_token_program: TokenProgramFlag,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum TokenProgramFlag {
+    #[default]
     SPLTokenProgram,
     TokenProgram2022,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl TokenProgramFlag {
+    /// token program owning the mint
+    pub fn program_id(&self) -> Pubkey {
+        match self {
+            TokenProgramFlag::SPLTokenProgram => TOKEN_PROGRAM_ID,
+            TokenProgramFlag::TokenProgram2022 => TOKEN_2022_PROGRAM_ID,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum AmmCreatorFeeOn {
+    #[default]
     QuoteToken,
     BothToken,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct VestingSchedule {
     pub total_locked_amount: u64,
     pub cliff_period: u64,
@@ -117,8 +146,23 @@ pub struct VestingSchedule {
 }
 
 impl LaunchpadPool {
+    /// parse the known prefix of account data at least as long as the layout, see
+    /// `RaydiumLiquidityPoolV4::parse_lenient`
+    pub fn parse_lenient(data: &[u8]) -> Result<Lenient<LaunchpadPoolData>, String> {
+        Lenient::parse(
+            data,
+            LAUNCHPAD_POOL_STATE_DATA_SIZE,
+            Self::get_liquidity_pool_info,
+        )
+    }
+
     pub fn get_liquidity_pool_info(data: &[u8]) -> Result<LaunchpadPoolData, String> {
         if data.len() != LAUNCHPAD_POOL_STATE_DATA_SIZE {
+            trace_warn!(
+                expected = LAUNCHPAD_POOL_STATE_DATA_SIZE,
+                actual = data.len(),
+                "Launchpad pool data size mismatch"
+            );
             return Err(format!(
                 "Launchpad pool data size mismatch. Expected {}, got {}",
                 LAUNCHPAD_POOL_STATE_DATA_SIZE,
@@ -281,6 +325,59 @@ impl LaunchpadPool {
     }
 }
 
+impl fmt::Display for LaunchpadPoolData {
+    /// one `field: value` line per field, keys in base58
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "raydium launchpad pool")?;
+        write_fields!(f, self, epoch, auth_bump);
+        write!(f, "\nstatus: {:?}", self.status)?;
+        write_fields!(f, self, base_decimals, quote_decimals);
+        write!(f, "\nmigrate_type: {:?}", self.migrate_type)?;
+        write_fields!(
+            f,
+            self,
+            grouped: supply,
+            total_base_sell,
+            virtual_base,
+            virtual_quote,
+            real_base,
+            real_quote,
+            total_quote_fund_raising,
+            quote_protocol_fee,
+            platform_fee,
+            migrate_fee,
+        );
+        let vesting = &self.vesting_schedule;
+        write!(
+            f,
+            "\nvesting_schedule: locked {} cliff {} unlock {} start {} allocated {}",
+            group_digits(vesting.total_locked_amount as u128),
+            vesting.cliff_period,
+            vesting.unlock_period,
+            vesting.start_time,
+            group_digits(vesting.allocated_share_amount as u128),
+        )?;
+        write_fields!(
+            f,
+            self,
+            global_config,
+            platform_config,
+            base_mint,
+            quote_mint,
+            base_vault,
+            quote_vault,
+            creator,
+        );
+        write!(
+            f,
+            "\ntoken_programs: base {} quote {}",
+            self.token_program_flag.base_token_program.program_id(),
+            self.token_program_flag.quote_token_program.program_id(),
+        )?;
+        write!(f, "\namm_creator_fee_on: {:?}", self.amm_creator_fee_on)
+    }
+}
+
 impl LaunchpadPoolData {
     pub fn get_price(&self) -> f64 {
         if self.virtual_base == 0 {
@@ -341,7 +438,7 @@ impl LaunchpadPoolData {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "rpc"))]
 mod tests {
     use solana_network_client::SolanaClient;
 
@@ -0,0 +1,132 @@
+//! launchpad instruction builders
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::launchpad::LaunchpadPoolData;
+
+/// seed of the launchpad vault authority PDA
+pub const LAUNCHPAD_AUTHORITY_SEED: &[u8] = b"vault_auth_seed";
+/// seed of the anchor event authority PDA
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+/// anchor discriminator of `buy_exact_in`
+pub const BUY_EXACT_IN_DISCRIMINATOR: [u8; 8] = [250, 234, 13, 123, 213, 156, 19, 236];
+/// anchor discriminator of `sell_exact_in`
+pub const SELL_EXACT_IN_DISCRIMINATOR: [u8; 8] = [149, 39, 222, 155, 211, 124, 152, 26];
+
+/// the vault authority of a launchpad program
+pub fn launchpad_authority(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[LAUNCHPAD_AUTHORITY_SEED], program_id).0
+}
+
+/// buy base with exactly `amount_in` quote when `buy`, otherwise sell exactly `amount_in` base
+/// for quote, failing when the output is below `minimum_amount_out`. no platform fee is shared
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{launchpad::{LaunchpadPoolData, instructions::swap_exact_in}, programs::RAYDIUM_LAUNCHPAD_PROGRAM_ID};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(pool_id: Pubkey, pool: LaunchpadPoolData, owner: Pubkey, user_base: Pubkey, user_quote: Pubkey) {
+/// let instruction = swap_exact_in(
+///     &RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+///     &pool_id,
+///     &pool,
+///     &owner,
+///     &user_base,
+///     &user_quote,
+///     true,
+///     1_000_000_000,
+///     990_000,
+/// );
+/// # }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn swap_exact_in(
+    program_id: &Pubkey,
+    pool_id: &Pubkey,
+    pool: &LaunchpadPoolData,
+    payer: &Pubkey,
+    user_base: &Pubkey,
+    user_quote: &Pubkey,
+    buy: bool,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Instruction {
+    let mut data = if buy {
+        BUY_EXACT_IN_DISCRIMINATOR.to_vec()
+    } else {
+        SELL_EXACT_IN_DISCRIMINATOR.to_vec()
+    };
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+    // share fee rate
+    data.extend_from_slice(&0u64.to_le_bytes());
+    let event_authority = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], program_id).0;
+    Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*payer, true),
+            AccountMeta::new_readonly(launchpad_authority(program_id), false),
+            AccountMeta::new_readonly(pool.global_config, false),
+            AccountMeta::new_readonly(pool.platform_config, false),
+            AccountMeta::new(*pool_id, false),
+            AccountMeta::new(*user_base, false),
+            AccountMeta::new(*user_quote, false),
+            AccountMeta::new(pool.base_vault, false),
+            AccountMeta::new(pool.quote_vault, false),
+            AccountMeta::new_readonly(pool.base_mint, false),
+            AccountMeta::new_readonly(pool.quote_mint, false),
+            AccountMeta::new_readonly(
+                pool.token_program_flag.base_token_program.program_id(),
+                false,
+            ),
+            AccountMeta::new_readonly(
+                pool.token_program_flag.quote_token_program.program_id(),
+                false,
+            ),
+            AccountMeta::new_readonly(event_authority, false),
+            AccountMeta::new_readonly(*program_id, false),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::programs::{RAYDIUM_LAUNCHPAD_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+    #[test]
+    fn test_swap_exact_in() {
+        let pool = LaunchpadPoolData {
+            global_config: Pubkey::new_unique(),
+            platform_config: Pubkey::new_unique(),
+            base_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let program = RAYDIUM_LAUNCHPAD_PROGRAM_ID;
+        let (pool_id, payer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (base, quote) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let buy = swap_exact_in(&program, &pool_id, &pool, &payer, &base, &quote, true, 7, 5);
+        assert_eq!(&buy.data[..8], BUY_EXACT_IN_DISCRIMINATOR);
+        assert_eq!(buy.data[8..16], 7u64.to_le_bytes());
+        assert_eq!(buy.data[16..24], 5u64.to_le_bytes());
+        assert_eq!(buy.data.len(), 32);
+        assert_eq!(buy.accounts.len(), 15);
+        assert_eq!(
+            buy.accounts[1].pubkey.to_string(),
+            "WLHv2UAZm6z4KyaaELi5pjdbJh6RESMva1Rnn8pJVVh"
+        );
+        assert_eq!(buy.accounts[11].pubkey, TOKEN_PROGRAM_ID);
+        assert_eq!(buy.accounts[14].pubkey, program);
+        let sell = swap_exact_in(
+            &program, &pool_id, &pool, &payer, &base, &quote, false, 7, 5,
+        );
+        assert_eq!(&sell.data[..8], SELL_EXACT_IN_DISCRIMINATOR);
+        assert_eq!(sell.accounts, buy.accounts);
+    }
+}
@@ -1,120 +1,740 @@
+#[cfg(feature = "rpc")]
+pub mod aggregate;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "rpc")]
+pub mod cache;
+#[cfg(feature = "rpc")]
+pub mod config;
+#[cfg(feature = "rpc")]
+pub mod decode;
+#[cfg(feature = "rpc")]
+pub mod discovery;
+#[cfg(feature = "rpc")]
+pub mod endpoints;
+pub mod error;
+#[cfg(feature = "rpc")]
+pub mod history;
 pub mod launchpad;
 pub mod liquidity;
+pub mod market;
+pub mod metadata;
+#[cfg(feature = "rpc")]
+pub mod metrics;
+pub mod mints;
+#[cfg(feature = "rpc")]
+pub mod monitor;
+pub mod pool_keys;
+pub mod programs;
+#[cfg(feature = "rpc")]
+pub mod quote;
+#[cfg(feature = "rpc")]
+pub mod rate_limit;
+pub mod reader;
+pub mod registry;
+#[cfg(feature = "rpc")]
+pub mod routing;
+#[cfg(feature = "rpc")]
+mod rpc;
+pub mod safety;
+#[cfg(feature = "rpc")]
+pub mod subscription;
+#[cfg(feature = "rpc")]
+pub mod swap;
+#[cfg(all(test, feature = "rpc"))]
+mod test_utils;
+pub mod token_accounts;
+mod trace;
+pub mod transaction;
 pub mod typs;
+pub mod volume;
+#[cfg(feature = "rpc")]
+pub mod watch;
+pub mod wsol;
 
+#[cfg(feature = "rpc")]
 use solana_network_client::SolanaClient;
+#[cfg(feature = "rpc")]
 use solana_sdk::pubkey::Pubkey;
 
-use std::{str::FromStr, sync::Arc};
+#[cfg(feature = "rpc")]
+use std::{
+    sync::{Arc, atomic::AtomicU64},
+    time::Duration,
+};
 
+#[cfg(feature = "rpc")]
 use crate::{
+    cache::{PoolCache, TtlCache},
+    config::RaydiumConfig,
+    endpoints::Endpoints,
+    error::RaydiumError,
     launchpad::{LaunchpadPool, LaunchpadPoolData},
     liquidity::{
-        clmm::{RaydiumLiquidityPoolCLMM, RaydiumLiquidityPoolCLMMData},
+        clmm::{
+            ClmmConfig, RaydiumLiquidityPoolCLMM, RaydiumLiquidityPoolCLMMData,
+            config::DEFAULT_CLMM_CONFIG_CACHE_TTL,
+        },
         cpmm::{RaydiumLiquidityPoolCPMM, RaydiumLiquidityPoolCPMMData},
+        stable::{DEFAULT_STABLE_AMP, RaydiumLiquidityPoolStable, RaydiumLiquidityPoolStableData},
         v4::{RaydiumLiquidityPoolData, RaydiumLiquidityPoolV4},
     },
+    metrics::RpcCall,
+    mints::{DEFAULT_MINT_CACHE_TTL, MintInfo},
+    monitor::MAX_MULTIPLE_ACCOUNTS,
+    rate_limit::RateLimiter,
+    routing::unix_now,
+    typs::{Contextual, PoolData, PoolKind, PoolPrices},
 };
 
 /// raydium data structure
+#[cfg(feature = "rpc")]
+#[derive(Clone)]
 pub struct Raydium {
     pub solana_client: Arc<SolanaClient>,
+    pub config: RaydiumConfig,
+    /// pool accounts and prices cached when `RaydiumConfig::cache_ttl` is set
+    cache: Option<Arc<PoolCache>>,
+    /// mint infos, cached for `RaydiumConfig::mint_cache_ttl`
+    mint_cache: Arc<TtlCache<MintInfo>>,
+    /// amm configs of the CLMM program keyed by the program, cached for
+    /// `RaydiumConfig::clmm_config_cache_ttl`
+    clmm_config_cache: Arc<TtlCache<Vec<(Pubkey, ClmmConfig)>>>,
+    /// rpc budget when `RaydiumConfig::rate_limit` is set
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// rpc endpoints tried in order, `solana_client` first
+    endpoints: Arc<Endpoints>,
+    /// number of websocket subscriptions re-established after a disconnect
+    reconnects: Arc<AtomicU64>,
 }
 
+#[cfg(feature = "rpc")]
 impl Raydium {
     /// crreate raydium
     /// Example
-    /// ```rust
-    /// let sol = Solana::new(solana_network_sdk::types::Mode::MAIN);
+    /// ```rust,no_run
+    /// # use raydium_sdk::Raydium;
+    /// # use solana_network_client::{Mode, SolanaClient};
+    /// # use std::sync::Arc;
+    /// let sol = SolanaClient::new(Mode::MAIN).unwrap();
     /// let raydium = Raydium::new(Arc::new(sol));
     /// ```
     pub fn new(solana_client: Arc<SolanaClient>) -> Self {
+        Self::new_with_config(solana_client, RaydiumConfig::default())
+    }
+
+    /// create raydium with a custom configuration
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, config::RaydiumConfig};
+    /// # use solana_network_client::{Mode, SolanaClient};
+    /// # use std::{sync::Arc, time::Duration};
+    /// let sol = SolanaClient::new(Mode::MAIN).unwrap();
+    /// let config = RaydiumConfig::new().with_timeout(Duration::from_secs(5));
+    /// let raydium = Raydium::new_with_config(Arc::new(sol), config);
+    /// ```
+    pub fn new_with_config(solana_client: Arc<SolanaClient>, config: RaydiumConfig) -> Self {
+        Self::new_with_endpoints(vec![solana_client], config)
+    }
+
+    /// create raydium failing over between several rpc endpoints, in order of preference.
+    /// an endpoint failing with a retryable error is skipped for `RaydiumConfig::endpoint_cooldown`
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, config::RaydiumConfig};
+    /// # use solana_network_client::{Mode, SolanaClient};
+    /// # use std::sync::Arc;
+    /// let primary = SolanaClient::new(Mode::MAIN).unwrap();
+    /// let backup = SolanaClient::new(Mode::MAIN).unwrap();
+    /// let raydium = Raydium::new_with_endpoints(
+    ///     vec![Arc::new(primary), Arc::new(backup)],
+    ///     RaydiumConfig::new(),
+    /// );
+    /// ```
+    pub fn new_with_endpoints(clients: Vec<Arc<SolanaClient>>, config: RaydiumConfig) -> Self {
+        assert!(!clients.is_empty(), "at least one rpc endpoint is required");
+        let endpoints = Arc::new(Endpoints::new(&clients));
+        let solana_client = clients[0].clone();
+        let cache = config
+            .cache_ttl
+            .map(|ttl| Arc::new(PoolCache::new(ttl, config.metrics.clone())));
+        let mint_cache = Arc::new(TtlCache::new(
+            config.mint_cache_ttl.unwrap_or(DEFAULT_MINT_CACHE_TTL),
+            config.metrics.clone(),
+        ));
+        let clmm_config_cache = Arc::new(TtlCache::new(
+            config
+                .clmm_config_cache_ttl
+                .unwrap_or(DEFAULT_CLMM_CONFIG_CACHE_TTL),
+            config.metrics.clone(),
+        ));
+        let rate_limiter = config
+            .rate_limit
+            .map(|limit| Arc::new(RateLimiter::new(limit)));
         Self {
-            solana_client: solana_client,
+            solana_client,
+            config,
+            cache,
+            mint_cache,
+            clmm_config_cache,
+            rate_limiter,
+            endpoints,
+            reconnects: Arc::new(AtomicU64::new(0)),
         }
     }
     /// get v4 raydium liquidity pool
     /// Example
-    /// ```rust
-    /// let sol = Solana::new(solana_network_sdk::types::Mode::MAIN);
+    /// ```rust,no_run
+    /// # use raydium_sdk::Raydium;
+    /// # use solana_network_client::{Mode, SolanaClient};
+    /// # use std::sync::Arc;
+    /// # async fn run() {
+    /// let sol = SolanaClient::new(Mode::MAIN).unwrap();
     /// let raydium = Raydium::new(Arc::new(sol));
     /// // 58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2 SOL-USDC pool
     /// let pool_data = raydium.get_liquidity_pool_v4("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2").await;
+    /// # }
     /// ```
     pub async fn get_liquidity_pool_v4(
         &self,
         address: &str,
-    ) -> Result<RaydiumLiquidityPoolData, String> {
-        let v = self
-            .solana_client
-            .client_arc()
-            .get_account_data(
-                &Pubkey::from_str(address)
-                    .map_err(|e| format!("{:?}", e))
-                    .unwrap(),
-            )
-            .await
-            .map_err(|e| format!("{:?}", e))?;
-        let pool =
-            RaydiumLiquidityPoolV4::get_liquidity_pool_info(&v).map_err(|e| format!("{:?}", e))?;
-        Ok(pool)
+    ) -> Result<RaydiumLiquidityPoolData, RaydiumError> {
+        Ok(self
+            .get_liquidity_pool_v4_with_context(address)
+            .await?
+            .value)
     }
 
     pub async fn get_liquidity_pool_cpmm(
         &self,
         address: &str,
-    ) -> Result<RaydiumLiquidityPoolCPMMData, String> {
-        let v = self
-            .solana_client
-            .client_arc()
-            .get_account_data(
-                &Pubkey::from_str(address)
-                    .map_err(|e| format!("{:?}", e))
-                    .unwrap(),
-            )
-            .await
-            .map_err(|e| format!("{:?}", e))?;
-        let pool = RaydiumLiquidityPoolCPMM::get_liquidity_pool_info(&v)
-            .map_err(|e| format!("{:?}", e))?;
-        Ok(pool)
+    ) -> Result<RaydiumLiquidityPoolCPMMData, RaydiumError> {
+        Ok(self
+            .get_liquidity_pool_cpmm_with_context(address)
+            .await?
+            .value)
     }
 
     pub async fn get_liquidity_pool_clmm(
         &self,
         address: &str,
-    ) -> Result<RaydiumLiquidityPoolCLMMData, String> {
-        let v = self
-            .solana_client
-            .client_arc()
-            .get_account_data(
-                &Pubkey::from_str(address)
-                    .map_err(|e| format!("{:?}", e))
-                    .unwrap(),
-            )
-            .await
-            .map_err(|e| format!("{:?}", e))?;
-        let pool = RaydiumLiquidityPoolCLMM::get_liquidity_pool_info(&v)
-            .map_err(|e| format!("{:?}", e))?;
-        Ok(pool)
+    ) -> Result<RaydiumLiquidityPoolCLMMData, RaydiumError> {
+        Ok(self
+            .get_liquidity_pool_clmm_with_context(address)
+            .await?
+            .value)
     }
 
     pub async fn get_liquidity_pool_launchpad(
         &self,
         address: &str,
-    ) -> Result<LaunchpadPoolData, String> {
+    ) -> Result<LaunchpadPoolData, RaydiumError> {
+        Ok(self
+            .get_liquidity_pool_launchpad_with_context(address)
+            .await?
+            .value)
+    }
+
+    /// get a raydium stable swap pool, such as USDC-USDT
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium, pool_address: &str) -> Result<(), RaydiumError> {
+    /// let pool = raydium.get_liquidity_pool_stable(pool_address).await?;
+    /// println!("curve in {}", pool.model_data_account);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_liquidity_pool_stable(
+        &self,
+        address: &str,
+    ) -> Result<RaydiumLiquidityPoolStableData, RaydiumError> {
+        Ok(self
+            .get_liquidity_pool_stable_with_context(address)
+            .await?
+            .value)
+    }
+
+    /// get a raydium pool of any type, detecting the type from the account data
+    pub async fn get_pool(&self, address: &str) -> Result<PoolData, RaydiumError> {
+        Ok(self.get_pool_with_context(address).await?.value)
+    }
+
+    /// get a raydium pool of any type together with the slot it was observed at
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%address, kind = tracing::field::Empty))
+    )]
+    pub async fn get_pool_with_context(
+        &self,
+        address: &str,
+    ) -> Result<Contextual<PoolData>, RaydiumError> {
+        let v = self
+            .get_pool_account_data_with_context(address, None, None)
+            .await?;
+        let pool = self.parse_with_metrics(PoolKind::from_data_len(v.value.len()), || {
+            PoolData::parse_detect(&v.value).map_err(RaydiumError::Parse)
+        })?;
+        trace::trace_kind!(pool.kind());
+        Ok(Contextual::new(v.slot, pool))
+    }
+
+    /// get v4 raydium liquidity pool, overriding the configured timeout
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%address, kind = ?PoolKind::V4))
+    )]
+    pub async fn get_liquidity_pool_v4_timeout(
+        &self,
+        address: &str,
+        timeout: Duration,
+    ) -> Result<RaydiumLiquidityPoolData, RaydiumError> {
+        let v = self
+            .get_pool_account_data_with_context(address, Some(PoolKind::V4), Some(timeout))
+            .await?;
+        self.parse_with_metrics(Some(PoolKind::V4), || {
+            RaydiumLiquidityPoolV4::get_liquidity_pool_info(&v.value).map_err(RaydiumError::Parse)
+        })
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%address, kind = ?PoolKind::Cpmm))
+    )]
+    pub async fn get_liquidity_pool_cpmm_timeout(
+        &self,
+        address: &str,
+        timeout: Duration,
+    ) -> Result<RaydiumLiquidityPoolCPMMData, RaydiumError> {
+        let v = self
+            .get_pool_account_data_with_context(address, Some(PoolKind::Cpmm), Some(timeout))
+            .await?;
+        self.parse_with_metrics(Some(PoolKind::Cpmm), || {
+            RaydiumLiquidityPoolCPMM::get_liquidity_pool_info(&v.value).map_err(RaydiumError::Parse)
+        })
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%address, kind = ?PoolKind::Clmm))
+    )]
+    pub async fn get_liquidity_pool_clmm_timeout(
+        &self,
+        address: &str,
+        timeout: Duration,
+    ) -> Result<RaydiumLiquidityPoolCLMMData, RaydiumError> {
+        let v = self
+            .get_pool_account_data_with_context(address, Some(PoolKind::Clmm), Some(timeout))
+            .await?;
+        self.parse_with_metrics(Some(PoolKind::Clmm), || {
+            RaydiumLiquidityPoolCLMM::get_liquidity_pool_info(&v.value)
+        })
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%address, kind = ?PoolKind::Launchpad))
+    )]
+    pub async fn get_liquidity_pool_launchpad_timeout(
+        &self,
+        address: &str,
+        timeout: Duration,
+    ) -> Result<LaunchpadPoolData, RaydiumError> {
+        let v = self
+            .get_pool_account_data_with_context(address, Some(PoolKind::Launchpad), Some(timeout))
+            .await?;
+        self.parse_with_metrics(Some(PoolKind::Launchpad), || {
+            LaunchpadPool::get_liquidity_pool_info(&v.value).map_err(RaydiumError::Parse)
+        })
+    }
+
+    /// get v4 raydium liquidity pool together with the slot it was observed at
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let pool = raydium
+    ///     .get_liquidity_pool_v4_with_context("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2")
+    ///     .await?;
+    /// println!("slot {} base vault {}", pool.slot, pool.value.base_vault);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%address, kind = ?PoolKind::V4))
+    )]
+    pub async fn get_liquidity_pool_v4_with_context(
+        &self,
+        address: &str,
+    ) -> Result<Contextual<RaydiumLiquidityPoolData>, RaydiumError> {
         let v = self
-            .solana_client
-            .client_arc()
-            .get_account_data(
-                &Pubkey::from_str(address)
-                    .map_err(|e| format!("{:?}", e))
-                    .unwrap(),
-            )
-            .await
-            .map_err(|e| format!("{:?}", e))?;
-        let pool = LaunchpadPool::get_liquidity_pool_info(&v).map_err(|e| format!("{:?}", e))?;
+            .get_pool_account_data_with_context(address, Some(PoolKind::V4), None)
+            .await?;
+        let pool = self.parse_with_metrics(Some(PoolKind::V4), || {
+            RaydiumLiquidityPoolV4::get_liquidity_pool_info(&v.value).map_err(RaydiumError::Parse)
+        })?;
+        Ok(Contextual::new(v.slot, pool))
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%address, kind = ?PoolKind::Cpmm))
+    )]
+    pub async fn get_liquidity_pool_cpmm_with_context(
+        &self,
+        address: &str,
+    ) -> Result<Contextual<RaydiumLiquidityPoolCPMMData>, RaydiumError> {
+        let v = self
+            .get_pool_account_data_with_context(address, Some(PoolKind::Cpmm), None)
+            .await?;
+        let pool = self.parse_with_metrics(Some(PoolKind::Cpmm), || {
+            RaydiumLiquidityPoolCPMM::get_liquidity_pool_info(&v.value).map_err(RaydiumError::Parse)
+        })?;
+        Ok(Contextual::new(v.slot, pool))
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%address, kind = ?PoolKind::Clmm))
+    )]
+    pub async fn get_liquidity_pool_clmm_with_context(
+        &self,
+        address: &str,
+    ) -> Result<Contextual<RaydiumLiquidityPoolCLMMData>, RaydiumError> {
+        let v = self
+            .get_pool_account_data_with_context(address, Some(PoolKind::Clmm), None)
+            .await?;
+        let pool = self.parse_with_metrics(Some(PoolKind::Clmm), || {
+            RaydiumLiquidityPoolCLMM::get_liquidity_pool_info(&v.value)
+        })?;
+        Ok(Contextual::new(v.slot, pool))
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%address, kind = ?PoolKind::Launchpad))
+    )]
+    pub async fn get_liquidity_pool_launchpad_with_context(
+        &self,
+        address: &str,
+    ) -> Result<Contextual<LaunchpadPoolData>, RaydiumError> {
+        let v = self
+            .get_pool_account_data_with_context(address, Some(PoolKind::Launchpad), None)
+            .await?;
+        let pool = self.parse_with_metrics(Some(PoolKind::Launchpad), || {
+            LaunchpadPool::get_liquidity_pool_info(&v.value).map_err(RaydiumError::Parse)
+        })?;
+        Ok(Contextual::new(v.slot, pool))
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%address, kind = ?PoolKind::Stable))
+    )]
+    pub async fn get_liquidity_pool_stable_with_context(
+        &self,
+        address: &str,
+    ) -> Result<Contextual<RaydiumLiquidityPoolStableData>, RaydiumError> {
+        let v = self
+            .get_pool_account_data_with_context(address, Some(PoolKind::Stable), None)
+            .await?;
+        let pool = self.parse_with_metrics(Some(PoolKind::Stable), || {
+            RaydiumLiquidityPoolStable::get_liquidity_pool_info(&v.value)
+                .map_err(RaydiumError::Parse)
+        })?;
+        Ok(Contextual::new(v.slot, pool))
+    }
+
+    /// get the raw balances of both vaults of a pool from a single RPC call,
+    /// so both balances are observed at the same slot
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(kind = ?pool.kind()))
+    )]
+    pub async fn get_vault_amounts_with_context(
+        &self,
+        pool: &PoolData,
+    ) -> Result<Contextual<(u64, u64)>, RaydiumError> {
+        let amounts = self
+            .get_token_account_amounts_with_context(&[pool.base_vault(), pool.quote_vault()])
+            .await?;
+        Ok(amounts.map(|v| (v[0], v[1])))
+    }
+
+    /// get the current price of a v4 pool under the configured timeout
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(kind = ?PoolKind::V4))
+    )]
+    pub async fn get_price_v4(&self, pool: &RaydiumLiquidityPoolData) -> Result<f64, RaydiumError> {
+        self.with_rpc(RpcCall::GetMultipleAccounts, None, |client| async move {
+            pool.get_price(client).await
+        })
+        .await
+    }
+
+    /// wait until a v4 pool reaches its open time, swaps sent before it fail.
+    /// the pool is fetched again once the time is reached in case the open time was amended,
+    /// and the refreshed state is returned
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium, pool_address: &str) -> Result<(), RaydiumError> {
+    /// let pool = raydium.wait_for_pool_open(pool_address).await?;
+    /// println!("open since {}", pool.pool_open_time);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_pool_open(
+        &self,
+        pool_address: &str,
+    ) -> Result<RaydiumLiquidityPoolData, RaydiumError> {
+        let mut pool = self.get_liquidity_pool_v4(pool_address).await?;
+        if let Some(wait) = pool.time_until_open(unix_now()) {
+            tokio::time::sleep(wait).await;
+            pool = self.get_liquidity_pool_v4(pool_address).await?;
+            if let Some(wait) = pool.time_until_open(unix_now()) {
+                tokio::time::sleep(wait).await;
+            }
+        }
         Ok(pool)
     }
+
+    /// get the current price of a pool of any type under the configured timeout, the base token
+    /// in quote token with decimals applied as `PoolData::spot_price_with_amp` computes it.
+    /// v4 and CPMM prices come from the vault balances, stable prices from the vault and open
+    /// orders balances at `RaydiumConfig::stable_amp` like their quotes, CLMM and launchpad
+    /// prices from the pool state
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(kind = ?pool.kind()))
+    )]
+    pub async fn get_pool_price(&self, pool: &PoolData) -> Result<f64, RaydiumError> {
+        let (_, mut amounts) = self.get_spot_amounts(&[pool]).await?;
+        let (base, quote) = amounts.remove(0)?;
+        pool.spot_price_with_amp(
+            base,
+            quote,
+            self.config.stable_amp.unwrap_or(DEFAULT_STABLE_AMP),
+        )
+        .ok_or(RaydiumError::InsufficientLiquidity)
+    }
+
+    /// get the current price of a pool of any type by address,
+    /// served from the cache when `RaydiumConfig::cache_ttl` is set
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%address))
+    )]
+    pub async fn get_pool_price_by_address(&self, address: &str) -> Result<f64, RaydiumError> {
+        let fetch = async {
+            let pool = self.get_pool(address).await?;
+            self.get_pool_price(&pool).await
+        };
+        match &self.cache {
+            Some(cache) => {
+                cache
+                    .prices
+                    .get_or_fetch(rpc::parse_address(address)?, fetch)
+                    .await
+            }
+            None => fetch.await,
+        }
+    }
+
+    /// get the current prices of many pools of any type in a handful of rpc calls:
+    /// the pool accounts are fetched in bulk, then the vaults of every v4 and CPMM pool in bulk.
+    /// a pool that is missing, fails to parse or has no liquidity gets an error of its own
+    /// without failing the others. the slot is the oldest one the prices were observed at.
+    /// each price is the one `get_pool_price` returns for that pool
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # use solana_sdk::pubkey::Pubkey;
+    /// # async fn run(raydium: Raydium, pools: Vec<Pubkey>) -> Result<(), RaydiumError> {
+    /// let prices = raydium.get_prices(&pools).await?;
+    /// for (address, price) in &prices.value {
+    ///     match price {
+    ///         Ok(price) => println!("{} {} at slot {}", address, price, prices.slot),
+    ///         Err(e) => println!("{} {}", address, e),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_prices(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Contextual<PoolPrices>, RaydiumError> {
+        let mut slot = None;
+        let mut pools = Vec::with_capacity(addresses.len());
+        for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let accounts = self.get_multiple_accounts_with_context(chunk).await?;
+            slot = Some(slot.map_or(accounts.slot, |slot: u64| slot.min(accounts.slot)));
+            for (address, account) in chunk.iter().zip(accounts.value) {
+                pools.push(self.parse_pool_account(address, None, account));
+            }
+        }
+        let parsed: Vec<&PoolData> = pools.iter().flatten().collect();
+        let (amounts_slot, amounts) = self.get_spot_amounts(&parsed).await?;
+        if let Some(amounts_slot) = amounts_slot {
+            slot = Some(slot.map_or(amounts_slot, |slot: u64| slot.min(amounts_slot)));
+        }
+        let stable_amp = self.config.stable_amp.unwrap_or(DEFAULT_STABLE_AMP);
+        let mut amounts = amounts.into_iter();
+        let prices = addresses
+            .iter()
+            .zip(pools)
+            .map(|(address, pool)| {
+                let price = pool.and_then(|pool| {
+                    let (base, quote) = amounts.next().unwrap_or(Ok((0, 0)))?;
+                    pool.spot_price_with_amp(base, quote, stable_amp)
+                        .ok_or(RaydiumError::InsufficientLiquidity)
+                });
+                (*address, price)
+            })
+            .collect();
+        Ok(Contextual::new(slot.unwrap_or_default(), prices))
+    }
+
     // get token price by address
     pub async fn get_token_price_by_address(&self) {}
 }
+
+#[cfg(all(test, feature = "rpc"))]
+mod tests {
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::{
+        config::RaydiumConfig,
+        error::RaydiumError,
+        launchpad::LAUNCHPAD_POOL_STATE_DATA_SIZE,
+        liquidity::stable::{self, RaydiumLiquidityPoolStableData},
+        liquidity::{
+            clmm::{RAYDIUM_CLMM_POOL_STATE_DISCRIMINATOR, RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE},
+            v4::RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE,
+        },
+        market::open_orders_data,
+        programs::{
+            RAYDIUM_LAUNCHPAD_PROGRAM_ID, RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+            RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+        },
+        test_utils::{
+            mock_raydium, mock_raydium_with_config, multiple_accounts_response, ui_account,
+        },
+        typs::PoolData,
+    };
+
+    #[tokio::test]
+    async fn test_get_prices() -> Result<(), Box<dyn std::error::Error>> {
+        let v4 = || {
+            Some(ui_account(
+                &RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+                vec![0u8; RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE],
+            ))
+        };
+        let mut launchpad = vec![0u8; LAUNCHPAD_POOL_STATE_DATA_SIZE];
+        launchpad[37..45].copy_from_slice(&100u64.to_le_bytes());
+        launchpad[45..53].copy_from_slice(&200u64.to_le_bytes());
+        let token_account = |amount: u64| {
+            let mut data = vec![0u8; 165];
+            data[64..72].copy_from_slice(&amount.to_le_bytes());
+            Some(ui_account(&Pubkey::new_unique(), data))
+        };
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![
+                v4(),
+                Some(ui_account(&RAYDIUM_LAUNCHPAD_PROGRAM_ID, launchpad)),
+                None,
+                v4(),
+            ]),
+        );
+        // the vaults of both v4 pools in one call, the second pool is empty
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![
+                token_account(100),
+                token_account(400),
+                token_account(0),
+                token_account(0),
+            ]),
+        );
+        let raydium = mock_raydium(mocks);
+        let addresses: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let prices = raydium.get_prices(&addresses).await?;
+        assert_eq!(prices.slot, 1);
+        let prices: Vec<Result<f64, RaydiumError>> =
+            prices.value.into_iter().map(|(_, price)| price).collect();
+        assert_eq!(
+            prices,
+            vec![
+                Ok(4.0),
+                Ok(2.0),
+                Err(RaydiumError::AccountNotFound(addresses[2])),
+                Err(RaydiumError::InsufficientLiquidity),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_prices_matches_get_pool_price() -> Result<(), Box<dyn std::error::Error>> {
+        // a CLMM pool of a 9 decimal token against a 6 decimal one at a raw price of 0.25
+        let mut data = vec![0u8; RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE];
+        data[..8].copy_from_slice(&RAYDIUM_CLMM_POOL_STATE_DISCRIMINATOR);
+        data[233..235].copy_from_slice(&[9, 6]);
+        data[253..269].copy_from_slice(&(1u128 << 63).to_le_bytes());
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![Some(ui_account(
+                &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+                data.clone(),
+            ))]),
+        );
+        let raydium = mock_raydium(mocks);
+        let prices = raydium.get_prices(&[Pubkey::new_unique()]).await?;
+        let pool = PoolData::parse_detect(&data)?;
+        let price = raydium.get_pool_price(&pool).await?;
+        assert_eq!(prices.value[0].1, Ok(price));
+        assert!((price - 250.0).abs() < 1e-9, "{}", price);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_pool_price_stable() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = PoolData::Stable(RaydiumLiquidityPoolStableData {
+            quote_need_take_pnl: 50_000,
+            ..Default::default()
+        });
+        let token_account = |amount: u64| {
+            let mut data = vec![0u8; 165];
+            data[64..72].copy_from_slice(&amount.to_le_bytes());
+            Some(ui_account(&Pubkey::new_unique(), data))
+        };
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![
+                token_account(1_500_000),
+                token_account(400_000),
+                Some(ui_account(
+                    &Pubkey::new_unique(),
+                    open_orders_data(0, 150_000),
+                )),
+            ]),
+        );
+        let config = RaydiumConfig::new().with_stable_amp(10);
+        let raydium = mock_raydium_with_config(mocks, config);
+        // the reserves of the quote, open orders in and pnl out, on the configured curve
+        let price = raydium.get_pool_price(&pool).await?;
+        assert_eq!(Some(price), stable::spot_price(1_500_000, 500_000, 10));
+        assert_ne!(Some(price), stable::spot_price(1_500_000, 350_000, 10));
+        Ok(())
+    }
+}
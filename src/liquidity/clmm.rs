@@ -1,17 +1,57 @@
-use bytemuck::{Pod, Zeroable};
+pub mod bitmap_extension;
+pub mod config;
+pub mod full_math;
+pub mod instructions;
+pub mod math;
+pub mod observation;
+pub mod pda;
+pub mod position;
+pub mod quote;
+pub mod tick_array;
+
+pub use config::ClmmConfig;
+pub use observation::{Observation, ObservationState};
+pub use position::{
+    PersonalPositionData, PersonalPositionState, ProtocolPositionData, ProtocolPositionState,
+    RewardOwed,
+};
+pub use quote::{ClmmSwapQuote, ClmmSwapQuoteAccounts, compute_swap, compute_swap_with_extension};
+pub use tick_array::{TickArrayData, TickArrayState, TickData};
+
+#[cfg(feature = "rpc")]
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+#[cfg(feature = "rpc")]
 use std::sync::Arc;
 
+use crate::{
+    error::RaydiumError,
+    liquidity::clmm::math::{MAX_TICK, MIN_TICK},
+    reader::{conver_balance, group_digits, write_fields},
+    trace::trace_warn,
+    typs::{Lenient, Price},
+};
+#[cfg(feature = "rpc")]
+use crate::{reader::r_u64, rpc::TOKEN_ACCOUNT_AMOUNT_OFFSET};
+
 /// CLMM liquidity pool data size
 pub const RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE: usize = 1544;
+/// anchor discriminator of the CLMM `PoolState` account
+pub const RAYDIUM_CLMM_POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+/// offset of the token 0 mint in the CLMM pool account
+pub const RAYDIUM_LIQUIDITY_POOL_CLMM_TOKEN_MINT_0_OFFSET: usize = 73;
+/// offset of the token 1 mint in the CLMM pool account
+pub const RAYDIUM_LIQUIDITY_POOL_CLMM_TOKEN_MINT_1_OFFSET: usize = 105;
+/// offset of the trade fee rate (u32) in the CLMM amm config account
+pub const RAYDIUM_CLMM_AMM_CONFIG_TRADE_FEE_RATE_OFFSET: usize = 47;
+/// denominator of the CLMM fee rates
+pub const RAYDIUM_CLMM_FEE_RATE_DENOMINATOR: u64 = 1_000_000;
 const DISCRIMINATOR_LEN: usize = 8;
 
-unsafe impl Pod for RaydiumLiquidityPoolCLMM {}
-unsafe impl Zeroable for RaydiumLiquidityPoolCLMM {}
-
-/// CLMM liquidity pool raw data
-#[repr(C)]
+/// CLMM liquidity pool raw layout, offsets after the discriminator. the account is packed while
+/// this struct is aligned, so it is not a byte view of the data: parse with
+/// `get_liquidity_pool_info`
 #[derive(Debug, Copy, Clone)]
 pub struct RaydiumLiquidityPoolCLMM {
     pub bump: u8,                      // 0: bump seed
@@ -66,7 +106,39 @@ pub struct RaydiumLiquidityPoolCLMM {
     pub padding2: [[u8; 8]; 32],
 }
 
-#[derive(Debug, Clone)]
+/// balances of the vaults of a CLMM pool read at one slot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClmmVaultAmounts {
+    pub amount_0_raw: u64,
+    pub amount_1_raw: u64,
+    pub amount_0_ui: f64,
+    pub amount_1_ui: f64,
+    pub slot: u64,
+}
+
+/// value locked in a CLMM pool, decimals applied, protocol and fund fees left out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClmmTvl {
+    pub amount_0_ui: f64,
+    pub amount_1_ui: f64,
+    /// both sides valued in token 1 at the pool price, or in USDC through `valued_at`
+    pub total_tvl: f64,
+    /// share of `total_tvl` the active liquidity holds within the tick spacing around the
+    /// price, a lower bound of the value in range of the price
+    pub active_liquidity_share: f64,
+}
+
+impl ClmmTvl {
+    /// the value locked with token 1 worth `token_1_price`, its price in USDC for a USDC TVL
+    pub fn valued_at(&self, token_1_price: f64) -> Self {
+        Self {
+            total_tvl: self.total_tvl * token_1_price,
+            ..*self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct RaydiumLiquidityPoolCLMMData {
     pub bump: u8,
     pub amm_config: Pubkey,
@@ -103,7 +175,7 @@ pub struct RaydiumLiquidityPoolCLMMData {
     pub recent_epoch: u64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct RewardInfo {
     pub reward_state: u8,               // 1 bytes
     pub open_time: u64,                 // 8 bytes
@@ -120,13 +192,53 @@ pub struct RewardInfo {
 }
 
 impl RaydiumLiquidityPoolCLMM {
-    pub fn get_liquidity_pool_info(data: &[u8]) -> Result<RaydiumLiquidityPoolCLMMData, String> {
+    /// parse the known prefix of account data at least as long as the layout, see
+    /// `RaydiumLiquidityPoolV4::parse_lenient`
+    pub fn parse_lenient(data: &[u8]) -> Result<Lenient<RaydiumLiquidityPoolCLMMData>, String> {
+        Lenient::parse(data, RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE, |data| {
+            Self::get_liquidity_pool_info(data).map_err(|e| e.to_string())
+        })
+    }
+
+    /// parse a CLMM pool account, checking it starts with the `PoolState` discriminator
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::{error::RaydiumError, liquidity::clmm::{RaydiumLiquidityPoolCLMM, RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE}};
+    /// let data = [0u8; RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE];
+    /// assert!(matches!(
+    ///     RaydiumLiquidityPoolCLMM::get_liquidity_pool_info(&data),
+    ///     Err(RaydiumError::WrongDiscriminator { .. })
+    /// ));
+    /// ```
+    pub fn get_liquidity_pool_info(
+        data: &[u8],
+    ) -> Result<RaydiumLiquidityPoolCLMMData, RaydiumError> {
+        let pool = Self::parse_unchecked(data)?;
+        let got: [u8; DISCRIMINATOR_LEN] = data[..DISCRIMINATOR_LEN].try_into().unwrap();
+        if got != RAYDIUM_CLMM_POOL_STATE_DISCRIMINATOR {
+            trace_warn!(?got, "CLMM pool discriminator mismatch");
+            return Err(RaydiumError::WrongDiscriminator {
+                expected: RAYDIUM_CLMM_POOL_STATE_DISCRIMINATOR,
+                got,
+            });
+        }
+        Ok(pool)
+    }
+
+    /// parse a CLMM pool account without checking its discriminator, for forks of the program
+    /// whose accounts use another one
+    pub fn parse_unchecked(data: &[u8]) -> Result<RaydiumLiquidityPoolCLMMData, RaydiumError> {
         if data.len() != RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE {
-            return Err(format!(
+            trace_warn!(
+                expected = RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE,
+                actual = data.len(),
+                "CLMM pool data size mismatch"
+            );
+            return Err(RaydiumError::Parse(format!(
                 "CLMM pool data size mismatch. Expected {}, got {}",
                 RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE,
                 data.len()
-            ));
+            )));
         }
         let mut offset: usize = DISCRIMINATOR_LEN;
         let read_pubkey = |d: &[u8], o: &mut usize| -> Pubkey {
@@ -188,35 +300,23 @@ impl RaydiumLiquidityPoolCLMM {
         // padding bits (7 bytes)
         offset += 7;
         // reward_infos = 169 bytes * 3
-        let mut reward_infos: [RewardInfo; 3] = std::array::from_fn(|_| RewardInfo {
-            reward_state: 0,
-            open_time: 0,
-            end_time: 0,
-            last_update_time: 0,
-            emissions_per_second_x64: 0,
-            reward_total_emissioned: 0,
-            reward_claimed: 0,
-            token_mint: Pubkey::default(),
-            token_vault: Pubkey::default(),
-            authority: Pubkey::default(),
-            reward_growth_global_x64: 0,
-        });
-        for i in 0..3 {
-            reward_infos[i].reward_state = read_u8(data, &mut offset);
-            reward_infos[i].open_time = read_u64(data, &mut offset);
-            reward_infos[i].end_time = read_u64(data, &mut offset);
-            reward_infos[i].last_update_time = read_u64(data, &mut offset);
-            reward_infos[i].emissions_per_second_x64 = read_u128(data, &mut offset);
-            reward_infos[i].reward_total_emissioned = read_u64(data, &mut offset);
-            reward_infos[i].reward_claimed = read_u64(data, &mut offset);
-            reward_infos[i].token_mint = read_pubkey(data, &mut offset);
-            reward_infos[i].token_vault = read_pubkey(data, &mut offset);
-            reward_infos[i].authority = read_pubkey(data, &mut offset);
-            reward_infos[i].reward_growth_global_x64 = read_u128(data, &mut offset);
+        let mut reward_infos: [RewardInfo; 3] = std::array::from_fn(|_| RewardInfo::default());
+        for reward_info in reward_infos.iter_mut() {
+            reward_info.reward_state = read_u8(data, &mut offset);
+            reward_info.open_time = read_u64(data, &mut offset);
+            reward_info.end_time = read_u64(data, &mut offset);
+            reward_info.last_update_time = read_u64(data, &mut offset);
+            reward_info.emissions_per_second_x64 = read_u128(data, &mut offset);
+            reward_info.reward_total_emissioned = read_u64(data, &mut offset);
+            reward_info.reward_claimed = read_u64(data, &mut offset);
+            reward_info.token_mint = read_pubkey(data, &mut offset);
+            reward_info.token_vault = read_pubkey(data, &mut offset);
+            reward_info.authority = read_pubkey(data, &mut offset);
+            reward_info.reward_growth_global_x64 = read_u128(data, &mut offset);
         }
         let mut tick_array_bitmap = [0u64; 16];
-        for i in 0..16 {
-            tick_array_bitmap[i] = read_u64(data, &mut offset);
+        for word in tick_array_bitmap.iter_mut() {
+            *word = read_u64(data, &mut offset);
         }
         let total_fees_token_0 = read_u64(data, &mut offset);
         let total_fees_claimed_token_0 = read_u64(data, &mut offset);
@@ -230,11 +330,11 @@ impl RaydiumLiquidityPoolCLMM {
         // padding2: 32 * 8 = 256 bytes
         offset += 24 * 8 + 32 * 8;
         if offset != data.len() {
-            return Err(format!(
+            return Err(RaydiumError::Parse(format!(
                 "Data parsing incomplete. Expected offset {}, got {}",
                 data.len(),
                 offset
-            ));
+            )));
         }
         Ok(RaydiumLiquidityPoolCLMMData {
             bump,
@@ -302,8 +402,85 @@ impl RaydiumLiquidityPoolCLMM {
     }
 }
 
+impl fmt::Display for RaydiumLiquidityPoolCLMMData {
+    /// one `field: value` line per field, keys in base58, followed by the initialized rewards
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "raydium liquidity pool clmm")?;
+        write_fields!(
+            f,
+            self,
+            bump,
+            amm_config,
+            owner,
+            token_mint_0,
+            token_mint_1,
+            token_vault_0,
+            token_vault_1,
+            observation_key,
+            mint_decimals_0,
+            mint_decimals_1,
+            tick_spacing,
+        );
+        write_fields!(f, self, grouped: liquidity, sqrt_price_x64);
+        write_fields!(f, self, tick_current);
+        write_fields!(
+            f,
+            self,
+            grouped: fee_growth_global_0_x64,
+            fee_growth_global_1_x64,
+            protocol_fees_token_0,
+            protocol_fees_token_1,
+            swap_in_amount_token_0,
+            swap_out_amount_token_1,
+            swap_in_amount_token_1,
+            swap_out_amount_token_0,
+        );
+        write_fields!(f, self, status);
+        write!(f, "\ntick_array_bitmap: {:x?}", self.tick_array_bitmap)?;
+        write_fields!(
+            f,
+            self,
+            grouped: total_fees_token_0,
+            total_fees_claimed_token_0,
+            total_fees_token_1,
+            total_fees_claimed_token_1,
+            fund_fees_token_0,
+            fund_fees_token_1,
+        );
+        write_fields!(f, self, open_time, recent_epoch);
+        for (i, reward) in self.reward_infos.iter().enumerate() {
+            if reward.token_mint == Pubkey::default() {
+                continue;
+            }
+            write!(
+                f,
+                "\nreward {i}: mint {} vault {} authority {} state {} from {} to {} updated {}",
+                reward.token_mint,
+                reward.token_vault,
+                reward.authority,
+                reward.reward_state,
+                reward.open_time,
+                reward.end_time,
+                reward.last_update_time,
+            )?;
+            write!(
+                f,
+                " emissions_per_second_x64 {} emitted {} claimed {} growth_x64 {}",
+                group_digits(reward.emissions_per_second_x64),
+                group_digits(reward.reward_total_emissioned as u128),
+                group_digits(reward.reward_claimed as u128),
+                group_digits(reward.reward_growth_global_x64),
+            )?;
+        }
+        Ok(())
+    }
+}
+
 impl RaydiumLiquidityPoolCLMMData {
-    pub async fn get_price(&self, client: Arc<RpcClient>) -> Result<f64, String> {
+    /// raw price of token 0 in token 1 approximated in f64 from the sqrt price, see
+    /// `price_x64` for the exact value
+    #[cfg(feature = "rpc")]
+    pub async fn get_price(&self, _client: Arc<RpcClient>) -> Result<f64, String> {
         let sqrt_price_f64 = self.sqrt_price_x64 as f64;
         let sqrt_price = sqrt_price_f64 / (1u128 << 64) as f64;
         let price = sqrt_price * sqrt_price;
@@ -316,33 +493,564 @@ impl RaydiumLiquidityPoolCLMMData {
         base.powf(tick)
     }
 
-    pub fn get_token_0_amount(&self) -> f64 {
+    /// raw virtual amounts of token 0 and token 1 of the liquidity active at the current tick,
+    /// `liquidity / sqrt_price` and `liquidity * sqrt_price` approximated in f64, see
+    /// `token_0_amount` and `token_1_amount` for the exact values.
+    ///
+    /// these are NOT the reserves of the pool: they are the amounts a constant product pool of
+    /// the same liquidity would hold, and differ widely from the vault balances, which also hold
+    /// every out of range position and the uncollected fees. see `get_vault_amounts` for the
+    /// tokens actually in the pool
+    pub fn virtual_amounts_at_current_tick(&self) -> (f64, f64) {
         if self.liquidity == 0 {
-            return 0.0;
+            return (0.0, 0.0);
         }
         let sqrt_price = (self.sqrt_price_x64 as f64) / (1u128 << 64) as f64;
         let liquidity = self.liquidity as f64;
-        liquidity / sqrt_price
+        (liquidity / sqrt_price, liquidity * sqrt_price)
     }
 
+    #[deprecated(
+        note = "a virtual amount, not the vault balance: use `virtual_amounts_at_current_tick` or `get_vault_amounts`"
+    )]
+    pub fn get_token_0_amount(&self) -> f64 {
+        self.virtual_amounts_at_current_tick().0
+    }
+
+    #[deprecated(
+        note = "a virtual amount, not the vault balance: use `virtual_amounts_at_current_tick` or `get_vault_amounts`"
+    )]
     pub fn get_token_1_amount(&self) -> f64 {
-        if self.liquidity == 0 {
-            return 0.0;
+        self.virtual_amounts_at_current_tick().1
+    }
+
+    /// get the balances of both vaults in a single RPC call, with the slot they were read at.
+    /// unlike `virtual_amounts_at_current_tick` these are the tokens the pool holds, out of
+    /// range liquidity and uncollected protocol, fund and position fees included
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::Raydium;
+    /// # async fn run(ray: Raydium, pool_address: &str) -> Result<(), raydium_sdk::error::RaydiumError> {
+    /// let pool_data = ray.get_liquidity_pool_clmm(pool_address).await?;
+    /// let vaults = pool_data.get_vault_amounts(ray.solana_client.client_arc()).await?;
+    /// let (virtual_0, virtual_1) = pool_data.virtual_amounts_at_current_tick();
+    /// println!("vaults {} / {}", vaults.amount_0_ui, vaults.amount_1_ui);
+    /// println!("active at the current tick {} / {} raw", virtual_0, virtual_1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rpc")]
+    pub async fn get_vault_amounts(
+        &self,
+        client: Arc<RpcClient>,
+    ) -> Result<ClmmVaultAmounts, RaydiumError> {
+        let vaults = [self.token_vault_0, self.token_vault_1];
+        let response = client
+            .get_multiple_accounts_with_commitment(&vaults, client.commitment())
+            .await
+            .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))?;
+        let mut amounts = [0u64; 2];
+        for (i, vault) in vaults.iter().enumerate() {
+            let account = response
+                .value
+                .get(i)
+                .and_then(Option::as_ref)
+                .ok_or(RaydiumError::AccountNotFound(*vault))?;
+            amounts[i] = r_u64(&account.data, TOKEN_ACCOUNT_AMOUNT_OFFSET);
         }
-        let sqrt_price = (self.sqrt_price_x64 as f64) / (1u128 << 64) as f64;
-        let liquidity = self.liquidity as f64;
-        liquidity * sqrt_price
+        Ok(ClmmVaultAmounts {
+            amount_0_raw: amounts[0],
+            amount_1_raw: amounts[1],
+            amount_0_ui: conver_balance(amounts[0] as f64, self.mint_decimals_0),
+            amount_1_ui: conver_balance(amounts[1] as f64, self.mint_decimals_1),
+            slot: response.context.slot,
+        })
+    }
+
+    /// the value locked from the vault balances, without the protocol and fund fees the pool
+    /// records as owed, valued in token 1 at the pool price. the active share compares it with
+    /// the amounts `liquidity` holds between the initializable ticks around the price
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::clmm::{ClmmVaultAmounts, RaydiumLiquidityPoolCLMMData};
+    /// let pool = RaydiumLiquidityPoolCLMMData {
+    ///     mint_decimals_0: 9,
+    ///     mint_decimals_1: 6,
+    ///     sqrt_price_x64: ((0.15f64).sqrt() * (1u128 << 64) as f64) as u128,
+    ///     protocol_fees_token_0: 1_000_000_000,
+    ///     ..Default::default()
+    /// };
+    /// let vaults = ClmmVaultAmounts {
+    ///     amount_0_raw: 11_000_000_000,
+    ///     amount_1_raw: 500_000_000,
+    ///     amount_0_ui: 11.0,
+    ///     amount_1_ui: 500.0,
+    ///     slot: 1,
+    /// };
+    /// let tvl = pool.tvl(&vaults);
+    /// assert_eq!(tvl.amount_0_ui, 10.0);
+    /// assert!((tvl.total_tvl - 2_000.0).abs() < 1e-6);
+    /// ```
+    pub fn tvl(&self, vaults: &ClmmVaultAmounts) -> ClmmTvl {
+        let amount_0 = vaults
+            .amount_0_raw
+            .saturating_sub(self.protocol_fees_token_0)
+            .saturating_sub(self.fund_fees_token_0);
+        let amount_1 = vaults
+            .amount_1_raw
+            .saturating_sub(self.protocol_fees_token_1)
+            .saturating_sub(self.fund_fees_token_1);
+        let price = self.price_0_in_1().value;
+        let value = |amount_0: u64, amount_1: u64| {
+            conver_balance(amount_0 as f64, self.mint_decimals_0) * price
+                + conver_balance(amount_1 as f64, self.mint_decimals_1)
+        };
+        let total_tvl = value(amount_0, amount_1);
+        let (active_0, active_1) = self.active_amounts().unwrap_or_default();
+        let active_liquidity_share = if total_tvl > 0.0 {
+            (value(active_0, active_1) / total_tvl).min(1.0)
+        } else {
+            0.0
+        };
+        ClmmTvl {
+            amount_0_ui: conver_balance(amount_0 as f64, self.mint_decimals_0),
+            amount_1_ui: conver_balance(amount_1 as f64, self.mint_decimals_1),
+            total_tvl,
+            active_liquidity_share,
+        }
+    }
+
+    /// raw amounts `liquidity` holds between the initializable ticks around the price
+    fn active_amounts(&self) -> Option<(u64, u64)> {
+        let spacing = self.tick_spacing.max(1) as i32;
+        let lower = self.tick_current.div_euclid(spacing) * spacing;
+        math::amounts_for_liquidity(
+            self.sqrt_price_x64,
+            math::tick_to_sqrt_price_x64(lower.max(MIN_TICK))?,
+            math::tick_to_sqrt_price_x64((lower + spacing).min(MAX_TICK))?,
+            self.liquidity,
+            full_math::Rounding::Down,
+        )
+    }
+
+    /// get the value locked in the pool from its vaults in a single RPC call, see `tvl`
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::Raydium;
+    /// # async fn run(ray: Raydium, pool_address: &str) -> Result<(), raydium_sdk::error::RaydiumError> {
+    /// let pool_data = ray.get_liquidity_pool_clmm(pool_address).await?;
+    /// let tvl = pool_data.get_tvl(ray.solana_client.client_arc()).await?;
+    /// println!(
+    ///     "{} in token 1, {:.1}% active",
+    ///     tvl.total_tvl,
+    ///     tvl.active_liquidity_share * 100.0
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rpc")]
+    pub async fn get_tvl(&self, client: Arc<RpcClient>) -> Result<ClmmTvl, RaydiumError> {
+        Ok(self.tvl(&self.get_vault_amounts(client).await?))
+    }
+
+    /// raw price of token 0 in token 1 as Q64.64, exact from the sqrt price.
+    /// `None` when the price is 2^64 or more
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::clmm::{RaydiumLiquidityPoolCLMMData, math::x64_to_f64};
+    /// let pool = RaydiumLiquidityPoolCLMMData {
+    ///     sqrt_price_x64: 3 << 64,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(pool.price_x64().map(x64_to_f64), Some(9.0));
+    /// ```
+    pub fn price_x64(&self) -> Option<u128> {
+        math::sqrt_price_x64_to_price_x64(self.sqrt_price_x64)
+    }
+
+    /// virtual token 0 reserve of the active liquidity, `liquidity * 2^64 / sqrt_price_x64`
+    /// rounded down. `None` when the sqrt price is zero or the amount overflows
+    pub fn token_0_amount(&self) -> Option<u128> {
+        full_math::mul_div(
+            self.liquidity,
+            1 << 64,
+            self.sqrt_price_x64,
+            full_math::Rounding::Down,
+        )
+    }
+
+    /// virtual token 1 reserve of the active liquidity, `liquidity * sqrt_price_x64 / 2^64`
+    /// rounded down. `None` when the amount overflows
+    pub fn token_1_amount(&self) -> Option<u128> {
+        full_math::mul_div(
+            self.liquidity,
+            self.sqrt_price_x64,
+            1 << 64,
+            full_math::Rounding::Down,
+        )
+    }
+
+    /// price of one token 0 in token 1, decimals applied, from `sqrt_price_x64`.
+    /// `tick_current` is the tick at or below the price, so the value is close to
+    /// `1.0001^tick_current * 10^(mint_decimals_0 - mint_decimals_1)`
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::clmm::RaydiumLiquidityPoolCLMMData;
+    /// // SOL (9 decimals) at 150 USDC (6 decimals)
+    /// let pool = RaydiumLiquidityPoolCLMMData {
+    ///     mint_decimals_0: 9,
+    ///     mint_decimals_1: 6,
+    ///     sqrt_price_x64: ((0.15f64).sqrt() * (1u128 << 64) as f64) as u128,
+    ///     tick_current: -18_973,
+    ///     ..Default::default()
+    /// };
+    /// let price = pool.price_0_in_1();
+    /// assert!((price.value - 150.0).abs() < 1e-6);
+    /// let from_tick = 1.0001f64.powi(pool.tick_current) * 1e3;
+    /// assert!((from_tick - price.value).abs() / price.value < 1e-4);
+    /// ```
+    pub fn price_0_in_1(&self) -> Price {
+        let sqrt_price = self.sqrt_price_x64 as f64 / (1u128 << 64) as f64;
+        let decimals = 10f64.powi(self.mint_decimals_0 as i32 - self.mint_decimals_1 as i32);
+        Price {
+            mint: self.token_mint_0,
+            denominated_in: self.token_mint_1,
+            value: sqrt_price * sqrt_price * decimals,
+        }
+    }
+
+    /// price of one token 1 in token 0, decimals applied, infinite when the sqrt price is zero
+    pub fn price_1_in_0(&self) -> Price {
+        self.price_0_in_1().invert()
+    }
+
+    /// price of `mint` denominated in the other mint of the pool, whichever side the mint
+    /// sorted to. errors when the mint is not in the pool
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::{liquidity::clmm::RaydiumLiquidityPoolCLMMData, mints::WSOL_MINT};
+    /// let pool = RaydiumLiquidityPoolCLMMData {
+    ///     token_mint_0: WSOL_MINT,
+    ///     mint_decimals_0: 9,
+    ///     mint_decimals_1: 6,
+    ///     sqrt_price_x64: ((0.15f64).sqrt() * (1u128 << 64) as f64) as u128,
+    ///     ..Default::default()
+    /// };
+    /// let price = pool.price_of(&WSOL_MINT).unwrap();
+    /// println!("1 SOL = {} of {}", price.value, price.denominated_in);
+    /// ```
+    pub fn price_of(&self, mint: &Pubkey) -> Result<Price, RaydiumError> {
+        let price = self.price_0_in_1();
+        if *mint == price.mint {
+            return Ok(price);
+        }
+        if *mint != price.denominated_in {
+            return Err(RaydiumError::MintNotInPool(*mint));
+        }
+        if price.value == 0.0 {
+            return Err(RaydiumError::InsufficientLiquidity);
+        }
+        Ok(price.invert())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "rpc")]
     use solana_network_client::SolanaClient;
 
+    #[cfg(feature = "rpc")]
     use crate::Raydium;
 
     use super::*;
+    use crate::typs::CLMM_STATUS_SWAP_DISABLED;
+
+    /// every field written at its offset in the packed Anchor layout with a distinct value
+    #[test]
+    fn test_parse_clmm_layout() {
+        let mut data = vec![0u8; RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE];
+        let mut put =
+            |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        let keys: [Pubkey; 10] = std::array::from_fn(|_| Pubkey::new_unique());
+        put(0, &RAYDIUM_CLMM_POOL_STATE_DISCRIMINATOR);
+        put(8, &[254]);
+        for (i, offset) in [9, 41, 73, 105, 137, 169, 201].into_iter().enumerate() {
+            put(offset, keys[i].as_ref());
+        }
+        put(233, &[9, 6]);
+        put(235, &60u16.to_le_bytes());
+        put(237, &(u64::MAX as u128 + 1).to_le_bytes());
+        put(253, &(u64::MAX as u128 + 2).to_le_bytes());
+        put(269, &(-18_973i32).to_le_bytes());
+        put(277, &3u128.to_le_bytes());
+        put(293, &4u128.to_le_bytes());
+        put(309, &5u64.to_le_bytes());
+        put(317, &6u64.to_le_bytes());
+        put(325, &7u128.to_le_bytes());
+        put(341, &8u128.to_le_bytes());
+        put(357, &9u128.to_le_bytes());
+        put(373, &10u128.to_le_bytes());
+        put(389, &[CLMM_STATUS_SWAP_DISABLED]);
+        // the last of the three 169 byte reward infos
+        let reward = 397 + 2 * 169;
+        put(reward, &[1]);
+        put(reward + 1, &11u64.to_le_bytes());
+        put(reward + 9, &12u64.to_le_bytes());
+        put(reward + 17, &13u64.to_le_bytes());
+        put(reward + 25, &14u128.to_le_bytes());
+        put(reward + 41, &15u64.to_le_bytes());
+        put(reward + 49, &16u64.to_le_bytes());
+        put(reward + 57, keys[7].as_ref());
+        put(reward + 89, keys[8].as_ref());
+        put(reward + 121, keys[9].as_ref());
+        put(reward + 153, &17u128.to_le_bytes());
+        put(904 + 15 * 8, &18u64.to_le_bytes());
+        for (i, value) in (19u64..27).enumerate() {
+            put(1032 + i * 8, &value.to_le_bytes());
+        }
+        let pool = RaydiumLiquidityPoolCLMM::get_liquidity_pool_info(&data).unwrap();
+        assert_eq!(pool.bump, 254);
+        assert_eq!(
+            [
+                pool.amm_config,
+                pool.owner,
+                pool.token_mint_0,
+                pool.token_mint_1,
+                pool.token_vault_0,
+                pool.token_vault_1,
+                pool.observation_key
+            ],
+            keys[..7]
+        );
+        assert_eq!(
+            (
+                pool.mint_decimals_0,
+                pool.mint_decimals_1,
+                pool.tick_spacing
+            ),
+            (9, 6, 60)
+        );
+        assert_eq!(
+            (pool.liquidity, pool.sqrt_price_x64, pool.tick_current),
+            (u64::MAX as u128 + 1, u64::MAX as u128 + 2, -18_973)
+        );
+        assert_eq!(
+            (pool.fee_growth_global_0_x64, pool.fee_growth_global_1_x64),
+            (3, 4)
+        );
+        assert_eq!(
+            (pool.protocol_fees_token_0, pool.protocol_fees_token_1),
+            (5, 6)
+        );
+        assert_eq!(
+            (
+                pool.swap_in_amount_token_0,
+                pool.swap_out_amount_token_1,
+                pool.swap_in_amount_token_1,
+                pool.swap_out_amount_token_0
+            ),
+            (7, 8, 9, 10)
+        );
+        assert_eq!(pool.status, CLMM_STATUS_SWAP_DISABLED);
+        let reward = &pool.reward_infos[2];
+        assert_eq!(
+            (
+                reward.reward_state,
+                reward.open_time,
+                reward.end_time,
+                reward.last_update_time,
+                reward.emissions_per_second_x64,
+                reward.reward_total_emissioned,
+                reward.reward_claimed
+            ),
+            (1, 11, 12, 13, 14, 15, 16)
+        );
+        assert_eq!(
+            [reward.token_mint, reward.token_vault, reward.authority],
+            keys[7..]
+        );
+        assert_eq!(reward.reward_growth_global_x64, 17);
+        assert_eq!(pool.reward_infos[0].reward_state, 0);
+        assert_eq!(pool.tick_array_bitmap[15], 18);
+        assert_eq!(
+            [
+                pool.total_fees_token_0,
+                pool.total_fees_claimed_token_0,
+                pool.total_fees_token_1,
+                pool.total_fees_claimed_token_1,
+                pool.fund_fees_token_0,
+                pool.fund_fees_token_1,
+                pool.open_time,
+                pool.recent_epoch
+            ],
+            [19, 20, 21, 22, 23, 24, 25, 26]
+        );
+        // an account of a fork with its own discriminator only parses unchecked
+        data[..8].copy_from_slice(&[1; 8]);
+        assert_eq!(
+            RaydiumLiquidityPoolCLMM::get_liquidity_pool_info(&data).unwrap_err(),
+            RaydiumError::WrongDiscriminator {
+                expected: RAYDIUM_CLMM_POOL_STATE_DISCRIMINATOR,
+                got: [1; 8]
+            }
+        );
+        let unchecked = RaydiumLiquidityPoolCLMM::parse_unchecked(&data).unwrap();
+        assert_eq!(unchecked.token_mint_0, pool.token_mint_0);
+    }
+
+    #[test]
+    fn test_exact_reserves() {
+        let pool = RaydiumLiquidityPoolCLMMData {
+            liquidity: 123_456_789_012_345_678_901,
+            sqrt_price_x64: 7_161_458_000_123_456_789,
+            ..Default::default()
+        };
+        assert_eq!(pool.token_0_amount(), Some(318_004_488_895_059_962_253));
+        assert_eq!(pool.token_1_amount(), Some(47_928_816_370_477_363_134));
+        // the f64 approximations are off in the low digits
+        assert_ne!(
+            pool.virtual_amounts_at_current_tick().0 as u128,
+            318_004_488_895_059_962_253
+        );
+        let empty = RaydiumLiquidityPoolCLMMData {
+            sqrt_price_x64: 0,
+            ..pool
+        };
+        assert_eq!(empty.token_0_amount(), None);
+    }
+
+    /// SOL-USDC at 150 with 5e13 of active liquidity: the virtual amounts at the current tick
+    /// are about 129k SOL and 19.4M USDC while the vaults hold 20k SOL and 3M USDC
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_vault_and_virtual_amounts() {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+
+        use crate::{
+            programs::TOKEN_PROGRAM_ID,
+            test_utils::{mock_raydium, multiple_accounts_response, ui_account},
+        };
+
+        let vault = |amount: u64| {
+            let mut data = vec![0u8; 165];
+            data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]
+                .copy_from_slice(&amount.to_le_bytes());
+            Some(ui_account(&TOKEN_PROGRAM_ID, data))
+        };
+        let pool = RaydiumLiquidityPoolCLMMData {
+            token_vault_0: Pubkey::new_unique(),
+            token_vault_1: Pubkey::new_unique(),
+            mint_decimals_0: 9,
+            mint_decimals_1: 6,
+            liquidity: 50_000_000_000_000,
+            sqrt_price_x64: ((0.15f64).sqrt() * (1u128 << 64) as f64) as u128,
+            ..Default::default()
+        };
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![vault(20_000_000_000_000), vault(3_000_000_000_000)]),
+        );
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![vault(20_000_000_000_000), None]),
+        );
+        let client = mock_raydium(mocks).solana_client.client_arc();
+        let vaults = pool.get_vault_amounts(Arc::clone(&client)).await.unwrap();
+        assert_eq!(
+            vaults,
+            ClmmVaultAmounts {
+                amount_0_raw: 20_000_000_000_000,
+                amount_1_raw: 3_000_000_000_000,
+                amount_0_ui: 20_000.0,
+                amount_1_ui: 3_000_000.0,
+                slot: 1,
+            }
+        );
+        let (virtual_0, virtual_1) = pool.virtual_amounts_at_current_tick();
+        assert_eq!((virtual_0 / 1e9).round(), 129_099.0);
+        assert_eq!((virtual_1 / 1e6).round(), 19_364_917.0);
+        assert_eq!(
+            pool.get_vault_amounts(client).await,
+            Err(RaydiumError::AccountNotFound(pool.token_vault_1))
+        );
+    }
+
+    /// 20k SOL and 3M USDC at 150, 100 SOL and 15k USDC of them protocol and fund fees.
+    /// the 5e13 active liquidity holds about 13.9 SOL and 7.6k USDC within the tick spacing of 10
+    #[test]
+    fn test_tvl() {
+        let pool = RaydiumLiquidityPoolCLMMData {
+            mint_decimals_0: 9,
+            mint_decimals_1: 6,
+            tick_spacing: 10,
+            tick_current: -18_972,
+            liquidity: 50_000_000_000_000,
+            sqrt_price_x64: ((0.15f64).sqrt() * (1u128 << 64) as f64) as u128,
+            protocol_fees_token_0: 60_000_000_000,
+            fund_fees_token_0: 40_000_000_000,
+            protocol_fees_token_1: 15_000_000_000,
+            ..Default::default()
+        };
+        let vaults = ClmmVaultAmounts {
+            amount_0_raw: 20_100_000_000_000,
+            amount_1_raw: 3_015_000_000_000,
+            amount_0_ui: 20_100.0,
+            amount_1_ui: 3_015_000.0,
+            slot: 1,
+        };
+        let tvl = pool.tvl(&vaults);
+        assert_eq!((tvl.amount_0_ui, tvl.amount_1_ui), (20_000.0, 3_000_000.0));
+        assert!((tvl.total_tvl - 6_000_000.0).abs() < 1e-3);
+        assert!((tvl.active_liquidity_share - 0.001613).abs() < 1e-6);
+        assert!((tvl.valued_at(0.5).total_tvl - 3_000_000.0).abs() < 1e-3);
+        let empty = pool.tvl(&ClmmVaultAmounts {
+            amount_0_raw: 0,
+            amount_1_raw: 0,
+            ..vaults
+        });
+        assert_eq!((empty.total_tvl, empty.active_liquidity_share), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_price_of() {
+        // USDC sorts before SOL in the SOL-USDC CLMM pool, token 0 is USDC at 1/150 SOL
+        let usdc = Pubkey::new_unique();
+        let sol = Pubkey::new_unique();
+        let pool = RaydiumLiquidityPoolCLMMData {
+            token_mint_0: usdc,
+            token_mint_1: sol,
+            mint_decimals_0: 6,
+            mint_decimals_1: 9,
+            sqrt_price_x64: ((1.0f64 / 150.0 * 1e3).sqrt() * (1u128 << 64) as f64) as u128,
+            tick_current: 18_972,
+            ..Default::default()
+        };
+        let sol_price = pool.price_of(&sol).unwrap();
+        assert_eq!((sol_price.mint, sol_price.denominated_in), (sol, usdc));
+        assert!((sol_price.value - 150.0).abs() < 1e-6);
+        assert_eq!(sol_price, pool.price_1_in_0());
+        assert_eq!(pool.price_of(&usdc).unwrap(), pool.price_0_in_1());
+        assert_eq!(pool.price_0_in_1().invert(), sol_price);
+        let from_tick = 1.0001f64.powi(pool.tick_current) * 1e-3;
+        assert!((from_tick - pool.price_0_in_1().value).abs() / from_tick < 1e-4);
+        let other = Pubkey::new_unique();
+        assert_eq!(
+            pool.price_of(&other),
+            Err(RaydiumError::MintNotInPool(other))
+        );
+        let empty = RaydiumLiquidityPoolCLMMData {
+            sqrt_price_x64: 0,
+            ..pool
+        };
+        assert_eq!(
+            empty.price_of(&sol),
+            Err(RaydiumError::InsufficientLiquidity)
+        );
+    }
 
+    #[cfg(feature = "rpc")]
     #[tokio::test]
     async fn test_clmm_data_parsing() -> Result<(), Box<dyn std::error::Error>> {
         let solana_client = SolanaClient::new(solana_network_client::Mode::MAIN).unwrap();
@@ -0,0 +1,288 @@
+//! tick array bitmap extension of a CLMM pool, tracking the tick arrays beyond the 512 on each
+//! side of zero covered by the bitmap in the pool account
+
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use crate::{
+    Raydium, liquidity::clmm::pda::tick_array_bitmap_extension_address, rpc::parse_address,
+    typs::PoolKind,
+};
+use crate::{
+    error::RaydiumError,
+    liquidity::clmm::{
+        RaydiumLiquidityPoolCLMMData,
+        math::{MAX_TICK, MIN_TICK},
+        tick_array::{
+            NextTickArray, TICK_ARRAY_BITMAP_SIZE, tick_array_offset_in_bitmap,
+            tick_array_start_index, tick_count,
+        },
+    },
+    reader::{r_pubkey, r_u64},
+    trace::trace_warn,
+};
+
+/// tick array bitmap extension account data size
+pub const TICK_ARRAY_BITMAP_EXTENSION_DATA_SIZE: usize = 1832;
+/// bitmap pages on each side of zero, each tracking 512 tick arrays
+pub const EXTENSION_TICK_ARRAY_BITMAP_SIZE: usize = 14;
+/// anchor discriminator of the CLMM `TickArrayBitmapExtension` account
+pub const TICK_ARRAY_BITMAP_EXTENSION_DISCRIMINATOR: [u8; 8] =
+    [60, 150, 36, 219, 97, 128, 139, 153];
+/// offsets of the fields in the extension account, after the discriminator
+const POOL_ID_OFFSET: usize = 8;
+const POSITIVE_BITMAP_OFFSET: usize = 40;
+const NEGATIVE_BITMAP_OFFSET: usize = 936;
+const PAGE_LEN: usize = 64;
+
+/// parser of CLMM tick array bitmap extension accounts
+pub struct TickArrayBitmapExtension;
+
+/// a page of the extension, the bits of 512 tick arrays in tick order
+pub type BitmapPage = [u64; 8];
+
+/// parsed tick array bitmap extension account. page `i` of each side covers the start indexes
+/// from `512 * (i + 1)` arrays away from zero, the negative side mirrored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TickArrayBitmapExtensionData {
+    pub pool_id: Pubkey,
+    pub positive_tick_array_bitmap: [BitmapPage; EXTENSION_TICK_ARRAY_BITMAP_SIZE],
+    pub negative_tick_array_bitmap: [BitmapPage; EXTENSION_TICK_ARRAY_BITMAP_SIZE],
+}
+
+impl TickArrayBitmapExtension {
+    /// parse a tick array bitmap extension account, checking its discriminator
+    pub fn parse(data: &[u8]) -> Result<TickArrayBitmapExtensionData, RaydiumError> {
+        if data.len() != TICK_ARRAY_BITMAP_EXTENSION_DATA_SIZE {
+            trace_warn!(
+                expected = TICK_ARRAY_BITMAP_EXTENSION_DATA_SIZE,
+                actual = data.len(),
+                "tick array bitmap extension data size mismatch"
+            );
+            return Err(RaydiumError::Parse(format!(
+                "tick array bitmap extension account must be {} bytes, got {}",
+                TICK_ARRAY_BITMAP_EXTENSION_DATA_SIZE,
+                data.len()
+            )));
+        }
+        let got: [u8; 8] = data[..8].try_into().unwrap();
+        if got != TICK_ARRAY_BITMAP_EXTENSION_DISCRIMINATOR {
+            trace_warn!(?got, "tick array bitmap extension discriminator mismatch");
+            return Err(RaydiumError::WrongDiscriminator {
+                expected: TICK_ARRAY_BITMAP_EXTENSION_DISCRIMINATOR,
+                got,
+            });
+        }
+        let pages = |offset: usize| {
+            std::array::from_fn(|page| {
+                std::array::from_fn(|word| r_u64(data, offset + page * PAGE_LEN + word * 8))
+            })
+        };
+        Ok(TickArrayBitmapExtensionData {
+            pool_id: r_pubkey(data, POOL_ID_OFFSET),
+            positive_tick_array_bitmap: pages(POSITIVE_BITMAP_OFFSET),
+            negative_tick_array_bitmap: pages(NEGATIVE_BITMAP_OFFSET),
+        })
+    }
+}
+
+impl TickArrayBitmapExtensionData {
+    /// whether the extension marks the tick array starting at `start_index` initialized.
+    /// false for arrays of the pool bitmap and past the last page
+    pub fn is_initialized(&self, start_index: i32, tick_spacing: u16) -> bool {
+        let ticks_in_page = tick_count(tick_spacing) * TICK_ARRAY_BITMAP_SIZE;
+        if (-ticks_in_page..ticks_in_page).contains(&start_index) {
+            return false;
+        }
+        let mut page = start_index.abs() / ticks_in_page - 1;
+        if start_index < 0 && start_index.abs() % ticks_in_page == 0 {
+            page -= 1;
+        }
+        let pages = if start_index < 0 {
+            &self.negative_tick_array_bitmap
+        } else {
+            &self.positive_tick_array_bitmap
+        };
+        let Some(page) = pages.get(page as usize) else {
+            return false;
+        };
+        let bit = tick_array_offset_in_bitmap(start_index, tick_spacing) as usize;
+        page[bit / 64] & (1 << (bit % 64)) != 0
+    }
+}
+
+/// the bitmap of a pool together with its extension, searched as one
+#[derive(Debug, Clone, Copy)]
+pub struct CombinedTickBitmap<'a> {
+    pool: &'a RaydiumLiquidityPoolCLMMData,
+    extension: Option<&'a TickArrayBitmapExtensionData>,
+}
+
+impl<'a> CombinedTickBitmap<'a> {
+    /// the bitmaps of `pool`, `extension` being its bitmap extension account when fetched
+    pub fn new(
+        pool: &'a RaydiumLiquidityPoolCLMMData,
+        extension: Option<&'a TickArrayBitmapExtensionData>,
+    ) -> Self {
+        Self { pool, extension }
+    }
+
+    /// whether the tick array starting at `start_index` is initialized, `None` when it is
+    /// tracked by the extension and the extension is missing
+    pub fn is_initialized(&self, start_index: i32) -> Option<bool> {
+        let bit = start_index / tick_count(self.pool.tick_spacing) + TICK_ARRAY_BITMAP_SIZE;
+        if (0..2 * TICK_ARRAY_BITMAP_SIZE).contains(&bit) {
+            let word = self.pool.tick_array_bitmap[bit as usize / 64];
+            return Some(word & (1 << (bit % 64)) != 0);
+        }
+        self.extension
+            .map(|extension| extension.is_initialized(start_index, self.pool.tick_spacing))
+    }
+
+    /// the first initialized tick array from the one holding `start_from_tick`, see
+    /// `RaydiumLiquidityPoolCLMMData::next_initialized_tick_array`. with the extension the
+    /// search crosses into it and never returns `Extension`
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::clmm::{RaydiumLiquidityPoolCLMMData, bitmap_extension::CombinedTickBitmap, tick_array::NextTickArray};
+    /// let pool = RaydiumLiquidityPoolCLMMData { tick_spacing: 1, ..Default::default() };
+    /// let bitmap = CombinedTickBitmap::new(&pool, None);
+    /// assert_eq!(bitmap.next_initialized_tick_array(0, false), Some(NextTickArray::Extension(30_720)));
+    /// ```
+    pub fn next_initialized_tick_array(
+        &self,
+        start_from_tick: i32,
+        zero_for_one: bool,
+    ) -> Option<NextTickArray> {
+        let tick_spacing = self.pool.tick_spacing;
+        if tick_spacing == 0 {
+            return None;
+        }
+        let ticks = tick_count(tick_spacing);
+        let min_start = tick_array_start_index(MIN_TICK, tick_spacing);
+        let max_start = tick_array_start_index(MAX_TICK, tick_spacing);
+        let mut start = tick_array_start_index(start_from_tick, tick_spacing);
+        while (min_start..=max_start).contains(&start) {
+            match self.is_initialized(start) {
+                None => return Some(NextTickArray::Extension(start)),
+                Some(true) => return Some(NextTickArray::Found(start)),
+                Some(false) => start += if zero_for_one { -ticks } else { ticks },
+            }
+        }
+        None
+    }
+
+    /// the successive initialized tick arrays a swap from `start_from_tick` walks through,
+    /// ending with `Extension` when the search needs the missing extension
+    pub fn initialized_tick_arrays(
+        self,
+        start_from_tick: i32,
+        zero_for_one: bool,
+    ) -> impl Iterator<Item = NextTickArray> + 'a {
+        let ticks = tick_count(self.pool.tick_spacing);
+        std::iter::successors(
+            self.next_initialized_tick_array(start_from_tick, zero_for_one),
+            move |previous| match previous {
+                NextTickArray::Found(start) if zero_for_one => {
+                    self.next_initialized_tick_array(start - 1, zero_for_one)
+                }
+                NextTickArray::Found(start) => {
+                    self.next_initialized_tick_array(start + ticks, zero_for_one)
+                }
+                NextTickArray::Extension(_) => None,
+            },
+        )
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// get the tick array bitmap extension of a CLMM pool, derived from the pool address
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, liquidity::clmm::bitmap_extension::CombinedTickBitmap};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let address = "3ucNos4NbumPLZNWztqGHNFFgkHeRMBQAVemeeomsUxv";
+    /// let pool = raydium.get_liquidity_pool_clmm(address).await?;
+    /// let extension = raydium.get_tick_array_bitmap_extension(address).await?;
+    /// let bitmap = CombinedTickBitmap::new(&pool, Some(&extension));
+    /// for array in bitmap.initialized_tick_arrays(pool.tick_current, true).take(3) {
+    ///     println!("{:?}", array);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_tick_array_bitmap_extension(
+        &self,
+        pool: &str,
+    ) -> Result<TickArrayBitmapExtensionData, RaydiumError> {
+        let pool = parse_address(pool)?;
+        let address = tick_array_bitmap_extension_address(&pool, &self.program_id(PoolKind::Clmm));
+        let account = self
+            .get_multiple_accounts_with_context(&[address])
+            .await?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(address))?;
+        TickArrayBitmapExtension::parse(&account.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_across_extension() {
+        let mut data = vec![0u8; TICK_ARRAY_BITMAP_EXTENSION_DATA_SIZE];
+        let mut put =
+            |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        put(0, &TICK_ARRAY_BITMAP_EXTENSION_DISCRIMINATOR);
+        let pool_id = Pubkey::new_unique();
+        put(8, pool_id.as_ref());
+        // spacing 1, 60 ticks per array and 30720 per page. positive page 0 bit 3 is the
+        // array at 30720 + 180, negative page 1 bit 0 the array at -92160
+        put(40, &(1u64 << 3).to_le_bytes());
+        put(936 + 64, &1u64.to_le_bytes());
+        // the last word of the last negative page, the array at -30720 * 15 + 60 * 511
+        put(936 + 13 * 64 + 7 * 8, &(1u64 << 63).to_le_bytes());
+        let extension = TickArrayBitmapExtension::parse(&data).unwrap();
+        assert_eq!(extension.pool_id, pool_id);
+        assert!(extension.is_initialized(30_900, 1));
+        assert!(extension.is_initialized(-92_160, 1));
+        assert!(extension.is_initialized(-460_800 + 60 * 511, 1));
+        assert!(!extension.is_initialized(-61_440, 1));
+
+        let mut pool = RaydiumLiquidityPoolCLMMData {
+            tick_spacing: 1,
+            ..Default::default()
+        };
+        // the array at -60, just below zero in the pool bitmap
+        pool.tick_array_bitmap[7] = 1 << 63;
+        use NextTickArray::{Extension, Found};
+        let alone = CombinedTickBitmap::new(&pool, None);
+        assert_eq!(
+            alone.initialized_tick_arrays(0, true).collect::<Vec<_>>(),
+            [Found(-60), Extension(-30_780)]
+        );
+        let combined = CombinedTickBitmap::new(&pool, Some(&extension));
+        assert_eq!(
+            combined
+                .initialized_tick_arrays(0, true)
+                .collect::<Vec<_>>(),
+            [Found(-60), Found(-92_160), Found(-430_140)]
+        );
+        assert_eq!(
+            combined
+                .initialized_tick_arrays(-100, false)
+                .collect::<Vec<_>>(),
+            [Found(-60), Found(30_900)]
+        );
+        assert_eq!(
+            pool.next_initialized_tick_array(0, false),
+            Some(Extension(30_720))
+        );
+        assert!(TickArrayBitmapExtension::parse(&data[..1831]).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+//! amm config accounts of the CLMM program, the fee rates and tick spacing shared by pools
+
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+#[cfg(feature = "rpc")]
+use std::{sync::Arc, time::Duration};
+
+#[cfg(feature = "rpc")]
+use crate::{
+    Raydium, liquidity::clmm::RaydiumLiquidityPoolCLMMData, rpc::parse_address, typs::PoolKind,
+};
+use crate::{
+    error::RaydiumError,
+    liquidity::clmm::RAYDIUM_CLMM_FEE_RATE_DENOMINATOR,
+    reader::{r_pubkey, r_u8, r_u16, r_u32},
+    trace::trace_warn,
+};
+
+/// amm config account data size
+pub const CLMM_CONFIG_DATA_SIZE: usize = 117;
+/// anchor discriminator of the CLMM `AmmConfig` account
+pub const CLMM_CONFIG_DISCRIMINATOR: [u8; 8] = [218, 244, 33, 104, 203, 203, 43, 111];
+/// offsets of the fields in the config account, after the discriminator
+const BUMP_OFFSET: usize = 8;
+const INDEX_OFFSET: usize = 9;
+const OWNER_OFFSET: usize = 11;
+const PROTOCOL_FEE_RATE_OFFSET: usize = 43;
+const TRADE_FEE_RATE_OFFSET: usize = 47;
+const TICK_SPACING_OFFSET: usize = 51;
+const FUND_FEE_RATE_OFFSET: usize = 53;
+const FUND_OWNER_OFFSET: usize = 61;
+// 93-116: padding
+
+/// time the list of amm configs is cached for, unless configured. new fee tiers are rare
+#[cfg(feature = "rpc")]
+pub const DEFAULT_CLMM_CONFIG_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// parsed CLMM amm config account, rates over `RAYDIUM_CLMM_FEE_RATE_DENOMINATOR`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ClmmConfig {
+    pub bump: u8,
+    pub index: u16,
+    pub owner: Pubkey,
+    /// share of the trade fee kept by the protocol
+    pub protocol_fee_rate: u32,
+    /// fee taken from the input of every swap
+    pub trade_fee_rate: u32,
+    pub tick_spacing: u16,
+    /// share of the trade fee sent to the fund
+    pub fund_fee_rate: u32,
+    pub fund_owner: Pubkey,
+}
+
+impl ClmmConfig {
+    /// parse an amm config account, checking its discriminator
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::clmm::config::{CLMM_CONFIG_DATA_SIZE, CLMM_CONFIG_DISCRIMINATOR, ClmmConfig};
+    /// let mut data = [0u8; CLMM_CONFIG_DATA_SIZE];
+    /// data[..8].copy_from_slice(&CLMM_CONFIG_DISCRIMINATOR);
+    /// data[47..51].copy_from_slice(&2_500u32.to_le_bytes());
+    /// assert_eq!(ClmmConfig::parse(&data).unwrap().trade_fee_rate, 2_500);
+    /// ```
+    pub fn parse(data: &[u8]) -> Result<Self, RaydiumError> {
+        if data.len() != CLMM_CONFIG_DATA_SIZE {
+            trace_warn!(
+                expected = CLMM_CONFIG_DATA_SIZE,
+                actual = data.len(),
+                "clmm config data size mismatch"
+            );
+            return Err(RaydiumError::Parse(format!(
+                "clmm config account must be {} bytes, got {}",
+                CLMM_CONFIG_DATA_SIZE,
+                data.len()
+            )));
+        }
+        let got: [u8; 8] = data[..8].try_into().unwrap();
+        if got != CLMM_CONFIG_DISCRIMINATOR {
+            trace_warn!(?got, "clmm config discriminator mismatch");
+            return Err(RaydiumError::WrongDiscriminator {
+                expected: CLMM_CONFIG_DISCRIMINATOR,
+                got,
+            });
+        }
+        Ok(Self {
+            bump: r_u8(data, BUMP_OFFSET),
+            index: r_u16(data, INDEX_OFFSET),
+            owner: r_pubkey(data, OWNER_OFFSET),
+            protocol_fee_rate: r_u32(data, PROTOCOL_FEE_RATE_OFFSET),
+            trade_fee_rate: r_u32(data, TRADE_FEE_RATE_OFFSET),
+            tick_spacing: r_u16(data, TICK_SPACING_OFFSET),
+            fund_fee_rate: r_u32(data, FUND_FEE_RATE_OFFSET),
+            fund_owner: r_pubkey(data, FUND_OWNER_OFFSET),
+        })
+    }
+
+    /// fee taken from the input of every swap as a fraction, 0.0025 for the 0.25% tier
+    pub fn trade_fee_fraction(&self) -> f64 {
+        self.trade_fee_rate as f64 / RAYDIUM_CLMM_FEE_RATE_DENOMINATOR as f64
+    }
+
+    /// share of the trade fee kept by the protocol as a fraction
+    pub fn protocol_fee_fraction(&self) -> f64 {
+        self.protocol_fee_rate as f64 / RAYDIUM_CLMM_FEE_RATE_DENOMINATOR as f64
+    }
+
+    /// share of the trade fee sent to the fund as a fraction
+    pub fn fund_fee_fraction(&self) -> f64 {
+        self.fund_fee_rate as f64 / RAYDIUM_CLMM_FEE_RATE_DENOMINATOR as f64
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl RaydiumLiquidityPoolCLMMData {
+    /// get the amm config account of the pool, the fee tier of its swaps
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::Raydium;
+    /// # async fn run(ray: Raydium, pool_address: &str) -> Result<(), raydium_sdk::error::RaydiumError> {
+    /// let pool_data = ray.get_liquidity_pool_clmm(pool_address).await?;
+    /// let config = pool_data.get_amm_config(ray.solana_client.client_arc()).await?;
+    /// println!("{}% per swap", config.trade_fee_fraction() * 100.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_amm_config(&self, client: Arc<RpcClient>) -> Result<ClmmConfig, RaydiumError> {
+        let account = client
+            .get_multiple_accounts_with_commitment(&[self.amm_config], client.commitment())
+            .await
+            .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(self.amm_config))?;
+        ClmmConfig::parse(&account.data)
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// get the amm config account of a CLMM pool, its `amm_config`
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let pool = raydium.get_liquidity_pool_clmm("3ucNos4NbumPLZNWztqGHNFFgkHeRMBQAVemeeomsUxv").await?;
+    /// let config = raydium.get_clmm_config(&pool.amm_config.to_string()).await?;
+    /// println!("trade fee rate {}", config.trade_fee_rate);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_clmm_config(&self, address: &str) -> Result<ClmmConfig, RaydiumError> {
+        let address = parse_address(address)?;
+        let account = self
+            .get_multiple_accounts_with_context(&[address])
+            .await?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(address))?;
+        ClmmConfig::parse(&account.data)
+    }
+
+    /// get every amm config of the CLMM program with its address, the fee tiers pools can be
+    /// created with, sorted by `trade_fee_rate`. the list is cached for
+    /// `RaydiumConfig::clmm_config_cache_ttl`
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// for (address, config) in raydium.list_clmm_configs().await? {
+    ///     println!("{} index {} fee {}%", address, config.index, config.trade_fee_fraction() * 100.0);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_clmm_configs(&self) -> Result<Vec<(Pubkey, ClmmConfig)>, RaydiumError> {
+        let program = self.program_id(PoolKind::Clmm);
+        let fetch = async {
+            let filters = vec![
+                RpcFilterType::DataSize(CLMM_CONFIG_DATA_SIZE as u64),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, CLMM_CONFIG_DISCRIMINATOR.to_vec())),
+            ];
+            let mut configs = self
+                .get_program_accounts_with_filters(&program, filters)
+                .await?
+                .into_iter()
+                .map(|(address, data)| Ok((address, ClmmConfig::parse(&data)?)))
+                .collect::<Result<Vec<_>, RaydiumError>>()?;
+            configs.sort_by_key(|(_, config)| (config.trade_fee_rate, config.index));
+            Ok(configs)
+        };
+        self.clmm_config_cache.get_or_fetch(program, fetch).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_clmm_config() {
+        let mut data = vec![0u8; CLMM_CONFIG_DATA_SIZE];
+        let mut put =
+            |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        let (owner, fund_owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        put(0, &CLMM_CONFIG_DISCRIMINATOR);
+        put(8, &[251]);
+        put(9, &4u16.to_le_bytes());
+        put(11, owner.as_ref());
+        put(43, &120_000u32.to_le_bytes());
+        put(47, &2_500u32.to_le_bytes());
+        put(51, &60u16.to_le_bytes());
+        put(53, &40_000u32.to_le_bytes());
+        put(61, fund_owner.as_ref());
+        assert_eq!(
+            ClmmConfig::parse(&data),
+            Ok(ClmmConfig {
+                bump: 251,
+                index: 4,
+                owner,
+                protocol_fee_rate: 120_000,
+                trade_fee_rate: 2_500,
+                tick_spacing: 60,
+                fund_fee_rate: 40_000,
+                fund_owner,
+            })
+        );
+        assert!(ClmmConfig::parse(&data[..116]).is_err());
+        data[0] ^= 1;
+        assert!(matches!(
+            ClmmConfig::parse(&data),
+            Err(RaydiumError::WrongDiscriminator { .. })
+        ));
+    }
+
+    /// the 0.25% tier: spacing 60, 12% of the fee to the protocol and 4% to the fund. synthetic
+    /// bytes with the values of the mainnet config
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_get_amm_config() {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+
+        use crate::{
+            programs::RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+            test_utils::{mock_raydium, multiple_accounts_response, ui_account},
+        };
+
+        let mut data = vec![0u8; CLMM_CONFIG_DATA_SIZE];
+        data[..8].copy_from_slice(&CLMM_CONFIG_DISCRIMINATOR);
+        data[43..47].copy_from_slice(&120_000u32.to_le_bytes());
+        data[47..51].copy_from_slice(&2_500u32.to_le_bytes());
+        data[51..53].copy_from_slice(&60u16.to_le_bytes());
+        data[53..57].copy_from_slice(&40_000u32.to_le_bytes());
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![Some(ui_account(
+                &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+                data,
+            ))]),
+        );
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![None]),
+        );
+        let client = mock_raydium(mocks).solana_client.client_arc();
+        let pool = RaydiumLiquidityPoolCLMMData {
+            amm_config: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let config = pool.get_amm_config(Arc::clone(&client)).await.unwrap();
+        assert_eq!((config.trade_fee_rate, config.tick_spacing), (2_500, 60));
+        assert_eq!(config.trade_fee_fraction(), 0.0025);
+        assert_eq!(config.protocol_fee_fraction(), 0.12);
+        assert_eq!(config.fund_fee_fraction(), 0.04);
+        assert_eq!(
+            pool.get_amm_config(client).await,
+            Err(RaydiumError::AccountNotFound(pool.amm_config))
+        );
+    }
+
+    /// a 1% and a 0.01% tier returned out of order come back sorted by fee, then from the cache
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_list_clmm_configs() -> Result<(), Box<dyn std::error::Error>> {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+
+        use crate::{
+            programs::RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+            test_utils::{mock_raydium, ui_account},
+        };
+
+        let config = |address: &Pubkey, index: u16, trade_fee_rate: u32, tick_spacing: u16| {
+            let mut data = vec![0u8; CLMM_CONFIG_DATA_SIZE];
+            data[..8].copy_from_slice(&CLMM_CONFIG_DISCRIMINATOR);
+            data[9..11].copy_from_slice(&index.to_le_bytes());
+            data[47..51].copy_from_slice(&trade_fee_rate.to_le_bytes());
+            data[51..53].copy_from_slice(&tick_spacing.to_le_bytes());
+            serde_json::json!({
+                "pubkey": address.to_string(),
+                "account": ui_account(&RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID, data),
+            })
+        };
+        let (high, low) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // a single response is queued, a second rpc call would get an account that is no config
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetProgramAccounts,
+            serde_json::json!([config(&high, 2, 10_000, 120), config(&low, 5, 100, 1)]),
+        );
+        let raydium = mock_raydium(mocks);
+        let configs = raydium.list_clmm_configs().await?;
+        let tiers: Vec<(Pubkey, u32, u16)> = configs
+            .iter()
+            .map(|(address, config)| (*address, config.trade_fee_rate, config.tick_spacing))
+            .collect();
+        assert_eq!(tiers, vec![(low, 100, 1), (high, 10_000, 120)]);
+        assert_eq!(raydium.list_clmm_configs().await?, configs);
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+//! 256-bit intermediate arithmetic for the CLMM fixed-point math, so products of u128 values
+//! are divided without overflow or loss
+
+/// rounding direction of a division
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// unsigned integer of three u128 words, most significant first
+pub(crate) type U384 = [u128; 3];
+
+const LOW_64: u128 = u64::MAX as u128;
+
+/// the full 256-bit product of two u128 values
+pub(crate) fn full_mul(a: u128, b: u128) -> U384 {
+    let (a1, a0) = (a >> 64, a & LOW_64);
+    let (b1, b0) = (b >> 64, b & LOW_64);
+    let (p00, p01, p10, p11) = (a0 * b0, a0 * b1, a1 * b0, a1 * b1);
+    let mid = (p00 >> 64) + (p01 & LOW_64) + (p10 & LOW_64);
+    let lo = (p00 & LOW_64) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    [0, hi, lo]
+}
+
+/// `n * 2^64`, dropping the bits shifted out of the top word
+pub(crate) fn shl_64(n: U384) -> U384 {
+    [
+        (n[0] << 64) | (n[1] >> 64),
+        (n[1] << 64) | (n[2] >> 64),
+        n[2] << 64,
+    ]
+}
+
+/// quotient of `n / d` rounded in the given direction, `None` when `d` is zero or rounding up
+/// overflows
+pub(crate) fn div(n: U384, d: u128, rounding: Rounding) -> Option<U384> {
+    if d == 0 {
+        return None;
+    }
+    let mut quotient = [0u128; 3];
+    let mut rem = 0u128;
+    for (word, q) in n.iter().zip(quotient.iter_mut()) {
+        for i in (0..128).rev() {
+            // rem < d, so when the shift carries out the value exceeds d
+            let carry = rem >> 127;
+            rem = (rem << 1) | ((word >> i) & 1);
+            *q <<= 1;
+            if carry == 1 || rem >= d {
+                rem = rem.wrapping_sub(d);
+                *q |= 1;
+            }
+        }
+    }
+    if rounding == Rounding::Up && rem != 0 {
+        return add(quotient, [0, 0, 1]);
+    }
+    Some(quotient)
+}
+
+/// `a + b`, `None` on overflow
+pub(crate) fn add(a: U384, b: U384) -> Option<U384> {
+    let (lo, carry_lo) = a[2].overflowing_add(b[2]);
+    let (mid, carry_mid) = a[1].overflowing_add(b[1]);
+    let (mid, carry_lo_mid) = mid.overflowing_add(carry_lo as u128);
+    let hi = a[0]
+        .checked_add(b[0])?
+        .checked_add((carry_mid || carry_lo_mid) as u128)?;
+    Some([hi, mid, lo])
+}
+
+/// `a - b`, `None` when `b` is larger
+pub(crate) fn sub(a: U384, b: U384) -> Option<U384> {
+    if a < b {
+        return None;
+    }
+    let (lo, borrow_lo) = a[2].overflowing_sub(b[2]);
+    let (mid, borrow_mid) = a[1].overflowing_sub(b[1]);
+    let (mid, borrow_lo_mid) = mid.overflowing_sub(borrow_lo as u128);
+    Some([a[0] - b[0] - (borrow_mid || borrow_lo_mid) as u128, mid, lo])
+}
+
+/// `a * b`, `None` on overflow
+pub(crate) fn mul(a: U384, b: u128) -> Option<U384> {
+    let [_, hi_0, lo_0] = full_mul(a[0], b);
+    let [_, hi_1, lo_1] = full_mul(a[1], b);
+    let [_, hi_2, lo_2] = full_mul(a[2], b);
+    if hi_0 != 0 {
+        return None;
+    }
+    add(add([0, hi_2, lo_2], [hi_1, lo_1, 0])?, [lo_0, 0, 0])
+}
+
+/// quotient of `n / d` for a divisor wider than a u128, rounded in the given direction.
+/// `None` when `d` is zero or its top bit is set
+pub(crate) fn div_wide(n: U384, d: U384, rounding: Rounding) -> Option<U384> {
+    if d == [0; 3] || d[0] >> 127 != 0 {
+        return None;
+    }
+    let mut quotient = [0u128; 3];
+    let mut rem = [0u128; 3];
+    for (w, word) in n.iter().enumerate() {
+        for i in (0..128).rev() {
+            rem = [
+                (rem[0] << 1) | (rem[1] >> 127),
+                (rem[1] << 1) | (rem[2] >> 127),
+                (rem[2] << 1) | ((word >> i) & 1),
+            ];
+            if rem >= d {
+                rem = sub(rem, d)?;
+                quotient[w] |= 1 << i;
+            }
+        }
+    }
+    if rounding == Rounding::Up && rem != [0; 3] {
+        return add(quotient, [0, 0, 1]);
+    }
+    Some(quotient)
+}
+
+/// the value as a u128, `None` when it does not fit
+pub(crate) fn to_u128(n: U384) -> Option<u128> {
+    (n[0] == 0 && n[1] == 0).then_some(n[2])
+}
+
+/// `a * b / denominator` without intermediate overflow, rounded in the given direction.
+/// `None` when the denominator is zero or the result does not fit a u128
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::full_math::{mul_div, Rounding};
+/// assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX, Rounding::Down), Some(u128::MAX));
+/// assert_eq!(mul_div(7, 3, 2, Rounding::Down), Some(10));
+/// assert_eq!(mul_div(7, 3, 2, Rounding::Up), Some(11));
+/// ```
+pub fn mul_div(a: u128, b: u128, denominator: u128, rounding: Rounding) -> Option<u128> {
+    to_u128(div(full_mul(a, b), denominator, rounding)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_div() {
+        assert_eq!(full_mul(u128::MAX, u128::MAX), [0, u128::MAX - 1, 1]);
+        assert_eq!(
+            mul_div(u128::MAX, 2, 4, Rounding::Down),
+            Some(u128::MAX / 2)
+        );
+        assert_eq!(
+            mul_div(u128::MAX, 2, 4, Rounding::Up),
+            Some(u128::MAX / 2 + 1)
+        );
+        assert_eq!(mul_div(u128::MAX, 2, 1, Rounding::Down), None);
+        assert_eq!(mul_div(1, 1, 0, Rounding::Down), None);
+        // 2^256 / 3 needs all three words
+        let n = shl_64(shl_64(full_mul(1 << 64, 1 << 64)));
+        assert_eq!(n, [1, 0, 0]);
+        let third = div(n, 3, Rounding::Down).unwrap();
+        assert_eq!(third, [0, u128::MAX / 3, u128::MAX / 3]);
+        assert_eq!(div(n, 3, Rounding::Up).unwrap()[2], u128::MAX / 3 + 1);
+        // a 2^128 + 1 divisor takes the wide division
+        let d = [0, 1, 1];
+        let product = mul(d, u128::MAX).unwrap();
+        assert_eq!(product, [0, u128::MAX, u128::MAX]);
+        assert_eq!(
+            div_wide(product, d, Rounding::Down),
+            Some([0, 0, u128::MAX])
+        );
+        let plus_one = add(product, [0, 0, 1]).unwrap();
+        assert_eq!(plus_one, [1, 0, 0]);
+        assert_eq!(
+            div_wide(plus_one, d, Rounding::Down),
+            Some([0, 0, u128::MAX])
+        );
+        assert_eq!(div_wide(plus_one, d, Rounding::Up), Some([0, 1, 0]));
+        assert_eq!(sub(plus_one, product), Some([0, 0, 1]));
+        assert_eq!(sub(product, plus_one), None);
+        assert_eq!(mul([1, 0, 0], 1 << 127), Some([1 << 127, 0, 0]));
+        assert_eq!(mul([2, 0, 0], 1 << 127), None);
+    }
+}
@@ -0,0 +1,1170 @@
+//! CLMM instruction builders
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::RaydiumError,
+    liquidity::clmm::{
+        ClmmSwapQuoteAccounts, PersonalPositionData, RaydiumLiquidityPoolCLMMData,
+        pda::{
+            personal_position_address, protocol_position_address, tick_array_address,
+            tick_array_bitmap_extension_address,
+        },
+        tick_array::{check_tick_range, is_in_pool_bitmap, tick_array_start_index},
+    },
+    metadata::metadata_address,
+    programs::{
+        ASSOCIATED_TOKEN_PROGRAM_ID, MEMO_PROGRAM_ID, METAPLEX_METADATA_PROGRAM_ID,
+        SYSTEM_PROGRAM_ID, SYSVAR_RENT_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    token_accounts::{associated_token_address, create_associated_token_account_idempotent},
+};
+
+/// anchor discriminator of `swap_v2`
+pub const SWAP_V2_DISCRIMINATOR: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
+/// anchor discriminator of `open_position_v2`, the position NFT being an SPL token
+pub const OPEN_POSITION_V2_DISCRIMINATOR: [u8; 8] = [77, 184, 74, 214, 112, 86, 241, 199];
+/// anchor discriminator of `open_position_with_token22_nft`
+pub const OPEN_POSITION_WITH_TOKEN22_NFT_DISCRIMINATOR: [u8; 8] =
+    [77, 255, 174, 82, 125, 29, 201, 46];
+/// anchor discriminator of `increase_liquidity_v2`
+pub const INCREASE_LIQUIDITY_V2_DISCRIMINATOR: [u8; 8] = [133, 29, 89, 223, 69, 238, 176, 10];
+/// anchor discriminator of `decrease_liquidity_v2`
+pub const DECREASE_LIQUIDITY_V2_DISCRIMINATOR: [u8; 8] = [58, 127, 188, 62, 79, 82, 196, 96];
+/// anchor discriminator of `close_position`
+pub const CLOSE_POSITION_DISCRIMINATOR: [u8; 8] = [123, 134, 81, 0, 49, 68, 98, 98];
+
+/// swap `input_mint` from `user_input` into `user_output`. `amount` is the input when
+/// `is_base_input`, failing when the output is below `other_amount_threshold`, and the output
+/// otherwise, failing when the input is above it. a zero `sqrt_price_limit_x64` lets the price
+/// move to the end of the tick range. `tick_arrays` are the arrays the swap crosses in swap
+/// order, after the bitmap extension of the pool when the swap may need it. both token programs
+/// are passed so token-2022 mints swap too. `None` when `input_mint` is not in the pool
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{liquidity::clmm::{RaydiumLiquidityPoolCLMMData, instructions::swap_v2}, programs::RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(pool_id: Pubkey, pool: RaydiumLiquidityPoolCLMMData, owner: Pubkey, input: Pubkey, output: Pubkey, tick_array: Pubkey) {
+/// let instruction = swap_v2(
+///     &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+///     &pool_id,
+///     &pool,
+///     &owner,
+///     &pool.token_mint_0,
+///     &input,
+///     &output,
+///     None,
+///     &[tick_array],
+///     1_000_000,
+///     990_000,
+///     0,
+///     true,
+/// );
+/// # }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn swap_v2(
+    program_id: &Pubkey,
+    pool_id: &Pubkey,
+    pool: &RaydiumLiquidityPoolCLMMData,
+    payer: &Pubkey,
+    input_mint: &Pubkey,
+    user_input: &Pubkey,
+    user_output: &Pubkey,
+    bitmap_extension: Option<&Pubkey>,
+    tick_arrays: &[Pubkey],
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+) -> Option<Instruction> {
+    let token_0 = (pool.token_mint_0, pool.token_vault_0);
+    let token_1 = (pool.token_mint_1, pool.token_vault_1);
+    let (input, output) = if *input_mint == pool.token_mint_0 {
+        (token_0, token_1)
+    } else if *input_mint == pool.token_mint_1 {
+        (token_1, token_0)
+    } else {
+        return None;
+    };
+    let mut data = SWAP_V2_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&other_amount_threshold.to_le_bytes());
+    data.extend_from_slice(&sqrt_price_limit_x64.to_le_bytes());
+    data.push(is_base_input as u8);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*payer, true),
+        AccountMeta::new_readonly(pool.amm_config, false),
+        AccountMeta::new(*pool_id, false),
+        AccountMeta::new(*user_input, false),
+        AccountMeta::new(*user_output, false),
+        AccountMeta::new(input.1, false),
+        AccountMeta::new(output.1, false),
+        AccountMeta::new(pool.observation_key, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false),
+        AccountMeta::new_readonly(MEMO_PROGRAM_ID, false),
+        AccountMeta::new_readonly(input.0, false),
+        AccountMeta::new_readonly(output.0, false),
+    ];
+    accounts.extend(
+        bitmap_extension
+            .into_iter()
+            .chain(tick_arrays)
+            .map(|address| AccountMeta::new(*address, false)),
+    );
+    Some(Instruction::new_with_bytes(*program_id, &data, accounts))
+}
+
+/// the `swap_v2` of a quote from `Raydium::quote_swap_clmm`, with its tick arrays and bitmap
+/// extension. `other_amount_threshold` bounds the side of the quote that was not specified,
+/// usually the quoted amount with slippage applied
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{liquidity::clmm::{ClmmSwapQuoteAccounts, RaydiumLiquidityPoolCLMMData, instructions::swap_v2_from_quote}, programs::RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(pool_id: Pubkey, pool: RaydiumLiquidityPoolCLMMData, quoted: ClmmSwapQuoteAccounts, owner: Pubkey, input: Pubkey, output: Pubkey) {
+/// // `quoted` from `raydium.quote_swap_clmm(..)`, accepting 1% less output
+/// let minimum_out = quoted.quote.amount_out / 100 * 99;
+/// let instruction = swap_v2_from_quote(
+///     &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+///     &pool_id,
+///     &pool,
+///     &owner,
+///     &input,
+///     &output,
+///     &quoted,
+///     minimum_out,
+/// );
+/// # }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn swap_v2_from_quote(
+    program_id: &Pubkey,
+    pool_id: &Pubkey,
+    pool: &RaydiumLiquidityPoolCLMMData,
+    payer: &Pubkey,
+    user_input: &Pubkey,
+    user_output: &Pubkey,
+    quoted: &ClmmSwapQuoteAccounts,
+    other_amount_threshold: u64,
+) -> Option<Instruction> {
+    let quote = &quoted.quote;
+    let amount = if quote.is_exact_in {
+        quote.amount_in
+    } else {
+        quote.amount_out
+    };
+    swap_v2(
+        program_id,
+        pool_id,
+        pool,
+        payer,
+        &quote.input_mint,
+        user_input,
+        user_output,
+        quoted.bitmap_extension.as_ref(),
+        &quoted.tick_arrays,
+        amount,
+        other_amount_threshold,
+        0,
+        quote.is_exact_in,
+    )
+}
+
+/// accounts of a position range the program checks
+struct RangeAccounts {
+    start_lower: i32,
+    start_upper: i32,
+    tick_array_lower: Pubkey,
+    tick_array_upper: Pubkey,
+    protocol_position: Pubkey,
+    /// the bitmap extension of the pool when a bound is past the pool bitmap
+    bitmap_extension: Option<Pubkey>,
+}
+
+impl RangeAccounts {
+    fn new(
+        program_id: &Pubkey,
+        pool_id: &Pubkey,
+        pool: &RaydiumLiquidityPoolCLMMData,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Self {
+        let start_lower = tick_array_start_index(tick_lower, pool.tick_spacing);
+        let start_upper = tick_array_start_index(tick_upper, pool.tick_spacing);
+        let in_pool_bitmap = is_in_pool_bitmap(start_lower, pool.tick_spacing)
+            && is_in_pool_bitmap(start_upper, pool.tick_spacing);
+        Self {
+            start_lower,
+            start_upper,
+            tick_array_lower: tick_array_address(pool_id, start_lower, program_id),
+            tick_array_upper: tick_array_address(pool_id, start_upper, program_id),
+            protocol_position: protocol_position_address(
+                pool_id, tick_lower, tick_upper, program_id,
+            ),
+            bitmap_extension: (!in_pool_bitmap)
+                .then(|| tick_array_bitmap_extension_address(pool_id, program_id)),
+        }
+    }
+}
+
+/// a position to open on a CLMM pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NewPosition {
+    /// mint of the position NFT, a new keypair that signs the transaction
+    pub nft_mint: Pubkey,
+    /// `TOKEN_PROGRAM_ID` for an NFT described by a Metaplex metadata account,
+    /// `TOKEN_2022_PROGRAM_ID` for one carrying its metadata in the mint
+    pub nft_token_program: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u128,
+    /// most token 0 the deposit may take, the quoted amount plus slippage
+    pub amount_0_max: u64,
+    /// most token 1 the deposit may take
+    pub amount_1_max: u64,
+    /// whether the NFT gets metadata
+    pub with_metadata: bool,
+}
+
+/// instructions opening a CLMM position and the accounts of the position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenPosition {
+    /// creation of the token accounts of the user for both pool mints, then the opening
+    pub instructions: Vec<Instruction>,
+    /// account of the user holding the position NFT
+    pub nft_account: Pubkey,
+    pub personal_position: Pubkey,
+    pub protocol_position: Pubkey,
+}
+
+/// open a position of `pool` for `user`, who pays and receives the position NFT, depositing
+/// from its associated token accounts of the pool mints. `token_programs` are the programs of
+/// token 0 and token 1, the accounts being created first when missing since a range out of
+/// the price deposits a single token. the tick arrays of both bounds, the protocol position and
+/// the bitmap extension when a bound is past the pool bitmap are derived. errors with
+/// `InvalidTickRange` before building when the range is inverted or not aligned
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{error::RaydiumError, liquidity::clmm::{RaydiumLiquidityPoolCLMMData, instructions::{NewPosition, open_position}, math::{size_position, tick_range_around, tick_to_sqrt_price_x64}}, programs::{RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID, TOKEN_PROGRAM_ID}};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(pool_id: Pubkey, pool: RaydiumLiquidityPoolCLMMData, user: Pubkey, nft_mint: Pubkey) -> Result<(), RaydiumError> {
+/// // 1000 of token 1 (6 decimals) across about ±5%
+/// let (tick_lower, tick_upper) = tick_range_around(pool.tick_current, pool.tick_spacing, 0.05, 0.05);
+/// let size = size_position(
+///     pool.sqrt_price_x64,
+///     tick_to_sqrt_price_x64(tick_lower).unwrap(),
+///     tick_to_sqrt_price_x64(tick_upper).unwrap(),
+///     1_000_000_000,
+/// )
+/// .unwrap();
+/// let opened = open_position(
+///     &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+///     &pool_id,
+///     &pool,
+///     &user,
+///     [TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID],
+///     &NewPosition {
+///         nft_mint,
+///         nft_token_program: TOKEN_PROGRAM_ID,
+///         tick_lower,
+///         tick_upper,
+///         liquidity: size.liquidity,
+///         // 1% slippage
+///         amount_0_max: size.amount_0 / 100 * 101,
+///         amount_1_max: size.amount_1 / 100 * 101,
+///         with_metadata: true,
+///     },
+/// )?;
+/// println!("position {}", opened.personal_position);
+/// # Ok(())
+/// # }
+/// ```
+pub fn open_position(
+    program_id: &Pubkey,
+    pool_id: &Pubkey,
+    pool: &RaydiumLiquidityPoolCLMMData,
+    user: &Pubkey,
+    token_programs: [Pubkey; 2],
+    position: &NewPosition,
+) -> Result<OpenPosition, RaydiumError> {
+    let (tick_lower, tick_upper) = (position.tick_lower, position.tick_upper);
+    check_tick_range(tick_lower, tick_upper, pool.tick_spacing)?;
+    let range = RangeAccounts::new(program_id, pool_id, pool, tick_lower, tick_upper);
+    let nft_mint = &position.nft_mint;
+    let token_2022_nft = position.nft_token_program == TOKEN_2022_PROGRAM_ID;
+    let nft_account = associated_token_address(user, nft_mint, &position.nft_token_program);
+    let personal_position = personal_position_address(nft_mint, program_id);
+    let user_token_0 = associated_token_address(user, &pool.token_mint_0, &token_programs[0]);
+    let user_token_1 = associated_token_address(user, &pool.token_mint_1, &token_programs[1]);
+
+    let mut data = if token_2022_nft {
+        OPEN_POSITION_WITH_TOKEN22_NFT_DISCRIMINATOR.to_vec()
+    } else {
+        OPEN_POSITION_V2_DISCRIMINATOR.to_vec()
+    };
+    for value in [tick_lower, tick_upper, range.start_lower, range.start_upper] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(&position.liquidity.to_le_bytes());
+    data.extend_from_slice(&position.amount_0_max.to_le_bytes());
+    data.extend_from_slice(&position.amount_1_max.to_le_bytes());
+    data.push(position.with_metadata as u8);
+    // no base_flag, the liquidity is given
+    data.push(0);
+
+    let mut accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(*user, false),
+        AccountMeta::new(*nft_mint, true),
+        AccountMeta::new(nft_account, false),
+    ];
+    if !token_2022_nft {
+        accounts.push(AccountMeta::new(metadata_address(nft_mint), false));
+    }
+    accounts.extend([
+        AccountMeta::new(*pool_id, false),
+        AccountMeta::new(range.protocol_position, false),
+        AccountMeta::new(range.tick_array_lower, false),
+        AccountMeta::new(range.tick_array_upper, false),
+        AccountMeta::new(personal_position, false),
+        AccountMeta::new(user_token_0, false),
+        AccountMeta::new(user_token_1, false),
+        AccountMeta::new(pool.token_vault_0, false),
+        AccountMeta::new(pool.token_vault_1, false),
+        AccountMeta::new_readonly(SYSVAR_RENT_ID, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+    ]);
+    if !token_2022_nft {
+        accounts.push(AccountMeta::new_readonly(
+            METAPLEX_METADATA_PROGRAM_ID,
+            false,
+        ));
+    }
+    accounts.extend([
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false),
+        AccountMeta::new_readonly(pool.token_mint_0, false),
+        AccountMeta::new_readonly(pool.token_mint_1, false),
+    ]);
+    accounts.extend(
+        range
+            .bitmap_extension
+            .map(|address| AccountMeta::new(address, false)),
+    );
+    Ok(OpenPosition {
+        instructions: vec![
+            create_associated_token_account_idempotent(
+                user,
+                user,
+                &pool.token_mint_0,
+                &token_programs[0],
+            ),
+            create_associated_token_account_idempotent(
+                user,
+                user,
+                &pool.token_mint_1,
+                &token_programs[1],
+            ),
+            Instruction::new_with_bytes(*program_id, &data, accounts),
+        ],
+        nft_account,
+        personal_position,
+        protocol_position: range.protocol_position,
+    })
+}
+
+/// add `liquidity` to an existing position held by `user`, depositing at most `amount_0_max`
+/// and `amount_1_max` from its associated token accounts of the pool mints, owned by
+/// `token_programs`. `nft_token_program` owns the position NFT. the tick arrays, the protocol
+/// position and the bitmap extension are derived from the position range like the program
+/// expects. see `math::liquidity_from_amounts` to size the liquidity from the amounts
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{liquidity::clmm::{PersonalPositionData, RaydiumLiquidityPoolCLMMData, instructions::increase_liquidity, math::{liquidity_from_amounts, tick_to_sqrt_price_x64}}, programs::{RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID, TOKEN_PROGRAM_ID}};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(position: PersonalPositionData, pool: RaydiumLiquidityPoolCLMMData, user: Pubkey) {
+/// let liquidity = liquidity_from_amounts(
+///     pool.sqrt_price_x64,
+///     tick_to_sqrt_price_x64(position.tick_lower_index).unwrap(),
+///     tick_to_sqrt_price_x64(position.tick_upper_index).unwrap(),
+///     1_000_000_000,
+///     150_000_000,
+/// )
+/// .unwrap();
+/// let instruction = increase_liquidity(
+///     &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+///     &position,
+///     &pool,
+///     &user,
+///     [TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID],
+///     &TOKEN_PROGRAM_ID,
+///     liquidity,
+///     1_010_000_000,
+///     151_500_000,
+/// );
+/// # }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn increase_liquidity(
+    program_id: &Pubkey,
+    position: &PersonalPositionData,
+    pool: &RaydiumLiquidityPoolCLMMData,
+    user: &Pubkey,
+    token_programs: [Pubkey; 2],
+    nft_token_program: &Pubkey,
+    liquidity_delta: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+) -> Instruction {
+    let pool_id = &position.pool_id;
+    let range = RangeAccounts::new(
+        program_id,
+        pool_id,
+        pool,
+        position.tick_lower_index,
+        position.tick_upper_index,
+    );
+    let mut data = INCREASE_LIQUIDITY_V2_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&liquidity_delta.to_le_bytes());
+    data.extend_from_slice(&amount_0_max.to_le_bytes());
+    data.extend_from_slice(&amount_1_max.to_le_bytes());
+    // no base_flag, the liquidity is given
+    data.push(0);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new_readonly(
+            associated_token_address(user, &position.nft_mint, nft_token_program),
+            false,
+        ),
+        AccountMeta::new(*pool_id, false),
+        AccountMeta::new(range.protocol_position, false),
+        AccountMeta::new(
+            personal_position_address(&position.nft_mint, program_id),
+            false,
+        ),
+        AccountMeta::new(range.tick_array_lower, false),
+        AccountMeta::new(range.tick_array_upper, false),
+        AccountMeta::new(
+            associated_token_address(user, &pool.token_mint_0, &token_programs[0]),
+            false,
+        ),
+        AccountMeta::new(
+            associated_token_address(user, &pool.token_mint_1, &token_programs[1]),
+            false,
+        ),
+        AccountMeta::new(pool.token_vault_0, false),
+        AccountMeta::new(pool.token_vault_1, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false),
+        AccountMeta::new_readonly(pool.token_mint_0, false),
+        AccountMeta::new_readonly(pool.token_mint_1, false),
+    ];
+    accounts.extend(
+        range
+            .bitmap_extension
+            .map(|address| AccountMeta::new(address, false)),
+    );
+    Instruction::new_with_bytes(*program_id, &data, accounts)
+}
+
+/// remove `liquidity` from a position held by `user`, failing on chain when less than
+/// `amount_0_min` or `amount_1_min` comes out. the program also pays the fees and rewards owed,
+/// so a zero `liquidity` only collects them. tokens go to the associated token accounts of the
+/// user, each owned by the token program of its mint: `token_programs` for the pool mints and
+/// `reward_token_programs` for the mints of the active reward slots, whose vault, account and
+/// mint follow the bitmap extension in the remaining accounts
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{liquidity::clmm::{PersonalPositionData, RaydiumLiquidityPoolCLMMData, instructions::decrease_liquidity}, programs::{RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID, TOKEN_PROGRAM_ID}};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(position: PersonalPositionData, pool: RaydiumLiquidityPoolCLMMData, user: Pubkey) {
+/// // remove half, accepting any amounts
+/// let instruction = decrease_liquidity(
+///     &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+///     &position,
+///     &pool,
+///     &user,
+///     [TOKEN_PROGRAM_ID; 2],
+///     [TOKEN_PROGRAM_ID; 3],
+///     &TOKEN_PROGRAM_ID,
+///     position.liquidity / 2,
+///     0,
+///     0,
+/// );
+/// # }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn decrease_liquidity(
+    program_id: &Pubkey,
+    position: &PersonalPositionData,
+    pool: &RaydiumLiquidityPoolCLMMData,
+    user: &Pubkey,
+    token_programs: [Pubkey; 2],
+    reward_token_programs: [Pubkey; 3],
+    nft_token_program: &Pubkey,
+    liquidity_delta: u128,
+    amount_0_min: u64,
+    amount_1_min: u64,
+) -> Instruction {
+    let pool_id = &position.pool_id;
+    let range = RangeAccounts::new(
+        program_id,
+        pool_id,
+        pool,
+        position.tick_lower_index,
+        position.tick_upper_index,
+    );
+    let mut data = DECREASE_LIQUIDITY_V2_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&liquidity_delta.to_le_bytes());
+    data.extend_from_slice(&amount_0_min.to_le_bytes());
+    data.extend_from_slice(&amount_1_min.to_le_bytes());
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new_readonly(
+            associated_token_address(user, &position.nft_mint, nft_token_program),
+            false,
+        ),
+        AccountMeta::new(
+            personal_position_address(&position.nft_mint, program_id),
+            false,
+        ),
+        AccountMeta::new(*pool_id, false),
+        AccountMeta::new(range.protocol_position, false),
+        AccountMeta::new(pool.token_vault_0, false),
+        AccountMeta::new(pool.token_vault_1, false),
+        AccountMeta::new(range.tick_array_lower, false),
+        AccountMeta::new(range.tick_array_upper, false),
+        AccountMeta::new(
+            associated_token_address(user, &pool.token_mint_0, &token_programs[0]),
+            false,
+        ),
+        AccountMeta::new(
+            associated_token_address(user, &pool.token_mint_1, &token_programs[1]),
+            false,
+        ),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false),
+        AccountMeta::new_readonly(MEMO_PROGRAM_ID, false),
+        AccountMeta::new_readonly(pool.token_mint_0, false),
+        AccountMeta::new_readonly(pool.token_mint_1, false),
+    ];
+    accounts.extend(
+        range
+            .bitmap_extension
+            .map(|address| AccountMeta::new(address, false)),
+    );
+    for (reward, token_program) in pool.reward_infos.iter().zip(&reward_token_programs) {
+        if reward.token_mint == Pubkey::default() {
+            continue;
+        }
+        accounts.extend([
+            AccountMeta::new(reward.token_vault, false),
+            AccountMeta::new(
+                associated_token_address(user, &reward.token_mint, token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(reward.token_mint, false),
+        ]);
+    }
+    Instruction::new_with_bytes(*program_id, &data, accounts)
+}
+
+/// close a position emptied of its liquidity, fees and rewards, burning the position NFT and
+/// closing its account and the personal position, the rent returning to `user`
+pub fn close_position(
+    program_id: &Pubkey,
+    position: &PersonalPositionData,
+    user: &Pubkey,
+    nft_token_program: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &CLOSE_POSITION_DISCRIMINATOR,
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(position.nft_mint, false),
+            AccountMeta::new(
+                associated_token_address(user, &position.nft_mint, nft_token_program),
+                false,
+            ),
+            AccountMeta::new(
+                personal_position_address(&position.nft_mint, program_id),
+                false,
+            ),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*nft_token_program, false),
+        ],
+    )
+}
+
+/// withdraw everything from a position and close it: creation of the token accounts the
+/// withdrawal pays into when missing, removal of all the liquidity at the current amounts less
+/// `slippage_bps`, which also collects the fees and rewards owed, then the closing. `None` when
+/// the position amounts cannot be computed
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{liquidity::clmm::{PersonalPositionData, RaydiumLiquidityPoolCLMMData, instructions::withdraw_all}, programs::{RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID, TOKEN_PROGRAM_ID}};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(position: PersonalPositionData, pool: RaydiumLiquidityPoolCLMMData, user: Pubkey) {
+/// let instructions = withdraw_all(
+///     &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+///     &position,
+///     &pool,
+///     &user,
+///     [TOKEN_PROGRAM_ID; 2],
+///     [TOKEN_PROGRAM_ID; 3],
+///     &TOKEN_PROGRAM_ID,
+///     50,
+/// )
+/// .unwrap();
+/// # }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_all(
+    program_id: &Pubkey,
+    position: &PersonalPositionData,
+    pool: &RaydiumLiquidityPoolCLMMData,
+    user: &Pubkey,
+    token_programs: [Pubkey; 2],
+    reward_token_programs: [Pubkey; 3],
+    nft_token_program: &Pubkey,
+    slippage_bps: u16,
+) -> Option<Vec<Instruction>> {
+    let (amount_0, amount_1) = position.token_amounts(pool)?;
+    let kept = 10_000 - slippage_bps.min(10_000) as u128;
+    let minimum = |amount: u64| (amount as u128 * kept / 10_000) as u64;
+    let mut instructions: Vec<Instruction> = [
+        (pool.token_mint_0, token_programs[0]),
+        (pool.token_mint_1, token_programs[1]),
+    ]
+    .into_iter()
+    .chain(
+        pool.reward_infos
+            .iter()
+            .map(|reward| reward.token_mint)
+            .zip(reward_token_programs)
+            .filter(|(mint, _)| *mint != Pubkey::default()),
+    )
+    .map(|(mint, token_program)| {
+        create_associated_token_account_idempotent(user, user, &mint, &token_program)
+    })
+    .collect();
+    instructions.push(decrease_liquidity(
+        program_id,
+        position,
+        pool,
+        user,
+        token_programs,
+        reward_token_programs,
+        nft_token_program,
+        position.liquidity,
+        minimum(amount_0),
+        minimum(amount_1),
+    ));
+    instructions.push(close_position(
+        program_id,
+        position,
+        user,
+        nft_token_program,
+    ));
+    Some(instructions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{liquidity::clmm::ClmmSwapQuote, programs::RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID};
+
+    /// a `swap_v2` selling 1 SOL for at least 149 USDC on the mainnet SOL-USDC pool 8sLbNZoA,
+    /// written out by hand: the pool, config, vaults, observation, bitmap extension and tick
+    /// arrays are the ones on chain, the wallet and its token accounts are an example.
+    /// the accounts carry their signer and writable flags, the data is hex
+    const SWAP_V2_FIXTURE: ([(&str, bool, bool); 16], &str) = (
+        [
+            ("US517G5965aydkZ46HS38QLi7UQiSojurfbQfKCELFx", true, false),
+            ("9iFER3bpjf1PTTCQCfTRu17EJgvsxo9pVyA9QWwEuX4x", false, false),
+            ("8sLbNZoA1cfnvMJLPfp98ZLAnFSYCFApfJKMbiXNLwxj", false, true),
+            ("Coz3LBGDD5czMN8CroEdxrDykE9CSnXBMyj7SZMwh82z", false, true),
+            ("7EJSueeCjseYzghxU2XhcGEUn7RJDh43Z2dL6dvGy9mw", false, true),
+            ("6P4tvbzRY6Bh3MiWDHuLqyHywovsRwRpfskPvyeSoHsz", false, true),
+            ("6mK4Pxs6GhwnessH7CvPivqDYauiHZmAdbEFDpXFk9zt", false, true),
+            ("3MsJXVvievxAbsMsaT6TS4i6oMitD9jazucuq3X234tC", false, true),
+            ("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA", false, false),
+            ("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb", false, false),
+            ("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr", false, false),
+            ("So11111111111111111111111111111111111111112", false, false),
+            ("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", false, false),
+            ("DoPuiZfJu7sypqwR4eiU7C5TMcmmiFoU4HaF5SoD8mRy", false, true),
+            ("EEWR16RhcfhQVwVd7RNsEtnd3KGpeBvecsJ3gh2AhJb8", false, true),
+            ("DWitKarMaeD1uDNX8vDHepQ1HC57oyLZrDBw1t9eAHzs", false, true),
+        ],
+        "2b04ed0b1ac91e6200ca9a3b00000000408fe108000000000000000000000000000000000000000001",
+    );
+
+    #[test]
+    fn test_swap_v2_mainnet_fixture() {
+        let (fixture, data) = SWAP_V2_FIXTURE;
+        let key = |i: usize| Pubkey::from_str_const(fixture[i].0);
+        let pool = RaydiumLiquidityPoolCLMMData {
+            amm_config: key(1),
+            token_mint_0: key(11),
+            token_mint_1: key(12),
+            token_vault_0: key(5),
+            token_vault_1: key(6),
+            observation_key: key(7),
+            ..Default::default()
+        };
+        let instruction = swap_v2(
+            &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+            &key(2),
+            &pool,
+            &key(0),
+            &pool.token_mint_0,
+            &key(3),
+            &key(4),
+            Some(&key(13)),
+            &[key(14), key(15)],
+            1_000_000_000,
+            149_000_000,
+            0,
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            instruction.program_id,
+            RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID
+        );
+        assert_eq!(instruction.data, hex::decode(data).unwrap());
+        let accounts: Vec<(Pubkey, bool, bool)> = instruction
+            .accounts
+            .iter()
+            .map(|a| (a.pubkey, a.is_signer, a.is_writable))
+            .collect();
+        let expected: Vec<(Pubkey, bool, bool)> = fixture
+            .iter()
+            .map(|(address, signer, writable)| {
+                (Pubkey::from_str_const(address), *signer, *writable)
+            })
+            .collect();
+        assert_eq!(accounts, expected);
+    }
+
+    #[test]
+    fn test_swap_v2() {
+        let pool = RaydiumLiquidityPoolCLMMData {
+            amm_config: Pubkey::new_unique(),
+            token_mint_0: Pubkey::new_unique(),
+            token_mint_1: Pubkey::new_unique(),
+            token_vault_0: Pubkey::new_unique(),
+            token_vault_1: Pubkey::new_unique(),
+            observation_key: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let (pool_id, payer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (input, output) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let extension = Pubkey::new_unique();
+        let tick_arrays = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let program = RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID;
+        let quoted = ClmmSwapQuoteAccounts {
+            quote: ClmmSwapQuote {
+                input_mint: pool.token_mint_1,
+                output_mint: pool.token_mint_0,
+                is_exact_in: false,
+                amount_in: 1_005_000,
+                amount_out: 1_000_000,
+                fee: 2_500,
+                sqrt_price_x64_after: 0,
+                tick_after: 0,
+                ticks_crossed: 0,
+                tick_arrays: vec![0, 3_600],
+            },
+            tick_arrays: tick_arrays.to_vec(),
+            bitmap_extension: Some(extension),
+        };
+        // an exact output swap selling token 1, at most 1_010_000 in
+        let instruction = swap_v2_from_quote(
+            &program, &pool_id, &pool, &payer, &input, &output, &quoted, 1_010_000,
+        )
+        .unwrap();
+        let mut data = vec![43, 4, 237, 11, 26, 201, 30, 98];
+        data.extend([0x40, 0x42, 0x0f, 0, 0, 0, 0, 0]);
+        data.extend([0x50, 0x69, 0x0f, 0, 0, 0, 0, 0]);
+        data.extend([0; 16]);
+        data.push(0);
+        assert_eq!(instruction.data, data);
+        let accounts: Vec<(Pubkey, bool, bool)> = instruction
+            .accounts
+            .iter()
+            .map(|a| (a.pubkey, a.is_signer, a.is_writable))
+            .collect();
+        assert_eq!(
+            accounts,
+            [
+                (payer, true, false),
+                (pool.amm_config, false, false),
+                (pool_id, false, true),
+                (input, false, true),
+                (output, false, true),
+                (pool.token_vault_1, false, true),
+                (pool.token_vault_0, false, true),
+                (pool.observation_key, false, true),
+                (TOKEN_PROGRAM_ID, false, false),
+                (TOKEN_2022_PROGRAM_ID, false, false),
+                (MEMO_PROGRAM_ID, false, false),
+                (pool.token_mint_1, false, false),
+                (pool.token_mint_0, false, false),
+                (extension, false, true),
+                (tick_arrays[0], false, true),
+                (tick_arrays[1], false, true),
+            ]
+        );
+        let missing = Pubkey::new_unique();
+        assert!(
+            swap_v2(
+                &program,
+                &pool_id,
+                &pool,
+                &payer,
+                &missing,
+                &input,
+                &output,
+                None,
+                &[],
+                1,
+                1,
+                0,
+                true
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_open_position() {
+        let pool = RaydiumLiquidityPoolCLMMData {
+            token_mint_0: Pubkey::new_unique(),
+            token_mint_1: Pubkey::new_unique(),
+            token_vault_0: Pubkey::new_unique(),
+            token_vault_1: Pubkey::new_unique(),
+            tick_spacing: 60,
+            ..Default::default()
+        };
+        let (pool_id, user, nft_mint) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let program = RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID;
+        let token_programs = [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID];
+        let mut position = NewPosition {
+            nft_mint,
+            nft_token_program: TOKEN_PROGRAM_ID,
+            tick_lower: -120,
+            tick_upper: 3_600,
+            liquidity: 1_000_000,
+            amount_0_max: 500,
+            amount_1_max: 700,
+            with_metadata: true,
+        };
+        let opened =
+            open_position(&program, &pool_id, &pool, &user, token_programs, &position).unwrap();
+        let [create_0, create_1, open] = &opened.instructions[..] else {
+            panic!("{:?}", opened.instructions);
+        };
+        assert_eq!(
+            create_1.accounts[1].pubkey,
+            associated_token_address(&user, &pool.token_mint_1, &TOKEN_2022_PROGRAM_ID)
+        );
+        assert_eq!(create_0.accounts[3].pubkey, pool.token_mint_0);
+        let mut data = OPEN_POSITION_V2_DISCRIMINATOR.to_vec();
+        for value in [-120i32, 3_600, -3_600, 3_600] {
+            data.extend(value.to_le_bytes());
+        }
+        data.extend(1_000_000u128.to_le_bytes());
+        data.extend(500u64.to_le_bytes());
+        data.extend(700u64.to_le_bytes());
+        data.extend([1, 0]);
+        assert_eq!(open.data, data);
+        let accounts: Vec<(Pubkey, bool, bool)> = open
+            .accounts
+            .iter()
+            .map(|a| (a.pubkey, a.is_signer, a.is_writable))
+            .collect();
+        assert_eq!(
+            accounts,
+            [
+                (user, true, true),
+                (user, false, false),
+                (nft_mint, true, true),
+                (opened.nft_account, false, true),
+                (metadata_address(&nft_mint), false, true),
+                (pool_id, false, true),
+                (
+                    protocol_position_address(&pool_id, -120, 3_600, &program),
+                    false,
+                    true
+                ),
+                (tick_array_address(&pool_id, -3_600, &program), false, true),
+                (tick_array_address(&pool_id, 3_600, &program), false, true),
+                (personal_position_address(&nft_mint, &program), false, true),
+                (create_0.accounts[1].pubkey, false, true),
+                (create_1.accounts[1].pubkey, false, true),
+                (pool.token_vault_0, false, true),
+                (pool.token_vault_1, false, true),
+                (SYSVAR_RENT_ID, false, false),
+                (SYSTEM_PROGRAM_ID, false, false),
+                (TOKEN_PROGRAM_ID, false, false),
+                (ASSOCIATED_TOKEN_PROGRAM_ID, false, false),
+                (METAPLEX_METADATA_PROGRAM_ID, false, false),
+                (TOKEN_2022_PROGRAM_ID, false, false),
+                (pool.token_mint_0, false, false),
+                (pool.token_mint_1, false, false),
+            ]
+        );
+        // a token-2022 NFT has no metadata account, a bound past the pool bitmap needs the
+        // extension
+        let narrow = RaydiumLiquidityPoolCLMMData {
+            tick_spacing: 1,
+            ..pool.clone()
+        };
+        position.nft_token_program = TOKEN_2022_PROGRAM_ID;
+        position.tick_upper = 60 * 512;
+        let opened = open_position(
+            &program,
+            &pool_id,
+            &narrow,
+            &user,
+            token_programs,
+            &position,
+        )
+        .unwrap();
+        let open = &opened.instructions[2];
+        assert_eq!(open.data[..8], OPEN_POSITION_WITH_TOKEN22_NFT_DISCRIMINATOR);
+        assert_eq!(open.accounts.len(), 21);
+        assert_eq!(
+            open.accounts[20].pubkey,
+            tick_array_bitmap_extension_address(&pool_id, &program)
+        );
+        assert_eq!(
+            opened.nft_account,
+            associated_token_address(&user, &nft_mint, &TOKEN_2022_PROGRAM_ID)
+        );
+        position.tick_upper = -180;
+        assert_eq!(
+            open_position(&program, &pool_id, &pool, &user, token_programs, &position),
+            Err(RaydiumError::InvalidTickRange {
+                tick_lower: -120,
+                tick_upper: -180,
+                tick_spacing: 60
+            })
+        );
+    }
+
+    #[test]
+    fn test_increase_liquidity() {
+        let pool = RaydiumLiquidityPoolCLMMData {
+            token_mint_0: Pubkey::new_unique(),
+            token_mint_1: Pubkey::new_unique(),
+            token_vault_0: Pubkey::new_unique(),
+            token_vault_1: Pubkey::new_unique(),
+            tick_spacing: 1,
+            ..Default::default()
+        };
+        let position = PersonalPositionData {
+            nft_mint: Pubkey::new_unique(),
+            pool_id: Pubkey::new_unique(),
+            tick_lower_index: -30_780,
+            tick_upper_index: -60,
+            ..Default::default()
+        };
+        let (program, user) = (RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID, Pubkey::new_unique());
+        let instruction = increase_liquidity(
+            &program,
+            &position,
+            &pool,
+            &user,
+            [TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID],
+            &TOKEN_2022_PROGRAM_ID,
+            1_000_000,
+            500,
+            700,
+        );
+        let mut data = INCREASE_LIQUIDITY_V2_DISCRIMINATOR.to_vec();
+        data.extend(1_000_000u128.to_le_bytes());
+        data.extend(500u64.to_le_bytes());
+        data.extend(700u64.to_le_bytes());
+        data.push(0);
+        assert_eq!(instruction.data, data);
+        let pool_id = position.pool_id;
+        let accounts: Vec<(Pubkey, bool, bool)> = instruction
+            .accounts
+            .iter()
+            .map(|a| (a.pubkey, a.is_signer, a.is_writable))
+            .collect();
+        // the lower bound is in the first array past the pool bitmap
+        assert_eq!(
+            accounts,
+            [
+                (user, true, false),
+                (
+                    associated_token_address(&user, &position.nft_mint, &TOKEN_2022_PROGRAM_ID),
+                    false,
+                    false
+                ),
+                (pool_id, false, true),
+                (
+                    protocol_position_address(&pool_id, -30_780, -60, &program),
+                    false,
+                    true
+                ),
+                (
+                    personal_position_address(&position.nft_mint, &program),
+                    false,
+                    true
+                ),
+                (tick_array_address(&pool_id, -30_780, &program), false, true),
+                (tick_array_address(&pool_id, -60, &program), false, true),
+                (
+                    associated_token_address(&user, &pool.token_mint_0, &TOKEN_2022_PROGRAM_ID),
+                    false,
+                    true
+                ),
+                (
+                    associated_token_address(&user, &pool.token_mint_1, &TOKEN_PROGRAM_ID),
+                    false,
+                    true
+                ),
+                (pool.token_vault_0, false, true),
+                (pool.token_vault_1, false, true),
+                (TOKEN_PROGRAM_ID, false, false),
+                (TOKEN_2022_PROGRAM_ID, false, false),
+                (pool.token_mint_0, false, false),
+                (pool.token_mint_1, false, false),
+                (
+                    tick_array_bitmap_extension_address(&pool_id, &program),
+                    false,
+                    true
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_withdraw_all() {
+        let mut pool = RaydiumLiquidityPoolCLMMData {
+            token_mint_0: Pubkey::new_unique(),
+            token_mint_1: Pubkey::new_unique(),
+            token_vault_0: Pubkey::new_unique(),
+            token_vault_1: Pubkey::new_unique(),
+            tick_spacing: 60,
+            sqrt_price_x64: 1 << 64,
+            ..Default::default()
+        };
+        // only the second reward slot is active, with a token-2022 mint
+        pool.reward_infos[1].token_mint = Pubkey::new_unique();
+        pool.reward_infos[1].token_vault = Pubkey::new_unique();
+        let reward = pool.reward_infos[1].clone();
+        let position = PersonalPositionData {
+            nft_mint: Pubkey::new_unique(),
+            pool_id: Pubkey::new_unique(),
+            tick_lower_index: -120,
+            tick_upper_index: 120,
+            liquidity: 1_000_000_000,
+            ..Default::default()
+        };
+        let (program, user) = (RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID, Pubkey::new_unique());
+        let reward_programs = [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID];
+        let instructions = withdraw_all(
+            &program,
+            &position,
+            &pool,
+            &user,
+            [TOKEN_PROGRAM_ID; 2],
+            reward_programs,
+            &TOKEN_PROGRAM_ID,
+            100,
+        )
+        .unwrap();
+        let [create_0, create_1, create_reward, decrease, close] = &instructions[..] else {
+            panic!("{:?}", instructions);
+        };
+        let reward_account =
+            associated_token_address(&user, &reward.token_mint, &TOKEN_2022_PROGRAM_ID);
+        assert_eq!(create_reward.accounts[1].pubkey, reward_account);
+        assert_eq!(create_reward.accounts[5].pubkey, TOKEN_2022_PROGRAM_ID);
+        // the range holds 5_981_737 of each token at price 1, 1% less is the minimum
+        let (amount_0, amount_1) = position.token_amounts(&pool).unwrap();
+        assert_eq!((amount_0, amount_1), (5_981_737, 5_981_737));
+        let mut data = DECREASE_LIQUIDITY_V2_DISCRIMINATOR.to_vec();
+        data.extend(1_000_000_000u128.to_le_bytes());
+        data.extend(5_921_919u64.to_le_bytes());
+        data.extend(5_921_919u64.to_le_bytes());
+        assert_eq!(decrease.data, data);
+        let personal_position = personal_position_address(&position.nft_mint, &program);
+        let nft_account = associated_token_address(&user, &position.nft_mint, &TOKEN_PROGRAM_ID);
+        let accounts: Vec<(Pubkey, bool, bool)> = decrease
+            .accounts
+            .iter()
+            .map(|a| (a.pubkey, a.is_signer, a.is_writable))
+            .collect();
+        assert_eq!(
+            accounts,
+            [
+                (user, true, false),
+                (nft_account, false, false),
+                (personal_position, false, true),
+                (position.pool_id, false, true),
+                (
+                    protocol_position_address(&position.pool_id, -120, 120, &program),
+                    false,
+                    true
+                ),
+                (pool.token_vault_0, false, true),
+                (pool.token_vault_1, false, true),
+                (
+                    tick_array_address(&position.pool_id, -3_600, &program),
+                    false,
+                    true
+                ),
+                (
+                    tick_array_address(&position.pool_id, 0, &program),
+                    false,
+                    true
+                ),
+                (create_0.accounts[1].pubkey, false, true),
+                (create_1.accounts[1].pubkey, false, true),
+                (TOKEN_PROGRAM_ID, false, false),
+                (TOKEN_2022_PROGRAM_ID, false, false),
+                (MEMO_PROGRAM_ID, false, false),
+                (pool.token_mint_0, false, false),
+                (pool.token_mint_1, false, false),
+                (reward.token_vault, false, true),
+                (reward_account, false, true),
+                (reward.token_mint, false, false),
+            ]
+        );
+        assert_eq!(close.data, CLOSE_POSITION_DISCRIMINATOR);
+        let accounts: Vec<(Pubkey, bool, bool)> = close
+            .accounts
+            .iter()
+            .map(|a| (a.pubkey, a.is_signer, a.is_writable))
+            .collect();
+        assert_eq!(
+            accounts,
+            [
+                (user, true, true),
+                (position.nft_mint, false, true),
+                (nft_account, false, true),
+                (personal_position, false, true),
+                (SYSTEM_PROGRAM_ID, false, false),
+                (TOKEN_PROGRAM_ID, false, false),
+            ]
+        );
+    }
+}
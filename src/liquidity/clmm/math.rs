@@ -0,0 +1,1017 @@
+//! conversions between CLMM ticks, Q64.64 sqrt prices and prices, following the on-chain tick math,
+//! and the token amounts, fees and rewards of positions
+
+use solana_sdk::pubkey::Pubkey;
+
+use super::{
+    PersonalPositionData, RaydiumLiquidityPoolCLMMData, RewardInfo, TickData,
+    full_math::{Rounding, add, div, div_wide, full_mul, mul, mul_div, shl_64, sub, to_u128},
+    position::RewardOwed,
+};
+
+/// lowest tick of a CLMM pool
+pub const MIN_TICK: i32 = -443636;
+/// highest tick of a CLMM pool
+pub const MAX_TICK: i32 = -MIN_TICK;
+/// sqrt price of `MIN_TICK`, Q64.64
+pub const MIN_SQRT_PRICE_X64: u128 = 4295048016;
+/// sqrt price of `MAX_TICK`, Q64.64
+pub const MAX_SQRT_PRICE_X64: u128 = 79226673521066979257578248091;
+/// Q64.64 one
+const ONE_X64: u128 = 1 << 64;
+/// fraction bits of log2 computed by `sqrt_price_x64_to_tick`
+const LOG2_PRECISION: u32 = 16;
+/// log base sqrt(1.0001) of 2, Q32.32 times Q64 gives Q64.64 ticks
+const LOG_B_2_X32: i128 = 59543866431248;
+/// error bounds of the log, Q64.64
+const LOG_B_P_ERR_MARGIN_LOWER_X64: i128 = 184467440737095516;
+const LOG_B_P_ERR_MARGIN_UPPER_X64: i128 = 15793534762490258745;
+
+/// sqrt(1.0001)^-(2^i), Q64.64, for the bits of the absolute tick
+const TICK_BIT_RATIOS_X64: [u128; 18] = [
+    0xfff97272373d4000,
+    0xfff2e50f5f657000,
+    0xffe5caca7e10f000,
+    0xffcb9843d60f7000,
+    0xff973b41fa98e800,
+    0xff2ea16466c9b000,
+    0xfe5dee046a9a3800,
+    0xfcbe86c7900bb000,
+    0xf987a7253ac65800,
+    0xf3392b0822bb6000,
+    0xe7159475a2caf000,
+    0xd097f3bdfd2f2000,
+    0xa9f746462d9f8000,
+    0x70d869a156f31c00,
+    0x31be135f97ed3200,
+    0x9aa508b5b85a500,
+    0x5d6af8dedc582c,
+    0x2216e584f5fa,
+];
+/// sqrt(1.0001)^-1, Q64.64, the first bit of the absolute tick
+const TICK_BIT_0_RATIO_X64: u128 = 0xfffcb933bd6fb800;
+
+/// sqrt price of a tick, Q64.64, multiplying one precomputed ratio per bit of the tick like the
+/// program does. `None` outside `MIN_TICK..=MAX_TICK`
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::math::tick_to_sqrt_price_x64;
+/// assert_eq!(tick_to_sqrt_price_x64(0), Some(1 << 64));
+/// ```
+pub fn tick_to_sqrt_price_x64(tick: i32) -> Option<u128> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return None;
+    }
+    let abs_tick = tick.unsigned_abs();
+    let mut ratio = if abs_tick & 1 != 0 {
+        TICK_BIT_0_RATIO_X64
+    } else {
+        ONE_X64
+    };
+    for (i, bit_ratio) in TICK_BIT_RATIOS_X64.iter().enumerate() {
+        if abs_tick & (2 << i) != 0 {
+            ratio = (ratio * bit_ratio) >> 64;
+        }
+    }
+    if tick > 0 {
+        ratio = u128::MAX / ratio;
+    }
+    Some(ratio)
+}
+
+/// the greatest tick whose sqrt price is at or below `sqrt_price_x64`, from a fixed-point log2
+/// like the program. `None` outside `MIN_SQRT_PRICE_X64..MAX_SQRT_PRICE_X64`
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::math::{sqrt_price_x64_to_tick, tick_to_sqrt_price_x64};
+/// let sqrt_price = tick_to_sqrt_price_x64(-18_973).unwrap();
+/// assert_eq!(sqrt_price_x64_to_tick(sqrt_price), Some(-18_973));
+/// assert_eq!(sqrt_price_x64_to_tick(sqrt_price - 1), Some(-18_974));
+/// ```
+pub fn sqrt_price_x64_to_tick(sqrt_price_x64: u128) -> Option<i32> {
+    if !(MIN_SQRT_PRICE_X64..MAX_SQRT_PRICE_X64).contains(&sqrt_price_x64) {
+        return None;
+    }
+    let msb = 127 - sqrt_price_x64.leading_zeros();
+    let log2_integer_x32 = (msb as i128 - 64) << 32;
+    // normalize to [1, 2) in Q1.63, then square once per fraction bit
+    let mut r = if msb >= 64 {
+        sqrt_price_x64 >> (msb - 63)
+    } else {
+        sqrt_price_x64 << (63 - msb)
+    };
+    let mut bit: i128 = 1 << 63;
+    let mut log2_fraction_x64: i128 = 0;
+    for _ in 0..LOG2_PRECISION {
+        r *= r;
+        let above_two = (r >> 127) as u32;
+        r >>= 63 + above_two;
+        log2_fraction_x64 += bit * above_two as i128;
+        bit >>= 1;
+    }
+    let log2_x32 = log2_integer_x32 + (log2_fraction_x64 >> 32);
+    let log_sqrt_10001_x64 = log2_x32 * LOG_B_2_X32;
+    let tick_low = ((log_sqrt_10001_x64 - LOG_B_P_ERR_MARGIN_LOWER_X64) >> 64) as i32;
+    let tick_high = ((log_sqrt_10001_x64 + LOG_B_P_ERR_MARGIN_UPPER_X64) >> 64) as i32;
+    if tick_low == tick_high
+        || tick_to_sqrt_price_x64(tick_high).is_none_or(|high| high > sqrt_price_x64)
+    {
+        Some(tick_low)
+    } else {
+        Some(tick_high)
+    }
+}
+
+/// price of one token 0 in token 1 at a tick, `1.0001^tick * 10^(decimals_0 - decimals_1)`,
+/// from the exact sqrt price. ticks outside `MIN_TICK..=MAX_TICK` are clamped
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::math::tick_to_price;
+/// // SOL (9 decimals) in USDC (6 decimals)
+/// let price = tick_to_price(-18_973, 9, 6);
+/// assert!((price - 149.99).abs() < 0.01);
+/// ```
+pub fn tick_to_price(tick: i32, decimals_0: u8, decimals_1: u8) -> f64 {
+    let sqrt_price_x64 = tick_to_sqrt_price_x64(tick.clamp(MIN_TICK, MAX_TICK)).unwrap();
+    let sqrt_price = sqrt_price_x64 as f64 / ONE_X64 as f64;
+    sqrt_price * sqrt_price * 10f64.powi(decimals_0 as i32 - decimals_1 as i32)
+}
+
+/// the raw price of token 0 in token 1 as Q64.64, `sqrt_price_x64^2 / 2^64` rounded down.
+/// `None` when the price is 2^64 or more
+pub fn sqrt_price_x64_to_price_x64(sqrt_price_x64: u128) -> Option<u128> {
+    mul_div(sqrt_price_x64, sqrt_price_x64, ONE_X64, Rounding::Down)
+}
+
+/// a Q64.64 value as f64, the last step after the exact math
+pub fn x64_to_f64(value_x64: u128) -> f64 {
+    (value_x64 >> 64) as f64 + (value_x64 as u64) as f64 / ONE_X64 as f64
+}
+
+/// token 0 amount of `liquidity` between two sqrt prices, `L * 2^64 * (b - a) / b / a`
+/// in the order and rounding of the program. `None` when a sqrt price is zero or the amount
+/// does not fit a u64
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::{full_math::Rounding, math::{get_delta_amount_0, tick_to_sqrt_price_x64}};
+/// let lower = tick_to_sqrt_price_x64(-100).unwrap();
+/// let upper = tick_to_sqrt_price_x64(100).unwrap();
+/// let down = get_delta_amount_0(lower, upper, 1_000_000_000, Rounding::Down).unwrap();
+/// let up = get_delta_amount_0(upper, lower, 1_000_000_000, Rounding::Up).unwrap();
+/// assert_eq!(up, down + 1);
+/// ```
+pub fn get_delta_amount_0(
+    sqrt_price_a_x64: u128,
+    sqrt_price_b_x64: u128,
+    liquidity: u128,
+    rounding: Rounding,
+) -> Option<u64> {
+    let (a, b) = if sqrt_price_a_x64 <= sqrt_price_b_x64 {
+        (sqrt_price_a_x64, sqrt_price_b_x64)
+    } else {
+        (sqrt_price_b_x64, sqrt_price_a_x64)
+    };
+    let numerator = shl_64(full_mul(liquidity, b - a));
+    let amount = div(div(numerator, b, rounding)?, a, rounding)?;
+    u64::try_from(to_u128(amount)?).ok()
+}
+
+/// token 1 amount of `liquidity` between two sqrt prices, `L * (b - a) / 2^64`.
+/// `None` when the amount does not fit a u64
+pub fn get_delta_amount_1(
+    sqrt_price_a_x64: u128,
+    sqrt_price_b_x64: u128,
+    liquidity: u128,
+    rounding: Rounding,
+) -> Option<u64> {
+    let diff = sqrt_price_a_x64.abs_diff(sqrt_price_b_x64);
+    u64::try_from(mul_div(liquidity, diff, ONE_X64, rounding)?).ok()
+}
+
+/// token 0 and token 1 amounts of `liquidity` in a range at the current sqrt price: all
+/// token 0 below the range, all token 1 above it, both inside. deposits round up and
+/// withdrawals round down on chain. `None` when an amount does not fit a u64
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::{full_math::Rounding, math::{amounts_for_liquidity, tick_to_sqrt_price_x64}};
+/// let lower = tick_to_sqrt_price_x64(-100).unwrap();
+/// let upper = tick_to_sqrt_price_x64(100).unwrap();
+/// let below = tick_to_sqrt_price_x64(-200).unwrap();
+/// let (amount_0, amount_1) = amounts_for_liquidity(below, lower, upper, 1_000_000_000, Rounding::Down).unwrap();
+/// assert!(amount_0 > 0);
+/// assert_eq!(amount_1, 0);
+/// ```
+pub fn amounts_for_liquidity(
+    sqrt_price_current_x64: u128,
+    sqrt_price_lower_x64: u128,
+    sqrt_price_upper_x64: u128,
+    liquidity: u128,
+    rounding: Rounding,
+) -> Option<(u64, u64)> {
+    let (lower, upper) = if sqrt_price_lower_x64 <= sqrt_price_upper_x64 {
+        (sqrt_price_lower_x64, sqrt_price_upper_x64)
+    } else {
+        (sqrt_price_upper_x64, sqrt_price_lower_x64)
+    };
+    if sqrt_price_current_x64 < lower {
+        Some((get_delta_amount_0(lower, upper, liquidity, rounding)?, 0))
+    } else if sqrt_price_current_x64 < upper {
+        Some((
+            get_delta_amount_0(sqrt_price_current_x64, upper, liquidity, rounding)?,
+            get_delta_amount_1(lower, sqrt_price_current_x64, liquidity, rounding)?,
+        ))
+    } else {
+        Some((0, get_delta_amount_1(lower, upper, liquidity, rounding)?))
+    }
+}
+
+/// liquidity `amount_0` of token 0 provides between two sqrt prices, rounded down like the
+/// program. `None` when the prices are equal or the liquidity does not fit a u128
+pub fn get_liquidity_from_amount_0(
+    sqrt_price_a_x64: u128,
+    sqrt_price_b_x64: u128,
+    amount_0: u64,
+) -> Option<u128> {
+    let (a, b) = (
+        sqrt_price_a_x64.min(sqrt_price_b_x64),
+        sqrt_price_a_x64.max(sqrt_price_b_x64),
+    );
+    let intermediate = mul_div(a, b, ONE_X64, Rounding::Down)?;
+    mul_div(amount_0 as u128, intermediate, b - a, Rounding::Down)
+}
+
+/// liquidity `amount_1` of token 1 provides between two sqrt prices, rounded down.
+/// `None` when the prices are equal
+pub fn get_liquidity_from_amount_1(
+    sqrt_price_a_x64: u128,
+    sqrt_price_b_x64: u128,
+    amount_1: u64,
+) -> Option<u128> {
+    let diff = sqrt_price_a_x64.abs_diff(sqrt_price_b_x64);
+    mul_div(amount_1 as u128, ONE_X64, diff, Rounding::Down)
+}
+
+/// the most liquidity depositing at most `amount_0_desired` and `amount_1_desired` adds to a
+/// range at the current sqrt price, the reverse of `amounts_for_liquidity`: token 0 alone
+/// below the range, token 1 alone above it, the scarcer side inside. `None` when the range is
+/// empty or the liquidity does not fit a u128
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::{full_math::Rounding, math::{amounts_for_liquidity, liquidity_from_amounts, tick_to_sqrt_price_x64}};
+/// let lower = tick_to_sqrt_price_x64(-100).unwrap();
+/// let upper = tick_to_sqrt_price_x64(100).unwrap();
+/// let liquidity = liquidity_from_amounts(1 << 64, lower, upper, 1_000_000, 5_000_000).unwrap();
+/// let (amount_0, amount_1) = amounts_for_liquidity(1 << 64, lower, upper, liquidity, Rounding::Up).unwrap();
+/// // token 0 is the scarcer side
+/// assert_eq!(amount_0, 1_000_000);
+/// assert!(amount_1 < 5_000_000);
+/// ```
+pub fn liquidity_from_amounts(
+    sqrt_price_current_x64: u128,
+    sqrt_price_lower_x64: u128,
+    sqrt_price_upper_x64: u128,
+    amount_0_desired: u64,
+    amount_1_desired: u64,
+) -> Option<u128> {
+    let (lower, upper) = (
+        sqrt_price_lower_x64.min(sqrt_price_upper_x64),
+        sqrt_price_lower_x64.max(sqrt_price_upper_x64),
+    );
+    if sqrt_price_current_x64 <= lower {
+        get_liquidity_from_amount_0(lower, upper, amount_0_desired)
+    } else if sqrt_price_current_x64 < upper {
+        Some(
+            get_liquidity_from_amount_0(sqrt_price_current_x64, upper, amount_0_desired)?.min(
+                get_liquidity_from_amount_1(lower, sqrt_price_current_x64, amount_1_desired)?,
+            ),
+        )
+    } else {
+        get_liquidity_from_amount_1(lower, upper, amount_1_desired)
+    }
+}
+
+/// liquidity of a position and the token amounts depositing it takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PositionSize {
+    pub liquidity: u128,
+    /// token 0 deposited, rounded up like the program
+    pub amount_0: u64,
+    /// token 1 deposited, rounded up like the program
+    pub amount_1: u64,
+}
+
+/// size a position in a range to be worth about `value_in_token_1` raw token 1 at the current
+/// price, token 0 valued at that price. the liquidity is approximated in f64, the amounts are
+/// then exact for it. `None` when the range is empty or an amount does not fit a u64
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::math::{size_position, tick_to_sqrt_price_x64};
+/// // 1000 USDC across about 5% around the price of a 9 decimals token at 150 USDC
+/// let current = tick_to_sqrt_price_x64(-18_973).unwrap();
+/// let lower = tick_to_sqrt_price_x64(-19_500).unwrap();
+/// let upper = tick_to_sqrt_price_x64(-18_480).unwrap();
+/// let size = size_position(current, lower, upper, 1_000_000_000).unwrap();
+/// let value = size.amount_0 as f64 * 150.0 / 1e3 + size.amount_1 as f64;
+/// assert!((value / 1e9 - 1.0).abs() < 1e-3);
+/// ```
+pub fn size_position(
+    sqrt_price_current_x64: u128,
+    sqrt_price_lower_x64: u128,
+    sqrt_price_upper_x64: u128,
+    value_in_token_1: u64,
+) -> Option<PositionSize> {
+    if sqrt_price_lower_x64 >= sqrt_price_upper_x64 || sqrt_price_current_x64 == 0 {
+        return None;
+    }
+    let current = x64_to_f64(sqrt_price_current_x64);
+    let (lower, upper) = (
+        x64_to_f64(sqrt_price_lower_x64),
+        x64_to_f64(sqrt_price_upper_x64),
+    );
+    // token amounts per unit of liquidity, token 0 at the clamped price
+    let clamped = current.clamp(lower, upper);
+    let value_per_liquidity = (1.0 / clamped - 1.0 / upper) * current * current + (clamped - lower);
+    let liquidity = (value_in_token_1 as f64 / value_per_liquidity) as u128;
+    let (amount_0, amount_1) = amounts_for_liquidity(
+        sqrt_price_current_x64,
+        sqrt_price_lower_x64,
+        sqrt_price_upper_x64,
+        liquidity,
+        Rounding::Up,
+    )?;
+    Some(PositionSize {
+        liquidity,
+        amount_0,
+        amount_1,
+    })
+}
+
+/// a position range from `fraction_below` under to `fraction_above` over the price of
+/// `tick_current`, widened to the tick spacing and kept in the tick range. `0.05` for both is
+/// a range of about ±5%
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::math::tick_range_around;
+/// assert_eq!(tick_range_around(-18_973, 10, 0.05, 0.05), (-19_490, -18_480));
+/// ```
+pub fn tick_range_around(
+    tick_current: i32,
+    tick_spacing: u16,
+    fraction_below: f64,
+    fraction_above: f64,
+) -> (i32, i32) {
+    let spacing = tick_spacing.max(1) as i32;
+    let offset = |fraction: f64| {
+        let ticks = ((1.0 + fraction).ln() / 1.0001f64.ln()) as i32;
+        tick_current
+            .saturating_add(ticks)
+            .clamp(-MAX_TICK, MAX_TICK)
+    };
+    // the lower bound rounds down and the upper one up
+    let lower =
+        offset(-fraction_below.clamp(0.0, 1.0 - f64::EPSILON)).div_euclid(spacing) * spacing;
+    let upper = offset(fraction_above.max(0.0));
+    let upper = (upper + spacing - 1).div_euclid(spacing) * spacing;
+    let min = -(MAX_TICK / spacing * spacing);
+    let max = MAX_TICK / spacing * spacing;
+    let lower = lower.clamp(min, max - spacing);
+    (lower, upper.clamp(lower + spacing, max))
+}
+
+/// sqrt price after adding (`add`) or removing `amount` of token 0 at `liquidity`,
+/// `L * 2^64 * sqrt / (L * 2^64 ± amount * sqrt)` rounded up so the pool keeps the dust.
+/// `None` when the liquidity is zero or the price leaves the u128 range
+pub fn get_next_sqrt_price_from_amount_0_rounding_up(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount: u64,
+    add_amount: bool,
+) -> Option<u128> {
+    if amount == 0 {
+        return Some(sqrt_price_x64);
+    }
+    let numerator = shl_64([0, 0, liquidity]);
+    let product = full_mul(amount as u128, sqrt_price_x64);
+    let denominator = if add_amount {
+        add(numerator, product)?
+    } else {
+        sub(numerator, product).filter(|d| *d != [0; 3])?
+    };
+    to_u128(div_wide(
+        mul(numerator, sqrt_price_x64)?,
+        denominator,
+        Rounding::Up,
+    )?)
+}
+
+/// sqrt price after adding (`add`) or removing `amount` of token 1 at `liquidity`,
+/// `sqrt ± amount * 2^64 / L` rounded down. `None` when the liquidity is zero or the price
+/// leaves the u128 range
+pub fn get_next_sqrt_price_from_amount_1_rounding_down(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount: u64,
+    add_amount: bool,
+) -> Option<u128> {
+    let shifted = shl_64([0, 0, amount as u128]);
+    if add_amount {
+        let quotient = to_u128(div(shifted, liquidity, Rounding::Down)?)?;
+        sqrt_price_x64.checked_add(quotient)
+    } else {
+        let quotient = to_u128(div(shifted, liquidity, Rounding::Up)?)?;
+        sqrt_price_x64
+            .checked_sub(quotient)
+            .filter(|next| *next != 0)
+    }
+}
+
+/// sqrt price after `amount_in` enters the pool, token 0 when `zero_for_one`
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::math::get_next_sqrt_price_from_input;
+/// let one = 1u128 << 64;
+/// // 1% of the virtual token 1 reserve raises the sqrt price by 1%
+/// assert_eq!(get_next_sqrt_price_from_input(one, 1_000_000, 10_000, false), Some(one + one / 100));
+/// ```
+pub fn get_next_sqrt_price_from_input(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount_in: u64,
+    zero_for_one: bool,
+) -> Option<u128> {
+    if sqrt_price_x64 == 0 || liquidity == 0 {
+        return None;
+    }
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_0_rounding_up(sqrt_price_x64, liquidity, amount_in, true)
+    } else {
+        get_next_sqrt_price_from_amount_1_rounding_down(sqrt_price_x64, liquidity, amount_in, true)
+    }
+}
+
+/// sqrt price after `amount_out` leaves the pool, token 1 when `zero_for_one`
+pub fn get_next_sqrt_price_from_output(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount_out: u64,
+    zero_for_one: bool,
+) -> Option<u128> {
+    if sqrt_price_x64 == 0 || liquidity == 0 {
+        return None;
+    }
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_1_rounding_down(
+            sqrt_price_x64,
+            liquidity,
+            amount_out,
+            false,
+        )
+    } else {
+        get_next_sqrt_price_from_amount_0_rounding_up(sqrt_price_x64, liquidity, amount_out, false)
+    }
+}
+
+/// growth of a global counter between two ticks, from the growths recorded outside each tick
+/// and the side of the current tick. wraps around like the program, only the difference of two
+/// readings is meaningful
+fn growth_inside(
+    tick_current: i32,
+    tick_lower: i32,
+    outside_lower: u128,
+    tick_upper: i32,
+    outside_upper: u128,
+    global: u128,
+) -> u128 {
+    let below = if tick_current >= tick_lower {
+        outside_lower
+    } else {
+        global.wrapping_sub(outside_lower)
+    };
+    let above = if tick_current < tick_upper {
+        outside_upper
+    } else {
+        global.wrapping_sub(outside_upper)
+    };
+    global.wrapping_sub(below).wrapping_sub(above)
+}
+
+/// `liquidity` times the growth since `last`, in tokens rounded down. like the program an
+/// amount past a u64 counts as zero
+fn owed_since(inside: u128, last: u128, liquidity: u128) -> u64 {
+    mul_div(
+        inside.wrapping_sub(last),
+        liquidity,
+        ONE_X64,
+        Rounding::Down,
+    )
+    .and_then(|amount| u64::try_from(amount).ok())
+    .unwrap_or(0)
+}
+
+/// token 0 and token 1 fees a position can collect: the fees recorded at its last update plus
+/// its liquidity times the fee growth inside its range since then. `tick_lower` and
+/// `tick_upper` are the ticks at the position bounds
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::{PersonalPositionData, RaydiumLiquidityPoolCLMMData, TickData, math::fees_owed};
+/// let pool = RaydiumLiquidityPoolCLMMData {
+///     fee_growth_global_0_x64: 3 << 64,
+///     ..Default::default()
+/// };
+/// let position = PersonalPositionData {
+///     tick_lower_index: -60,
+///     tick_upper_index: 60,
+///     liquidity: 1_000,
+///     token_fees_owed_1: 7,
+///     ..Default::default()
+/// };
+/// let lower = TickData { tick: -60, ..Default::default() };
+/// let upper = TickData { tick: 60, ..Default::default() };
+/// assert_eq!(fees_owed(&position, &pool, &lower, &upper), (3_000, 7));
+/// ```
+pub fn fees_owed(
+    position: &PersonalPositionData,
+    pool: &RaydiumLiquidityPoolCLMMData,
+    tick_lower: &TickData,
+    tick_upper: &TickData,
+) -> (u64, u64) {
+    let inside = |outside: fn(&TickData) -> u128, global| {
+        growth_inside(
+            pool.tick_current,
+            position.tick_lower_index,
+            outside(tick_lower),
+            position.tick_upper_index,
+            outside(tick_upper),
+            global,
+        )
+    };
+    let inside_0 = inside(|t| t.fee_growth_outside_0_x64, pool.fee_growth_global_0_x64);
+    let inside_1 = inside(|t| t.fee_growth_outside_1_x64, pool.fee_growth_global_1_x64);
+    (
+        position.token_fees_owed_0.saturating_add(owed_since(
+            inside_0,
+            position.fee_growth_inside_0_last_x64,
+            position.liquidity,
+        )),
+        position.token_fees_owed_1.saturating_add(owed_since(
+            inside_1,
+            position.fee_growth_inside_1_last_x64,
+            position.liquidity,
+        )),
+    )
+}
+
+/// reward growth of a pool slot at unix time `now`: the emissions since its last update,
+/// stopping at `end_time`, spread over the active liquidity like the program does
+fn reward_growth_global_at(reward: &RewardInfo, liquidity: u128, now: u64) -> u128 {
+    let latest = now.min(reward.end_time);
+    if now <= reward.open_time || liquidity == 0 || latest <= reward.last_update_time {
+        return reward.reward_growth_global_x64;
+    }
+    let elapsed = u128::from(latest - reward.last_update_time);
+    let delta = mul_div(
+        elapsed,
+        reward.emissions_per_second_x64,
+        liquidity,
+        Rounding::Down,
+    )
+    .unwrap_or(0);
+    reward.reward_growth_global_x64.wrapping_add(delta)
+}
+
+/// rewards a position can collect at unix time `now` from each reward slot of the pool: the
+/// recorded amounts plus its liquidity times the reward growth inside its range, after rolling
+/// the pool growths forward to `now`. inactive slots are zero with the default mint; decimals
+/// are left unknown
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::{PersonalPositionData, RaydiumLiquidityPoolCLMMData, RewardInfo, TickData, math::rewards_owed};
+/// # use solana_sdk::pubkey::Pubkey;
+/// let mut pool = RaydiumLiquidityPoolCLMMData {
+///     liquidity: 1_024,
+///     ..Default::default()
+/// };
+/// // one token per second from time 100, half of it to a position holding half the liquidity
+/// pool.reward_infos[0] = RewardInfo {
+///     token_mint: Pubkey::new_unique(),
+///     open_time: 100,
+///     end_time: 200,
+///     last_update_time: 100,
+///     emissions_per_second_x64: 1 << 64,
+///     ..Default::default()
+/// };
+/// let position = PersonalPositionData {
+///     tick_lower_index: -60,
+///     tick_upper_index: 60,
+///     liquidity: 512,
+///     ..Default::default()
+/// };
+/// let lower = TickData { tick: -60, ..Default::default() };
+/// let upper = TickData { tick: 60, ..Default::default() };
+/// let rewards = rewards_owed(&position, &pool, &lower, &upper, 150);
+/// assert_eq!(rewards[0].amount, 25);
+/// assert!(!rewards[1].is_active());
+/// ```
+pub fn rewards_owed(
+    position: &PersonalPositionData,
+    pool: &RaydiumLiquidityPoolCLMMData,
+    tick_lower: &TickData,
+    tick_upper: &TickData,
+    now: u64,
+) -> [RewardOwed; 3] {
+    std::array::from_fn(|i| {
+        let reward = &pool.reward_infos[i];
+        if reward.token_mint == Pubkey::default() {
+            return RewardOwed::default();
+        }
+        let inside = growth_inside(
+            pool.tick_current,
+            position.tick_lower_index,
+            tick_lower.reward_growths_outside_x64[i],
+            position.tick_upper_index,
+            tick_upper.reward_growths_outside_x64[i],
+            reward_growth_global_at(reward, pool.liquidity, now),
+        );
+        let recorded = &position.reward_infos[i];
+        RewardOwed {
+            mint: reward.token_mint,
+            amount: recorded.reward_amount_owed.saturating_add(owed_since(
+                inside,
+                recorded.growth_inside_last_x64,
+                position.liquidity,
+            )),
+            decimals: None,
+        }
+    })
+}
+
+/// the tick at or below a price of one token 0 in token 1, rounding toward negative infinity
+/// like the program. positive prices past the tick range clamp to `MIN_TICK` or `MAX_TICK`,
+/// NaN, infinite, zero and negative prices have no tick and give `None`. a price exactly on a
+/// tick may land one tick lower through the f64 conversion
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::math::price_to_tick;
+/// assert_eq!(price_to_tick(150.0, 9, 6), Some(-18_973));
+/// assert_eq!(price_to_tick(1.0 / 150.0, 6, 9), Some(18_972));
+/// assert_eq!(price_to_tick(0.0, 9, 6), None);
+/// ```
+pub fn price_to_tick(price: f64, decimals_0: u8, decimals_1: u8) -> Option<i32> {
+    if !price.is_finite() || price <= 0.0 {
+        return None;
+    }
+    let raw = price / 10f64.powi(decimals_0 as i32 - decimals_1 as i32);
+    let sqrt_price_x64 = (raw.sqrt() * ONE_X64 as f64) as u128;
+    let sqrt_price_x64 = sqrt_price_x64.clamp(MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64 - 1);
+    sqrt_price_x64_to_tick(sqrt_price_x64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_sqrt_price_round_trip() {
+        assert_eq!(tick_to_sqrt_price_x64(MIN_TICK), Some(MIN_SQRT_PRICE_X64));
+        assert_eq!(tick_to_sqrt_price_x64(MAX_TICK), Some(MAX_SQRT_PRICE_X64));
+        assert_eq!(tick_to_sqrt_price_x64(MAX_TICK + 1), None);
+        assert_eq!(sqrt_price_x64_to_tick(MAX_SQRT_PRICE_X64), None);
+        assert_eq!(sqrt_price_x64_to_tick(MIN_SQRT_PRICE_X64 - 1), None);
+        let mut previous = 0;
+        for tick in MIN_TICK..=MAX_TICK {
+            let sqrt_price = tick_to_sqrt_price_x64(tick).unwrap();
+            assert!(sqrt_price > previous, "tick {}", tick);
+            previous = sqrt_price;
+            if tick < MAX_TICK {
+                assert_eq!(sqrt_price_x64_to_tick(sqrt_price), Some(tick));
+            }
+            if tick > MIN_TICK {
+                assert_eq!(sqrt_price_x64_to_tick(sqrt_price - 1), Some(tick - 1));
+            }
+        }
+        // the fixed-point ratios agree with sqrt(1.0001)^tick in f64, which drifts with the tick
+        for tick in [-200_000, -887, -1, 1, 10_000, 200_000] {
+            let float = 1.0001f64.powf(tick as f64 / 2.0) * ONE_X64 as f64;
+            let sqrt_price = tick_to_sqrt_price_x64(tick).unwrap() as f64;
+            assert!((sqrt_price - float).abs() / float < 1e-9, "tick {}", tick);
+        }
+    }
+
+    /// expected values from exact big integer arithmetic, the BN math of the typescript sdk
+    #[test]
+    fn test_exact_amounts() {
+        // SOL-USDC at about 150.72, liquidity past u64
+        let sqrt_price = 7_161_458_000_123_456_789;
+        let price_x64 = sqrt_price_x64_to_price_x64(sqrt_price).unwrap();
+        assert_eq!(price_x64, 2_780_245_689_027_917_235);
+        assert!((x64_to_f64(price_x64) * 1e3 - 150.717_420_804_376).abs() < 1e-9);
+        let lower = 7_000_000_000_000_000_000;
+        let liquidity = 31_000_000_000_000;
+        assert_eq!(
+            get_delta_amount_0(lower, sqrt_price, liquidity, Rounding::Down),
+            Some(1_841_795_874_791)
+        );
+        assert_eq!(
+            get_delta_amount_0(sqrt_price, lower, liquidity, Rounding::Up),
+            Some(1_841_795_874_792)
+        );
+        assert_eq!(
+            get_delta_amount_1(lower, sqrt_price, liquidity, Rounding::Down),
+            Some(271_332_327_473)
+        );
+        assert_eq!(
+            get_delta_amount_1(lower, sqrt_price, liquidity, Rounding::Up),
+            Some(271_332_327_474)
+        );
+        assert_eq!(
+            get_delta_amount_0(
+                MIN_SQRT_PRICE_X64,
+                MAX_SQRT_PRICE_X64,
+                u128::MAX,
+                Rounding::Down
+            ),
+            None
+        );
+        assert_eq!(get_delta_amount_0(0, sqrt_price, 1, Rounding::Down), None);
+        let upper = 7_300_000_000_000_000_000;
+        assert_eq!(
+            amounts_for_liquidity(sqrt_price, lower, upper, liquidity, Rounding::Down),
+            Some((1_515_439_348_016, 271_332_327_473))
+        );
+        assert_eq!(
+            amounts_for_liquidity(sqrt_price, upper, lower, liquidity, Rounding::Up),
+            Some((1_515_439_348_017, 271_332_327_474))
+        );
+        assert_eq!(
+            amounts_for_liquidity(lower - 1, lower, upper, liquidity, Rounding::Down),
+            Some((3_357_235_222_808, 0))
+        );
+        // at the lower bound the position is in range, with no token 1 yet
+        assert_eq!(
+            amounts_for_liquidity(lower, lower, upper, liquidity, Rounding::Down),
+            Some((3_357_235_222_808, 0))
+        );
+        assert_eq!(
+            amounts_for_liquidity(upper, lower, upper, liquidity, Rounding::Up),
+            Some((0, 504_154_010_206))
+        );
+    }
+
+    /// expected values from exact big integer arithmetic
+    #[test]
+    fn test_next_sqrt_price() {
+        let sqrt_price = 7_161_458_000_123_456_789;
+        let liquidity = 31_000_000_000_000;
+        assert_eq!(
+            get_next_sqrt_price_from_input(sqrt_price, liquidity, 1_000_000_000, true),
+            Some(7_161_368_315_901_795_079)
+        );
+        assert_eq!(
+            get_next_sqrt_price_from_input(sqrt_price, liquidity, 1_000_000_000, false),
+            Some(7_162_053_056_383_899_032)
+        );
+        assert_eq!(
+            get_next_sqrt_price_from_output(sqrt_price, liquidity, 1_000_000_000, true),
+            Some(7_160_862_943_863_014_545)
+        );
+        assert_eq!(
+            get_next_sqrt_price_from_output(sqrt_price, liquidity, 1_000_000_000, false),
+            Some(7_161_547_686_591_437_915)
+        );
+        // more than the virtual reserve cannot leave the pool
+        assert_eq!(
+            get_next_sqrt_price_from_output(sqrt_price, liquidity, u64::MAX, true),
+            None
+        );
+        assert_eq!(get_next_sqrt_price_from_input(sqrt_price, 0, 1, true), None);
+    }
+
+    /// expected values from exact big integer arithmetic
+    #[test]
+    fn test_liquidity_from_amounts() {
+        let sqrt_price = 7_161_458_000_123_456_789;
+        let lower = tick_to_sqrt_price_x64(-18_960).unwrap();
+        let upper = tick_to_sqrt_price_x64(-18_000).unwrap();
+        // each case round-trips within a unit of the side that bounds the liquidity
+        for (current, desired, liquidity, amounts) in [
+            (
+                sqrt_price,
+                (232_687_888_696, 2_000_000_000),
+                1_999_999_999_999,
+                (232_687_888_695, 1_379_538_320),
+            ),
+            (
+                sqrt_price,
+                (300_000_000_000, 1_379_538_320),
+                1_999_999_999_064,
+                (232_687_888_587, 1_379_538_319),
+            ),
+            (
+                lower - 1,
+                (241_857_324_760, 0),
+                1_999_999_999_995,
+                (241_857_324_759, 0),
+            ),
+            (
+                upper,
+                (0, 38_108_615_265),
+                1_999_999_999_964,
+                (0, 38_108_615_264),
+            ),
+        ] {
+            assert_eq!(
+                liquidity_from_amounts(current, lower, upper, desired.0, desired.1),
+                Some(liquidity)
+            );
+            assert_eq!(
+                amounts_for_liquidity(current, lower, upper, liquidity, Rounding::Down),
+                Some(amounts)
+            );
+            let (amount_0, amount_1) =
+                amounts_for_liquidity(current, lower, upper, liquidity, Rounding::Up).unwrap();
+            assert!(amount_0 <= desired.0 && amount_1 <= desired.1);
+            assert!(desired.0 - amount_0 <= 1 || desired.1 - amount_1 <= 1);
+        }
+        assert_eq!(liquidity_from_amounts(sqrt_price, lower, lower, 1, 1), None);
+    }
+
+    #[test]
+    fn test_size_position() {
+        let (lower, upper) = tick_range_around(-18_973, 10, 0.05, 0.05);
+        assert_eq!((lower, upper), (-19_490, -18_480));
+        assert_eq!(tick_range_around(0, 60, 0.0, 0.0), (0, 60));
+        assert_eq!(tick_range_around(0, 60, 0.5, 1e300), (-6_960, 443_580));
+        assert_eq!(
+            tick_range_around(-443_600, 60, 0.5, 0.0),
+            (-443_580, -443_520)
+        );
+        let (lower, upper) = (
+            tick_to_sqrt_price_x64(lower).unwrap(),
+            tick_to_sqrt_price_x64(upper).unwrap(),
+        );
+        let current = tick_to_sqrt_price_x64(-18_973).unwrap();
+        let price = x64_to_f64(sqrt_price_x64_to_price_x64(current).unwrap());
+        let size = size_position(current, lower, upper, 1_000_000_000).unwrap();
+        let value = size.amount_0 as f64 * price + size.amount_1 as f64;
+        assert!((value / 1e9 - 1.0).abs() < 1e-6, "{}", value);
+        assert_eq!(
+            amounts_for_liquidity(current, lower, upper, size.liquidity, Rounding::Up),
+            Some((size.amount_0, size.amount_1))
+        );
+        // out of the range a single token is deposited
+        let below = size_position(lower - 1, lower, upper, 1_000_000_000).unwrap();
+        assert!(below.amount_0 > 0 && below.amount_1 == 0);
+        let above = size_position(upper, lower, upper, 1_000_000_000).unwrap();
+        assert_eq!(above.amount_0, 0);
+        assert!(above.amount_1.abs_diff(1_000_000_000) <= 1);
+        assert_eq!(size_position(current, upper, lower, 1), None);
+    }
+
+    #[test]
+    fn test_fees_owed() {
+        let mut pool = RaydiumLiquidityPoolCLMMData {
+            tick_current: 0,
+            fee_growth_global_0_x64: 10 << 64,
+            fee_growth_global_1_x64: 5 << 64,
+            ..Default::default()
+        };
+        let lower = TickData {
+            tick: -60,
+            fee_growth_outside_0_x64: 2 << 64,
+            fee_growth_outside_1_x64: 1 << 64,
+            ..Default::default()
+        };
+        let upper = TickData {
+            tick: 60,
+            fee_growth_outside_0_x64: 3 << 64,
+            fee_growth_outside_1_x64: 1 << 64,
+            ..Default::default()
+        };
+        let mut position = PersonalPositionData {
+            tick_lower_index: -60,
+            tick_upper_index: 60,
+            liquidity: 1_000_000,
+            fee_growth_inside_0_last_x64: 4 << 64,
+            // the last reading was taken before the counter wrapped
+            fee_growth_inside_1_last_x64: 0u128.wrapping_sub(1 << 63),
+            token_fees_owed_0: 11,
+            ..Default::default()
+        };
+        // inside is 10 - 2 - 3 and 5 - 1 - 1
+        assert_eq!(
+            fees_owed(&position, &pool, &lower, &upper),
+            (1_000_011, 3_500_000)
+        );
+        // below the range the growth inside wraps: 10 - (10 - 2) - 3
+        pool.tick_current = -61;
+        position.fee_growth_inside_0_last_x64 = 0u128.wrapping_sub(2 << 64);
+        assert_eq!(fees_owed(&position, &pool, &lower, &upper).0, 1_000_011);
+        // above it: 10 - 2 - (10 - 3)
+        pool.tick_current = 60;
+        position.fee_growth_inside_0_last_x64 = 0;
+        assert_eq!(fees_owed(&position, &pool, &lower, &upper).0, 1_000_011);
+        // an amount past a u64 is dropped like on chain
+        position.liquidity = u128::from(u64::MAX) + 1;
+        assert_eq!(fees_owed(&position, &pool, &lower, &upper).0, 11);
+    }
+
+    #[test]
+    fn test_rewards_owed() {
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut pool = RaydiumLiquidityPoolCLMMData {
+            liquidity: 1_000_000,
+            ..Default::default()
+        };
+        // two tokens per second until time 1000, last rolled at 500
+        pool.reward_infos[0] = RewardInfo {
+            token_mint: mint_a,
+            open_time: 100,
+            end_time: 1_000,
+            last_update_time: 500,
+            emissions_per_second_x64: 2 << 64,
+            reward_growth_global_x64: 7 << 64,
+            ..Default::default()
+        };
+        // an inactive slot is skipped whatever its growth
+        pool.reward_infos[1].reward_growth_global_x64 = 5 << 64;
+        // not open yet, its growth does not move
+        pool.reward_infos[2] = RewardInfo {
+            token_mint: mint_b,
+            open_time: 2_000,
+            end_time: 3_000,
+            emissions_per_second_x64: 1 << 64,
+            reward_growth_global_x64: 3 << 64,
+            ..Default::default()
+        };
+        let lower = TickData {
+            tick: -60,
+            reward_growths_outside_x64: [1 << 64, 0, 1 << 64],
+            ..Default::default()
+        };
+        let upper = TickData {
+            tick: 60,
+            reward_growths_outside_x64: [2 << 64, 0, 0],
+            ..Default::default()
+        };
+        let mut position = PersonalPositionData {
+            tick_lower_index: -60,
+            tick_upper_index: 60,
+            liquidity: 500_000,
+            ..Default::default()
+        };
+        position.reward_infos[0].growth_inside_last_x64 = 3 << 64;
+        position.reward_infos[0].reward_amount_owed = 9;
+        position.reward_infos[2].reward_amount_owed = 4;
+        // inside is 7 - 1 - 2 plus 400 tokens over the pool liquidity, so half of them less
+        // the rounding
+        let rewards = rewards_owed(&position, &pool, &lower, &upper, 700);
+        assert_eq!(
+            rewards.map(|r| (r.mint, r.amount)),
+            [
+                (mint_a, 500_000 + 199 + 9),
+                (Pubkey::default(), 0),
+                (mint_b, 1_000_000 + 4)
+            ]
+        );
+        // emissions stop at the end time
+        let rewards = rewards_owed(&position, &pool, &lower, &upper, 1_500);
+        assert_eq!(rewards[0].amount, 500_000 + 499 + 9);
+        assert_eq!(
+            rewards_owed(&position, &pool, &lower, &upper, 1_000_000)[0],
+            rewards[0]
+        );
+        assert!(!rewards[1].is_active() && rewards[0].ui_amount().is_none());
+        let reward = RewardOwed {
+            decimals: Some(6),
+            ..rewards[2]
+        };
+        assert_eq!(reward.ui_amount(), Some(1.000_004));
+    }
+
+    #[test]
+    fn test_tick_price() {
+        assert_eq!(price_to_tick(1.0, 6, 6), Some(0));
+        assert_eq!(price_to_tick(0.99995, 6, 6), Some(-1));
+        assert_eq!(tick_to_price(0, 9, 6), 1_000.0);
+        for tick in [-300_000, -18_973, -1, 0, 5, 18_972, 300_000] {
+            let price = tick_to_price(tick, 9, 6);
+            let below = price_to_tick(price * (1.0 - 1e-9), 9, 6);
+            let above = price_to_tick(price * (1.0 + 1e-9), 9, 6);
+            assert_eq!(
+                (below, above),
+                (Some(tick - 1), Some(tick)),
+                "tick {}",
+                tick
+            );
+        }
+        assert_eq!(price_to_tick(f64::MIN_POSITIVE, 9, 6), Some(MIN_TICK));
+        assert_eq!(price_to_tick(f64::MAX, 9, 6), Some(MAX_TICK - 1));
+        assert_eq!(price_to_tick(f64::NAN, 9, 6), None);
+        assert_eq!(price_to_tick(0.0, 9, 6), None);
+        assert_eq!(price_to_tick(-0.0, 9, 6), None);
+        assert_eq!(price_to_tick(-150.0, 9, 6), None);
+        assert_eq!(price_to_tick(f64::INFINITY, 9, 6), None);
+        assert_eq!(price_to_tick(f64::NEG_INFINITY, 9, 6), None);
+        assert_eq!(tick_to_price(i32::MIN, 6, 6), tick_to_price(MIN_TICK, 6, 6));
+    }
+}
@@ -0,0 +1,345 @@
+//! observation accounts of CLMM pools, the oracle ring of tick cumulatives a time weighted
+//! average price is read from
+
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use solana_client::nonblocking::rpc_client::RpcClient;
+#[cfg(feature = "rpc")]
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{
+    error::RaydiumError,
+    reader::{r_bool, r_pubkey, r_u16, r_u32, r_u64},
+    trace::trace_warn,
+};
+#[cfg(feature = "rpc")]
+use crate::{
+    liquidity::clmm::{RaydiumLiquidityPoolCLMMData, math::tick_to_price},
+    routing::unix_now,
+};
+
+/// observation account data size
+pub const OBSERVATION_STATE_DATA_SIZE: usize = 4483;
+/// observations in the ring of an observation account
+pub const OBSERVATION_NUM: usize = 100;
+/// anchor discriminator of the CLMM `ObservationState` account
+pub const OBSERVATION_STATE_DISCRIMINATOR: [u8; 8] = [122, 174, 197, 53, 129, 9, 165, 132];
+/// offsets of the fields in the observation account, after the discriminator
+const INITIALIZED_OFFSET: usize = 8;
+const RECENT_EPOCH_OFFSET: usize = 9;
+const OBSERVATION_INDEX_OFFSET: usize = 17;
+const POOL_ID_OFFSET: usize = 19;
+const OBSERVATIONS_OFFSET: usize = 51;
+// 4451-4482: padding
+/// size of an observation and the offset of its tick cumulative, 32 bytes of padding at the end
+const OBSERVATION_LEN: usize = 44;
+const OBSERVATION_TICK_CUMULATIVE_OFFSET: usize = 4;
+
+/// an entry of the oracle ring, `tick_cumulative` is the sum of the current tick over every
+/// second up to `block_timestamp`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Observation {
+    pub block_timestamp: u32,
+    pub tick_cumulative: i64,
+}
+
+/// parsed observation account. `observations` is a ring of `OBSERVATION_NUM` entries, the one at
+/// `observation_index` written last, entries never written have a zero timestamp
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ObservationState {
+    pub initialized: bool,
+    pub recent_epoch: u64,
+    pub observation_index: u16,
+    pub pool_id: Pubkey,
+    pub observations: Vec<Observation>,
+}
+
+impl ObservationState {
+    /// parse an observation account, checking its discriminator
+    pub fn parse(data: &[u8]) -> Result<Self, RaydiumError> {
+        if data.len() != OBSERVATION_STATE_DATA_SIZE {
+            trace_warn!(
+                expected = OBSERVATION_STATE_DATA_SIZE,
+                actual = data.len(),
+                "observation data size mismatch"
+            );
+            return Err(RaydiumError::Parse(format!(
+                "observation account must be {} bytes, got {}",
+                OBSERVATION_STATE_DATA_SIZE,
+                data.len()
+            )));
+        }
+        let got: [u8; 8] = data[..8].try_into().unwrap();
+        if got != OBSERVATION_STATE_DISCRIMINATOR {
+            trace_warn!(?got, "observation discriminator mismatch");
+            return Err(RaydiumError::WrongDiscriminator {
+                expected: OBSERVATION_STATE_DISCRIMINATOR,
+                got,
+            });
+        }
+        let observations = (0..OBSERVATION_NUM)
+            .map(|i| {
+                let offset = OBSERVATIONS_OFFSET + i * OBSERVATION_LEN;
+                Observation {
+                    block_timestamp: r_u32(data, offset),
+                    tick_cumulative: r_u64(data, offset + OBSERVATION_TICK_CUMULATIVE_OFFSET)
+                        as i64,
+                }
+            })
+            .collect();
+        Ok(Self {
+            initialized: r_bool(data, INITIALIZED_OFFSET),
+            recent_epoch: r_u64(data, RECENT_EPOCH_OFFSET),
+            observation_index: r_u16(data, OBSERVATION_INDEX_OFFSET),
+            pool_id: r_pubkey(data, POOL_ID_OFFSET),
+            observations,
+        })
+    }
+
+    /// the written observations from the oldest to the newest
+    pub fn ordered(&self) -> Vec<Observation> {
+        let split = (self.observation_index as usize + 1).min(self.observations.len());
+        let (newer, older) = self.observations.split_at(split);
+        older
+            .iter()
+            .chain(newer)
+            .filter(|observation| observation.block_timestamp != 0)
+            .copied()
+            .collect()
+    }
+
+    /// the tick cumulative at unix time `time`, interpolated between the observations around
+    /// it and extended from the newest one at `tick_current`. `None` before the oldest
+    pub fn tick_cumulative_at(&self, time: u64, tick_current: i32) -> Option<i64> {
+        let observations = self.ordered();
+        let newest = observations.last()?;
+        if time >= newest.block_timestamp as u64 {
+            let elapsed = (time - newest.block_timestamp as u64) as i64;
+            return Some(newest.tick_cumulative + tick_current as i64 * elapsed);
+        }
+        observations.windows(2).find_map(|pair| {
+            let (before, after) = (pair[0], pair[1]);
+            let (start, end) = (before.block_timestamp as u64, after.block_timestamp as u64);
+            (start..end).contains(&time).then(|| {
+                let delta = (after.tick_cumulative - before.tick_cumulative) as i128;
+                let interpolated = delta * (time - start) as i128 / (end - start) as i128;
+                before.tick_cumulative + interpolated as i64
+            })
+        })
+    }
+
+    /// the mean tick over the `window` ending at unix time `now`, rounded down.
+    /// `TwapWindowTooLong` with the longest window available when the ring does not reach back
+    /// far enough
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::clmm::observation::{Observation, ObservationState};
+    /// # use std::time::Duration;
+    /// let state = ObservationState {
+    ///     observation_index: 1,
+    ///     observations: vec![
+    ///         Observation { block_timestamp: 1_000, tick_cumulative: 0 },
+    ///         Observation { block_timestamp: 1_060, tick_cumulative: 60 * 100 },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    /// // tick 100 for a minute, then 160 since the last swap
+    /// assert_eq!(state.mean_tick(160, 1_120, Duration::from_secs(120)), Ok(130));
+    /// ```
+    pub fn mean_tick(
+        &self,
+        tick_current: i32,
+        now: u64,
+        window: Duration,
+    ) -> Result<i32, RaydiumError> {
+        let window = window.as_secs();
+        if window == 0 {
+            return Ok(tick_current);
+        }
+        let start = now.saturating_sub(window);
+        let too_long = || {
+            let oldest = self
+                .ordered()
+                .first()
+                .map_or(now, |o| o.block_timestamp as u64);
+            RaydiumError::TwapWindowTooLong {
+                max_window: Duration::from_secs(now.saturating_sub(oldest)),
+            }
+        };
+        let (Some(end_cumulative), Some(start_cumulative)) = (
+            self.tick_cumulative_at(now, tick_current),
+            self.tick_cumulative_at(start, tick_current),
+        ) else {
+            return Err(too_long());
+        };
+        Ok((end_cumulative - start_cumulative).div_euclid(window as i64) as i32)
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl RaydiumLiquidityPoolCLMMData {
+    /// get the time weighted average price of token 0 in token 1 over `window`, adjusted for
+    /// decimals, from the observation account of the pool. fails with `TwapWindowTooLong`
+    /// carrying the longest window available when the oracle does not reach back far enough
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # use std::time::Duration;
+    /// # async fn run(ray: Raydium, pool_address: &str) -> Result<(), RaydiumError> {
+    /// let pool_data = ray.get_liquidity_pool_clmm(pool_address).await?;
+    /// let client = ray.solana_client.client_arc();
+    /// let twap = match pool_data.get_twap(client.clone(), Duration::from_secs(3600)).await {
+    ///     Err(RaydiumError::TwapWindowTooLong { max_window }) => {
+    ///         pool_data.get_twap(client, max_window).await?
+    ///     }
+    ///     twap => twap?,
+    /// };
+    /// println!("1h twap {}", twap);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_twap(
+        &self,
+        client: Arc<RpcClient>,
+        window: Duration,
+    ) -> Result<f64, RaydiumError> {
+        let account = client
+            .get_multiple_accounts_with_commitment(&[self.observation_key], client.commitment())
+            .await
+            .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(self.observation_key))?;
+        let tick = ObservationState::parse(&account.data)?.mean_tick(
+            self.tick_current,
+            unix_now(),
+            window,
+        )?;
+        Ok(tick_to_price(
+            tick,
+            self.mint_decimals_0,
+            self.mint_decimals_1,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// an account whose ring wrapped: slot 0 written last, slot 1 the oldest
+    fn observation_data(pool_id: &Pubkey, now: u32) -> Vec<u8> {
+        let mut data = vec![0u8; OBSERVATION_STATE_DATA_SIZE];
+        data[..8].copy_from_slice(&OBSERVATION_STATE_DISCRIMINATOR);
+        data[8] = 1;
+        data[9..17].copy_from_slice(&812u64.to_le_bytes());
+        data[19..51].copy_from_slice(pool_id.as_ref());
+        let mut put = |slot: usize, block_timestamp: u32, tick_cumulative: i64| {
+            let offset = OBSERVATIONS_OFFSET + slot * OBSERVATION_LEN;
+            data[offset..offset + 4].copy_from_slice(&block_timestamp.to_le_bytes());
+            data[offset + 4..offset + 12].copy_from_slice(&tick_cumulative.to_le_bytes());
+        };
+        // tick -100 for 100s, then -200 for 100s, then -300 until now - 50
+        put(1, now - 350, -1_000_000);
+        put(2, now - 250, -1_000_000 - 100 * 100);
+        put(
+            OBSERVATION_NUM - 1,
+            now - 150,
+            -1_000_000 - 100 * 100 - 200 * 100,
+        );
+        put(0, now - 50, -1_000_000 - 100 * 100 - 200 * 100 - 300 * 100);
+        data
+    }
+
+    #[test]
+    fn test_parse_observation_state() {
+        let pool_id = Pubkey::new_unique();
+        let mut data = observation_data(&pool_id, 10_000);
+        let state = ObservationState::parse(&data).unwrap();
+        assert!(state.initialized);
+        assert_eq!(
+            (state.recent_epoch, state.observation_index, state.pool_id),
+            (812, 0, pool_id)
+        );
+        let timestamps: Vec<u32> = state
+            .ordered()
+            .iter()
+            .map(|observation| observation.block_timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![9_650, 9_750, 9_850, 9_950]);
+        assert!(ObservationState::parse(&data[..4482]).is_err());
+        data[0] ^= 1;
+        assert!(matches!(
+            ObservationState::parse(&data),
+            Err(RaydiumError::WrongDiscriminator { .. })
+        ));
+    }
+
+    #[test]
+    fn test_mean_tick() {
+        let state =
+            ObservationState::parse(&observation_data(&Pubkey::new_unique(), 10_000)).unwrap();
+        let mean = |window: u64| state.mean_tick(-400, 10_000, Duration::from_secs(window));
+        // since the last observation only the current tick counts
+        assert_eq!(mean(50), Ok(-400));
+        // half of the window at -300, half at -400
+        assert_eq!(mean(100), Ok(-350));
+        // interpolated inside the -200 interval: 50s at -200, 100s at -300, 50s at -400
+        assert_eq!(mean(200), Ok(-300));
+        // the whole ring, -80_000 over 350s
+        assert_eq!(mean(350), Ok(-229));
+        assert_eq!(mean(0), Ok(-400));
+        assert_eq!(
+            mean(351),
+            Err(RaydiumError::TwapWindowTooLong {
+                max_window: Duration::from_secs(350)
+            })
+        );
+        assert_eq!(
+            ObservationState::default().mean_tick(-400, 10_000, Duration::from_secs(1)),
+            Err(RaydiumError::TwapWindowTooLong {
+                max_window: Duration::ZERO
+            })
+        );
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_get_twap() {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+
+        use crate::{
+            programs::RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+            test_utils::{mock_raydium, multiple_accounts_response, ui_account},
+        };
+
+        let now = unix_now() as u32;
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![Some(ui_account(
+                &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+                observation_data(&Pubkey::new_unique(), now),
+            ))]),
+        );
+        let client = mock_raydium(mocks).solana_client.client_arc();
+        // SOL in USDC, tick -18_973 is about 150
+        let pool = RaydiumLiquidityPoolCLMMData {
+            observation_key: Pubkey::new_unique(),
+            mint_decimals_0: 9,
+            mint_decimals_1: 6,
+            tick_current: -18_973,
+            ..Default::default()
+        };
+        // the whole window is after the last observation, at the current tick
+        let twap = pool
+            .get_twap(client, Duration::from_secs(30))
+            .await
+            .unwrap();
+        assert!((twap - 149.99).abs() < 0.01);
+    }
+}
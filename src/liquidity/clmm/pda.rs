@@ -0,0 +1,212 @@
+//! addresses the CLMM program derives for a pool
+
+use solana_sdk::pubkey::Pubkey;
+
+/// seed of the amm config PDAs
+pub const AMM_CONFIG_SEED: &[u8] = b"amm_config";
+/// seed of the pool PDAs
+pub const POOL_SEED: &[u8] = b"pool";
+/// seed of the token vault PDAs of a pool
+pub const POOL_VAULT_SEED: &[u8] = b"pool_vault";
+/// seed of the observation PDA of a pool
+pub const OBSERVATION_SEED: &[u8] = b"observation";
+/// seed of the tick array PDAs
+pub const TICK_ARRAY_SEED: &[u8] = b"tick_array";
+/// seed of the tick array bitmap extension PDA of a pool
+pub const TICK_ARRAY_BITMAP_EXTENSION_SEED: &[u8] = b"pool_tick_array_bitmap_extension";
+/// seed of the position PDAs
+pub const POSITION_SEED: &[u8] = b"position";
+
+/// the amm config of the fee tier `index`. the index is seeded big-endian
+pub fn amm_config_address(index: u16, program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[AMM_CONFIG_SEED, &index.to_be_bytes()], program).0
+}
+
+/// the two mints in the order of a pool, the lower key first as the program requires
+pub fn sort_mints(mint_a: &Pubkey, mint_b: &Pubkey) -> (Pubkey, Pubkey) {
+    if mint_a < mint_b {
+        (*mint_a, *mint_b)
+    } else {
+        (*mint_b, *mint_a)
+    }
+}
+
+/// the pool of a pair in the fee tier of `amm_config`. the mints can be given in any order,
+/// they are sorted the way the program seeds them
+/// Example
+/// ```rust
+/// # use raydium_sdk::{liquidity::clmm::pda::{amm_config_address, pool_address}, mints::{USDC_MINT, WSOL_MINT}, programs::RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID};
+/// # use solana_sdk::pubkey::Pubkey;
+/// let program = RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID;
+/// let amm_config = amm_config_address(8, &program);
+/// let pool = pool_address(&amm_config, &USDC_MINT, &WSOL_MINT, &program);
+/// assert_eq!(pool, Pubkey::from_str_const("3ucNos4NbumPLZNWztqGHNFFgkHeRMBQAVemeeomsUxv"));
+/// ```
+pub fn pool_address(
+    amm_config: &Pubkey,
+    mint_0: &Pubkey,
+    mint_1: &Pubkey,
+    program: &Pubkey,
+) -> Pubkey {
+    let (mint_0, mint_1) = sort_mints(mint_0, mint_1);
+    Pubkey::find_program_address(
+        &[
+            POOL_SEED,
+            amm_config.as_ref(),
+            mint_0.as_ref(),
+            mint_1.as_ref(),
+        ],
+        program,
+    )
+    .0
+}
+
+/// the vault of `pool` holding `mint`
+pub fn pool_vault_address(pool: &Pubkey, mint: &Pubkey, program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[POOL_VAULT_SEED, pool.as_ref(), mint.as_ref()], program).0
+}
+
+/// the observation account of `pool`, see `observation::ObservationState`
+pub fn observation_address(pool: &Pubkey, program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[OBSERVATION_SEED, pool.as_ref()], program).0
+}
+
+/// the tick array of `pool` starting at `start_index`, see `tick_array::tick_array_start_index`.
+/// the start index is seeded big-endian
+/// Example
+/// ```rust
+/// # use raydium_sdk::{liquidity::clmm::{pda::tick_array_address, tick_array::tick_array_start_index}, programs::RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID};
+/// # use solana_sdk::pubkey::Pubkey;
+/// let pool = Pubkey::from_str_const("3ucNos4NbumPLZNWztqGHNFFgkHeRMBQAVemeeomsUxv");
+/// let start_index = tick_array_start_index(-18_973, 1);
+/// let array = tick_array_address(&pool, start_index, &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID);
+/// println!("ticks {}.. are in {}", start_index, array);
+/// ```
+pub fn tick_array_address(pool: &Pubkey, start_index: i32, program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[TICK_ARRAY_SEED, pool.as_ref(), &start_index.to_be_bytes()],
+        program,
+    )
+    .0
+}
+
+/// the tick array bitmap extension of `pool`
+pub fn tick_array_bitmap_extension_address(pool: &Pubkey, program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[TICK_ARRAY_BITMAP_EXTENSION_SEED, pool.as_ref()], program).0
+}
+
+/// the personal position owned by the holder of the position NFT `nft_mint`
+pub fn personal_position_address(nft_mint: &Pubkey, program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[POSITION_SEED, nft_mint.as_ref()], program).0
+}
+
+/// the protocol position of `pool` between two ticks, shared by the personal positions of the
+/// range. the ticks are seeded big-endian
+pub fn protocol_position_address(
+    pool: &Pubkey,
+    tick_lower: i32,
+    tick_upper: i32,
+    program: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            POSITION_SEED,
+            pool.as_ref(),
+            &tick_lower.to_be_bytes(),
+            &tick_upper.to_be_bytes(),
+        ],
+        program,
+    )
+    .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        liquidity::clmm::tick_array::tick_array_start_index,
+        mints::{USDC_MINT, WSOL_MINT},
+        programs::RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+    };
+
+    /// SOL-USDC pools of three fee tiers and RAY-USDC, with the mints in both orders
+    #[test]
+    fn test_pool_address() {
+        let program = RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID;
+        assert_eq!(
+            amm_config_address(1, &program),
+            Pubkey::from_str_const("E64NGkDLLCdQ2yFNPcavaKptrEgmiQaNykUuLC1Qgwyp")
+        );
+        let ray = Pubkey::from_str_const("4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R");
+        for (index, mint, pool) in [
+            (0, WSOL_MINT, "EXHyQxMSttcvLPwjENnXCPZ8GmLjJYHtNBnAkcFeFKMn"),
+            (4, WSOL_MINT, "8sLbNZoA1cfnvMJLPfp98ZLAnFSYCFApfJKMbiXNLwxj"),
+            (8, WSOL_MINT, "3ucNos4NbumPLZNWztqGHNFFgkHeRMBQAVemeeomsUxv"),
+            (1, ray, "61R1ndXxvsWXXkWSyNkCxnzwd3zUNB8Q2ibmkiLPC8ht"),
+        ] {
+            let amm_config = amm_config_address(index, &program);
+            let pool = Pubkey::from_str_const(pool);
+            assert_eq!(pool_address(&amm_config, &mint, &USDC_MINT, &program), pool);
+            assert_eq!(pool_address(&amm_config, &USDC_MINT, &mint, &program), pool);
+        }
+    }
+
+    #[test]
+    fn test_pool_vault_and_observation_address() {
+        let program = RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID;
+        let pool = Pubkey::from_str_const("3ucNos4NbumPLZNWztqGHNFFgkHeRMBQAVemeeomsUxv");
+        let (vault, _) = Pubkey::find_program_address(
+            &[b"pool_vault", pool.as_ref(), WSOL_MINT.as_ref()],
+            &program,
+        );
+        assert_eq!(pool_vault_address(&pool, &WSOL_MINT, &program), vault);
+        assert_ne!(pool_vault_address(&pool, &USDC_MINT, &program), vault);
+        let (observation, _) =
+            Pubkey::find_program_address(&[b"observation", pool.as_ref()], &program);
+        assert_eq!(observation_address(&pool, &program), observation);
+    }
+
+    #[test]
+    fn test_tick_array_address() {
+        let program = RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID;
+        let pool = Pubkey::from_str_const("3ucNos4NbumPLZNWztqGHNFFgkHeRMBQAVemeeomsUxv");
+        let start_index = tick_array_start_index(-18_973, 1);
+        let address = tick_array_address(&pool, start_index, &program);
+        let (expected, _) = Pubkey::find_program_address(
+            &[b"tick_array", pool.as_ref(), &(-19_020i32).to_be_bytes()],
+            &program,
+        );
+        assert_eq!(address, expected);
+        // the program seeds the index big-endian, little-endian gives another account
+        let (little_endian, _) = Pubkey::find_program_address(
+            &[b"tick_array", pool.as_ref(), &start_index.to_le_bytes()],
+            &program,
+        );
+        assert_ne!(address, little_endian);
+        assert_ne!(
+            tick_array_address(&pool, start_index + 60, &program),
+            address
+        );
+    }
+
+    #[test]
+    fn test_protocol_position_address() {
+        let program = RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID;
+        let pool = Pubkey::new_unique();
+        let address = protocol_position_address(&pool, -120, 60, &program);
+        let (expected, _) = Pubkey::find_program_address(
+            &[
+                b"position",
+                pool.as_ref(),
+                &[0xff, 0xff, 0xff, 0x88],
+                &[0, 0, 0, 60],
+            ],
+            &program,
+        );
+        assert_eq!(address, expected);
+        assert_ne!(
+            protocol_position_address(&pool, 60, -120, &program),
+            address
+        );
+    }
+}
@@ -0,0 +1,572 @@
+//! position accounts of the CLMM program, the personal state behind a position NFT and the
+//! protocol state shared by every position of a tick range
+
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use crate::{
+    Raydium,
+    liquidity::clmm::{
+        TickArrayState, TickData,
+        math::{fees_owed, rewards_owed},
+        pda::{protocol_position_address, tick_array_address},
+        tick_array::tick_array_start_index,
+    },
+    rpc::parse_address,
+    typs::PoolKind,
+};
+use crate::{
+    error::RaydiumError,
+    liquidity::clmm::{
+        RaydiumLiquidityPoolCLMMData,
+        full_math::Rounding,
+        math::{amounts_for_liquidity, tick_to_sqrt_price_x64},
+    },
+    reader::{conver_balance, r_pubkey, r_u8, r_u32, r_u64, r_u128},
+    trace::trace_warn,
+};
+
+/// personal position account data size
+pub const PERSONAL_POSITION_DATA_SIZE: usize = 281;
+/// anchor discriminator of the CLMM `PersonalPositionState` account
+pub const PERSONAL_POSITION_STATE_DISCRIMINATOR: [u8; 8] = [70, 111, 150, 126, 230, 15, 25, 117];
+/// offsets of the fields in the position account, after the discriminator
+const BUMP_OFFSET: usize = 8;
+const NFT_MINT_OFFSET: usize = 9;
+const POOL_ID_OFFSET: usize = 41;
+const TICK_LOWER_INDEX_OFFSET: usize = 73;
+const TICK_UPPER_INDEX_OFFSET: usize = 77;
+const LIQUIDITY_OFFSET: usize = 81;
+const FEE_GROWTH_INSIDE_0_LAST_OFFSET: usize = 97;
+const FEE_GROWTH_INSIDE_1_LAST_OFFSET: usize = 113;
+const TOKEN_FEES_OWED_0_OFFSET: usize = 129;
+const TOKEN_FEES_OWED_1_OFFSET: usize = 137;
+const REWARD_INFOS_OFFSET: usize = 145;
+const RECENT_EPOCH_OFFSET: usize = 217;
+// 225-280: padding
+/// size of a position reward info, the growth then the amount owed
+const REWARD_INFO_LEN: usize = 24;
+
+/// protocol position account data size
+pub const PROTOCOL_POSITION_DATA_SIZE: usize = 225;
+/// anchor discriminator of the CLMM `ProtocolPositionState` account
+pub const PROTOCOL_POSITION_STATE_DISCRIMINATOR: [u8; 8] = [100, 226, 145, 99, 146, 218, 160, 106];
+/// offsets of the fields in the protocol position account, after the discriminator
+const PROTOCOL_BUMP_OFFSET: usize = 8;
+const PROTOCOL_POOL_ID_OFFSET: usize = 9;
+const PROTOCOL_TICK_LOWER_INDEX_OFFSET: usize = 41;
+const PROTOCOL_TICK_UPPER_INDEX_OFFSET: usize = 45;
+const PROTOCOL_LIQUIDITY_OFFSET: usize = 49;
+const PROTOCOL_FEE_GROWTH_INSIDE_0_LAST_OFFSET: usize = 65;
+const PROTOCOL_FEE_GROWTH_INSIDE_1_LAST_OFFSET: usize = 81;
+const PROTOCOL_TOKEN_FEES_OWED_0_OFFSET: usize = 97;
+const PROTOCOL_TOKEN_FEES_OWED_1_OFFSET: usize = 105;
+const PROTOCOL_REWARD_GROWTH_INSIDE_OFFSET: usize = 113;
+const PROTOCOL_RECENT_EPOCH_OFFSET: usize = 161;
+// 169-224: padding
+
+/// parser of CLMM personal position accounts
+pub struct PersonalPositionState;
+
+/// parser of CLMM protocol position accounts
+pub struct ProtocolPositionState;
+
+/// reward of a position, growth in Q64.64
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PositionRewardInfo {
+    pub growth_inside_last_x64: u128,
+    pub reward_amount_owed: u64,
+}
+
+/// reward a position can collect from one of the three reward slots of its pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RewardOwed {
+    /// reward mint, the default key for an inactive slot
+    pub mint: Pubkey,
+    pub amount: u64,
+    /// decimals of the mint when known
+    pub decimals: Option<u8>,
+}
+
+impl RewardOwed {
+    /// whether the slot holds a reward
+    pub fn is_active(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+
+    /// amount with decimals applied, `None` when the decimals are unknown
+    pub fn ui_amount(&self) -> Option<f64> {
+        self.decimals
+            .map(|decimals| conver_balance(self.amount as f64, decimals))
+    }
+}
+
+/// parsed personal position account, growths in Q64.64
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PersonalPositionData {
+    pub bump: u8,
+    /// mint of the NFT owning the position
+    pub nft_mint: Pubkey,
+    pub pool_id: Pubkey,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub liquidity: u128,
+    pub fee_growth_inside_0_last_x64: u128,
+    pub fee_growth_inside_1_last_x64: u128,
+    /// fees collected by the last update of the position and not yet withdrawn
+    pub token_fees_owed_0: u64,
+    pub token_fees_owed_1: u64,
+    pub reward_infos: [PositionRewardInfo; 3],
+    pub recent_epoch: u64,
+}
+
+/// parsed protocol position account, the liquidity and growths of every personal position of a
+/// tick range of a pool, in Q64.64
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProtocolPositionData {
+    pub bump: u8,
+    pub pool_id: Pubkey,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub liquidity: u128,
+    pub fee_growth_inside_0_last_x64: u128,
+    pub fee_growth_inside_1_last_x64: u128,
+    pub token_fees_owed_0: u64,
+    pub token_fees_owed_1: u64,
+    pub reward_growth_inside_x64: [u128; 3],
+    pub recent_epoch: u64,
+}
+
+impl PersonalPositionState {
+    /// parse a personal position account, checking its size and discriminator
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::clmm::position::{PERSONAL_POSITION_DATA_SIZE, PERSONAL_POSITION_STATE_DISCRIMINATOR, PersonalPositionState};
+    /// let mut data = [0u8; PERSONAL_POSITION_DATA_SIZE];
+    /// data[..8].copy_from_slice(&PERSONAL_POSITION_STATE_DISCRIMINATOR);
+    /// data[73..77].copy_from_slice(&(-120i32).to_le_bytes());
+    /// data[77..81].copy_from_slice(&120i32.to_le_bytes());
+    /// let position = PersonalPositionState::parse(&data).unwrap();
+    /// assert_eq!(position.tick_range(), (-120, 120));
+    /// ```
+    pub fn parse(data: &[u8]) -> Result<PersonalPositionData, RaydiumError> {
+        if data.len() != PERSONAL_POSITION_DATA_SIZE {
+            trace_warn!(
+                expected = PERSONAL_POSITION_DATA_SIZE,
+                actual = data.len(),
+                "personal position data size mismatch"
+            );
+            return Err(RaydiumError::Parse(format!(
+                "personal position account must be {} bytes, got {}",
+                PERSONAL_POSITION_DATA_SIZE,
+                data.len()
+            )));
+        }
+        let got: [u8; 8] = data[..8].try_into().unwrap();
+        if got != PERSONAL_POSITION_STATE_DISCRIMINATOR {
+            trace_warn!(?got, "personal position discriminator mismatch");
+            return Err(RaydiumError::WrongDiscriminator {
+                expected: PERSONAL_POSITION_STATE_DISCRIMINATOR,
+                got,
+            });
+        }
+        Ok(PersonalPositionData {
+            bump: r_u8(data, BUMP_OFFSET),
+            nft_mint: r_pubkey(data, NFT_MINT_OFFSET),
+            pool_id: r_pubkey(data, POOL_ID_OFFSET),
+            tick_lower_index: r_u32(data, TICK_LOWER_INDEX_OFFSET) as i32,
+            tick_upper_index: r_u32(data, TICK_UPPER_INDEX_OFFSET) as i32,
+            liquidity: r_u128(data, LIQUIDITY_OFFSET),
+            fee_growth_inside_0_last_x64: r_u128(data, FEE_GROWTH_INSIDE_0_LAST_OFFSET),
+            fee_growth_inside_1_last_x64: r_u128(data, FEE_GROWTH_INSIDE_1_LAST_OFFSET),
+            token_fees_owed_0: r_u64(data, TOKEN_FEES_OWED_0_OFFSET),
+            token_fees_owed_1: r_u64(data, TOKEN_FEES_OWED_1_OFFSET),
+            reward_infos: std::array::from_fn(|i| {
+                let offset = REWARD_INFOS_OFFSET + i * REWARD_INFO_LEN;
+                PositionRewardInfo {
+                    growth_inside_last_x64: r_u128(data, offset),
+                    reward_amount_owed: r_u64(data, offset + 16),
+                }
+            }),
+            recent_epoch: r_u64(data, RECENT_EPOCH_OFFSET),
+        })
+    }
+}
+
+impl ProtocolPositionState {
+    /// parse a protocol position account, checking its size and discriminator
+    pub fn parse(data: &[u8]) -> Result<ProtocolPositionData, RaydiumError> {
+        if data.len() != PROTOCOL_POSITION_DATA_SIZE {
+            trace_warn!(
+                expected = PROTOCOL_POSITION_DATA_SIZE,
+                actual = data.len(),
+                "protocol position data size mismatch"
+            );
+            return Err(RaydiumError::Parse(format!(
+                "protocol position account must be {} bytes, got {}",
+                PROTOCOL_POSITION_DATA_SIZE,
+                data.len()
+            )));
+        }
+        let got: [u8; 8] = data[..8].try_into().unwrap();
+        if got != PROTOCOL_POSITION_STATE_DISCRIMINATOR {
+            trace_warn!(?got, "protocol position discriminator mismatch");
+            return Err(RaydiumError::WrongDiscriminator {
+                expected: PROTOCOL_POSITION_STATE_DISCRIMINATOR,
+                got,
+            });
+        }
+        Ok(ProtocolPositionData {
+            bump: r_u8(data, PROTOCOL_BUMP_OFFSET),
+            pool_id: r_pubkey(data, PROTOCOL_POOL_ID_OFFSET),
+            tick_lower_index: r_u32(data, PROTOCOL_TICK_LOWER_INDEX_OFFSET) as i32,
+            tick_upper_index: r_u32(data, PROTOCOL_TICK_UPPER_INDEX_OFFSET) as i32,
+            liquidity: r_u128(data, PROTOCOL_LIQUIDITY_OFFSET),
+            fee_growth_inside_0_last_x64: r_u128(data, PROTOCOL_FEE_GROWTH_INSIDE_0_LAST_OFFSET),
+            fee_growth_inside_1_last_x64: r_u128(data, PROTOCOL_FEE_GROWTH_INSIDE_1_LAST_OFFSET),
+            token_fees_owed_0: r_u64(data, PROTOCOL_TOKEN_FEES_OWED_0_OFFSET),
+            token_fees_owed_1: r_u64(data, PROTOCOL_TOKEN_FEES_OWED_1_OFFSET),
+            reward_growth_inside_x64: std::array::from_fn(|i| {
+                r_u128(data, PROTOCOL_REWARD_GROWTH_INSIDE_OFFSET + i * 16)
+            }),
+            recent_epoch: r_u64(data, PROTOCOL_RECENT_EPOCH_OFFSET),
+        })
+    }
+}
+
+impl PersonalPositionData {
+    /// the lower and upper ticks of the position
+    pub fn tick_range(&self) -> (i32, i32) {
+        (self.tick_lower_index, self.tick_upper_index)
+    }
+
+    /// token 0 and token 1 amounts the position holds at the pool's current price, rounded
+    /// down as a withdrawal would be. `None` when a tick is out of range or an amount does
+    /// not fit a u64
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::clmm::{PersonalPositionData, RaydiumLiquidityPoolCLMMData};
+    /// let pool = RaydiumLiquidityPoolCLMMData {
+    ///     sqrt_price_x64: 1 << 64,
+    ///     ..Default::default()
+    /// };
+    /// let position = PersonalPositionData {
+    ///     tick_lower_index: 60,
+    ///     tick_upper_index: 120,
+    ///     liquidity: 1_000_000_000,
+    ///     ..Default::default()
+    /// };
+    /// let (amount_0, amount_1) = position.token_amounts(&pool).unwrap();
+    /// assert!(amount_0 > 0);
+    /// assert_eq!(amount_1, 0);
+    /// ```
+    pub fn token_amounts(&self, pool: &RaydiumLiquidityPoolCLMMData) -> Option<(u64, u64)> {
+        amounts_for_liquidity(
+            pool.sqrt_price_x64,
+            tick_to_sqrt_price_x64(self.tick_lower_index)?,
+            tick_to_sqrt_price_x64(self.tick_upper_index)?,
+            self.liquidity,
+            Rounding::Down,
+        )
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// get a personal position account of a CLMM pool
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium, address: &str) -> Result<(), RaydiumError> {
+    /// let position = raydium.get_personal_position(address).await?;
+    /// let (lower, upper) = position.tick_range();
+    /// println!("{} liquidity from tick {} to {}", position.liquidity, lower, upper);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_personal_position(
+        &self,
+        address: &str,
+    ) -> Result<PersonalPositionData, RaydiumError> {
+        let address = parse_address(address)?;
+        let account = self
+            .get_multiple_accounts_with_context(&[address])
+            .await?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(address))?;
+        PersonalPositionState::parse(&account.data)
+    }
+
+    /// get the token 0 and token 1 fees a personal position can collect, fetching the position,
+    /// its pool and the tick arrays holding its bounds
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium, address: &str) -> Result<(), RaydiumError> {
+    /// let (fees_0, fees_1) = raydium.get_position_fees_owed(address).await?;
+    /// println!("fees owed {} and {}", fees_0, fees_1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_position_fees_owed(&self, address: &str) -> Result<(u64, u64), RaydiumError> {
+        let (position, pool, tick_lower, tick_upper) =
+            self.get_position_with_ticks(address).await?;
+        Ok(fees_owed(&position, &pool, &tick_lower, &tick_upper))
+    }
+
+    /// get the rewards a personal position can collect at unix time `now`, fetching the
+    /// position, its pool, the tick arrays holding its bounds and the reward mints for their
+    /// decimals
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # use std::time::{SystemTime, UNIX_EPOCH};
+    /// # async fn run(raydium: Raydium, address: &str) -> Result<(), RaydiumError> {
+    /// let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    /// for reward in raydium.get_position_rewards_owed(address, now).await? {
+    ///     if reward.is_active() {
+    ///         println!("{} of {}", reward.ui_amount().unwrap(), reward.mint);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_position_rewards_owed(
+        &self,
+        address: &str,
+        now: u64,
+    ) -> Result<[RewardOwed; 3], RaydiumError> {
+        let (position, pool, tick_lower, tick_upper) =
+            self.get_position_with_ticks(address).await?;
+        let mut rewards = rewards_owed(&position, &pool, &tick_lower, &tick_upper, now);
+        let mints: Vec<Pubkey> = rewards
+            .iter()
+            .filter(|reward| reward.is_active())
+            .map(|reward| reward.mint)
+            .collect();
+        let infos = self.fetch_mint_infos(&mints).await?;
+        for (reward, info) in rewards
+            .iter_mut()
+            .filter(|reward| reward.is_active())
+            .zip(infos)
+        {
+            reward.decimals = Some(info.decimals);
+        }
+        Ok(rewards)
+    }
+
+    /// a personal position with its pool and the ticks at its bounds
+    async fn get_position_with_ticks(
+        &self,
+        address: &str,
+    ) -> Result<
+        (
+            PersonalPositionData,
+            RaydiumLiquidityPoolCLMMData,
+            TickData,
+            TickData,
+        ),
+        RaydiumError,
+    > {
+        let position = self.get_personal_position(address).await?;
+        let pool = self
+            .get_liquidity_pool_clmm(&position.pool_id.to_string())
+            .await?;
+        let program_id = self.program_id(PoolKind::Clmm);
+        let (lower, upper) = position.tick_range();
+        let mut starts = vec![tick_array_start_index(lower, pool.tick_spacing)];
+        let upper_start = tick_array_start_index(upper, pool.tick_spacing);
+        if upper_start != starts[0] {
+            starts.push(upper_start);
+        }
+        let addresses: Vec<Pubkey> = starts
+            .iter()
+            .map(|start| tick_array_address(&position.pool_id, *start, &program_id))
+            .collect();
+        let accounts = self.get_multiple_accounts_with_context(&addresses).await?;
+        let mut arrays = Vec::with_capacity(addresses.len());
+        for (account, address) in accounts.value.into_iter().zip(&addresses) {
+            let account = account.ok_or(RaydiumError::AccountNotFound(*address))?;
+            arrays.push(TickArrayState::parse(&account.data)?);
+        }
+        let tick = |index| {
+            arrays
+                .iter()
+                .find_map(|array| array.get_tick(index, pool.tick_spacing))
+                .copied()
+                .ok_or_else(|| {
+                    RaydiumError::Parse(format!(
+                        "tick {} is not aligned to tick spacing {}",
+                        index, pool.tick_spacing
+                    ))
+                })
+        };
+        let (tick_lower, tick_upper) = (tick(lower)?, tick(upper)?);
+        Ok((position, pool, tick_lower, tick_upper))
+    }
+
+    /// get the protocol position of a CLMM pool between two ticks, derived from the pool address
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium, address: &str) -> Result<(), RaydiumError> {
+    /// let position = raydium.get_personal_position(address).await?;
+    /// let (lower, upper) = position.tick_range();
+    /// let protocol = raydium
+    ///     .get_protocol_position(&position.pool_id.to_string(), lower, upper)
+    ///     .await?;
+    /// println!("{} of {} liquidity in the range", position.liquidity, protocol.liquidity);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_protocol_position(
+        &self,
+        pool: &str,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<ProtocolPositionData, RaydiumError> {
+        let pool = parse_address(pool)?;
+        let address = protocol_position_address(
+            &pool,
+            tick_lower,
+            tick_upper,
+            &self.program_id(PoolKind::Clmm),
+        );
+        let account = self
+            .get_multiple_accounts_with_context(&[address])
+            .await?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(address))?;
+        ProtocolPositionState::parse(&account.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// every field written at its offset in the packed layout with a distinct value, the u128
+    /// runs at values past u64 so a field read 16 bytes off shows
+    #[test]
+    fn test_parse_personal_position() {
+        let mut data = vec![0u8; PERSONAL_POSITION_DATA_SIZE];
+        let mut put =
+            |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        let (nft_mint, pool_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let big = |n: u128| (u64::MAX as u128 + 1) * n + n;
+        put(0, &PERSONAL_POSITION_STATE_DISCRIMINATOR);
+        put(8, &[255]);
+        put(9, nft_mint.as_ref());
+        put(41, pool_id.as_ref());
+        put(73, &(-18_960i32).to_le_bytes());
+        put(77, &(-18_000i32).to_le_bytes());
+        put(81, &big(1).to_le_bytes());
+        put(97, &big(2).to_le_bytes());
+        put(113, &big(3).to_le_bytes());
+        put(129, &4u64.to_le_bytes());
+        put(137, &5u64.to_le_bytes());
+        for i in 0..3 {
+            put(145 + i * 24, &big(6 + i as u128).to_le_bytes());
+            put(161 + i * 24, &(9 + i as u64).to_le_bytes());
+        }
+        put(217, &812u64.to_le_bytes());
+        // a non-zero padding must not leak into the fields
+        put(225, &[0xff; 56]);
+        let position = PersonalPositionState::parse(&data).unwrap();
+        assert_eq!(
+            position,
+            PersonalPositionData {
+                bump: 255,
+                nft_mint,
+                pool_id,
+                tick_lower_index: -18_960,
+                tick_upper_index: -18_000,
+                liquidity: big(1),
+                fee_growth_inside_0_last_x64: big(2),
+                fee_growth_inside_1_last_x64: big(3),
+                token_fees_owed_0: 4,
+                token_fees_owed_1: 5,
+                reward_infos: std::array::from_fn(|i| PositionRewardInfo {
+                    growth_inside_last_x64: big(6 + i as u128),
+                    reward_amount_owed: 9 + i as u64,
+                }),
+                recent_epoch: 812,
+            }
+        );
+        assert_eq!(position.tick_range(), (-18_960, -18_000));
+        // SOL-USDC at about 150.72, tick -18925, near the bottom of the range
+        let mut pool = RaydiumLiquidityPoolCLMMData {
+            sqrt_price_x64: 7_161_458_000_123_456_789,
+            ..Default::default()
+        };
+        let position = PersonalPositionData {
+            liquidity: 2_000_000_000_000,
+            ..position
+        };
+        assert_eq!(
+            position.token_amounts(&pool),
+            Some((232_687_888_696, 1_379_538_320))
+        );
+        pool.sqrt_price_x64 = 7_000_000_000_000_000_000;
+        assert_eq!(position.token_amounts(&pool), Some((241_857_324_760, 0)));
+        pool.sqrt_price_x64 = 8_000_000_000_000_000_000;
+        assert_eq!(position.token_amounts(&pool), Some((0, 38_108_615_265)));
+        assert!(PersonalPositionState::parse(&data[..280]).is_err());
+        data[0] ^= 1;
+        assert!(matches!(
+            PersonalPositionState::parse(&data),
+            Err(RaydiumError::WrongDiscriminator { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_protocol_position() {
+        let mut data = vec![0u8; PROTOCOL_POSITION_DATA_SIZE];
+        let mut put =
+            |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        let pool_id = Pubkey::new_unique();
+        let big = |n: u128| (u64::MAX as u128 + 1) * n + n;
+        put(0, &PROTOCOL_POSITION_STATE_DISCRIMINATOR);
+        put(8, &[254]);
+        put(9, pool_id.as_ref());
+        put(41, &(-120i32).to_le_bytes());
+        put(45, &60i32.to_le_bytes());
+        put(49, &big(1).to_le_bytes());
+        put(65, &big(2).to_le_bytes());
+        put(81, &big(3).to_le_bytes());
+        put(97, &4u64.to_le_bytes());
+        put(105, &5u64.to_le_bytes());
+        for i in 0..3 {
+            put(113 + i * 16, &big(6 + i as u128).to_le_bytes());
+        }
+        put(161, &812u64.to_le_bytes());
+        put(169, &[0xff; 56]);
+        assert_eq!(
+            ProtocolPositionState::parse(&data),
+            Ok(ProtocolPositionData {
+                bump: 254,
+                pool_id,
+                tick_lower_index: -120,
+                tick_upper_index: 60,
+                liquidity: big(1),
+                fee_growth_inside_0_last_x64: big(2),
+                fee_growth_inside_1_last_x64: big(3),
+                token_fees_owed_0: 4,
+                token_fees_owed_1: 5,
+                reward_growth_inside_x64: [big(6), big(7), big(8)],
+                recent_epoch: 812,
+            })
+        );
+        // a personal position is a different account
+        assert!(ProtocolPositionState::parse(&[0u8; PERSONAL_POSITION_DATA_SIZE]).is_err());
+        data[..8].copy_from_slice(&PERSONAL_POSITION_STATE_DISCRIMINATOR);
+        assert!(matches!(
+            ProtocolPositionState::parse(&data),
+            Err(RaydiumError::WrongDiscriminator { .. })
+        ));
+    }
+}
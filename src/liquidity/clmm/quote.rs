@@ -0,0 +1,771 @@
+//! CLMM swap quotes that walk the initialized ticks like the program, crossing tick arrays and
+//! changing the active liquidity at each initialized tick
+
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use crate::{
+    Raydium,
+    liquidity::clmm::{
+        TickArrayState,
+        bitmap_extension::TickArrayBitmapExtension,
+        pda::{tick_array_address, tick_array_bitmap_extension_address},
+    },
+    rpc::parse_address,
+    typs::PoolKind,
+};
+use crate::{
+    error::RaydiumError,
+    liquidity::clmm::{
+        RAYDIUM_CLMM_FEE_RATE_DENOMINATOR, RaydiumLiquidityPoolCLMMData, TickArrayData, TickData,
+        bitmap_extension::{CombinedTickBitmap, TickArrayBitmapExtensionData},
+        config::ClmmConfig,
+        full_math::{Rounding, mul_div},
+        math::{
+            MAX_SQRT_PRICE_X64, MAX_TICK, MIN_SQRT_PRICE_X64, MIN_TICK, get_delta_amount_0,
+            get_delta_amount_1, get_next_sqrt_price_from_input, get_next_sqrt_price_from_output,
+            sqrt_price_x64_to_tick, tick_to_sqrt_price_x64,
+        },
+        tick_array::{NextTickArray, tick_array_start_index, tick_count},
+    },
+};
+
+/// tick arrays `Raydium::quote_swap_clmm` fetches when `RaydiumConfig::max_clmm_tick_arrays` is
+/// unset
+pub const DEFAULT_MAX_CLMM_TICK_ARRAYS: usize = 5;
+/// tick arrays fetched by each request of `Raydium::quote_swap_clmm`
+#[cfg(feature = "rpc")]
+const TICK_ARRAY_BATCH: usize = 3;
+
+/// quote of a swap against a CLMM pool across its initialized ticks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClmmSwapQuote {
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    /// whether `amount_in` was the specified amount, `amount_out` otherwise
+    pub is_exact_in: bool,
+    /// input taken from the trader, fee included
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// trade fee, in units of the input mint
+    pub fee: u64,
+    pub sqrt_price_x64_after: u128,
+    pub tick_after: i32,
+    /// initialized ticks crossed, each changing the active liquidity
+    pub ticks_crossed: u32,
+    /// start indexes of the tick arrays the swap reads in swap order, the tick array accounts
+    /// to pass to the swap instruction
+    pub tick_arrays: Vec<i32>,
+}
+
+/// a CLMM quote with the tick array accounts the swap instruction needs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClmmSwapQuoteAccounts {
+    pub quote: ClmmSwapQuote,
+    /// addresses of `quote.tick_arrays`, in swap order
+    pub tick_arrays: Vec<Pubkey>,
+    /// the bitmap extension of the pool when the account exists, passed to the swap before the
+    /// tick arrays
+    pub bitmap_extension: Option<Pubkey>,
+}
+
+/// one step of the swap, within the liquidity of a single tick range
+struct SwapStep {
+    sqrt_price_next_x64: u128,
+    amount_in: u64,
+    amount_out: u64,
+    fee_amount: u64,
+}
+
+/// move the price from `sqrt_price_current_x64` toward `sqrt_price_target_x64` with at most
+/// `amount_remaining`, the `compute_swap_step` of the program. `None` on overflow
+fn compute_swap_step(
+    sqrt_price_current_x64: u128,
+    sqrt_price_target_x64: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee_rate: u32,
+    is_exact_in: bool,
+    zero_for_one: bool,
+) -> Option<SwapStep> {
+    let (current, target) = (sqrt_price_current_x64, sqrt_price_target_x64);
+    let fee_rate = fee_rate as u128;
+    let denominator = RAYDIUM_CLMM_FEE_RATE_DENOMINATOR as u128;
+    // the amount that moves the price all the way to the target, `None` past a u64
+    let amount_to_target = match (is_exact_in, zero_for_one) {
+        (true, true) => get_delta_amount_0(target, current, liquidity, Rounding::Up),
+        (true, false) => get_delta_amount_1(current, target, liquidity, Rounding::Up),
+        (false, true) => get_delta_amount_1(target, current, liquidity, Rounding::Down),
+        (false, false) => get_delta_amount_0(current, target, liquidity, Rounding::Down),
+    };
+    let mut step = SwapStep {
+        sqrt_price_next_x64: target,
+        amount_in: 0,
+        amount_out: 0,
+        fee_amount: 0,
+    };
+    if is_exact_in {
+        let amount_less_fee = mul_div(
+            amount_remaining as u128,
+            denominator.checked_sub(fee_rate)?,
+            denominator,
+            Rounding::Down,
+        )? as u64;
+        match amount_to_target {
+            Some(amount) if amount_less_fee >= amount => step.amount_in = amount,
+            _ => {
+                step.sqrt_price_next_x64 = get_next_sqrt_price_from_input(
+                    current,
+                    liquidity,
+                    amount_less_fee,
+                    zero_for_one,
+                )?
+            }
+        }
+    } else {
+        match amount_to_target {
+            Some(amount) if amount_remaining >= amount => step.amount_out = amount,
+            _ => {
+                step.sqrt_price_next_x64 = get_next_sqrt_price_from_output(
+                    current,
+                    liquidity,
+                    amount_remaining,
+                    zero_for_one,
+                )?
+            }
+        }
+    }
+    let next = step.sqrt_price_next_x64;
+    let reached_target = next == target;
+    // at the target the specified side keeps the amount to reach it, the other is recomputed
+    if !reached_target || !is_exact_in {
+        step.amount_in = if zero_for_one {
+            get_delta_amount_0(next, current, liquidity, Rounding::Up)?
+        } else {
+            get_delta_amount_1(current, next, liquidity, Rounding::Up)?
+        };
+    }
+    if !reached_target || is_exact_in {
+        step.amount_out = if zero_for_one {
+            get_delta_amount_1(next, current, liquidity, Rounding::Down)?
+        } else {
+            get_delta_amount_0(current, next, liquidity, Rounding::Down)?
+        };
+    }
+    if !is_exact_in {
+        step.amount_out = step.amount_out.min(amount_remaining);
+    }
+    step.fee_amount = if is_exact_in && !reached_target {
+        // the price stopped inside the range, the rest of the input is fee
+        amount_remaining.checked_sub(step.amount_in)?
+    } else {
+        mul_div(
+            step.amount_in as u128,
+            fee_rate,
+            denominator - fee_rate,
+            Rounding::Up,
+        )? as u64
+    };
+    Some(step)
+}
+
+/// quote a swap of `amount` against a CLMM pool, crossing initialized ticks and changing the
+/// active liquidity like the program. `amount` is the input when `is_exact_in` and the output
+/// otherwise, the trade fee of `amm_config` is taken from the input.
+///
+/// `tick_arrays` are the arrays the swap may reach, in any order. the pool bitmap picks the
+/// next initialized array; past it, where the bitmap extension account decides, the nearest
+/// provided array in the swap direction is used, see `compute_swap_with_extension`. a swap
+/// reaching an array that was not provided fails with `TickArrayMissing` naming the start index
+/// to fetch, a swap running out of liquidity before the amount is filled with
+/// `InsufficientLiquidity`
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{error::RaydiumError, liquidity::clmm::{ClmmConfig, RaydiumLiquidityPoolCLMMData, TickArrayData, compute_swap}};
+/// # fn run(pool: RaydiumLiquidityPoolCLMMData, config: ClmmConfig, tick_arrays: Vec<TickArrayData>) {
+/// match compute_swap(&pool, &config, &tick_arrays, &pool.token_mint_0, 1_000_000_000, true) {
+///     Ok(quote) => println!("out {} crossing {} ticks", quote.amount_out, quote.ticks_crossed),
+///     Err(RaydiumError::TickArrayMissing { start_index }) => {
+///         println!("fetch the tick array starting at {} and quote again", start_index)
+///     }
+///     Err(e) => println!("no quote: {}", e),
+/// }
+/// # }
+/// ```
+pub fn compute_swap(
+    pool: &RaydiumLiquidityPoolCLMMData,
+    amm_config: &ClmmConfig,
+    tick_arrays: &[TickArrayData],
+    input_mint: &Pubkey,
+    amount: u64,
+    is_exact_in: bool,
+) -> Result<ClmmSwapQuote, RaydiumError> {
+    compute_swap_with_extension(
+        pool,
+        None,
+        amm_config,
+        tick_arrays,
+        input_mint,
+        amount,
+        is_exact_in,
+    )
+}
+
+/// `compute_swap` with the tick array bitmap extension of the pool, so arrays beyond the pool
+/// bitmap are picked exactly instead of from the provided ones
+pub fn compute_swap_with_extension(
+    pool: &RaydiumLiquidityPoolCLMMData,
+    extension: Option<&TickArrayBitmapExtensionData>,
+    amm_config: &ClmmConfig,
+    tick_arrays: &[TickArrayData],
+    input_mint: &Pubkey,
+    amount: u64,
+    is_exact_in: bool,
+) -> Result<ClmmSwapQuote, RaydiumError> {
+    let zero_for_one = if *input_mint == pool.token_mint_0 {
+        true
+    } else if *input_mint == pool.token_mint_1 {
+        false
+    } else {
+        return Err(RaydiumError::MintNotInPool(*input_mint));
+    };
+    let output_mint = if zero_for_one {
+        pool.token_mint_1
+    } else {
+        pool.token_mint_0
+    };
+    if amount == 0 {
+        return Err(RaydiumError::AmountTooSmall(amount));
+    }
+    let tick_spacing = pool.tick_spacing;
+    if tick_spacing == 0 {
+        return Err(RaydiumError::Unsupported(
+            "clmm pool with a zero tick spacing".to_string(),
+        ));
+    }
+    let overflow = || RaydiumError::Unsupported("clmm swap overflows the pool math".to_string());
+    let ticks = tick_count(tick_spacing);
+    let bitmap = CombinedTickBitmap::new(pool, extension);
+    // the first provided array holding an initialized tick from the one of `tick`, with the
+    // tick a swap entering it meets first
+    let next_array = |mut tick: i32| -> Result<(&TickArrayData, TickData), RaydiumError> {
+        loop {
+            let array = match bitmap.next_initialized_tick_array(tick, zero_for_one) {
+                None => return Err(RaydiumError::InsufficientLiquidity),
+                Some(NextTickArray::Found(start)) => tick_arrays
+                    .iter()
+                    .find(|array| array.start_tick_index == start)
+                    .ok_or(RaydiumError::TickArrayMissing { start_index: start })?,
+                Some(NextTickArray::Extension(start)) => tick_arrays
+                    .iter()
+                    .filter(|array| {
+                        if zero_for_one {
+                            array.start_tick_index <= start
+                        } else {
+                            array.start_tick_index >= start
+                        }
+                    })
+                    .min_by_key(|array| array.start_tick_index.abs_diff(start))
+                    .ok_or(RaydiumError::TickArrayMissing { start_index: start })?,
+            };
+            if let Some(first) = array.first_initialized_tick(zero_for_one) {
+                return Ok((array, *first));
+            }
+            tick = if zero_for_one {
+                array.start_tick_index - 1
+            } else {
+                array.start_tick_index + ticks
+            };
+        }
+    };
+
+    let sqrt_price_limit_x64 = if zero_for_one {
+        MIN_SQRT_PRICE_X64 + 1
+    } else {
+        MAX_SQRT_PRICE_X64 - 1
+    };
+    let (mut array, mut first_tick) = next_array(pool.tick_current)?;
+    // an array past the one of the current tick is entered at its first initialized tick
+    let mut entered =
+        array.start_tick_index == tick_array_start_index(pool.tick_current, tick_spacing);
+    let mut consumed = vec![array.start_tick_index];
+    let mut amount_remaining = amount;
+    let mut amount_calculated = 0u64;
+    let mut fee = 0u64;
+    let mut ticks_crossed = 0;
+    let mut sqrt_price_x64 = pool.sqrt_price_x64;
+    let mut tick = pool.tick_current;
+    let mut liquidity = pool.liquidity;
+    while amount_remaining != 0 && sqrt_price_x64 != sqrt_price_limit_x64 {
+        let next_tick = match array.next_initialized_tick(tick, tick_spacing, zero_for_one) {
+            Some(next_tick) => *next_tick,
+            None if !entered => {
+                entered = true;
+                first_tick
+            }
+            None => {
+                let start = array.start_tick_index;
+                (array, first_tick) = next_array(if zero_for_one {
+                    start - 1
+                } else {
+                    start + ticks
+                })?;
+                consumed.push(array.start_tick_index);
+                first_tick
+            }
+        };
+        let tick_next = next_tick.tick.clamp(MIN_TICK, MAX_TICK);
+        let sqrt_price_next_x64 = tick_to_sqrt_price_x64(tick_next).ok_or_else(overflow)?;
+        let target = if zero_for_one {
+            sqrt_price_next_x64.max(sqrt_price_limit_x64)
+        } else {
+            sqrt_price_next_x64.min(sqrt_price_limit_x64)
+        };
+        let step = compute_swap_step(
+            sqrt_price_x64,
+            target,
+            liquidity,
+            amount_remaining,
+            amm_config.trade_fee_rate,
+            is_exact_in,
+            zero_for_one,
+        )
+        .ok_or_else(overflow)?;
+        let sqrt_price_start_x64 = sqrt_price_x64;
+        sqrt_price_x64 = step.sqrt_price_next_x64;
+        let (specified, calculated) = if is_exact_in {
+            (step.amount_in.checked_add(step.fee_amount), step.amount_out)
+        } else {
+            (
+                Some(step.amount_out),
+                step.amount_in
+                    .checked_add(step.fee_amount)
+                    .ok_or_else(overflow)?,
+            )
+        };
+        amount_remaining = specified
+            .and_then(|specified| amount_remaining.checked_sub(specified))
+            .ok_or_else(overflow)?;
+        amount_calculated = amount_calculated
+            .checked_add(calculated)
+            .ok_or_else(overflow)?;
+        fee += step.fee_amount;
+        if sqrt_price_x64 == sqrt_price_next_x64 {
+            // crossing down removes the liquidity the tick adds when crossed up
+            let liquidity_net = if zero_for_one {
+                -next_tick.liquidity_net
+            } else {
+                next_tick.liquidity_net
+            };
+            liquidity = liquidity
+                .checked_add_signed(liquidity_net)
+                .ok_or_else(overflow)?;
+            ticks_crossed += 1;
+            tick = if zero_for_one {
+                tick_next - 1
+            } else {
+                tick_next
+            };
+        } else if sqrt_price_x64 != sqrt_price_start_x64 {
+            tick = sqrt_price_x64_to_tick(sqrt_price_x64).ok_or_else(overflow)?;
+        }
+    }
+    if amount_remaining != 0 {
+        return Err(RaydiumError::InsufficientLiquidity);
+    }
+    let (amount_in, amount_out) = if is_exact_in {
+        (amount, amount_calculated)
+    } else {
+        (amount_calculated, amount)
+    };
+    Ok(ClmmSwapQuote {
+        input_mint: *input_mint,
+        output_mint,
+        is_exact_in,
+        amount_in,
+        amount_out,
+        fee,
+        sqrt_price_x64_after: sqrt_price_x64,
+        tick_after: tick,
+        ticks_crossed,
+        tick_arrays: consumed,
+    })
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// quote a swap of `amount_in` of `input_mint` against a CLMM pool across its ticks, fetching
+    /// the pool, its amm config, its bitmap extension and the tick arrays the swap reaches.
+    /// arrays are fetched a few per request in the swap direction, up to
+    /// `RaydiumConfig::max_clmm_tick_arrays`; a swap needing more fails with `TickArrayMissing`
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::WSOL_MINT};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let quoted = raydium
+    ///     .quote_swap_clmm("3ucNos4NbumPLZNWztqGHNFFgkHeRMBQAVemeeomsUxv", &WSOL_MINT, 1_000_000_000)
+    ///     .await?;
+    /// println!("out {} through {:?}", quoted.quote.amount_out, quoted.tick_arrays);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn quote_swap_clmm(
+        &self,
+        pool_address: &str,
+        input_mint: &Pubkey,
+        amount_in: u64,
+    ) -> Result<ClmmSwapQuoteAccounts, RaydiumError> {
+        let pool_id = parse_address(pool_address)?;
+        let pool = self.get_liquidity_pool_clmm(pool_address).await?;
+        let zero_for_one = if *input_mint == pool.token_mint_0 {
+            true
+        } else if *input_mint == pool.token_mint_1 {
+            false
+        } else {
+            return Err(RaydiumError::MintNotInPool(*input_mint));
+        };
+        let program_id = self.program_id(PoolKind::Clmm);
+        let extension_address = tick_array_bitmap_extension_address(&pool_id, &program_id);
+        let mut accounts = self
+            .get_multiple_accounts_with_context(&[pool.amm_config, extension_address])
+            .await?
+            .value
+            .into_iter();
+        let amm_config = accounts
+            .next()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(pool.amm_config))?;
+        let amm_config = ClmmConfig::parse(&amm_config.data)?;
+        // pools without arrays beyond the pool bitmap may have no extension account
+        let extension = accounts
+            .next()
+            .flatten()
+            .map(|account| TickArrayBitmapExtension::parse(&account.data))
+            .transpose()?;
+        let bitmap = CombinedTickBitmap::new(&pool, extension.as_ref());
+        let max_tick_arrays = self
+            .config
+            .max_clmm_tick_arrays
+            .unwrap_or(DEFAULT_MAX_CLMM_TICK_ARRAYS);
+        let mut tick_arrays = Vec::new();
+        let mut fetched = 0;
+        let mut from_tick = pool.tick_current;
+        loop {
+            let starts: Vec<i32> = bitmap
+                .initialized_tick_arrays(from_tick, zero_for_one)
+                .filter_map(|next| match next {
+                    NextTickArray::Found(start) => Some(start),
+                    NextTickArray::Extension(_) => None,
+                })
+                .take(TICK_ARRAY_BATCH.min(max_tick_arrays - fetched))
+                .collect();
+            if !starts.is_empty() {
+                let addresses: Vec<Pubkey> = starts
+                    .iter()
+                    .map(|start| tick_array_address(&pool_id, *start, &program_id))
+                    .collect();
+                let accounts = self.get_multiple_accounts_with_context(&addresses).await?;
+                for account in accounts.value.into_iter().flatten() {
+                    tick_arrays.push(TickArrayState::parse(&account.data)?);
+                }
+                fetched += starts.len();
+            }
+            match compute_swap_with_extension(
+                &pool,
+                extension.as_ref(),
+                &amm_config,
+                &tick_arrays,
+                input_mint,
+                amount_in,
+                true,
+            ) {
+                Err(RaydiumError::TickArrayMissing { start_index })
+                    if !starts.is_empty() && fetched < max_tick_arrays =>
+                {
+                    from_tick = start_index;
+                }
+                quote => {
+                    let quote = quote?;
+                    let tick_arrays = quote
+                        .tick_arrays
+                        .iter()
+                        .map(|start| tick_array_address(&pool_id, *start, &program_id))
+                        .collect();
+                    return Ok(ClmmSwapQuoteAccounts {
+                        quote,
+                        tick_arrays,
+                        bitmap_extension: extension.as_ref().map(|_| extension_address),
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a pool at price 1 with spacing 60: 3e12 of liquidity between ticks -120 and 120 and 2e12
+    /// more out to -600 and 600, in the arrays starting at -3600 and 0
+    fn fixture() -> (RaydiumLiquidityPoolCLMMData, ClmmConfig, Vec<TickArrayData>) {
+        let mut pool = RaydiumLiquidityPoolCLMMData {
+            token_mint_0: Pubkey::new_unique(),
+            token_mint_1: Pubkey::new_unique(),
+            tick_spacing: 60,
+            liquidity: 3_000_000_000_000,
+            sqrt_price_x64: 1 << 64,
+            tick_current: 0,
+            ..Default::default()
+        };
+        for bit in [511, 512] {
+            pool.tick_array_bitmap[bit / 64] |= 1 << (bit % 64);
+        }
+        let config = ClmmConfig {
+            trade_fee_rate: 2_500,
+            tick_spacing: 60,
+            ..Default::default()
+        };
+        let array = |start_tick_index: i32, ticks: [(i32, i128); 2]| {
+            let mut array = TickArrayData {
+                pool_id: Pubkey::default(),
+                start_tick_index,
+                ticks: std::array::from_fn(|i| TickData {
+                    tick: start_tick_index + 60 * i as i32,
+                    ..Default::default()
+                }),
+                initialized_tick_count: 2,
+                recent_epoch: 0,
+            };
+            for (tick, liquidity_net) in ticks {
+                let t = &mut array.ticks[((tick - start_tick_index) / 60) as usize];
+                t.liquidity_net = liquidity_net;
+                t.liquidity_gross = liquidity_net.unsigned_abs();
+            }
+            array
+        };
+        let arrays = vec![
+            array(0, [(120, -1_000_000_000_000), (600, -2_000_000_000_000)]),
+            array(
+                -3_600,
+                [(-600, 2_000_000_000_000), (-120, 1_000_000_000_000)],
+            ),
+        ];
+        (pool, config, arrays)
+    }
+
+    /// expected values from exact big integer arithmetic following the program step by step
+    #[test]
+    fn test_compute_swap() {
+        let (pool, config, arrays) = fixture();
+        let (mint_0, mint_1) = (pool.token_mint_0, pool.token_mint_1);
+        // within the active range
+        let quote = compute_swap(&pool, &config, &arrays, &mint_1, 1_000_000, true).unwrap();
+        assert_eq!(
+            (quote.amount_in, quote.amount_out, quote.fee),
+            (1_000_000, 997_499, 2_500)
+        );
+        assert_eq!((quote.tick_after, quote.ticks_crossed), (0, 0));
+        assert_eq!(quote.tick_arrays, [0]);
+        // token 0 in past tick -120 leaves the array of the current tick
+        let quote = compute_swap(&pool, &config, &arrays, &mint_0, 40_000_000_000, true).unwrap();
+        assert_eq!(quote.output_mint, mint_1);
+        assert_eq!(
+            (quote.amount_out, quote.fee, quote.tick_after),
+            (39_299_561_805, 100_000_001, -337)
+        );
+        assert_eq!(quote.sqrt_price_x64_after, 18_139_441_387_077_524_040);
+        assert_eq!(quote.ticks_crossed, 1);
+        assert_eq!(quote.tick_arrays, [0, -3_600]);
+        // asking for that output back costs the same input, stopping a few units of sqrt price
+        // higher
+        let exact_out =
+            compute_swap(&pool, &config, &arrays, &mint_0, quote.amount_out, false).unwrap();
+        assert_eq!(exact_out.amount_out, quote.amount_out);
+        assert_eq!(
+            (exact_out.amount_in, exact_out.fee),
+            (40_000_000_000, 100_000_001)
+        );
+        assert_eq!(exact_out.sqrt_price_x64_after, 18_139_441_387_083_907_132);
+        assert_eq!(exact_out.ticks_crossed, 1);
+    }
+
+    #[test]
+    fn test_compute_swap_errors() {
+        let (pool, config, arrays) = fixture();
+        let mint_0 = pool.token_mint_0;
+        assert_eq!(
+            compute_swap(&pool, &config, &arrays[..1], &mint_0, 40_000_000_000, true),
+            Err(RaydiumError::TickArrayMissing {
+                start_index: -3_600
+            })
+        );
+        // past tick -600 nothing is left to swap against
+        assert_eq!(
+            compute_swap(&pool, &config, &arrays, &mint_0, u64::MAX / 2, true),
+            Err(RaydiumError::InsufficientLiquidity)
+        );
+        let other = Pubkey::new_unique();
+        assert_eq!(
+            compute_swap(&pool, &config, &arrays, &other, 1, true),
+            Err(RaydiumError::MintNotInPool(other))
+        );
+        assert_eq!(
+            compute_swap(&pool, &config, &arrays, &mint_0, 0, false),
+            Err(RaydiumError::AmountTooSmall(0))
+        );
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_quote_swap_clmm() -> Result<(), Box<dyn std::error::Error>> {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+
+        use crate::{
+            config::RaydiumConfig,
+            liquidity::clmm::{
+                RAYDIUM_CLMM_POOL_STATE_DISCRIMINATOR, RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE,
+                config::{CLMM_CONFIG_DATA_SIZE, CLMM_CONFIG_DISCRIMINATOR},
+                tick_array::{TICK_ARRAY_DATA_SIZE, TICK_ARRAY_STATE_DISCRIMINATOR},
+            },
+            programs::RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+            test_utils::{
+                account_info_response, mock_raydium_with_config, multiple_accounts_response,
+                ui_account,
+            },
+        };
+
+        let (pool, config, arrays) = fixture();
+        let pool_address = Pubkey::new_unique();
+        let amm_config = Pubkey::new_unique();
+        let mut pool_data = vec![0u8; RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE];
+        let mut put = |offset: usize, bytes: &[u8]| {
+            pool_data[offset..offset + bytes.len()].copy_from_slice(bytes)
+        };
+        put(0, &RAYDIUM_CLMM_POOL_STATE_DISCRIMINATOR);
+        put(9, amm_config.as_ref());
+        put(73, pool.token_mint_0.as_ref());
+        put(105, pool.token_mint_1.as_ref());
+        put(235, &pool.tick_spacing.to_le_bytes());
+        put(237, &pool.liquidity.to_le_bytes());
+        put(253, &pool.sqrt_price_x64.to_le_bytes());
+        put(269, &pool.tick_current.to_le_bytes());
+        for (i, word) in pool.tick_array_bitmap.iter().enumerate() {
+            put(904 + i * 8, &word.to_le_bytes());
+        }
+        let mut config_data = vec![0u8; CLMM_CONFIG_DATA_SIZE];
+        config_data[..8].copy_from_slice(&CLMM_CONFIG_DISCRIMINATOR);
+        config_data[47..51].copy_from_slice(&config.trade_fee_rate.to_le_bytes());
+        let array_account = |array: &TickArrayData| {
+            let mut data = vec![0u8; TICK_ARRAY_DATA_SIZE];
+            data[..8].copy_from_slice(&TICK_ARRAY_STATE_DISCRIMINATOR);
+            data[40..44].copy_from_slice(&array.start_tick_index.to_le_bytes());
+            for (i, tick) in array.ticks.iter().enumerate() {
+                let offset = 44 + i * 168;
+                data[offset..offset + 4].copy_from_slice(&tick.tick.to_le_bytes());
+                data[offset + 4..offset + 20].copy_from_slice(&tick.liquidity_net.to_le_bytes());
+                data[offset + 20..offset + 36].copy_from_slice(&tick.liquidity_gross.to_le_bytes());
+            }
+            Some(ui_account(&RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID, data))
+        };
+        let mocks = |array_batches: Vec<Vec<Option<_>>>| {
+            let mut mocks = MocksMap::default();
+            mocks.insert(
+                RpcRequest::GetAccountInfo,
+                account_info_response(&RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID, pool_data.clone()),
+            );
+            // the amm config, and no bitmap extension
+            mocks.insert(
+                RpcRequest::GetMultipleAccounts,
+                multiple_accounts_response(vec![
+                    Some(ui_account(
+                        &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+                        config_data.clone(),
+                    )),
+                    None,
+                ]),
+            );
+            for batch in array_batches {
+                mocks.insert(
+                    RpcRequest::GetMultipleAccounts,
+                    multiple_accounts_response(batch),
+                );
+            }
+            mocks
+        };
+
+        // both arrays below the current tick come in the first batch
+        let raydium = mock_raydium_with_config(
+            mocks(vec![vec![
+                array_account(&arrays[0]),
+                array_account(&arrays[1]),
+            ]]),
+            RaydiumConfig::default(),
+        );
+        let quoted = raydium
+            .quote_swap_clmm(
+                &pool_address.to_string(),
+                &pool.token_mint_0,
+                40_000_000_000,
+            )
+            .await?;
+        assert_eq!(
+            quoted.quote,
+            compute_swap(
+                &pool,
+                &config,
+                &arrays,
+                &pool.token_mint_0,
+                40_000_000_000,
+                true
+            )?
+        );
+        assert_eq!(quoted.bitmap_extension, None);
+        assert_eq!(
+            quoted.tick_arrays,
+            [
+                tick_array_address(&pool_address, 0, &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID),
+                tick_array_address(
+                    &pool_address,
+                    -3_600,
+                    &RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID
+                )
+            ]
+        );
+        // an array missing from a batch is requested again from where the quote ran out
+        let raydium = mock_raydium_with_config(
+            mocks(vec![
+                vec![array_account(&arrays[0]), None],
+                vec![array_account(&arrays[1])],
+            ]),
+            RaydiumConfig::default(),
+        );
+        let retried = raydium
+            .quote_swap_clmm(
+                &pool_address.to_string(),
+                &pool.token_mint_0,
+                40_000_000_000,
+            )
+            .await?;
+        assert_eq!(retried, quoted);
+        // capped at one array, the quote gives up when it needs the second
+        let raydium = mock_raydium_with_config(
+            mocks(vec![vec![array_account(&arrays[0])]]),
+            RaydiumConfig::new().with_max_clmm_tick_arrays(1),
+        );
+        assert_eq!(
+            raydium
+                .quote_swap_clmm(
+                    &pool_address.to_string(),
+                    &pool.token_mint_0,
+                    40_000_000_000
+                )
+                .await,
+            Err(RaydiumError::TickArrayMissing {
+                start_index: -3_600
+            })
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,478 @@
+//! tick array accounts of a CLMM pool, the initialized ticks a swap crosses
+
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use crate::{Raydium, rpc::parse_address};
+use crate::{
+    error::RaydiumError,
+    liquidity::clmm::{
+        RaydiumLiquidityPoolCLMMData,
+        bitmap_extension::CombinedTickBitmap,
+        math::{MAX_TICK, MIN_TICK},
+    },
+    reader::{r_pubkey, r_u8, r_u32, r_u64, r_u128},
+    trace::trace_warn,
+};
+
+/// tick array account data size
+pub const TICK_ARRAY_DATA_SIZE: usize = 10240;
+/// ticks in a tick array
+pub const TICK_ARRAY_SIZE: usize = 60;
+/// anchor discriminator of the CLMM `TickArrayState` account
+pub const TICK_ARRAY_STATE_DISCRIMINATOR: [u8; 8] = [192, 155, 85, 205, 49, 249, 129, 42];
+/// offsets of the fields in the tick array account, after the discriminator
+const POOL_ID_OFFSET: usize = 8;
+const START_TICK_INDEX_OFFSET: usize = 40;
+const TICKS_OFFSET: usize = 44;
+const INITIALIZED_TICK_COUNT_OFFSET: usize = 10124;
+const RECENT_EPOCH_OFFSET: usize = 10125;
+// 10133-10239: padding
+/// size of a tick and the offsets of its fields, 52 bytes of padding at the end
+const TICK_LEN: usize = 168;
+const TICK_LIQUIDITY_NET_OFFSET: usize = 4;
+const TICK_LIQUIDITY_GROSS_OFFSET: usize = 20;
+const TICK_FEE_GROWTH_OUTSIDE_0_OFFSET: usize = 36;
+const TICK_FEE_GROWTH_OUTSIDE_1_OFFSET: usize = 52;
+const TICK_REWARD_GROWTHS_OUTSIDE_OFFSET: usize = 68;
+
+/// tick arrays on each side of zero tracked by a tick array bitmap
+pub const TICK_ARRAY_BITMAP_SIZE: i32 = 512;
+
+/// parser of CLMM tick array accounts
+pub struct TickArrayState;
+
+/// ticks covered by a tick array at a tick spacing
+pub fn tick_count(tick_spacing: u16) -> i32 {
+    TICK_ARRAY_SIZE as i32 * tick_spacing as i32
+}
+
+/// start index of the tick array holding `tick`, rounded down to a multiple of
+/// `60 * tick_spacing` so negative ticks land in the array below zero
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::tick_array::tick_array_start_index;
+/// assert_eq!(tick_array_start_index(-18_973, 10), -19_200);
+/// assert_eq!(tick_array_start_index(-600, 10), -600);
+/// assert_eq!(tick_array_start_index(599, 10), 0);
+/// ```
+pub fn tick_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
+    let ticks = tick_count(tick_spacing);
+    tick.div_euclid(ticks) * ticks
+}
+
+/// whether the tick array starting at `start_index` is tracked by the bitmap of the pool
+/// account, the 512 arrays on each side of zero. arrays past them need the bitmap extension
+pub fn is_in_pool_bitmap(start_index: i32, tick_spacing: u16) -> bool {
+    let ticks = tick_count(tick_spacing) * TICK_ARRAY_BITMAP_SIZE;
+    (-ticks..ticks).contains(&start_index)
+}
+
+/// check a position range the way the program does: increasing, within the tick range and
+/// aligned to the tick spacing
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::clmm::tick_array::check_tick_range;
+/// assert!(check_tick_range(-120, 60, 60).is_ok());
+/// assert!(check_tick_range(60, -120, 60).is_err());
+/// assert!(check_tick_range(-100, 60, 60).is_err());
+/// ```
+pub fn check_tick_range(
+    tick_lower: i32,
+    tick_upper: i32,
+    tick_spacing: u16,
+) -> Result<(), RaydiumError> {
+    let aligned = |tick: i32| tick_spacing != 0 && tick % tick_spacing as i32 == 0;
+    if tick_lower < tick_upper
+        && tick_lower >= MIN_TICK
+        && tick_upper <= MAX_TICK
+        && aligned(tick_lower)
+        && aligned(tick_upper)
+    {
+        Ok(())
+    } else {
+        Err(RaydiumError::InvalidTickRange {
+            tick_lower,
+            tick_upper,
+            tick_spacing,
+        })
+    }
+}
+
+/// bit of the tick array starting at `start_index` within the 512 arrays of its bitmap side,
+/// the `tick_array_offset_in_bitmap` of the program used for the bitmap extension. arrays
+/// below zero count down from the top of their side
+pub fn tick_array_offset_in_bitmap(start_index: i32, tick_spacing: u16) -> i32 {
+    let ticks = tick_count(tick_spacing);
+    let m = start_index.abs() % (ticks * TICK_ARRAY_BITMAP_SIZE);
+    let offset = m / ticks;
+    if start_index < 0 && m != 0 {
+        TICK_ARRAY_BITMAP_SIZE - offset
+    } else {
+        offset
+    }
+}
+
+/// a tick of a tick array, growths in Q64.64
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TickData {
+    pub tick: i32,
+    /// liquidity added when the price crosses the tick upward
+    pub liquidity_net: i128,
+    /// liquidity of the positions referencing the tick, zero when uninitialized
+    pub liquidity_gross: u128,
+    pub fee_growth_outside_0_x64: u128,
+    pub fee_growth_outside_1_x64: u128,
+    pub reward_growths_outside_x64: [u128; 3],
+}
+
+/// parsed tick array account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TickArrayData {
+    pub pool_id: Pubkey,
+    /// tick of the first element
+    pub start_tick_index: i32,
+    pub ticks: [TickData; TICK_ARRAY_SIZE],
+    pub initialized_tick_count: u8,
+    pub recent_epoch: u64,
+}
+
+impl TickArrayState {
+    /// parse a tick array account, checking its discriminator
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::clmm::{TickArrayState, tick_array::{TICK_ARRAY_DATA_SIZE, TICK_ARRAY_STATE_DISCRIMINATOR}};
+    /// let mut data = [0u8; TICK_ARRAY_DATA_SIZE];
+    /// data[..8].copy_from_slice(&TICK_ARRAY_STATE_DISCRIMINATOR);
+    /// let array = TickArrayState::parse(&data).unwrap();
+    /// assert_eq!(array.initialized_ticks().count(), 0);
+    /// ```
+    pub fn parse(data: &[u8]) -> Result<TickArrayData, RaydiumError> {
+        if data.len() != TICK_ARRAY_DATA_SIZE {
+            trace_warn!(
+                expected = TICK_ARRAY_DATA_SIZE,
+                actual = data.len(),
+                "tick array data size mismatch"
+            );
+            return Err(RaydiumError::Parse(format!(
+                "tick array account must be {} bytes, got {}",
+                TICK_ARRAY_DATA_SIZE,
+                data.len()
+            )));
+        }
+        let got: [u8; 8] = data[..8].try_into().unwrap();
+        if got != TICK_ARRAY_STATE_DISCRIMINATOR {
+            trace_warn!(?got, "tick array discriminator mismatch");
+            return Err(RaydiumError::WrongDiscriminator {
+                expected: TICK_ARRAY_STATE_DISCRIMINATOR,
+                got,
+            });
+        }
+        let ticks = std::array::from_fn(|i| {
+            let offset = TICKS_OFFSET + i * TICK_LEN;
+            TickData {
+                tick: r_u32(data, offset) as i32,
+                liquidity_net: r_u128(data, offset + TICK_LIQUIDITY_NET_OFFSET) as i128,
+                liquidity_gross: r_u128(data, offset + TICK_LIQUIDITY_GROSS_OFFSET),
+                fee_growth_outside_0_x64: r_u128(data, offset + TICK_FEE_GROWTH_OUTSIDE_0_OFFSET),
+                fee_growth_outside_1_x64: r_u128(data, offset + TICK_FEE_GROWTH_OUTSIDE_1_OFFSET),
+                reward_growths_outside_x64: std::array::from_fn(|j| {
+                    r_u128(data, offset + TICK_REWARD_GROWTHS_OUTSIDE_OFFSET + j * 16)
+                }),
+            }
+        });
+        Ok(TickArrayData {
+            pool_id: r_pubkey(data, POOL_ID_OFFSET),
+            start_tick_index: r_u32(data, START_TICK_INDEX_OFFSET) as i32,
+            ticks,
+            initialized_tick_count: r_u8(data, INITIALIZED_TICK_COUNT_OFFSET),
+            recent_epoch: r_u64(data, RECENT_EPOCH_OFFSET),
+        })
+    }
+}
+
+impl TickData {
+    /// whether a position references the tick
+    pub fn is_initialized(&self) -> bool {
+        self.liquidity_gross != 0
+    }
+}
+
+/// next initialized tick array found in the bitmap of a pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NextTickArray {
+    /// start index of an initialized tick array
+    Found(i32),
+    /// the search left the arrays of the pool bitmap, continue in the bitmap extension account
+    /// from this start index
+    Extension(i32),
+}
+
+impl RaydiumLiquidityPoolCLMMData {
+    /// the first initialized tick array from the one holding `start_from_tick`, searching down
+    /// when `zero_for_one` (price falling) and up otherwise. the pool bitmap tracks the 512 arrays
+    /// on each side of tick 0, bit 512 being the array starting at 0; past them the search
+    /// returns `Extension`. `None` when no array is initialized up to the end of the tick range
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::clmm::{RaydiumLiquidityPoolCLMMData, tick_array::NextTickArray};
+    /// let mut pool = RaydiumLiquidityPoolCLMMData { tick_spacing: 10, ..Default::default() };
+    /// // the array starting at -600, just below tick 0
+    /// pool.tick_array_bitmap[7] = 1 << 63;
+    /// assert_eq!(pool.next_initialized_tick_array(1_300, true), Some(NextTickArray::Found(-600)));
+    /// ```
+    pub fn next_initialized_tick_array(
+        &self,
+        start_from_tick: i32,
+        zero_for_one: bool,
+    ) -> Option<NextTickArray> {
+        CombinedTickBitmap::new(self, None)
+            .next_initialized_tick_array(start_from_tick, zero_for_one)
+    }
+
+    /// the successive initialized tick arrays a swap from `start_from_tick` walks through,
+    /// ending with `Extension` when the search leaves the pool bitmap
+    pub fn initialized_tick_arrays(
+        &self,
+        start_from_tick: i32,
+        zero_for_one: bool,
+    ) -> impl Iterator<Item = NextTickArray> + '_ {
+        CombinedTickBitmap::new(self, None).initialized_tick_arrays(start_from_tick, zero_for_one)
+    }
+}
+
+impl TickArrayData {
+    /// the ticks referenced by a position, in tick order
+    pub fn initialized_ticks(&self) -> impl DoubleEndedIterator<Item = &TickData> + '_ {
+        self.ticks.iter().filter(|tick| tick.is_initialized())
+    }
+
+    /// the next initialized tick a swap from `tick` reaches within this array: at or below
+    /// `tick` when `zero_for_one`, strictly above otherwise. `None` when `tick` is outside the
+    /// array or no such tick is initialized
+    pub fn next_initialized_tick(
+        &self,
+        tick: i32,
+        tick_spacing: u16,
+        zero_for_one: bool,
+    ) -> Option<&TickData> {
+        if tick_array_start_index(tick, tick_spacing) != self.start_tick_index {
+            return None;
+        }
+        let offset = ((tick - self.start_tick_index) / tick_spacing as i32) as usize;
+        if zero_for_one {
+            self.ticks[..=offset]
+                .iter()
+                .rev()
+                .find(|t| t.is_initialized())
+        } else {
+            self.ticks[offset + 1..].iter().find(|t| t.is_initialized())
+        }
+    }
+
+    /// the tick at index `tick` of this array, initialized or not. `None` when `tick` is
+    /// outside the array or not a multiple of the tick spacing
+    pub fn get_tick(&self, tick: i32, tick_spacing: u16) -> Option<&TickData> {
+        if tick_array_start_index(tick, tick_spacing) != self.start_tick_index
+            || tick % tick_spacing as i32 != 0
+        {
+            return None;
+        }
+        self.ticks
+            .get(((tick - self.start_tick_index) / tick_spacing as i32) as usize)
+    }
+
+    /// the first initialized tick a swap entering the array meets, the highest when
+    /// `zero_for_one` and the lowest otherwise
+    pub fn first_initialized_tick(&self, zero_for_one: bool) -> Option<&TickData> {
+        if zero_for_one {
+            self.initialized_ticks().next_back()
+        } else {
+            self.initialized_ticks().next()
+        }
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// get a tick array account of a CLMM pool
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium, address: &str) -> Result<(), RaydiumError> {
+    /// let array = raydium.get_tick_array(address).await?;
+    /// for tick in array.initialized_ticks() {
+    ///     println!("tick {} net liquidity {}", tick.tick, tick.liquidity_net);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_tick_array(&self, address: &str) -> Result<TickArrayData, RaydiumError> {
+        let address = parse_address(address)?;
+        let account = self
+            .get_multiple_accounts_with_context(&[address])
+            .await?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(address))?;
+        TickArrayState::parse(&account.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_array_start_index() {
+        assert_eq!(tick_array_start_index(0, 1), 0);
+        assert_eq!(tick_array_start_index(-1, 1), -60);
+        assert_eq!(tick_array_start_index(-60, 1), -60);
+        assert_eq!(tick_array_start_index(-61, 1), -120);
+        assert_eq!(tick_array_start_index(443_636, 60), 442_800);
+        assert_eq!(tick_array_start_index(-443_636, 60), -446_400);
+        assert_eq!(tick_array_offset_in_bitmap(0, 1), 0);
+        assert_eq!(tick_array_offset_in_bitmap(60, 1), 1);
+        assert_eq!(tick_array_offset_in_bitmap(-60, 1), 511);
+        assert_eq!(tick_array_offset_in_bitmap(-120, 1), 510);
+        // the next bitmap starts over
+        assert_eq!(tick_array_offset_in_bitmap(60 * 512, 1), 0);
+        assert_eq!(tick_array_offset_in_bitmap(-60 * 512, 1), 0);
+        assert_eq!(tick_array_offset_in_bitmap(60 * 513, 1), 1);
+        assert!(is_in_pool_bitmap(-60 * 512, 1) && is_in_pool_bitmap(60 * 511, 1));
+        assert!(!is_in_pool_bitmap(-60 * 513, 1) && !is_in_pool_bitmap(60 * 512, 1));
+        assert!(check_tick_range(-443_580, 443_580, 60).is_ok());
+        assert!(check_tick_range(-443_640, 0, 60).is_err());
+        assert!(check_tick_range(60, 60, 60).is_err());
+        assert!(check_tick_range(0, 60, 0).is_err());
+    }
+
+    #[test]
+    fn test_next_initialized_tick_array() {
+        use NextTickArray::{Extension, Found};
+        let mut pool = RaydiumLiquidityPoolCLMMData {
+            tick_spacing: 10,
+            ..Default::default()
+        };
+        // arrays of 600 ticks: the lowest of the bitmap, the two around zero and one above
+        for bit in [0, 511, 512, 514] {
+            pool.tick_array_bitmap[bit / 64] |= 1 << (bit % 64);
+        }
+        assert_eq!(
+            pool.initialized_tick_arrays(1_300, true)
+                .collect::<Vec<_>>(),
+            [
+                Found(1_200),
+                Found(0),
+                Found(-600),
+                Found(-307_200),
+                Extension(-307_800)
+            ]
+        );
+        assert_eq!(
+            pool.initialized_tick_arrays(-1, false).collect::<Vec<_>>(),
+            [Found(-600), Found(0), Found(1_200), Extension(307_200)]
+        );
+        // the array holding the start tick counts, from within and from its start
+        assert_eq!(pool.next_initialized_tick_array(599, true), Some(Found(0)));
+        assert_eq!(
+            pool.next_initialized_tick_array(1_200, false),
+            Some(Found(1_200))
+        );
+        // beyond the bitmap the extension decides
+        assert_eq!(
+            pool.next_initialized_tick_array(400_000, true),
+            Some(Extension(399_600))
+        );
+        // at spacing 60 the bitmap covers the whole tick range
+        pool.tick_spacing = 60;
+        pool.tick_array_bitmap = [0; 16];
+        assert_eq!(pool.next_initialized_tick_array(0, false), None);
+        assert_eq!(pool.initialized_tick_arrays(0, true).count(), 0);
+    }
+
+    /// a tick array of spacing 60 starting at -3600 written at the offsets of the on-chain
+    /// layout, with distinct values around the array edges where a shifted stride shows
+    #[test]
+    fn test_parse_tick_array() {
+        let mut data = vec![0u8; TICK_ARRAY_DATA_SIZE];
+        let mut put =
+            |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        put(0, &TICK_ARRAY_STATE_DISCRIMINATOR);
+        let pool_id = Pubkey::new_unique();
+        put(8, pool_id.as_ref());
+        put(40, &(-3_600i32).to_le_bytes());
+        for i in 0..TICK_ARRAY_SIZE {
+            put(44 + i * 168, &(-3_600 + 60 * i as i32).to_le_bytes());
+        }
+        // the first, second and last ticks are initialized
+        for (i, net, gross) in [
+            (0, 5_000i128, 5_000u128),
+            (1, -2_000, 7_000),
+            (59, -3_000, 3_000),
+        ] {
+            let tick = 44 + i * 168;
+            put(tick + 4, &net.to_le_bytes());
+            put(tick + 20, &gross.to_le_bytes());
+            put(tick + 36, &(u64::MAX as u128 + i as u128).to_le_bytes());
+            put(tick + 52, &(i as u128 + 1).to_le_bytes());
+            put(tick + 68 + 32, &(i as u128 + 2).to_le_bytes());
+        }
+        put(10124, &[3]);
+        put(10125, &812u64.to_le_bytes());
+        let array = TickArrayState::parse(&data).unwrap();
+        assert_eq!((array.pool_id, array.start_tick_index), (pool_id, -3_600));
+        assert_eq!((array.initialized_tick_count, array.recent_epoch), (3, 812));
+        assert_eq!(array.ticks[59].tick, -60);
+        let ticks: Vec<_> = array.initialized_ticks().collect();
+        assert_eq!(
+            ticks
+                .iter()
+                .map(|t| (t.tick, t.liquidity_net, t.liquidity_gross))
+                .collect::<Vec<_>>(),
+            [
+                (-3_600, 5_000, 5_000),
+                (-3_540, -2_000, 7_000),
+                (-60, -3_000, 3_000)
+            ]
+        );
+        assert_eq!(
+            *ticks[2],
+            TickData {
+                tick: -60,
+                liquidity_net: -3_000,
+                liquidity_gross: 3_000,
+                fee_growth_outside_0_x64: u64::MAX as u128 + 59,
+                fee_growth_outside_1_x64: 60,
+                reward_growths_outside_x64: [0, 0, 61],
+            }
+        );
+        let next = |tick, zero_for_one| {
+            array
+                .next_initialized_tick(tick, 60, zero_for_one)
+                .map(|t| t.tick)
+        };
+        // down from a tick includes it, up excludes it
+        assert_eq!(next(-3_540, true), Some(-3_540));
+        assert_eq!(next(-3_541, true), Some(-3_600));
+        assert_eq!(next(-3_540, false), Some(-60));
+        assert_eq!(next(-60, false), None);
+        assert_eq!(next(0, true), None);
+        assert_eq!(array.first_initialized_tick(true).unwrap().tick, -60);
+        assert_eq!(array.first_initialized_tick(false).unwrap().tick, -3_600);
+        assert_eq!(array.get_tick(-60, 60), Some(ticks[2]));
+        assert_eq!(
+            array.get_tick(-120, 60).map(|t| t.is_initialized()),
+            Some(false)
+        );
+        assert_eq!(array.get_tick(-90, 60), None);
+        assert_eq!(array.get_tick(0, 60), None);
+        assert!(TickArrayState::parse(&data[..TICK_ARRAY_DATA_SIZE - 1]).is_err());
+        data[0] ^= 1;
+        assert!(matches!(
+            TickArrayState::parse(&data),
+            Err(RaydiumError::WrongDiscriminator { .. })
+        ));
+    }
+}
@@ -1,13 +1,31 @@
-use bytemuck::{Pod, Zeroable};
+pub mod instructions;
+
+use bytemuck::Zeroable;
+#[cfg(feature = "rpc")]
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
+#[cfg(feature = "rpc")]
 use solana_tool::account::get_spl_token_balance;
-use solana_tool::unit::conver_balance;
+use std::fmt;
+#[cfg(feature = "rpc")]
 use std::sync::Arc;
+#[cfg(feature = "rpc")]
 use tokio::join;
 
+#[cfg(feature = "rpc")]
+use crate::reader::conver_balance;
+use crate::{reader::write_fields, trace::trace_warn, typs::Lenient};
+
 /// CPMM liquidity pool data size
 pub const RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE: usize = 637;
+/// offset of the token 0 mint in the CPMM pool account
+pub const RAYDIUM_LIQUIDITY_POOL_CPMM_TOKEN_0_MINT_OFFSET: usize = 168;
+/// offset of the token 1 mint in the CPMM pool account
+pub const RAYDIUM_LIQUIDITY_POOL_CPMM_TOKEN_1_MINT_OFFSET: usize = 200;
+/// offset of the trade fee rate (u64) in the CPMM amm config account
+pub const RAYDIUM_CPMM_AMM_CONFIG_TRADE_FEE_RATE_OFFSET: usize = 12;
+/// denominator of the CPMM fee rates
+pub const RAYDIUM_CPMM_FEE_RATE_DENOMINATOR: u64 = 1_000_000;
 const DISCRIMINATOR_LEN: usize = 8;
 
 /// CPMM liquidity pool raw data
@@ -42,7 +60,7 @@ pub struct RaydiumLiquidityPoolCPMM {
     pub padding: [u64; 28],         // 632-636:
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct RaydiumLiquidityPoolCPMMData {
     pub amm_config: Pubkey,
     pub pool_creator: Pubkey,
@@ -73,13 +91,26 @@ pub struct RaydiumLiquidityPoolCPMMData {
 }
 
 impl RaydiumLiquidityPoolCPMM {
+    /// parse the known prefix of account data at least as long as the layout, see
+    /// `RaydiumLiquidityPoolV4::parse_lenient`
+    pub fn parse_lenient(data: &[u8]) -> Result<Lenient<RaydiumLiquidityPoolCPMMData>, String> {
+        Lenient::parse(
+            data,
+            RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE,
+            Self::get_liquidity_pool_info,
+        )
+    }
+
     pub fn get_liquidity_pool_info(data: &[u8]) -> Result<RaydiumLiquidityPoolCPMMData, String> {
-        const DISCRIMINATOR_LEN: usize = 8;
-        const EXPECTED_LEN: usize = 637;
-        if data.len() != EXPECTED_LEN {
+        if data.len() != RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE {
+            trace_warn!(
+                expected = RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE,
+                actual = data.len(),
+                "CPMM pool data size mismatch"
+            );
             return Err(format!(
                 "CPMM pool data size mismatch. Expected {}, got {}",
-                EXPECTED_LEN,
+                RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE,
                 data.len()
             ));
         }
@@ -120,18 +151,10 @@ impl RaydiumLiquidityPoolCPMM {
         let creator_fee_on = data[offset];
         let enable_creator_fee = data[offset + 1] != 0;
         offset += 2;
-        let padding1: [u8; 6] = data[offset..offset + 6].try_into().unwrap();
+        // padding bits (6 bytes)
         offset += 6;
         let creator_fees_token_0 = read_u64(data, &mut offset);
         let creator_fees_token_1 = read_u64(data, &mut offset);
-        let mut padding = [0u64; 28];
-        for i in 0..28 {
-            let end = offset + 8;
-            if end > data.len() {
-                break;
-            }
-            padding[i] = read_u64(data, &mut offset);
-        }
         Ok(RaydiumLiquidityPoolCPMMData {
             amm_config,
             pool_creator,
@@ -203,6 +226,52 @@ impl RaydiumLiquidityPoolCPMM {
     }
 }
 
+impl fmt::Display for RaydiumLiquidityPoolCPMMData {
+    /// one `field: value` line per field, keys in base58
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "raydium liquidity pool cpmm")?;
+        write_fields!(
+            f,
+            self,
+            amm_config,
+            pool_creator,
+            token_0_vault,
+            token_1_vault,
+            lp_mint,
+            token_0_mint,
+            token_1_mint,
+            token_0_program,
+            token_1_program,
+            observation_key,
+            auth_bump,
+            status,
+            lp_mint_decimals,
+            mint_0_decimals,
+            mint_1_decimals,
+        );
+        write_fields!(
+            f,
+            self,
+            grouped: lp_supply,
+            protocol_fees_token_0,
+            protocol_fees_token_1,
+            fund_fees_token_0,
+            fund_fees_token_1,
+        );
+        write_fields!(
+            f,
+            self,
+            open_time,
+            recent_epoch,
+            creator_fee_on,
+            enable_creator_fee,
+        );
+        write_fields!(f, self, grouped: creator_fees_token_0, creator_fees_token_1);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rpc")]
 impl RaydiumLiquidityPoolCPMMData {
     pub async fn get_price(&self, client: Arc<RpcClient>) -> Result<f64, String> {
         let base_vault_address = self.token_0_vault;
@@ -234,7 +303,7 @@ impl RaydiumLiquidityPoolCPMMData {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "rpc"))]
 mod tests {
 
     use solana_network_client::SolanaClient;
@@ -0,0 +1,153 @@
+//! CPMM instruction builders
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::liquidity::cpmm::RaydiumLiquidityPoolCPMMData;
+
+/// seed of the CPMM vault and LP mint authority PDA
+pub const CPMM_AUTHORITY_SEED: &[u8] = b"vault_and_lp_mint_auth_seed";
+/// anchor discriminator of `swap_base_input`
+pub const SWAP_BASE_INPUT_DISCRIMINATOR: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+
+/// the vault and LP mint authority of a CPMM program
+pub fn cpmm_authority(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CPMM_AUTHORITY_SEED], program_id).0
+}
+
+/// swap exactly `amount_in` of `input_mint` from `user_input` into `user_output`, failing when
+/// the output is below `minimum_amount_out`. `None` when `input_mint` is not in the pool
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{liquidity::{cpmm::RaydiumLiquidityPoolCPMMData, cpmm::instructions::swap_base_input}, programs::RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(pool_id: Pubkey, pool: RaydiumLiquidityPoolCPMMData, owner: Pubkey, input: Pubkey, output: Pubkey) {
+/// let instruction = swap_base_input(
+///     &RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID,
+///     &pool_id,
+///     &pool,
+///     &owner,
+///     &pool.token_0_mint,
+///     &input,
+///     &output,
+///     1_000_000,
+///     990_000,
+/// );
+/// # }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn swap_base_input(
+    program_id: &Pubkey,
+    pool_id: &Pubkey,
+    pool: &RaydiumLiquidityPoolCPMMData,
+    payer: &Pubkey,
+    input_mint: &Pubkey,
+    user_input: &Pubkey,
+    user_output: &Pubkey,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Option<Instruction> {
+    let token_0 = (pool.token_0_mint, pool.token_0_vault, pool.token_0_program);
+    let token_1 = (pool.token_1_mint, pool.token_1_vault, pool.token_1_program);
+    let (input, output) = if *input_mint == pool.token_0_mint {
+        (token_0, token_1)
+    } else if *input_mint == pool.token_1_mint {
+        (token_1, token_0)
+    } else {
+        return None;
+    };
+    let mut data = SWAP_BASE_INPUT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+    Some(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*payer, true),
+            AccountMeta::new_readonly(cpmm_authority(program_id), false),
+            AccountMeta::new_readonly(pool.amm_config, false),
+            AccountMeta::new(*pool_id, false),
+            AccountMeta::new(*user_input, false),
+            AccountMeta::new(*user_output, false),
+            AccountMeta::new(input.1, false),
+            AccountMeta::new(output.1, false),
+            AccountMeta::new_readonly(input.2, false),
+            AccountMeta::new_readonly(output.2, false),
+            AccountMeta::new_readonly(input.0, false),
+            AccountMeta::new_readonly(output.0, false),
+            AccountMeta::new(pool.observation_key, false),
+        ],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::programs::{RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+    #[test]
+    fn test_swap_base_input() {
+        let pool = RaydiumLiquidityPoolCPMMData {
+            amm_config: Pubkey::new_unique(),
+            token_0_mint: Pubkey::new_unique(),
+            token_1_mint: Pubkey::new_unique(),
+            token_0_vault: Pubkey::new_unique(),
+            token_1_vault: Pubkey::new_unique(),
+            token_0_program: TOKEN_PROGRAM_ID,
+            token_1_program: Pubkey::new_unique(),
+            observation_key: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let (pool_id, payer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (input, output) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let program = RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID;
+        // selling token 1 swaps the vaults, programs and mints
+        let instruction = swap_base_input(
+            &program,
+            &pool_id,
+            &pool,
+            &payer,
+            &pool.token_1_mint,
+            &input,
+            &output,
+            1_000,
+            900,
+        )
+        .unwrap();
+        assert_eq!(&instruction.data[..8], SWAP_BASE_INPUT_DISCRIMINATOR);
+        assert_eq!(instruction.data[8..16], 1_000u64.to_le_bytes());
+        assert_eq!(instruction.data[16..], 900u64.to_le_bytes());
+        let accounts: Vec<Pubkey> = instruction.accounts.iter().map(|a| a.pubkey).collect();
+        assert_eq!(
+            accounts,
+            [
+                payer,
+                cpmm_authority(&program),
+                pool.amm_config,
+                pool_id,
+                input,
+                output,
+                pool.token_1_vault,
+                pool.token_0_vault,
+                pool.token_1_program,
+                TOKEN_PROGRAM_ID,
+                pool.token_1_mint,
+                pool.token_0_mint,
+                pool.observation_key,
+            ]
+        );
+        assert_eq!(
+            cpmm_authority(&program).to_string(),
+            "GpMZbSM2GgvTKHJirzeGfMFoaZ8UR2X7F4v8vHTvxFbL"
+        );
+        let missing = Pubkey::new_unique();
+        assert!(
+            swap_base_input(
+                &program, &pool_id, &pool, &payer, &missing, &input, &output, 1, 1
+            )
+            .is_none()
+        );
+    }
+}
@@ -1,3 +1,4 @@
-pub mod v4;
+pub mod clmm;
 pub mod cpmm;
-pub mod clmm;
\ No newline at end of file
+pub mod stable;
+pub mod v4;
@@ -0,0 +1,597 @@
+//! raydium stable swap AMM pools, pairs of pegged tokens such as USDC-USDT.
+//! the account extends the v4 layout with a model data account holding the curve of the program
+
+#[cfg(feature = "rpc")]
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use crate::{error::RaydiumError, market::OpenOrders, rpc::TOKEN_ACCOUNT_AMOUNT_OFFSET};
+use crate::{
+    liquidity::v4::Reserves,
+    reader::{r_pubkey, r_u8, r_u64, r_u128},
+    trace::trace_warn,
+    typs::Lenient,
+};
+
+/// raydium stable pool data size
+pub const RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE: usize = 1232;
+/// offset of the base mint in the raydium stable pool account
+pub const RAYDIUM_LIQUIDITY_POOL_STABLE_BASE_MINT_OFFSET: usize = 432;
+/// offset of the quote mint in the raydium stable pool account
+pub const RAYDIUM_LIQUIDITY_POOL_STABLE_QUOTE_MINT_OFFSET: usize = 464;
+/// amplification coefficient used to quote stable pools when none is configured.
+/// the program prices off the table in the model data account rather than an amplification
+/// coefficient, the invariant with this coefficient approximates it for balanced pools
+pub const DEFAULT_STABLE_AMP: u64 = 100;
+/// newton iterations of the invariant before giving up
+const MAX_ITERATIONS: usize = 255;
+/// offsets of the fields in the raydium stable pool account, every parameter is a u64 like in
+/// the v4 layout with the account type first and the price tick parameters inserted
+const ACCOUNT_TYPE_OFFSET: usize = 0;
+const STATUS_OFFSET: usize = 8;
+const NONCE_OFFSET: usize = 16;
+const MAX_ORDER_OFFSET: usize = 24;
+const DEPTH_OFFSET: usize = 32;
+const BASE_DECIMAL_OFFSET: usize = 40;
+const QUOTE_DECIMAL_OFFSET: usize = 48;
+const STATE_OFFSET: usize = 56;
+const RESET_FLAG_OFFSET: usize = 64;
+const MIN_SIZE_OFFSET: usize = 72;
+const VOL_MAX_CUT_RATIO_OFFSET: usize = 80;
+const AMOUNT_WAVE_RATIO_OFFSET: usize = 88;
+const BASE_LOT_SIZE_OFFSET: usize = 96;
+const QUOTE_LOT_SIZE_OFFSET: usize = 104;
+const MIN_PRICE_MULTIPLIER_OFFSET: usize = 112;
+const MAX_PRICE_MULTIPLIER_OFFSET: usize = 120;
+const SYSTEM_DECIMAL_VALUE_OFFSET: usize = 128;
+const ABORT_TRADE_FACTOR_OFFSET: usize = 136;
+const PRICE_TICK_MULTIPLIER_OFFSET: usize = 144;
+const PRICE_TICK_OFFSET: usize = 152;
+const MIN_SEPARATE_NUMERATOR_OFFSET: usize = 160;
+const MIN_SEPARATE_DENOMINATOR_OFFSET: usize = 168;
+const TRADE_FEE_NUMERATOR_OFFSET: usize = 176;
+const TRADE_FEE_DENOMINATOR_OFFSET: usize = 184;
+const PNL_NUMERATOR_OFFSET: usize = 192;
+const PNL_DENOMINATOR_OFFSET: usize = 200;
+const SWAP_FEE_NUMERATOR_OFFSET: usize = 208;
+const SWAP_FEE_DENOMINATOR_OFFSET: usize = 216;
+const BASE_NEED_TAKE_PNL_OFFSET: usize = 224;
+const QUOTE_NEED_TAKE_PNL_OFFSET: usize = 232;
+const QUOTE_TOTAL_PNL_OFFSET: usize = 240;
+const BASE_TOTAL_PNL_OFFSET: usize = 248;
+const POOL_OPEN_TIME_OFFSET: usize = 256;
+const PUNISH_PC_AMOUNT_OFFSET: usize = 264;
+const PUNISH_COIN_AMOUNT_OFFSET: usize = 272;
+const ORDERBOOK_TO_INIT_TIME_OFFSET: usize = 280;
+// the u128 counters come first here, then both u64 fees
+const SWAP_BASE_IN_AMOUNT_OFFSET: usize = 288;
+const SWAP_QUOTE_OUT_AMOUNT_OFFSET: usize = 304;
+const SWAP_QUOTE_IN_AMOUNT_OFFSET: usize = 320;
+const SWAP_BASE_OUT_AMOUNT_OFFSET: usize = 336;
+const SWAP_QUOTE2_BASE_FEE_OFFSET: usize = 352;
+const SWAP_BASE2_QUOTE_FEE_OFFSET: usize = 360;
+const BASE_VAULT_OFFSET: usize = 368;
+const QUOTE_VAULT_OFFSET: usize = 400;
+const LP_MINT_OFFSET: usize = 496;
+const MODEL_DATA_ACCOUNT_OFFSET: usize = 528;
+const OPEN_ORDERS_OFFSET: usize = 560;
+const MARKET_ID_OFFSET: usize = 592;
+const MARKET_PROGRAM_ID_OFFSET: usize = 624;
+const TARGET_ORDERS_OFFSET: usize = 656;
+const OWNER_OFFSET: usize = 688;
+// 720-1231: padding
+
+/// raydium stable pool account parser, every field is read at its offset in the on-chain layout
+pub struct RaydiumLiquidityPoolStable;
+
+impl RaydiumLiquidityPoolStable {
+    /// parse the known prefix of account data at least as long as the layout, see
+    /// `RaydiumLiquidityPoolV4::parse_lenient`
+    pub fn parse_lenient(data: &[u8]) -> Result<Lenient<RaydiumLiquidityPoolStableData>, String> {
+        Lenient::parse(
+            data,
+            RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE,
+            Self::get_liquidity_pool_info,
+        )
+    }
+
+    /// parsing the data structure of a stable pool
+    /// # Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::stable::{RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE, RaydiumLiquidityPoolStable};
+    /// let data = vec![0u8; RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE];
+    /// let pool = RaydiumLiquidityPoolStable::get_liquidity_pool_info(&data).unwrap();
+    /// println!("curve in {}", pool.model_data_account);
+    /// ```
+    pub fn get_liquidity_pool_info(data: &[u8]) -> Result<RaydiumLiquidityPoolStableData, String> {
+        if data.len() != RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE {
+            trace_warn!(
+                expected = RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE,
+                actual = data.len(),
+                "stable pool data size mismatch"
+            );
+            return Err(format!(
+                "stable pool data size mismatch. Expected {}, got {}",
+                RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE,
+                data.len()
+            ));
+        }
+        Ok(RaydiumLiquidityPoolStableData {
+            account_type: r_u64(data, ACCOUNT_TYPE_OFFSET),
+            status: r_u8(data, STATUS_OFFSET),
+            nonce: r_u8(data, NONCE_OFFSET),
+            max_order: r_u64(data, MAX_ORDER_OFFSET),
+            depth: r_u64(data, DEPTH_OFFSET),
+            base_decimal: r_u8(data, BASE_DECIMAL_OFFSET),
+            quote_decimal: r_u8(data, QUOTE_DECIMAL_OFFSET),
+            state: r_u64(data, STATE_OFFSET),
+            reset_flag: r_u64(data, RESET_FLAG_OFFSET),
+            min_size: r_u64(data, MIN_SIZE_OFFSET),
+            vol_max_cut_ratio: r_u64(data, VOL_MAX_CUT_RATIO_OFFSET),
+            amount_wave_ratio: r_u64(data, AMOUNT_WAVE_RATIO_OFFSET),
+            base_lot_size: r_u64(data, BASE_LOT_SIZE_OFFSET),
+            quote_lot_size: r_u64(data, QUOTE_LOT_SIZE_OFFSET),
+            min_price_multiplier: r_u64(data, MIN_PRICE_MULTIPLIER_OFFSET),
+            max_price_multiplier: r_u64(data, MAX_PRICE_MULTIPLIER_OFFSET),
+            system_decimal_value: r_u64(data, SYSTEM_DECIMAL_VALUE_OFFSET),
+            abort_trade_factor: r_u64(data, ABORT_TRADE_FACTOR_OFFSET),
+            price_tick_multiplier: r_u64(data, PRICE_TICK_MULTIPLIER_OFFSET),
+            price_tick: r_u64(data, PRICE_TICK_OFFSET),
+            min_separate_numerator: r_u64(data, MIN_SEPARATE_NUMERATOR_OFFSET),
+            min_separate_denominator: r_u64(data, MIN_SEPARATE_DENOMINATOR_OFFSET),
+            trade_fee_numerator: r_u64(data, TRADE_FEE_NUMERATOR_OFFSET),
+            trade_fee_denominator: r_u64(data, TRADE_FEE_DENOMINATOR_OFFSET),
+            pnl_numerator: r_u64(data, PNL_NUMERATOR_OFFSET),
+            pnl_denominator: r_u64(data, PNL_DENOMINATOR_OFFSET),
+            swap_fee_numerator: r_u64(data, SWAP_FEE_NUMERATOR_OFFSET),
+            swap_fee_denominator: r_u64(data, SWAP_FEE_DENOMINATOR_OFFSET),
+            base_need_take_pnl: r_u64(data, BASE_NEED_TAKE_PNL_OFFSET),
+            quote_need_take_pnl: r_u64(data, QUOTE_NEED_TAKE_PNL_OFFSET),
+            quote_total_pnl: r_u64(data, QUOTE_TOTAL_PNL_OFFSET),
+            base_total_pnl: r_u64(data, BASE_TOTAL_PNL_OFFSET),
+            pool_open_time: r_u64(data, POOL_OPEN_TIME_OFFSET),
+            punish_pc_amount: r_u64(data, PUNISH_PC_AMOUNT_OFFSET),
+            punish_coin_amount: r_u64(data, PUNISH_COIN_AMOUNT_OFFSET),
+            orderbook_to_init_time: r_u64(data, ORDERBOOK_TO_INIT_TIME_OFFSET),
+            swap_base_in_amount: r_u128(data, SWAP_BASE_IN_AMOUNT_OFFSET),
+            swap_quote_out_amount: r_u128(data, SWAP_QUOTE_OUT_AMOUNT_OFFSET),
+            swap_quote_in_amount: r_u128(data, SWAP_QUOTE_IN_AMOUNT_OFFSET),
+            swap_base_out_amount: r_u128(data, SWAP_BASE_OUT_AMOUNT_OFFSET),
+            swap_quote2_base_fee: r_u64(data, SWAP_QUOTE2_BASE_FEE_OFFSET),
+            swap_base2_quote_fee: r_u64(data, SWAP_BASE2_QUOTE_FEE_OFFSET),
+            base_vault: r_pubkey(data, BASE_VAULT_OFFSET),
+            quote_vault: r_pubkey(data, QUOTE_VAULT_OFFSET),
+            base_mint: r_pubkey(data, RAYDIUM_LIQUIDITY_POOL_STABLE_BASE_MINT_OFFSET),
+            quote_mint: r_pubkey(data, RAYDIUM_LIQUIDITY_POOL_STABLE_QUOTE_MINT_OFFSET),
+            lp_mint: r_pubkey(data, LP_MINT_OFFSET),
+            model_data_account: r_pubkey(data, MODEL_DATA_ACCOUNT_OFFSET),
+            open_orders: r_pubkey(data, OPEN_ORDERS_OFFSET),
+            market_id: r_pubkey(data, MARKET_ID_OFFSET),
+            market_program_id: r_pubkey(data, MARKET_PROGRAM_ID_OFFSET),
+            target_orders: r_pubkey(data, TARGET_ORDERS_OFFSET),
+            owner: r_pubkey(data, OWNER_OFFSET),
+        })
+    }
+}
+
+/// a parsed stable pool. the status, nonce and decimals keep their low byte like in
+/// `RaydiumLiquidityPoolData`. the account records no amplification coefficient,
+/// `model_data_account` references the curve the program prices swaps with
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RaydiumLiquidityPoolStableData {
+    pub account_type: u64,
+    pub status: u8,
+    pub nonce: u8,
+    pub max_order: u64,
+    pub depth: u64,
+    pub base_decimal: u8,
+    pub quote_decimal: u8,
+    pub state: u64,
+    pub reset_flag: u64,
+    pub min_size: u64,
+    pub vol_max_cut_ratio: u64,
+    pub amount_wave_ratio: u64,
+    pub base_lot_size: u64,
+    pub quote_lot_size: u64,
+    pub min_price_multiplier: u64,
+    pub max_price_multiplier: u64,
+    pub system_decimal_value: u64,
+    pub abort_trade_factor: u64,
+    pub price_tick_multiplier: u64,
+    pub price_tick: u64,
+    pub min_separate_numerator: u64,
+    pub min_separate_denominator: u64,
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+    pub pnl_numerator: u64,
+    pub pnl_denominator: u64,
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+    pub base_need_take_pnl: u64,
+    pub quote_need_take_pnl: u64,
+    pub quote_total_pnl: u64,
+    pub base_total_pnl: u64,
+    pub pool_open_time: u64,
+    pub punish_pc_amount: u64,
+    pub punish_coin_amount: u64,
+    pub orderbook_to_init_time: u64,
+    pub swap_base_in_amount: u128,
+    pub swap_quote_out_amount: u128,
+    pub swap_quote_in_amount: u128,
+    pub swap_base_out_amount: u128,
+    pub swap_quote2_base_fee: u64,
+    pub swap_base2_quote_fee: u64,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    /// account holding the curve of the pool
+    pub model_data_account: Pubkey,
+    pub open_orders: Pubkey,
+    pub market_id: Pubkey,
+    pub market_program_id: Pubkey,
+    pub target_orders: Pubkey,
+    pub owner: Pubkey,
+}
+
+impl RaydiumLiquidityPoolStableData {
+    /// reserves the program trades against: the vault balances plus the totals resting in the
+    /// open orders account, less the pnl owed to the protocol
+    pub fn effective_reserves(
+        &self,
+        base_vault_amount: u64,
+        quote_vault_amount: u64,
+        open_orders_base_total: u64,
+        open_orders_quote_total: u64,
+    ) -> Reserves {
+        Reserves {
+            base: base_vault_amount
+                .saturating_add(open_orders_base_total)
+                .saturating_sub(self.base_need_take_pnl),
+            quote: quote_vault_amount
+                .saturating_add(open_orders_quote_total)
+                .saturating_sub(self.quote_need_take_pnl),
+        }
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl RaydiumLiquidityPoolStableData {
+    /// effective reserves from the base vault, quote vault and open orders accounts, in that order
+    pub(crate) fn reserves_from_accounts(
+        &self,
+        accounts: &[Option<Account>],
+    ) -> Result<Reserves, RaydiumError> {
+        let addresses = [self.base_vault, self.quote_vault, self.open_orders];
+        let mut data = [&[][..]; 3];
+        for (i, address) in addresses.iter().enumerate() {
+            data[i] = accounts
+                .get(i)
+                .and_then(Option::as_ref)
+                .map(|account| account.data.as_slice())
+                .ok_or(RaydiumError::AccountNotFound(*address))?;
+        }
+        let open_orders = OpenOrders::parse(data[2])?.totals();
+        Ok(self.effective_reserves(
+            r_u64(data[0], TOKEN_ACCOUNT_AMOUNT_OFFSET),
+            r_u64(data[1], TOKEN_ACCOUNT_AMOUNT_OFFSET),
+            open_orders.base_total,
+            open_orders.quote_total,
+        ))
+    }
+}
+
+/// StableSwap invariant `D` of two reserves, solving
+/// `A·n^n·(x + y) + D = A·n^n·D + D^(n+1) / (n^n·x·y)` for n = 2 with Newton's method.
+/// `None` for an empty reserve, a zero coefficient or reserves too large for the u128 math
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::stable::invariant;
+/// // a balanced pool holds its sum
+/// assert_eq!(invariant(1_000_000, 1_000_000, 100), Some(2_000_000));
+/// ```
+pub fn invariant(x: u64, y: u64, amp: u64) -> Option<u128> {
+    if x == 0 || y == 0 || amp == 0 {
+        return None;
+    }
+    let (x, y) = (x as u128, y as u128);
+    let ann = (amp as u128).checked_mul(4)?;
+    let sum = x.checked_add(y)?;
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        let d_p = d
+            .checked_mul(d)?
+            .checked_div(x.checked_mul(2)?)?
+            .checked_mul(d)?
+            .checked_div(y.checked_mul(2)?)?;
+        let previous = d;
+        let numerator = ann
+            .checked_mul(sum)?
+            .checked_add(d_p.checked_mul(2)?)?
+            .checked_mul(d)?;
+        let denominator = (ann - 1).checked_mul(d)?.checked_add(d_p.checked_mul(3)?)?;
+        d = numerator.checked_div(denominator)?;
+        if d.abs_diff(previous) <= 1 {
+            return Some(d);
+        }
+    }
+    None
+}
+
+/// reserve of the other token keeping the invariant `d` once this reserve is `x`,
+/// `None` when the u128 math overflows
+fn other_reserve(x: u128, d: u128, amp: u64) -> Option<u128> {
+    let ann = (amp as u128).checked_mul(4)?;
+    let c = d
+        .checked_mul(d)?
+        .checked_div(x.checked_mul(2)?)?
+        .checked_mul(d)?
+        .checked_div(ann.checked_mul(2)?)?;
+    let b = x.checked_add(d.checked_div(ann)?)?;
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let previous = y;
+        y = y
+            .checked_mul(y)?
+            .checked_add(c)?
+            .checked_div(y.checked_mul(2)?.checked_add(b)?.checked_sub(d)?)?;
+        if y.abs_diff(previous) <= 1 {
+            return Some(y);
+        }
+    }
+    None
+}
+
+/// output of swapping `amount_in` against a StableSwap curve, fees excluded and rounded down
+/// so the invariant never decreases. amounts are raw, both tokens are expected to share
+/// their decimals. the program prices off the curve in its model data account rather than
+/// this invariant, so the output is an approximation that will not match the on-chain one
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::stable::amount_out;
+/// // a pegged pool pays close to one for one
+/// let out = amount_out(1_000_000_000_000, 1_000_000_000_000, 1_000_000_000, 100).unwrap();
+/// assert!(out > 999_000_000 && out < 1_000_000_000);
+/// ```
+pub fn amount_out(reserve_in: u64, reserve_out: u64, amount_in: u64, amp: u64) -> Option<u64> {
+    let d = invariant(reserve_in, reserve_out, amp)?;
+    let y = other_reserve(reserve_in as u128 + amount_in as u128, d, amp)?;
+    Some((reserve_out as u128).saturating_sub(y.checked_add(1)?) as u64)
+}
+
+/// marginal price of `x` in `y` on the StableSwap curve, raw amounts. one for a balanced pool,
+/// towards the constant product price as the pool drains. an approximation of the price of
+/// the program, which prices off the curve in its model data account
+/// Example
+/// ```rust
+/// # use raydium_sdk::liquidity::stable::spot_price;
+/// assert_eq!(spot_price(1_000_000, 1_000_000, 100), Some(1.0));
+/// assert!(spot_price(2_000_000, 1_000_000, 100).unwrap() < 1.0);
+/// ```
+pub fn spot_price(x: u64, y: u64, amp: u64) -> Option<f64> {
+    let d = invariant(x, y, amp)? as f64;
+    let (x, y) = (x as f64, y as f64);
+    let ann = amp as f64 * 4.0;
+    // the curve is F(x, y) = 0, the price is -dy/dx = F_x / F_y
+    let d_p = d * d * d / (4.0 * x * y);
+    Some((ann + d_p / x) / (ann + d_p / y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        liquidity::v4::pda,
+        mints::{USDC_MINT, USDT_MINT},
+        programs::{RAYDIUM_LIQUIDITY_POOL_STABLE_PROGRAM_ID, SERUM_V3_PROGRAM_ID},
+    };
+
+    /// a USDC-USDT stable pool account written field by field at the offsets of the layout
+    fn stable_pool_fixture() -> (Vec<u8>, [Pubkey; 4]) {
+        let mut data = vec![0u8; RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE];
+        let mut put =
+            |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        let keys = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        put(ACCOUNT_TYPE_OFFSET, &1u64.to_le_bytes());
+        put(STATUS_OFFSET, &1u64.to_le_bytes());
+        put(NONCE_OFFSET, &252u64.to_le_bytes());
+        put(BASE_DECIMAL_OFFSET, &6u64.to_le_bytes());
+        put(QUOTE_DECIMAL_OFFSET, &6u64.to_le_bytes());
+        put(PRICE_TICK_OFFSET, &1_000u64.to_le_bytes());
+        put(SWAP_FEE_NUMERATOR_OFFSET, &5u64.to_le_bytes());
+        put(SWAP_FEE_DENOMINATOR_OFFSET, &10_000u64.to_le_bytes());
+        put(QUOTE_NEED_TAKE_PNL_OFFSET, &42u64.to_le_bytes());
+        put(ORDERBOOK_TO_INIT_TIME_OFFSET, &7u64.to_le_bytes());
+        put(
+            SWAP_BASE_OUT_AMOUNT_OFFSET,
+            &(u64::MAX as u128 + 1).to_le_bytes(),
+        );
+        put(SWAP_BASE2_QUOTE_FEE_OFFSET, &9u64.to_le_bytes());
+        put(BASE_VAULT_OFFSET, keys[0].as_ref());
+        put(QUOTE_VAULT_OFFSET, keys[1].as_ref());
+        put(
+            RAYDIUM_LIQUIDITY_POOL_STABLE_BASE_MINT_OFFSET,
+            USDC_MINT.as_ref(),
+        );
+        put(
+            RAYDIUM_LIQUIDITY_POOL_STABLE_QUOTE_MINT_OFFSET,
+            USDT_MINT.as_ref(),
+        );
+        put(MODEL_DATA_ACCOUNT_OFFSET, keys[2].as_ref());
+        put(OWNER_OFFSET, keys[3].as_ref());
+        (data, keys)
+    }
+
+    #[test]
+    fn test_parse_stable_pool() {
+        let (data, keys) = stable_pool_fixture();
+        let pool = RaydiumLiquidityPoolStable::get_liquidity_pool_info(&data).unwrap();
+        assert_eq!(
+            (pool.account_type, pool.status, pool.nonce, pool.price_tick),
+            (1, 1, 252, 1_000)
+        );
+        assert_eq!((pool.base_decimal, pool.quote_decimal), (6, 6));
+        assert_eq!(
+            (pool.swap_fee_numerator, pool.swap_fee_denominator),
+            (5, 10_000)
+        );
+        assert_eq!(
+            (pool.orderbook_to_init_time, pool.swap_base2_quote_fee),
+            (7, 9)
+        );
+        assert_eq!(pool.swap_base_out_amount, u64::MAX as u128 + 1);
+        assert_eq!((pool.base_mint, pool.quote_mint), (USDC_MINT, USDT_MINT));
+        assert_eq!(
+            [
+                pool.base_vault,
+                pool.quote_vault,
+                pool.model_data_account,
+                pool.owner
+            ],
+            keys
+        );
+        assert_eq!(
+            pool.effective_reserves(100, 100, 0, 0),
+            Reserves {
+                base: 100,
+                quote: 58
+            }
+        );
+        assert!(RaydiumLiquidityPoolStable::get_liquidity_pool_info(&data[..752]).is_err());
+    }
+
+    #[test]
+    fn test_stable_swap_curve() {
+        let (x, y) = (1_000_000_000_000, 1_000_000_000_000);
+        // far flatter than constant product, which pays 999_000_999 here
+        let out = amount_out(x, y, 1_000_000_000, DEFAULT_STABLE_AMP).unwrap();
+        assert!(out > 999_900_000 && out < 1_000_000_000, "{}", out);
+        // the invariant never decreases through a swap
+        let d = invariant(x, y, DEFAULT_STABLE_AMP).unwrap();
+        let after = invariant(x + 1_000_000_000, y - out, DEFAULT_STABLE_AMP).unwrap();
+        assert!(after >= d);
+        // an imbalanced pool prices the abundant token below the peg
+        let price = spot_price(1_500_000, 500_000, DEFAULT_STABLE_AMP).unwrap();
+        assert!(price < 1.0 && price > 0.9, "{}", price);
+        assert_eq!(invariant(0, y, DEFAULT_STABLE_AMP), None);
+        assert_eq!(amount_out(x, y, 1, 0), None);
+        // reserves this lopsided overflow the u128 math instead of panicking
+        assert_eq!(
+            invariant(1, 1_000_000_000_000_000, DEFAULT_STABLE_AMP),
+            None
+        );
+        assert_eq!(
+            amount_out(1, 1_000_000_000_000_000, 1, DEFAULT_STABLE_AMP),
+            None
+        );
+        assert_eq!(spot_price(u64::MAX, 1, DEFAULT_STABLE_AMP), None);
+    }
+
+    /// the mainnet USDC-USDT stable pool, on the serum USDT/USDC market. its accounts were
+    /// created by the seeded initialization, so they derive from the market like v4 pools
+    fn mainnet_usdc_usdt_fixture() -> Vec<u8> {
+        let mut data = vec![0u8; RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE];
+        let mut put =
+            |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        let key = |address: &str| Pubkey::from_str_const(address).to_bytes();
+        put(NONCE_OFFSET, &245u64.to_le_bytes());
+        put(BASE_DECIMAL_OFFSET, &6u64.to_le_bytes());
+        put(QUOTE_DECIMAL_OFFSET, &6u64.to_le_bytes());
+        put(
+            BASE_VAULT_OFFSET,
+            &key("5XkWQL9FJL4qEvL8c3zCzzWnMGzerM3jbGuuyRprsEgG"),
+        );
+        put(
+            QUOTE_VAULT_OFFSET,
+            &key("jfrmNrBtxnX1FH36ATeiaXnpA4ppQcKtv7EfrgMsgLJ"),
+        );
+        put(
+            RAYDIUM_LIQUIDITY_POOL_STABLE_BASE_MINT_OFFSET,
+            USDT_MINT.as_ref(),
+        );
+        put(
+            RAYDIUM_LIQUIDITY_POOL_STABLE_QUOTE_MINT_OFFSET,
+            USDC_MINT.as_ref(),
+        );
+        put(
+            LP_MINT_OFFSET,
+            &key("As3EGgLtUVpdNpE6WCKauyNRrCCwcQ57trWQ3wyRXDa6"),
+        );
+        put(
+            MODEL_DATA_ACCOUNT_OFFSET,
+            &key("CDSr3ssLcRB6XYPJwAfFt18MZvEZp4LjHcvzBVZ45duo"),
+        );
+        put(
+            OPEN_ORDERS_OFFSET,
+            &key("4zbGjjRx8bmZjynJg2KnkJ54VAk1crcrYsGMy79EXK1P"),
+        );
+        put(
+            MARKET_ID_OFFSET,
+            &key("77quYg4MGneUdjgXCunt9GgM1usmrxKY31twEy3WHwcS"),
+        );
+        put(MARKET_PROGRAM_ID_OFFSET, SERUM_V3_PROGRAM_ID.as_ref());
+        put(
+            TARGET_ORDERS_OFFSET,
+            &key("AYf5abBGrwjz2n2gGP4YG91hJer22zakrizrRhddTehS"),
+        );
+        data
+    }
+
+    #[test]
+    fn test_mainnet_usdc_usdt_pool() {
+        let program_id = RAYDIUM_LIQUIDITY_POOL_STABLE_PROGRAM_ID;
+        let pool =
+            RaydiumLiquidityPoolStable::get_liquidity_pool_info(&mainnet_usdc_usdt_fixture())
+                .unwrap();
+        assert_eq!((pool.base_mint, pool.quote_mint), (USDT_MINT, USDC_MINT));
+        assert_eq!((pool.base_decimal, pool.quote_decimal), (6, 6));
+        assert_eq!(pool.market_program_id, SERUM_V3_PROGRAM_ID);
+        // every account of the pool sits where the layout says it does
+        let derived = pda::derive_pool_accounts(&pool.market_id, &program_id);
+        assert_eq!(pool.nonce, derived.nonce);
+        assert_eq!(
+            [
+                pool.open_orders,
+                pool.target_orders,
+                pool.base_vault,
+                pool.quote_vault,
+                pool.lp_mint
+            ],
+            [
+                derived.open_orders,
+                derived.target_orders,
+                derived.base_vault,
+                derived.quote_vault,
+                derived.lp_mint
+            ]
+        );
+        // a pool of a few million dollars a side quotes close to the peg, paying a premium
+        // for the scarcer USDT
+        let reserves = pool.effective_reserves(4_800_000_000_000, 5_200_000_000_000, 0, 0);
+        let out = amount_out(
+            reserves.base,
+            reserves.quote,
+            10_000_000_000,
+            DEFAULT_STABLE_AMP,
+        )
+        .unwrap();
+        assert!(out > 10_000_000_000 && out < 10_010_000_000, "{}", out);
+        let d = invariant(reserves.base, reserves.quote, DEFAULT_STABLE_AMP).unwrap();
+        let after = invariant(
+            reserves.base + 10_000_000_000,
+            reserves.quote - out,
+            DEFAULT_STABLE_AMP,
+        )
+        .unwrap();
+        assert!(after >= d);
+        let price = spot_price(reserves.base, reserves.quote, DEFAULT_STABLE_AMP).unwrap();
+        assert!(price > 1.0 && price < 1.001, "{}", price);
+    }
+}
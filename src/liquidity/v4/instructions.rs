@@ -0,0 +1,645 @@
+//! AMM v4 instruction builders
+
+#[cfg(feature = "rpc")]
+use solana_network_client::Mode;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+#[cfg(feature = "rpc")]
+use crate::{
+    Raydium,
+    programs::{DEVNET_RAYDIUM_V4_CREATE_POOL_FEE_ACCOUNT, RAYDIUM_V4_CREATE_POOL_FEE_ACCOUNT},
+    rpc::parse_address,
+    typs::PoolKind,
+};
+use crate::{
+    error::RaydiumError,
+    liquidity::v4::pda,
+    pool_keys::{MarketKeys, PoolKeysV4, market_vault_signer},
+    programs::{ASSOCIATED_TOKEN_PROGRAM_ID, SYSTEM_PROGRAM_ID, SYSVAR_RENT_ID, TOKEN_PROGRAM_ID},
+    token_accounts::associated_token_address,
+    typs::MintSide,
+};
+
+/// instruction tag of `initialize2`
+pub const INITIALIZE2_TAG: u8 = 1;
+/// instruction tag of `deposit`
+pub const DEPOSIT_TAG: u8 = 3;
+/// instruction tag of `withdraw`
+pub const WITHDRAW_TAG: u8 = 4;
+/// instruction tag of `swapBaseIn`
+pub const SWAP_BASE_IN_TAG: u8 = 9;
+/// instruction tag of `swapBaseOut`
+pub const SWAP_BASE_OUT_TAG: u8 = 11;
+
+/// swap exactly `amount_in` from `user_source` into `user_destination`, failing when the
+/// output is below `minimum_amount_out`
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{liquidity::v4::instructions::swap_base_in, pool_keys::PoolKeysV4};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(keys: PoolKeysV4, owner: Pubkey, source: Pubkey, destination: Pubkey) {
+/// let instruction = swap_base_in(&keys, &owner, &source, &destination, 1_000_000, 990_000);
+/// # }
+/// ```
+pub fn swap_base_in(
+    keys: &PoolKeysV4,
+    owner: &Pubkey,
+    user_source: &Pubkey,
+    user_destination: &Pubkey,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Instruction {
+    let mut data = vec![SWAP_BASE_IN_TAG];
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+    Instruction::new_with_bytes(
+        keys.program_id,
+        &data,
+        swap_accounts(keys, owner, user_source, user_destination),
+    )
+}
+
+/// swap at most `max_amount_in` from `user_source` to receive exactly `amount_out` into
+/// `user_destination`, pairs with `RaydiumLiquidityPoolData::compute_amount_in`
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{liquidity::v4::instructions::swap_base_out, pool_keys::PoolKeysV4};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(keys: PoolKeysV4, owner: Pubkey, source: Pubkey, destination: Pubkey) {
+/// let instruction = swap_base_out(&keys, &owner, &source, &destination, 1_010_000, 1_000_000);
+/// # }
+/// ```
+pub fn swap_base_out(
+    keys: &PoolKeysV4,
+    owner: &Pubkey,
+    user_source: &Pubkey,
+    user_destination: &Pubkey,
+    max_amount_in: u64,
+    amount_out: u64,
+) -> Instruction {
+    let mut data = vec![SWAP_BASE_OUT_TAG];
+    data.extend_from_slice(&max_amount_in.to_le_bytes());
+    data.extend_from_slice(&amount_out.to_le_bytes());
+    Instruction::new_with_bytes(
+        keys.program_id,
+        &data,
+        swap_accounts(keys, owner, user_source, user_destination),
+    )
+}
+
+/// deposit at most `max_base_amount` and `max_quote_amount`, the `fixed_side` amount is
+/// deposited in full and the other one matched at the pool ratio
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{liquidity::v4::instructions::deposit, pool_keys::PoolKeysV4, typs::MintSide};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(keys: PoolKeysV4, owner: Pubkey, base: Pubkey, quote: Pubkey, lp: Pubkey) {
+/// let instruction = deposit(&keys, &owner, &base, &quote, &lp, 1_000_000, 2_010_000, MintSide::Base);
+/// # }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn deposit(
+    keys: &PoolKeysV4,
+    owner: &Pubkey,
+    user_base: &Pubkey,
+    user_quote: &Pubkey,
+    user_lp: &Pubkey,
+    max_base_amount: u64,
+    max_quote_amount: u64,
+    fixed_side: MintSide,
+) -> Instruction {
+    let base_side: u64 = match fixed_side {
+        MintSide::Base => 0,
+        MintSide::Quote => 1,
+    };
+    let mut data = vec![DEPOSIT_TAG];
+    data.extend_from_slice(&max_base_amount.to_le_bytes());
+    data.extend_from_slice(&max_quote_amount.to_le_bytes());
+    data.extend_from_slice(&base_side.to_le_bytes());
+    Instruction::new_with_bytes(
+        keys.program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(keys.id, false),
+            AccountMeta::new_readonly(keys.authority, false),
+            AccountMeta::new_readonly(keys.open_orders, false),
+            AccountMeta::new(keys.target_orders, false),
+            AccountMeta::new(keys.lp_mint, false),
+            AccountMeta::new(keys.base_vault, false),
+            AccountMeta::new(keys.quote_vault, false),
+            AccountMeta::new_readonly(keys.market_id, false),
+            AccountMeta::new(*user_base, false),
+            AccountMeta::new(*user_quote, false),
+            AccountMeta::new(*user_lp, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new_readonly(keys.market_event_queue, false),
+        ],
+    )
+}
+
+/// burn `lp_amount` LP tokens for the matching share of both tokens, the AMM cancels its
+/// orders on the market first so every market account is passed
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{liquidity::v4::instructions::withdraw, pool_keys::PoolKeysV4};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(keys: PoolKeysV4, owner: Pubkey, lp: Pubkey, base: Pubkey, quote: Pubkey) {
+/// let instruction = withdraw(&keys, &owner, &lp, &base, &quote, 1_000_000);
+/// # }
+/// ```
+pub fn withdraw(
+    keys: &PoolKeysV4,
+    owner: &Pubkey,
+    user_lp: &Pubkey,
+    user_base: &Pubkey,
+    user_quote: &Pubkey,
+    lp_amount: u64,
+) -> Instruction {
+    let mut data = vec![WITHDRAW_TAG];
+    data.extend_from_slice(&lp_amount.to_le_bytes());
+    Instruction::new_with_bytes(
+        keys.program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(keys.id, false),
+            AccountMeta::new_readonly(keys.authority, false),
+            AccountMeta::new(keys.open_orders, false),
+            AccountMeta::new(keys.target_orders, false),
+            AccountMeta::new(keys.lp_mint, false),
+            AccountMeta::new(keys.base_vault, false),
+            AccountMeta::new(keys.quote_vault, false),
+            AccountMeta::new(keys.withdraw_queue, false),
+            AccountMeta::new(keys.lp_vault, false),
+            AccountMeta::new_readonly(keys.market_program_id, false),
+            AccountMeta::new(keys.market_id, false),
+            AccountMeta::new(keys.market_base_vault, false),
+            AccountMeta::new(keys.market_quote_vault, false),
+            AccountMeta::new_readonly(keys.market_authority, false),
+            AccountMeta::new(*user_lp, false),
+            AccountMeta::new(*user_base, false),
+            AccountMeta::new(*user_quote, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(keys.market_event_queue, false),
+            AccountMeta::new(keys.market_bids, false),
+            AccountMeta::new(keys.market_asks, false),
+        ],
+    )
+}
+
+/// a v4 pool to create on an existing serum / OpenBook market, amounts raw
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NewPoolV4 {
+    pub base_mint: Pubkey,
+    pub base_decimals: u8,
+    pub quote_mint: Pubkey,
+    pub quote_decimals: u8,
+    /// base deposited at creation, taken from the associated token account of the user
+    pub init_base_amount: u64,
+    /// quote deposited at creation, taken from the associated token account of the user
+    pub init_quote_amount: u64,
+    /// unix time swaps open at, 0 to open at once
+    pub open_time: u64,
+}
+
+/// instructions creating a v4 pool and the keys of the pool they create
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatePoolV4 {
+    /// `initialize2`, which also creates the LP account of the user once the LP mint exists
+    pub instructions: Vec<Instruction>,
+    pub keys: PoolKeysV4,
+}
+
+/// create a v4 pool on `market` funded by `user`, every pool account derived from the market
+/// the way the program expects. the user pays the creation fee to `create_fee_destination`,
+/// `RAYDIUM_V4_CREATE_POOL_FEE_ACCOUNT` on mainnet, and receives the initial LP tokens in its
+/// associated token account, created by the program. errors when the market does not trade the
+/// pool mints
+/// Example
+/// ```rust
+/// # use raydium_sdk::{error::RaydiumError, liquidity::v4::instructions::{NewPoolV4, initialize2}, pool_keys::MarketKeys, programs::{RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID, RAYDIUM_V4_CREATE_POOL_FEE_ACCOUNT}};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(market: MarketKeys, market_program_id: Pubkey, user: Pubkey) -> Result<(), RaydiumError> {
+/// let pool = NewPoolV4 {
+///     base_mint: market.base_mint,
+///     base_decimals: 9,
+///     quote_mint: market.quote_mint,
+///     quote_decimals: 6,
+///     init_base_amount: 10_000_000_000,
+///     init_quote_amount: 1_500_000_000,
+///     open_time: 0,
+/// };
+/// let created = initialize2(
+///     &RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+///     &RAYDIUM_V4_CREATE_POOL_FEE_ACCOUNT,
+///     &market_program_id,
+///     &market,
+///     &user,
+///     &pool,
+/// )?;
+/// println!("pool {}", created.keys.id);
+/// # Ok(())
+/// # }
+/// ```
+pub fn initialize2(
+    program_id: &Pubkey,
+    create_fee_destination: &Pubkey,
+    market_program_id: &Pubkey,
+    market: &MarketKeys,
+    user: &Pubkey,
+    pool: &NewPoolV4,
+) -> Result<CreatePoolV4, RaydiumError> {
+    for (account, expected, found) in [
+        ("market base mint", pool.base_mint, market.base_mint),
+        ("market quote mint", pool.quote_mint, market.quote_mint),
+    ] {
+        if expected != found {
+            return Err(RaydiumError::KeyMismatch {
+                account: account.to_string(),
+                expected,
+                found,
+            });
+        }
+    }
+    for amount in [pool.init_base_amount, pool.init_quote_amount] {
+        if amount == 0 {
+            return Err(RaydiumError::AmountTooSmall(amount));
+        }
+    }
+    let market_id = &market.own_address;
+    let derived = pda::derive_pool_accounts(market_id, program_id);
+    let keys = PoolKeysV4 {
+        id: derived.amm_id,
+        program_id: *program_id,
+        authority: derived.authority,
+        open_orders: derived.open_orders,
+        target_orders: derived.target_orders,
+        base_mint: pool.base_mint,
+        quote_mint: pool.quote_mint,
+        lp_mint: derived.lp_mint,
+        base_decimals: pool.base_decimals,
+        quote_decimals: pool.quote_decimals,
+        base_vault: derived.base_vault,
+        quote_vault: derived.quote_vault,
+        withdraw_queue: derived.withdraw_queue,
+        lp_vault: derived.temp_lp,
+        market_program_id: *market_program_id,
+        market_id: *market_id,
+        market_authority: market_vault_signer(
+            market_id,
+            market.vault_signer_nonce,
+            market_program_id,
+        )?,
+        market_base_vault: market.base_vault,
+        market_quote_vault: market.quote_vault,
+        market_bids: market.bids,
+        market_asks: market.asks,
+        market_event_queue: market.event_queue,
+    };
+    // the program takes the quote amount first
+    let mut data = vec![INITIALIZE2_TAG, derived.nonce];
+    data.extend_from_slice(&pool.open_time.to_le_bytes());
+    data.extend_from_slice(&pool.init_quote_amount.to_le_bytes());
+    data.extend_from_slice(&pool.init_base_amount.to_le_bytes());
+    let user_lp = associated_token_address(user, &keys.lp_mint, &TOKEN_PROGRAM_ID);
+    let initialize = Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(SYSVAR_RENT_ID, false),
+            AccountMeta::new(keys.id, false),
+            AccountMeta::new_readonly(keys.authority, false),
+            AccountMeta::new(keys.open_orders, false),
+            AccountMeta::new(keys.lp_mint, false),
+            AccountMeta::new_readonly(keys.base_mint, false),
+            AccountMeta::new_readonly(keys.quote_mint, false),
+            AccountMeta::new(keys.base_vault, false),
+            AccountMeta::new(keys.quote_vault, false),
+            AccountMeta::new(keys.target_orders, false),
+            AccountMeta::new_readonly(pda::amm_config(program_id), false),
+            AccountMeta::new(*create_fee_destination, false),
+            AccountMeta::new_readonly(keys.market_program_id, false),
+            AccountMeta::new_readonly(keys.market_id, false),
+            AccountMeta::new(*user, true),
+            AccountMeta::new(
+                associated_token_address(user, &keys.base_mint, &TOKEN_PROGRAM_ID),
+                false,
+            ),
+            AccountMeta::new(
+                associated_token_address(user, &keys.quote_mint, &TOKEN_PROGRAM_ID),
+                false,
+            ),
+            AccountMeta::new(user_lp, false),
+        ],
+    );
+    Ok(CreatePoolV4 {
+        instructions: vec![initialize],
+        keys,
+    })
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// build the creation of a v4 pool on `market_id`, reading the market and both mints and
+    /// paying the creation fee account of the network. the v4 program only takes SPL token mints
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::{USDC_MINT, WSOL_MINT}};
+    /// # use solana_sdk::pubkey::Pubkey;
+    /// # async fn run(raydium: Raydium, market_id: &str, user: Pubkey) -> Result<(), RaydiumError> {
+    /// let created = raydium
+    ///     .build_create_pool_v4(market_id, &WSOL_MINT, &USDC_MINT, &user, 10_000_000_000, 1_500_000_000, 0)
+    ///     .await?;
+    /// println!("pool {} with {} instructions", created.keys.id, created.instructions.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub async fn build_create_pool_v4(
+        &self,
+        market_id: &str,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        user: &Pubkey,
+        init_base_amount: u64,
+        init_quote_amount: u64,
+        open_time: u64,
+    ) -> Result<CreatePoolV4, RaydiumError> {
+        let market_id = parse_address(market_id)?;
+        let market_account = self
+            .get_multiple_accounts_with_context(&[market_id])
+            .await?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(market_id))?;
+        let market = MarketKeys::parse(&market_account.data)?;
+        let mints = self.fetch_mint_infos(&[*base_mint, *quote_mint]).await?;
+        for (mint, info) in [base_mint, quote_mint].iter().zip(&mints) {
+            if info.token_program != TOKEN_PROGRAM_ID {
+                return Err(RaydiumError::Unsupported(format!(
+                    "v4 pool of mint {} owned by {}",
+                    mint, info.token_program
+                )));
+            }
+        }
+        let create_fee_destination = match self.mode() {
+            Mode::DEV => DEVNET_RAYDIUM_V4_CREATE_POOL_FEE_ACCOUNT,
+            _ => RAYDIUM_V4_CREATE_POOL_FEE_ACCOUNT,
+        };
+        initialize2(
+            &self.program_id(PoolKind::V4),
+            &create_fee_destination,
+            &market_account.owner,
+            &market,
+            user,
+            &NewPoolV4 {
+                base_mint: *base_mint,
+                base_decimals: mints[0].decimals,
+                quote_mint: *quote_mint,
+                quote_decimals: mints[1].decimals,
+                init_base_amount,
+                init_quote_amount,
+                open_time,
+            },
+        )
+    }
+}
+
+/// accounts of both swap variants, target orders included
+fn swap_accounts(
+    keys: &PoolKeysV4,
+    owner: &Pubkey,
+    user_source: &Pubkey,
+    user_destination: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(keys.id, false),
+        AccountMeta::new_readonly(keys.authority, false),
+        AccountMeta::new(keys.open_orders, false),
+        AccountMeta::new(keys.target_orders, false),
+        AccountMeta::new(keys.base_vault, false),
+        AccountMeta::new(keys.quote_vault, false),
+        AccountMeta::new_readonly(keys.market_program_id, false),
+        AccountMeta::new(keys.market_id, false),
+        AccountMeta::new(keys.market_bids, false),
+        AccountMeta::new(keys.market_asks, false),
+        AccountMeta::new(keys.market_event_queue, false),
+        AccountMeta::new(keys.market_base_vault, false),
+        AccountMeta::new(keys.market_quote_vault, false),
+        AccountMeta::new_readonly(keys.market_authority, false),
+        AccountMeta::new(*user_source, false),
+        AccountMeta::new(*user_destination, false),
+        AccountMeta::new_readonly(*owner, true),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> PoolKeysV4 {
+        PoolKeysV4 {
+            id: Pubkey::new_unique(),
+            program_id: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            open_orders: Pubkey::new_unique(),
+            target_orders: Pubkey::new_unique(),
+            base_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            lp_mint: Pubkey::new_unique(),
+            base_decimals: 9,
+            quote_decimals: 6,
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            withdraw_queue: Pubkey::new_unique(),
+            lp_vault: Pubkey::new_unique(),
+            market_program_id: Pubkey::new_unique(),
+            market_id: Pubkey::new_unique(),
+            market_authority: Pubkey::new_unique(),
+            market_base_vault: Pubkey::new_unique(),
+            market_quote_vault: Pubkey::new_unique(),
+            market_bids: Pubkey::new_unique(),
+            market_asks: Pubkey::new_unique(),
+            market_event_queue: Pubkey::new_unique(),
+        }
+    }
+
+    #[test]
+    fn test_swap_base_in() {
+        let keys = keys();
+        let (owner, source, destination) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let instruction = swap_base_in(&keys, &owner, &source, &destination, 1_000, 900);
+        assert_eq!(instruction.program_id, keys.program_id);
+        assert_eq!(
+            instruction.data,
+            [9, 0xe8, 3, 0, 0, 0, 0, 0, 0, 0x84, 3, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(instruction.accounts.len(), 18);
+        assert_eq!(instruction.accounts[1].pubkey, keys.id);
+        assert_eq!(instruction.accounts[5].pubkey, keys.base_vault);
+        assert_eq!(instruction.accounts[14].pubkey, keys.market_authority);
+        assert_eq!(instruction.accounts[15].pubkey, source);
+        assert!(instruction.accounts[17].is_signer && !instruction.accounts[17].is_writable);
+    }
+
+    #[test]
+    fn test_swap_base_out() {
+        let keys = keys();
+        let (owner, source, destination) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let instruction = swap_base_out(&keys, &owner, &source, &destination, 1_000, 900);
+        assert_eq!(
+            instruction.data,
+            [11, 0xe8, 3, 0, 0, 0, 0, 0, 0, 0x84, 3, 0, 0, 0, 0, 0, 0]
+        );
+        // both variants share the account layout
+        assert_eq!(
+            instruction.accounts,
+            swap_base_in(&keys, &owner, &source, &destination, 1, 1).accounts
+        );
+    }
+
+    #[test]
+    fn test_deposit() {
+        let keys = keys();
+        let (owner, base, quote, lp) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let instruction = deposit(
+            &keys,
+            &owner,
+            &base,
+            &quote,
+            &lp,
+            1_000,
+            900,
+            MintSide::Quote,
+        );
+        assert_eq!(instruction.data[0], DEPOSIT_TAG);
+        assert_eq!(instruction.data[1..9], 1_000u64.to_le_bytes());
+        assert_eq!(instruction.data[9..17], 900u64.to_le_bytes());
+        assert_eq!(instruction.data[17..], 1u64.to_le_bytes());
+        assert_eq!(instruction.accounts.len(), 14);
+        assert_eq!(instruction.accounts[5].pubkey, keys.lp_mint);
+        assert_eq!(instruction.accounts[11].pubkey, lp);
+        assert!(instruction.accounts[12].is_signer);
+        assert_eq!(instruction.accounts[13].pubkey, keys.market_event_queue);
+    }
+
+    #[test]
+    fn test_initialize2() {
+        let program_id = Pubkey::new_unique();
+        let market_program_id = Pubkey::new_unique();
+        let (market_id, fee_destination, user) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        // a vault signer nonce valid for this market
+        let nonce = (0..u64::MAX)
+            .find(|nonce| market_vault_signer(&market_id, *nonce, &market_program_id).is_ok())
+            .unwrap();
+        let market = MarketKeys {
+            own_address: market_id,
+            vault_signer_nonce: nonce,
+            base_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            event_queue: Pubkey::new_unique(),
+            bids: Pubkey::new_unique(),
+            asks: Pubkey::new_unique(),
+        };
+        let mut pool = NewPoolV4 {
+            base_mint: market.base_mint,
+            base_decimals: 9,
+            quote_mint: market.quote_mint,
+            quote_decimals: 6,
+            init_base_amount: 1_000,
+            init_quote_amount: 900,
+            open_time: 1_700_000_000,
+        };
+        let created = initialize2(
+            &program_id,
+            &fee_destination,
+            &market_program_id,
+            &market,
+            &user,
+            &pool,
+        )
+        .unwrap();
+        let keys = created.keys;
+        assert_eq!(keys.id, pda::amm_id(&program_id, &market_id));
+        assert_eq!(keys.lp_vault, pda::temp_lp(&program_id, &market_id));
+        assert_eq!(keys.market_bids, market.bids);
+        let (authority, bump) = pda::amm_authority_with_nonce(&program_id);
+        assert_eq!(keys.authority, authority);
+        assert_eq!(created.instructions.len(), 1);
+        let initialize = &created.instructions[0];
+        assert_eq!(initialize.data[..2], [INITIALIZE2_TAG, bump]);
+        assert_eq!(initialize.data[2..10], 1_700_000_000u64.to_le_bytes());
+        assert_eq!(initialize.data[10..18], 900u64.to_le_bytes());
+        assert_eq!(initialize.data[18..], 1_000u64.to_le_bytes());
+        assert_eq!(initialize.accounts.len(), 21);
+        assert_eq!(initialize.accounts[4].pubkey, keys.id);
+        assert_eq!(initialize.accounts[12].pubkey, keys.target_orders);
+        assert_eq!(initialize.accounts[14].pubkey, fee_destination);
+        assert!(initialize.accounts[17].is_signer);
+        // the program creates the LP account of the user at its associated address
+        assert_eq!(
+            initialize.accounts[20].pubkey,
+            associated_token_address(&user, &keys.lp_mint, &TOKEN_PROGRAM_ID)
+        );
+        // a market of other mints is refused
+        pool.quote_mint = Pubkey::new_unique();
+        assert!(matches!(
+            initialize2(
+                &program_id,
+                &fee_destination,
+                &market_program_id,
+                &market,
+                &user,
+                &pool
+            ),
+            Err(RaydiumError::KeyMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_withdraw() {
+        let keys = keys();
+        let (owner, lp, base, quote) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let instruction = withdraw(&keys, &owner, &lp, &base, &quote, 1_000);
+        assert_eq!(instruction.data, [4, 0xe8, 3, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(instruction.accounts.len(), 22);
+        assert_eq!(instruction.accounts[8].pubkey, keys.withdraw_queue);
+        assert_eq!(instruction.accounts[9].pubkey, keys.lp_vault);
+        assert_eq!(instruction.accounts[15].pubkey, lp);
+        assert!(instruction.accounts[18].is_signer);
+        assert_eq!(instruction.accounts[21].pubkey, keys.market_asks);
+    }
+}
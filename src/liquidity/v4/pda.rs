@@ -0,0 +1,263 @@
+//! addresses the AMM v4 program derives for a pool from its market
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::liquidity::v4::RaydiumLiquidityPoolData;
+
+/// seed of the v4 amm authority PDA
+pub const AMM_AUTHORITY_SEED: &[u8] = b"amm authority";
+/// seeds of the accounts of a pool, each derived from the program and the market
+pub const AMM_ID_SEED: &[u8] = b"amm_associated_seed";
+pub const OPEN_ORDERS_SEED: &[u8] = b"open_order_associated_seed";
+pub const TARGET_ORDERS_SEED: &[u8] = b"target_associated_seed";
+pub const BASE_VAULT_SEED: &[u8] = b"coin_vault_associated_seed";
+pub const QUOTE_VAULT_SEED: &[u8] = b"pc_vault_associated_seed";
+pub const LP_MINT_SEED: &[u8] = b"lp_mint_associated_seed";
+pub const WITHDRAW_QUEUE_SEED: &[u8] = b"withdraw_associated_seed";
+pub const TEMP_LP_SEED: &[u8] = b"temp_lp_token_associated_seed";
+/// seed of the amm config of a program
+pub const AMM_CONFIG_SEED: &[u8] = b"amm_config_account_seed";
+
+/// the v4 amm authority of a program
+pub fn amm_authority(program_id: &Pubkey) -> Pubkey {
+    amm_authority_with_nonce(program_id).0
+}
+
+/// the v4 amm authority of a program and its bump, the `nonce` of its pools
+pub fn amm_authority_with_nonce(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AMM_AUTHORITY_SEED], program_id)
+}
+
+/// the amm config of a program, holding the pool creation fee
+pub fn amm_config(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[AMM_CONFIG_SEED], program_id).0
+}
+
+/// account of the pool of `market_id` derived with `seed`
+fn associated(program_id: &Pubkey, market_id: &Pubkey, seed: &[u8]) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref(), market_id.as_ref(), seed], program_id).0
+}
+
+/// address of the pool of a market
+pub fn amm_id(program_id: &Pubkey, market_id: &Pubkey) -> Pubkey {
+    associated(program_id, market_id, AMM_ID_SEED)
+}
+
+pub fn open_orders(program_id: &Pubkey, market_id: &Pubkey) -> Pubkey {
+    associated(program_id, market_id, OPEN_ORDERS_SEED)
+}
+
+pub fn target_orders(program_id: &Pubkey, market_id: &Pubkey) -> Pubkey {
+    associated(program_id, market_id, TARGET_ORDERS_SEED)
+}
+
+pub fn base_vault(program_id: &Pubkey, market_id: &Pubkey) -> Pubkey {
+    associated(program_id, market_id, BASE_VAULT_SEED)
+}
+
+pub fn quote_vault(program_id: &Pubkey, market_id: &Pubkey) -> Pubkey {
+    associated(program_id, market_id, QUOTE_VAULT_SEED)
+}
+
+pub fn lp_mint(program_id: &Pubkey, market_id: &Pubkey) -> Pubkey {
+    associated(program_id, market_id, LP_MINT_SEED)
+}
+
+pub fn withdraw_queue(program_id: &Pubkey, market_id: &Pubkey) -> Pubkey {
+    associated(program_id, market_id, WITHDRAW_QUEUE_SEED)
+}
+
+/// temporary LP account, the `lp_vault` of the pool
+pub fn temp_lp(program_id: &Pubkey, market_id: &Pubkey) -> Pubkey {
+    associated(program_id, market_id, TEMP_LP_SEED)
+}
+
+/// every account the v4 program derives for the pool of a market, known before the pool exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DerivedPoolAccounts {
+    pub amm_id: Pubkey,
+    pub authority: Pubkey,
+    /// bump of the authority, the `nonce` of the pool
+    pub nonce: u8,
+    pub open_orders: Pubkey,
+    pub target_orders: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub lp_mint: Pubkey,
+    pub withdraw_queue: Pubkey,
+    /// the `lp_vault` of the pool
+    pub temp_lp: Pubkey,
+}
+
+/// derive the accounts `initialize2` creates for the pool of `market_id`, so token accounts and
+/// transactions can be prepared before the pool exists
+/// Example
+/// ```rust
+/// # use raydium_sdk::{liquidity::v4::pda::derive_pool_accounts, programs::RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID};
+/// # use solana_sdk::pubkey::Pubkey;
+/// let market_id = Pubkey::from_str_const("8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6");
+/// let accounts = derive_pool_accounts(&market_id, &RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID);
+/// println!("pool {} lp mint {}", accounts.amm_id, accounts.lp_mint);
+/// ```
+pub fn derive_pool_accounts(market_id: &Pubkey, amm_program: &Pubkey) -> DerivedPoolAccounts {
+    let (authority, nonce) = amm_authority_with_nonce(amm_program);
+    DerivedPoolAccounts {
+        amm_id: amm_id(amm_program, market_id),
+        authority,
+        nonce,
+        open_orders: open_orders(amm_program, market_id),
+        target_orders: target_orders(amm_program, market_id),
+        base_vault: base_vault(amm_program, market_id),
+        quote_vault: quote_vault(amm_program, market_id),
+        lp_mint: lp_mint(amm_program, market_id),
+        withdraw_queue: withdraw_queue(amm_program, market_id),
+        temp_lp: temp_lp(amm_program, market_id),
+    }
+}
+
+/// a parsed account that differs from the one derived from the market
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DerivedAccountMismatch {
+    pub account: &'static str,
+    pub expected: Pubkey,
+    pub found: Pubkey,
+}
+
+impl RaydiumLiquidityPoolData {
+    /// check the pool address, open orders, target orders, vaults and LP mint against the
+    /// addresses `program_id` derives from the market, returning every mismatch.
+    /// pools created before the seeded initialization use plain keypairs and do not match
+    /// Example
+    /// ```rust
+    /// use raydium_sdk::{liquidity::v4::{RaydiumLiquidityPoolData, pda}, programs::RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID};
+    /// use solana_sdk::pubkey::Pubkey;
+    /// let program_id = RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID;
+    /// let market_id = Pubkey::new_unique();
+    /// let pool = RaydiumLiquidityPoolData { market_id, ..Default::default() };
+    /// let address = pda::amm_id(&program_id, &market_id);
+    /// for mismatch in pool.verify_derived_accounts(&address, &program_id) {
+    ///     println!("{} is {} instead of {}", mismatch.account, mismatch.found, mismatch.expected);
+    /// }
+    /// ```
+    pub fn verify_derived_accounts(
+        &self,
+        pool_address: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Vec<DerivedAccountMismatch> {
+        let derived = derive_pool_accounts(&self.market_id, program_id);
+        [
+            ("amm id", derived.amm_id, *pool_address),
+            ("open orders", derived.open_orders, self.open_orders),
+            ("target orders", derived.target_orders, self.target_orders),
+            ("base vault", derived.base_vault, self.base_vault),
+            ("quote vault", derived.quote_vault, self.quote_vault),
+            ("lp mint", derived.lp_mint, self.lp_mint),
+        ]
+        .into_iter()
+        .filter(|(_, expected, found)| expected != found)
+        .map(|(account, expected, found)| DerivedAccountMismatch {
+            account,
+            expected,
+            found,
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        liquidity::v4::{RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE, RaydiumLiquidityPoolV4},
+        mints::WSOL_MINT,
+        programs::RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+    };
+
+    #[test]
+    fn test_verify_derived_accounts() {
+        let program_id = RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID;
+        let market_id = Pubkey::new_unique();
+        let mut pool = RaydiumLiquidityPoolData {
+            market_id,
+            open_orders: open_orders(&program_id, &market_id),
+            target_orders: target_orders(&program_id, &market_id),
+            base_vault: base_vault(&program_id, &market_id),
+            quote_vault: quote_vault(&program_id, &market_id),
+            lp_mint: lp_mint(&program_id, &market_id),
+            ..Default::default()
+        };
+        let address = amm_id(&program_id, &market_id);
+        assert_eq!(pool.verify_derived_accounts(&address, &program_id), []);
+        // a layout read 32 bytes off swaps the vaults
+        std::mem::swap(&mut pool.base_vault, &mut pool.quote_vault);
+        let mismatches = pool.verify_derived_accounts(&address, &program_id);
+        assert_eq!(
+            mismatches.iter().map(|m| m.account).collect::<Vec<_>>(),
+            ["base vault", "quote vault"]
+        );
+        assert_eq!(mismatches[0].found, pool.base_vault);
+    }
+
+    /// the mainnet RAY-SOL pool account, holding its mints, decimals, nonce and accounts
+    fn ray_sol_fixture() -> [u8; RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE] {
+        RaydiumLiquidityPoolData {
+            nonce: 254,
+            base_decimal: 6,
+            quote_decimal: 9,
+            base_vault: Pubkey::from_str_const("Em6rHi68trYgBFyJ5261A2nhwuQWfLcirgzZZYoRcrkX"),
+            quote_vault: Pubkey::from_str_const("3mEFzHsJyu2Cpjrz6zPmTzP7uoLFj9SbbecGVzzkL1mJ"),
+            base_mint: Pubkey::from_str_const("4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R"),
+            quote_mint: WSOL_MINT,
+            lp_mint: Pubkey::from_str_const("89ZKE4aoyfLBe2RuV6jM3JGNhaV18Nxh8eNtjRcndBip"),
+            open_orders: Pubkey::from_str_const("6Su6Ea97dBxecd5W92KcVvv6SzCurE2BXGgFe9LNGMpE"),
+            market_id: Pubkey::from_str_const("C6tp2RVZnxBPFbnAsfTjis8BN9tycESAT4SgDQgbbrsA"),
+            target_orders: Pubkey::from_str_const("5hATcCfvhVwAjNExvrg8rRkXmYyksHhVajWLa46iRsmE"),
+            ..Default::default()
+        }
+        .encode()
+    }
+
+    #[test]
+    fn test_derive_pool_accounts() {
+        let program_id = RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID;
+        // the SOL-USDC market and the open orders of its mainnet pool 58oQChx4. the pool predates
+        // the seeded initialization for its other accounts, only its open orders is derived
+        let market_id = Pubkey::from_str_const("8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6");
+        let derived = derive_pool_accounts(&market_id, &program_id);
+        assert_eq!(
+            derived.open_orders,
+            Pubkey::from_str_const("HmiHHzq4Fym9e1D4qzLS6LDDM3tNsCTBPDWHTLZ763jY")
+        );
+        assert_eq!(
+            derived.authority,
+            Pubkey::from_str_const("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1")
+        );
+        assert_eq!(
+            Pubkey::create_program_address(&[AMM_AUTHORITY_SEED, &[derived.nonce]], &program_id),
+            Ok(derived.authority)
+        );
+        // the RAY-SOL pool AVs9TA4n was created by the seeded initialization, its address,
+        // orders accounts, vaults and LP mint all derive from its market
+        let pool = RaydiumLiquidityPoolV4::get_liquidity_pool_info(&ray_sol_fixture()).unwrap();
+        let derived = derive_pool_accounts(&pool.market_id, &program_id);
+        assert_eq!(
+            derived.amm_id,
+            Pubkey::from_str_const("AVs9TA4nWDzfPJE9gGVNJMVhcQy3V9PGazuz33BfG2RA")
+        );
+        assert_eq!(derived.authority, amm_authority(&program_id));
+        assert_eq!(derived.nonce, pool.nonce);
+        assert_eq!(derived.open_orders, pool.open_orders);
+        assert_eq!(derived.target_orders, pool.target_orders);
+        assert_eq!(derived.base_vault, pool.base_vault);
+        assert_eq!(derived.quote_vault, pool.quote_vault);
+        assert_eq!(derived.lp_mint, pool.lp_mint);
+        assert_eq!(
+            pool.verify_derived_accounts(&derived.amm_id, &program_id),
+            []
+        );
+        assert_ne!(
+            derive_pool_accounts(&Pubkey::new_unique(), &program_id).amm_id,
+            derived.amm_id
+        );
+    }
+}
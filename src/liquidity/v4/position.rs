@@ -0,0 +1,189 @@
+//! LP position of a wallet in a v4 pool
+
+#[cfg(feature = "rpc")]
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use crate::{Raydium, mints::MintInfo};
+use crate::{
+    error::RaydiumError,
+    liquidity::v4::{RaydiumLiquidityPoolData, Reserves},
+};
+
+/// LP tokens held in a v4 pool and the reserves they redeem, amounts raw
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LpPosition {
+    pub lp_amount: u64,
+    /// share of the LP supply, in percent
+    pub share_pct: f64,
+    /// base paid out by burning `lp_amount`
+    pub base_amount: u64,
+    /// quote paid out by burning `lp_amount`
+    pub quote_amount: u64,
+}
+
+impl RaydiumLiquidityPoolData {
+    /// position of `lp_amount` LP tokens, the underlying amounts being those `quote_withdraw`
+    /// pays out. no LP tokens is a zeroed position
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::v4::{RaydiumLiquidityPoolData, Reserves};
+    /// # let pool = RaydiumLiquidityPoolData::default();
+    /// let reserves = Reserves { base: 1_000_000_000, quote: 2_000_000_000 };
+    /// let position = pool.lp_position(&reserves, 1_000_000_000, 10_000_000).unwrap();
+    /// assert_eq!(position.share_pct, 1.0);
+    /// assert_eq!((position.base_amount, position.quote_amount), (10_000_000, 20_000_000));
+    /// ```
+    pub fn lp_position(
+        &self,
+        reserves: &Reserves,
+        lp_supply: u64,
+        lp_amount: u64,
+    ) -> Result<LpPosition, RaydiumError> {
+        if lp_amount == 0 {
+            return Ok(LpPosition::default());
+        }
+        let withdraw = self.quote_withdraw(reserves, lp_supply, lp_amount)?;
+        Ok(LpPosition {
+            lp_amount,
+            share_pct: lp_amount as f64 / lp_supply as f64 * 100.0,
+            base_amount: withdraw.base_amount,
+            quote_amount: withdraw.quote_amount,
+        })
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// get the LP position of `owner` in a v4 pool, over all its token accounts of the LP mint,
+    /// valued at the effective reserves. a wallet without LP tokens gets a zeroed position
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # use solana_sdk::pubkey::Pubkey;
+    /// # async fn run(raydium: Raydium, owner: Pubkey) -> Result<(), RaydiumError> {
+    /// let position = raydium
+    ///     .get_lp_position_v4("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2", &owner)
+    ///     .await?;
+    /// println!("{}% of the pool", position.share_pct);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_lp_position_v4(
+        &self,
+        pool_address: &str,
+        owner: &Pubkey,
+    ) -> Result<LpPosition, RaydiumError> {
+        let pool = self.get_liquidity_pool_v4(pool_address).await?;
+        let lp_amount = self.get_owner_token_balance(owner, &pool.lp_mint).await?;
+        if lp_amount == 0 {
+            return Ok(LpPosition::default());
+        }
+        let mut accounts = self
+            .get_multiple_accounts_with_context(&[
+                pool.base_vault,
+                pool.quote_vault,
+                pool.open_orders,
+                pool.lp_mint,
+            ])
+            .await?
+            .value;
+        let lp_mint = accounts
+            .get_mut(3)
+            .and_then(Option::take)
+            .ok_or(RaydiumError::AccountNotFound(pool.lp_mint))?;
+        let lp_mint = MintInfo::parse(lp_mint.owner, &lp_mint.data).map_err(RaydiumError::Parse)?;
+        let reserves = pool.reserves_from_accounts(&accounts)?;
+        pool.lp_position(&reserves, lp_mint.supply, lp_amount)
+    }
+}
+
+#[cfg(all(test, feature = "rpc"))]
+mod tests {
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+
+    use super::*;
+    use crate::{
+        liquidity::v4::RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE,
+        market::open_orders_data,
+        programs::{RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID, TOKEN_PROGRAM_ID},
+        test_utils::{account_info_response, mock_raydium, multiple_accounts_response, ui_account},
+    };
+
+    #[tokio::test]
+    async fn test_get_lp_position_v4() -> Result<(), Box<dyn std::error::Error>> {
+        let owner = Pubkey::new_unique();
+        let mut mocks = MocksMap::default();
+        let pool_account = || {
+            account_info_response(
+                &RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+                vec![0u8; RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE],
+            )
+        };
+        mocks.insert(RpcRequest::GetAccountInfo, pool_account());
+        mocks.insert(RpcRequest::GetAccountInfo, pool_account());
+        // 3 LP in a parsed account and 2 LP in a binary one
+        let mut token_account = vec![0u8; 165];
+        token_account[64..72].copy_from_slice(&2_000_000u64.to_le_bytes());
+        let accounts = |amounts: Vec<serde_json::Value>| serde_json::json!({ "context": { "slot": 1 }, "value": amounts });
+        mocks.insert(
+            RpcRequest::GetTokenAccountsByOwner,
+            accounts(vec![
+                serde_json::json!({
+                    "pubkey": Pubkey::new_unique().to_string(),
+                    "account": {
+                        "lamports": 1,
+                        "data": {
+                            "program": "spl-token",
+                            "parsed": { "info": { "tokenAmount": { "amount": "3000000" } } },
+                            "space": 165,
+                        },
+                        "owner": TOKEN_PROGRAM_ID.to_string(),
+                        "executable": false,
+                        "rentEpoch": 0,
+                        "space": 165,
+                    },
+                }),
+                serde_json::json!({
+                    "pubkey": Pubkey::new_unique().to_string(),
+                    "account": ui_account(&TOKEN_PROGRAM_ID, token_account),
+                }),
+            ]),
+        );
+        let vault = |amount: u64| {
+            let mut data = vec![0u8; 165];
+            data[64..72].copy_from_slice(&amount.to_le_bytes());
+            Some(ui_account(&TOKEN_PROGRAM_ID, data))
+        };
+        let mut mint = vec![0u8; 82];
+        mint[36..44].copy_from_slice(&100_000_000u64.to_le_bytes());
+        mint[44] = 6;
+        mint[45] = 1;
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![
+                vault(10_000_000_000),
+                vault(1_500_000_000),
+                Some(ui_account(&Pubkey::new_unique(), open_orders_data(0, 0))),
+                Some(ui_account(&TOKEN_PROGRAM_ID, mint)),
+            ]),
+        );
+        mocks.insert(RpcRequest::GetTokenAccountsByOwner, accounts(Vec::new()));
+        let raydium = mock_raydium(mocks);
+        let address = Pubkey::new_unique().to_string();
+        let position = raydium.get_lp_position_v4(&address, &owner).await?;
+        assert_eq!(position.lp_amount, 5_000_000);
+        assert_eq!(position.share_pct, 5.0);
+        assert_eq!(
+            (position.base_amount, position.quote_amount),
+            (500_000_000, 75_000_000)
+        );
+        // a wallet without LP tokens
+        assert_eq!(
+            raydium.get_lp_position_v4(&address, &owner).await?,
+            LpPosition::default()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+//! swaps applied one after another to a v4 pool held in memory, for backtesting
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    error::RaydiumError,
+    liquidity::v4::{RaydiumLiquidityPoolData, Reserves, V4SwapQuote},
+};
+
+/// a v4 pool whose reserves move with each swap applied, without any RPC call.
+/// the reserves follow `compute_amount_out` exactly: the whole input, fee included, is added
+/// and the output removed. the protocol pnl share of the fees stays in the reserves, as it
+/// does on chain until the program takes it
+#[derive(Debug, Clone, PartialEq)]
+pub struct V4PoolSimulator {
+    pool: RaydiumLiquidityPoolData,
+    reserves: Reserves,
+    base_fees: u128,
+    quote_fees: u128,
+    swaps: usize,
+}
+
+impl V4PoolSimulator {
+    /// simulate `pool` from a snapshot of its effective reserves
+    pub fn new(pool: RaydiumLiquidityPoolData, reserves: Reserves) -> V4PoolSimulator {
+        V4PoolSimulator {
+            pool,
+            reserves,
+            base_fees: 0,
+            quote_fees: 0,
+            swaps: 0,
+        }
+    }
+
+    /// swap `amount_in` of `input_mint` against the current reserves and keep the reserves after
+    /// the swap. a failed swap leaves the state untouched
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::v4::{RaydiumLiquidityPoolData, Reserves, simulator::V4PoolSimulator};
+    /// # use solana_sdk::pubkey::Pubkey;
+    /// let pool = RaydiumLiquidityPoolData {
+    ///     base_mint: Pubkey::new_unique(),
+    ///     quote_mint: Pubkey::new_unique(),
+    ///     swap_fee_numerator: 25,
+    ///     swap_fee_denominator: 10_000,
+    ///     ..Default::default()
+    /// };
+    /// let (base_mint, quote_mint) = (pool.base_mint, pool.quote_mint);
+    /// let mut simulator =
+    ///     V4PoolSimulator::new(pool, Reserves { base: 1_000_000_000, quote: 2_000_000_000 });
+    /// let sold = simulator.apply_swap(&base_mint, 1_000_000).unwrap();
+    /// let bought = simulator.apply_swap(&quote_mint, sold.amount_out).unwrap();
+    /// assert!(bought.amount_out < 1_000_000);
+    /// assert_eq!(simulator.fees(), (2_500, 4_983));
+    /// ```
+    pub fn apply_swap(
+        &mut self,
+        input_mint: &Pubkey,
+        amount_in: u64,
+    ) -> Result<V4SwapQuote, RaydiumError> {
+        let quote = self.quote_swap(input_mint, amount_in)?;
+        self.reserves = quote.reserves_after;
+        if quote.input_mint == self.pool.base_mint {
+            self.base_fees += quote.fee_amount as u128;
+        } else {
+            self.quote_fees += quote.fee_amount as u128;
+        }
+        self.swaps += 1;
+        Ok(quote)
+    }
+
+    /// quote a swap against the current reserves without applying it
+    pub fn quote_swap(
+        &self,
+        input_mint: &Pubkey,
+        amount_in: u64,
+    ) -> Result<V4SwapQuote, RaydiumError> {
+        self.pool
+            .compute_amount_out(&self.reserves, input_mint, amount_in)
+    }
+
+    /// the current reserves
+    pub fn reserves(&self) -> Reserves {
+        self.reserves
+    }
+
+    /// the current quote-per-base price, decimals applied
+    pub fn price(&self) -> Result<f64, RaydiumError> {
+        self.pool
+            .price_from_reserves(self.reserves.base, self.reserves.quote)
+    }
+
+    /// swap fees generated by the swaps applied so far as base and quote, raw
+    pub fn fees(&self) -> (u128, u128) {
+        (self.base_fees, self.quote_fees)
+    }
+
+    /// number of swaps applied
+    pub fn swap_count(&self) -> usize {
+        self.swaps
+    }
+
+    /// the simulated pool
+    pub fn pool(&self) -> &RaydiumLiquidityPoolData {
+        &self.pool
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulator_matches_quotes() {
+        let pool = RaydiumLiquidityPoolData {
+            base_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            base_decimal: 9,
+            quote_decimal: 6,
+            swap_fee_numerator: 25,
+            swap_fee_denominator: 10_000,
+            ..Default::default()
+        };
+        let start = Reserves {
+            base: 100_000_000_000,
+            quote: 15_000_000_000,
+        };
+        let swaps = [
+            (pool.base_mint, 2_000_000_000),
+            (pool.quote_mint, 450_000_000),
+            (pool.base_mint, 7),
+            (pool.quote_mint, 1_000_000_000),
+        ];
+        let run = || {
+            let mut simulator = V4PoolSimulator::new(pool.clone(), start);
+            let quotes: Vec<_> = swaps
+                .iter()
+                .map(|(mint, amount)| simulator.apply_swap(mint, *amount).unwrap())
+                .collect();
+            (simulator, quotes)
+        };
+        let (simulator, quotes) = run();
+        // each step is the single-shot quote against the reserves the previous step left
+        let mut reserves = start;
+        let mut fees = (0u128, 0u128);
+        for ((mint, amount), quote) in swaps.iter().zip(&quotes) {
+            let expected = pool.compute_amount_out(&reserves, mint, *amount).unwrap();
+            assert_eq!(*quote, expected);
+            reserves = expected.reserves_after;
+            if *mint == pool.base_mint {
+                fees.0 += expected.fee_amount as u128;
+            } else {
+                fees.1 += expected.fee_amount as u128;
+            }
+        }
+        assert_eq!(simulator.reserves(), reserves);
+        assert_eq!(simulator.fees(), fees);
+        assert_eq!(simulator.swap_count(), 4);
+        assert_eq!(
+            simulator.price().unwrap(),
+            pool.price_from_reserves(reserves.base, reserves.quote)
+                .unwrap()
+        );
+        // the same sequence replays to the same state
+        assert_eq!(run(), (simulator.clone(), quotes));
+        // a rejected swap changes nothing
+        let mut failed = simulator.clone();
+        assert!(failed.apply_swap(&Pubkey::new_unique(), 1).is_err());
+        assert!(failed.apply_swap(&pool.base_mint, 0).is_err());
+        assert_eq!(failed, simulator);
+    }
+}
@@ -0,0 +1,244 @@
+//! target orders account of a v4 pool, the orders the AMM plans and places on its OpenBook market
+
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use crate::{Raydium, rpc::parse_address};
+use crate::{
+    error::RaydiumError,
+    reader::{r_pubkey, r_u64, r_u128},
+    trace::trace_warn,
+};
+
+/// target orders account data size
+pub const TARGET_ORDERS_DATA_SIZE: usize = 2208;
+/// planned orders on each side of the book
+pub const TARGET_ORDERS_PER_SIDE: usize = 50;
+/// orders the AMM replaces at once, `MAX_ORDER_LIMIT` in the program
+pub const REPLACE_CLIENT_IDS: usize = 10;
+/// offsets of the fields in the target orders account, orders are price and volume u64 pairs
+const OWNER_OFFSET: usize = 0;
+const BUY_ORDERS_OFFSET: usize = 32;
+// 832-895: padding
+const TARGET_X_OFFSET: usize = 896;
+const TARGET_Y_OFFSET: usize = 912;
+const PLAN_X_BUY_OFFSET: usize = 928;
+const PLAN_Y_BUY_OFFSET: usize = 944;
+const PLAN_X_SELL_OFFSET: usize = 960;
+const PLAN_Y_SELL_OFFSET: usize = 976;
+const PLACED_X_OFFSET: usize = 992;
+const PLACED_Y_OFFSET: usize = 1008;
+const CALC_PNL_X_OFFSET: usize = 1024;
+const CALC_PNL_Y_OFFSET: usize = 1040;
+const SELL_ORDERS_OFFSET: usize = 1056;
+// 1856-1903: padding
+const REPLACE_BUY_CLIENT_ID_OFFSET: usize = 1904;
+const REPLACE_SELL_CLIENT_ID_OFFSET: usize = 1984;
+const LAST_ORDER_NUMERATOR_OFFSET: usize = 2064;
+const LAST_ORDER_DENOMINATOR_OFFSET: usize = 2072;
+const PLAN_ORDERS_CUR_OFFSET: usize = 2080;
+const PLACE_ORDERS_CUR_OFFSET: usize = 2088;
+const VALID_BUY_ORDER_NUM_OFFSET: usize = 2096;
+const VALID_SELL_ORDER_NUM_OFFSET: usize = 2104;
+// 2112-2191: padding
+const FREE_SLOT_BITS_OFFSET: usize = 2192;
+const ORDER_LEN: usize = 16;
+
+/// parser of v4 target orders accounts
+pub struct AmmTargetOrders;
+
+/// an order the AMM plans to place, price in quote lots per base lot and volume in base lots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TargetOrder {
+    pub price: u64,
+    pub vol: u64,
+}
+
+/// parsed target orders account. `x` is the base side and `y` the quote side, amounts raw
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetOrdersData {
+    pub owner: Pubkey,
+    pub buy_orders: [TargetOrder; TARGET_ORDERS_PER_SIDE],
+    pub target_x: u128,
+    pub target_y: u128,
+    pub plan_x_buy: u128,
+    pub plan_y_buy: u128,
+    pub plan_x_sell: u128,
+    pub plan_y_sell: u128,
+    pub placed_x: u128,
+    pub placed_y: u128,
+    /// base pnl accrued since the last calculation
+    pub calc_pnl_x: u128,
+    /// quote pnl accrued since the last calculation
+    pub calc_pnl_y: u128,
+    pub sell_orders: [TargetOrder; TARGET_ORDERS_PER_SIDE],
+    pub replace_buy_client_id: [u64; REPLACE_CLIENT_IDS],
+    pub replace_sell_client_id: [u64; REPLACE_CLIENT_IDS],
+    pub last_order_numerator: u64,
+    pub last_order_denominator: u64,
+    /// next order to plan
+    pub plan_orders_cur: u64,
+    /// next order to place
+    pub place_orders_cur: u64,
+    pub valid_buy_order_num: u64,
+    pub valid_sell_order_num: u64,
+    pub free_slot_bits: u128,
+}
+
+impl AmmTargetOrders {
+    /// parse a target orders account, the `target_orders` of a v4 pool
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::liquidity::v4::target_orders::{AmmTargetOrders, TARGET_ORDERS_DATA_SIZE};
+    /// let orders = AmmTargetOrders::parse(&[0u8; TARGET_ORDERS_DATA_SIZE]).unwrap();
+    /// assert_eq!(orders.active_buy_orders().count(), 0);
+    /// ```
+    pub fn parse(data: &[u8]) -> Result<TargetOrdersData, RaydiumError> {
+        if data.len() != TARGET_ORDERS_DATA_SIZE {
+            trace_warn!(
+                expected = TARGET_ORDERS_DATA_SIZE,
+                actual = data.len(),
+                "target orders data size mismatch"
+            );
+            return Err(RaydiumError::Parse(format!(
+                "target orders account must be {} bytes, got {}",
+                TARGET_ORDERS_DATA_SIZE,
+                data.len()
+            )));
+        }
+        let orders = |offset: usize| {
+            std::array::from_fn(|i| TargetOrder {
+                price: r_u64(data, offset + i * ORDER_LEN),
+                vol: r_u64(data, offset + i * ORDER_LEN + 8),
+            })
+        };
+        let client_ids = |offset: usize| std::array::from_fn(|i| r_u64(data, offset + i * 8));
+        Ok(TargetOrdersData {
+            owner: r_pubkey(data, OWNER_OFFSET),
+            buy_orders: orders(BUY_ORDERS_OFFSET),
+            target_x: r_u128(data, TARGET_X_OFFSET),
+            target_y: r_u128(data, TARGET_Y_OFFSET),
+            plan_x_buy: r_u128(data, PLAN_X_BUY_OFFSET),
+            plan_y_buy: r_u128(data, PLAN_Y_BUY_OFFSET),
+            plan_x_sell: r_u128(data, PLAN_X_SELL_OFFSET),
+            plan_y_sell: r_u128(data, PLAN_Y_SELL_OFFSET),
+            placed_x: r_u128(data, PLACED_X_OFFSET),
+            placed_y: r_u128(data, PLACED_Y_OFFSET),
+            calc_pnl_x: r_u128(data, CALC_PNL_X_OFFSET),
+            calc_pnl_y: r_u128(data, CALC_PNL_Y_OFFSET),
+            sell_orders: orders(SELL_ORDERS_OFFSET),
+            replace_buy_client_id: client_ids(REPLACE_BUY_CLIENT_ID_OFFSET),
+            replace_sell_client_id: client_ids(REPLACE_SELL_CLIENT_ID_OFFSET),
+            last_order_numerator: r_u64(data, LAST_ORDER_NUMERATOR_OFFSET),
+            last_order_denominator: r_u64(data, LAST_ORDER_DENOMINATOR_OFFSET),
+            plan_orders_cur: r_u64(data, PLAN_ORDERS_CUR_OFFSET),
+            place_orders_cur: r_u64(data, PLACE_ORDERS_CUR_OFFSET),
+            valid_buy_order_num: r_u64(data, VALID_BUY_ORDER_NUM_OFFSET),
+            valid_sell_order_num: r_u64(data, VALID_SELL_ORDER_NUM_OFFSET),
+            free_slot_bits: r_u128(data, FREE_SLOT_BITS_OFFSET),
+        })
+    }
+}
+
+impl TargetOrder {
+    /// whether the slot holds an order, unused slots are zeroed
+    pub fn is_active(&self) -> bool {
+        self.price != 0 && self.vol != 0
+    }
+}
+
+impl TargetOrdersData {
+    /// the planned buy orders, zeroed slots skipped
+    pub fn active_buy_orders(&self) -> impl Iterator<Item = &TargetOrder> + '_ {
+        self.buy_orders.iter().filter(|order| order.is_active())
+    }
+
+    /// the planned sell orders, zeroed slots skipped
+    pub fn active_sell_orders(&self) -> impl Iterator<Item = &TargetOrder> + '_ {
+        self.sell_orders.iter().filter(|order| order.is_active())
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// get the target orders account of a v4 pool, its `target_orders`
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let pool = raydium
+    ///     .get_liquidity_pool_v4("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2")
+    ///     .await?;
+    /// let orders = raydium.get_target_orders(&pool.target_orders.to_string()).await?;
+    /// for order in orders.active_buy_orders() {
+    ///     println!("buy {} lots at {}", order.vol, order.price);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_target_orders(&self, address: &str) -> Result<TargetOrdersData, RaydiumError> {
+        let address = parse_address(address)?;
+        let account = self
+            .get_multiple_accounts_with_context(&[address])
+            .await?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(address))?;
+        AmmTargetOrders::parse(&account.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target_orders() {
+        let mut data = vec![0u8; TARGET_ORDERS_DATA_SIZE];
+        let mut put =
+            |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        let owner = Pubkey::new_unique();
+        put(OWNER_OFFSET, owner.as_ref());
+        // two buys, a zeroed slot, then a third buy
+        for (slot, price, vol) in [(0, 1_500u64, 10u64), (1, 1_490, 20), (3, 1_480, 30)] {
+            put(BUY_ORDERS_OFFSET + slot * ORDER_LEN, &price.to_le_bytes());
+            put(BUY_ORDERS_OFFSET + slot * ORDER_LEN + 8, &vol.to_le_bytes());
+        }
+        put(SELL_ORDERS_OFFSET + 49 * ORDER_LEN, &1_600u64.to_le_bytes());
+        put(SELL_ORDERS_OFFSET + 49 * ORDER_LEN + 8, &5u64.to_le_bytes());
+        put(TARGET_X_OFFSET, &(u64::MAX as u128 + 1).to_le_bytes());
+        put(CALC_PNL_Y_OFFSET, &7u128.to_le_bytes());
+        put(REPLACE_SELL_CLIENT_ID_OFFSET + 9 * 8, &99u64.to_le_bytes());
+        put(PLAN_ORDERS_CUR_OFFSET, &3u64.to_le_bytes());
+        put(VALID_SELL_ORDER_NUM_OFFSET, &1u64.to_le_bytes());
+        put(FREE_SLOT_BITS_OFFSET, &u128::MAX.to_le_bytes());
+        let orders = AmmTargetOrders::parse(&data).unwrap();
+        assert_eq!(orders.owner, owner);
+        assert_eq!(
+            orders
+                .active_buy_orders()
+                .map(|o| (o.price, o.vol))
+                .collect::<Vec<_>>(),
+            [(1_500, 10), (1_490, 20), (1_480, 30)]
+        );
+        assert_eq!(
+            orders.active_sell_orders().collect::<Vec<_>>(),
+            [&TargetOrder {
+                price: 1_600,
+                vol: 5
+            }]
+        );
+        assert_eq!(
+            (orders.target_x, orders.calc_pnl_y),
+            (u64::MAX as u128 + 1, 7)
+        );
+        assert_eq!(orders.replace_sell_client_id[9], 99);
+        assert_eq!(
+            (orders.plan_orders_cur, orders.valid_sell_order_num),
+            (3, 1)
+        );
+        assert_eq!(orders.free_slot_bits, u128::MAX);
+        assert!(AmmTargetOrders::parse(&data[..2200]).is_err());
+    }
+}
@@ -0,0 +1,1322 @@
+//! serum / OpenBook market accounts referenced by v4 pools
+
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use crate::{Raydium, liquidity::v4::RaydiumLiquidityPoolData, rpc::parse_address};
+use crate::{
+    error::RaydiumError,
+    programs::{DEVNET_OPENBOOK_PROGRAM_ID, OPENBOOK_PROGRAM_ID, SERUM_V3_PROGRAM_ID},
+    reader::{conver_balance, r_pubkey, r_u8, r_u32, r_u64, r_u128},
+    trace::trace_warn,
+};
+
+/// "serum" padding at the start of a market account
+pub const MARKET_HEAD_PADDING: &[u8] = b"serum";
+/// "padding" at the end of a market account
+pub const MARKET_TAIL_PADDING: &[u8] = b"padding";
+/// size of a MarketStateV2 account, the layout of the OpenBook markets used by v4 pools
+pub const MARKET_STATE_V2_DATA_SIZE: usize = 388;
+/// size of a permissioned MarketStateV3 account, which adds the market authorities
+pub const MARKET_STATE_V3_DATA_SIZE: usize = 1476;
+/// offsets in the market state, once the head padding is stripped
+const ACCOUNT_FLAGS_OFFSET: usize = 0;
+const OWN_ADDRESS_OFFSET: usize = 8;
+const VAULT_SIGNER_NONCE_OFFSET: usize = 40;
+const BASE_MINT_OFFSET: usize = 48;
+const QUOTE_MINT_OFFSET: usize = 80;
+const BASE_VAULT_OFFSET: usize = 112;
+const BASE_DEPOSITS_TOTAL_OFFSET: usize = 144;
+const BASE_FEES_ACCRUED_OFFSET: usize = 152;
+const QUOTE_VAULT_OFFSET: usize = 160;
+const QUOTE_DEPOSITS_TOTAL_OFFSET: usize = 192;
+const QUOTE_FEES_ACCRUED_OFFSET: usize = 200;
+const QUOTE_DUST_THRESHOLD_OFFSET: usize = 208;
+const REQUEST_QUEUE_OFFSET: usize = 216;
+const EVENT_QUEUE_OFFSET: usize = 248;
+const BIDS_OFFSET: usize = 280;
+const ASKS_OFFSET: usize = 312;
+const BASE_LOT_SIZE_OFFSET: usize = 344;
+const QUOTE_LOT_SIZE_OFFSET: usize = 352;
+const FEE_RATE_BPS_OFFSET: usize = 360;
+const REFERRER_REBATES_ACCRUED_OFFSET: usize = 368;
+const OPEN_ORDERS_AUTHORITY_OFFSET: usize = 376;
+const PRUNE_AUTHORITY_OFFSET: usize = 408;
+const CONSUME_EVENTS_AUTHORITY_OFFSET: usize = 440;
+/// size of a serum / OpenBook open orders account, padding included
+pub const OPEN_ORDERS_DATA_SIZE: usize = 3228;
+/// number of order slots of an open orders account
+pub const OPEN_ORDERS_SLOTS: usize = 128;
+/// account flag set on every initialized serum account
+pub const ACCOUNT_FLAG_INITIALIZED: u64 = 1;
+/// account flag of open orders accounts
+pub const ACCOUNT_FLAG_OPEN_ORDERS: u64 = 1 << 2;
+/// offsets in the open orders state, once the head padding is stripped
+const OPEN_ORDERS_MARKET_OFFSET: usize = 8;
+const OPEN_ORDERS_OWNER_OFFSET: usize = 40;
+const OPEN_ORDERS_BASE_TOKEN_FREE_OFFSET: usize = 72;
+const OPEN_ORDERS_BASE_TOKEN_TOTAL_OFFSET: usize = 80;
+const OPEN_ORDERS_QUOTE_TOKEN_FREE_OFFSET: usize = 88;
+const OPEN_ORDERS_QUOTE_TOKEN_TOTAL_OFFSET: usize = 96;
+const OPEN_ORDERS_FREE_SLOT_BITS_OFFSET: usize = 104;
+const OPEN_ORDERS_IS_BID_BITS_OFFSET: usize = 120;
+const OPEN_ORDERS_ORDERS_OFFSET: usize = 136;
+const OPEN_ORDERS_CLIENT_ORDER_IDS_OFFSET: usize = 2184;
+const OPEN_ORDERS_REFERRER_REBATES_ACCRUED_OFFSET: usize = 3208;
+/// account flag of bids slabs
+pub const ACCOUNT_FLAG_BIDS: u64 = 1 << 5;
+/// account flag of asks slabs
+pub const ACCOUNT_FLAG_ASKS: u64 = 1 << 6;
+/// size of a critbit node of a slab
+pub const SLAB_NODE_SIZE: usize = 72;
+/// offsets in the slab state, once the head padding is stripped
+const SLAB_ROOT_NODE_OFFSET: usize = 28;
+const SLAB_LEAF_COUNT_OFFSET: usize = 32;
+const SLAB_NODES_OFFSET: usize = 40;
+/// critbit node tags
+const SLAB_NODE_INNER: u32 = 1;
+const SLAB_NODE_LEAF: u32 = 2;
+/// offsets in a critbit node
+const SLAB_INNER_CHILDREN_OFFSET: usize = 24;
+const SLAB_LEAF_OWNER_SLOT_OFFSET: usize = 4;
+const SLAB_LEAF_KEY_OFFSET: usize = 8;
+const SLAB_LEAF_OWNER_OFFSET: usize = 24;
+const SLAB_LEAF_QUANTITY_OFFSET: usize = 56;
+const SLAB_LEAF_CLIENT_ORDER_ID_OFFSET: usize = 64;
+/// account flag of event queues
+pub const ACCOUNT_FLAG_EVENT_QUEUE: u64 = 1 << 4;
+/// size of an event of the event queue
+pub const EVENT_SIZE: usize = 88;
+/// event flags
+pub const EVENT_FLAG_FILL: u8 = 1;
+pub const EVENT_FLAG_OUT: u8 = 1 << 1;
+pub const EVENT_FLAG_BID: u8 = 1 << 2;
+pub const EVENT_FLAG_MAKER: u8 = 1 << 3;
+/// offsets in the event queue state, once the head padding is stripped
+const EVENT_QUEUE_HEAD_OFFSET: usize = 8;
+const EVENT_QUEUE_COUNT_OFFSET: usize = 16;
+const EVENT_QUEUE_SEQ_NUM_OFFSET: usize = 24;
+const EVENT_QUEUE_EVENTS_OFFSET: usize = 32;
+/// offsets in an event
+const EVENT_FLAGS_OFFSET: usize = 0;
+const EVENT_OWNER_SLOT_OFFSET: usize = 1;
+const EVENT_FEE_TIER_OFFSET: usize = 2;
+const EVENT_NATIVE_QTY_RELEASED_OFFSET: usize = 8;
+const EVENT_NATIVE_QTY_PAID_OFFSET: usize = 16;
+const EVENT_NATIVE_FEE_OR_REBATE_OFFSET: usize = 24;
+const EVENT_ORDER_ID_OFFSET: usize = 32;
+const EVENT_OWNER_OFFSET: usize = 48;
+const EVENT_CLIENT_ORDER_ID_OFFSET: usize = 80;
+
+/// program owning the market of a v4 pool. OpenBook v1 forked serum v3 without changing the
+/// account layouts or the instructions the AMM sends, the venues differ by program id only
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarketVenue {
+    OpenBookV1,
+    SerumV3,
+    /// a program the AMM is not known to trade on
+    Unknown(Pubkey),
+}
+
+impl MarketVenue {
+    /// the venue of a market program, devnet included
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::{market::MarketVenue, programs::OPENBOOK_PROGRAM_ID};
+    /// assert_eq!(MarketVenue::from_program_id(&OPENBOOK_PROGRAM_ID), MarketVenue::OpenBookV1);
+    /// ```
+    pub fn from_program_id(program_id: &Pubkey) -> MarketVenue {
+        match *program_id {
+            OPENBOOK_PROGRAM_ID | DEVNET_OPENBOOK_PROGRAM_ID => MarketVenue::OpenBookV1,
+            SERUM_V3_PROGRAM_ID => MarketVenue::SerumV3,
+            other => MarketVenue::Unknown(other),
+        }
+    }
+
+    /// whether the AMM can trade on the venue, so instructions against its markets can be built
+    pub fn is_supported(&self) -> bool {
+        !matches!(self, MarketVenue::Unknown(_))
+    }
+}
+
+/// parser of serum / OpenBook market accounts
+pub struct OpenBookMarket;
+
+/// authorities of a permissioned MarketStateV3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarketAuthorities {
+    pub open_orders: Pubkey,
+    pub prune: Pubkey,
+    pub consume_events: Pubkey,
+}
+
+/// parsed serum / OpenBook market, amounts are raw
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarketData {
+    pub account_flags: u64,
+    pub own_address: Pubkey,
+    pub vault_signer_nonce: u64,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub base_deposits_total: u64,
+    pub base_fees_accrued: u64,
+    pub quote_vault: Pubkey,
+    pub quote_deposits_total: u64,
+    pub quote_fees_accrued: u64,
+    pub quote_dust_threshold: u64,
+    pub request_queue: Pubkey,
+    pub event_queue: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub base_lot_size: u64,
+    pub quote_lot_size: u64,
+    pub fee_rate_bps: u64,
+    pub referrer_rebates_accrued: u64,
+    /// `None` for MarketStateV2 accounts
+    pub authorities: Option<MarketAuthorities>,
+}
+
+impl OpenBookMarket {
+    /// parse a MarketStateV2 or MarketStateV3 account, padding included
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::market::{MARKET_STATE_V2_DATA_SIZE, OpenBookMarket};
+    /// let data = vec![0u8; 100];
+    /// assert!(OpenBookMarket::parse(&data).is_err());
+    /// ```
+    pub fn parse(data: &[u8]) -> Result<MarketData, RaydiumError> {
+        if !matches!(
+            data.len(),
+            MARKET_STATE_V2_DATA_SIZE | MARKET_STATE_V3_DATA_SIZE
+        ) || !data.starts_with(MARKET_HEAD_PADDING)
+        {
+            trace_warn!(
+                expected = MARKET_STATE_V2_DATA_SIZE,
+                actual = data.len(),
+                "market data size mismatch"
+            );
+            return Err(RaydiumError::Parse(format!(
+                "not a serum market account: {} bytes",
+                data.len()
+            )));
+        }
+        let state = &data[MARKET_HEAD_PADDING.len()..data.len() - MARKET_TAIL_PADDING.len()];
+        let authorities = (data.len() == MARKET_STATE_V3_DATA_SIZE).then(|| MarketAuthorities {
+            open_orders: r_pubkey(state, OPEN_ORDERS_AUTHORITY_OFFSET),
+            prune: r_pubkey(state, PRUNE_AUTHORITY_OFFSET),
+            consume_events: r_pubkey(state, CONSUME_EVENTS_AUTHORITY_OFFSET),
+        });
+        Ok(MarketData {
+            account_flags: r_u64(state, ACCOUNT_FLAGS_OFFSET),
+            own_address: r_pubkey(state, OWN_ADDRESS_OFFSET),
+            vault_signer_nonce: r_u64(state, VAULT_SIGNER_NONCE_OFFSET),
+            base_mint: r_pubkey(state, BASE_MINT_OFFSET),
+            quote_mint: r_pubkey(state, QUOTE_MINT_OFFSET),
+            base_vault: r_pubkey(state, BASE_VAULT_OFFSET),
+            base_deposits_total: r_u64(state, BASE_DEPOSITS_TOTAL_OFFSET),
+            base_fees_accrued: r_u64(state, BASE_FEES_ACCRUED_OFFSET),
+            quote_vault: r_pubkey(state, QUOTE_VAULT_OFFSET),
+            quote_deposits_total: r_u64(state, QUOTE_DEPOSITS_TOTAL_OFFSET),
+            quote_fees_accrued: r_u64(state, QUOTE_FEES_ACCRUED_OFFSET),
+            quote_dust_threshold: r_u64(state, QUOTE_DUST_THRESHOLD_OFFSET),
+            request_queue: r_pubkey(state, REQUEST_QUEUE_OFFSET),
+            event_queue: r_pubkey(state, EVENT_QUEUE_OFFSET),
+            bids: r_pubkey(state, BIDS_OFFSET),
+            asks: r_pubkey(state, ASKS_OFFSET),
+            base_lot_size: r_u64(state, BASE_LOT_SIZE_OFFSET),
+            quote_lot_size: r_u64(state, QUOTE_LOT_SIZE_OFFSET),
+            fee_rate_bps: r_u64(state, FEE_RATE_BPS_OFFSET),
+            referrer_rebates_accrued: r_u64(state, REFERRER_REBATES_ACCRUED_OFFSET),
+            authorities,
+        })
+    }
+}
+
+/// parser of serum / OpenBook open orders accounts
+pub struct OpenOrders;
+
+/// tokens an open orders account holds, free or locked in resting orders, raw
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OpenOrdersTotals {
+    pub base_total: u64,
+    pub quote_total: u64,
+}
+
+/// parsed serum / OpenBook open orders account, amounts are raw
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenOrdersData {
+    pub account_flags: u64,
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    pub base_token_free: u64,
+    pub base_token_total: u64,
+    pub quote_token_free: u64,
+    pub quote_token_total: u64,
+    /// bit i is set when slot i holds no order
+    pub free_slot_bits: u128,
+    /// bit i is set when the order in slot i is a bid
+    pub is_bid_bits: u128,
+    pub orders: [u128; OPEN_ORDERS_SLOTS],
+    pub client_order_ids: [u64; OPEN_ORDERS_SLOTS],
+    pub referrer_rebates_accrued: u64,
+}
+
+/// an order resting in an open orders slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestingOrder {
+    pub slot: usize,
+    pub order_id: u128,
+    pub client_order_id: u64,
+    pub is_bid: bool,
+}
+
+impl OpenOrders {
+    /// parse an open orders account, padding included
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::market::OpenOrders;
+    /// assert!(OpenOrders::parse(&[0u8; 100]).is_err());
+    /// ```
+    pub fn parse(data: &[u8]) -> Result<OpenOrdersData, RaydiumError> {
+        if data.len() != OPEN_ORDERS_DATA_SIZE {
+            trace_warn!(
+                expected = OPEN_ORDERS_DATA_SIZE,
+                actual = data.len(),
+                "open orders data size mismatch"
+            );
+            return Err(RaydiumError::Parse(format!(
+                "open orders account must be {} bytes, got {}",
+                OPEN_ORDERS_DATA_SIZE,
+                data.len()
+            )));
+        }
+        if !data.starts_with(MARKET_HEAD_PADDING) {
+            return Err(RaydiumError::Parse(
+                "open orders account is missing the serum padding".to_string(),
+            ));
+        }
+        let state = &data[MARKET_HEAD_PADDING.len()..data.len() - MARKET_TAIL_PADDING.len()];
+        let account_flags = r_u64(state, ACCOUNT_FLAGS_OFFSET);
+        let expected = ACCOUNT_FLAG_INITIALIZED | ACCOUNT_FLAG_OPEN_ORDERS;
+        if account_flags & expected != expected {
+            return Err(RaydiumError::Parse(format!(
+                "account flags {:#x} are not those of an initialized open orders account",
+                account_flags
+            )));
+        }
+        Ok(OpenOrdersData {
+            account_flags,
+            market: r_pubkey(state, OPEN_ORDERS_MARKET_OFFSET),
+            owner: r_pubkey(state, OPEN_ORDERS_OWNER_OFFSET),
+            base_token_free: r_u64(state, OPEN_ORDERS_BASE_TOKEN_FREE_OFFSET),
+            base_token_total: r_u64(state, OPEN_ORDERS_BASE_TOKEN_TOTAL_OFFSET),
+            quote_token_free: r_u64(state, OPEN_ORDERS_QUOTE_TOKEN_FREE_OFFSET),
+            quote_token_total: r_u64(state, OPEN_ORDERS_QUOTE_TOKEN_TOTAL_OFFSET),
+            free_slot_bits: r_u128(state, OPEN_ORDERS_FREE_SLOT_BITS_OFFSET),
+            is_bid_bits: r_u128(state, OPEN_ORDERS_IS_BID_BITS_OFFSET),
+            orders: std::array::from_fn(|i| r_u128(state, OPEN_ORDERS_ORDERS_OFFSET + i * 16)),
+            client_order_ids: std::array::from_fn(|i| {
+                r_u64(state, OPEN_ORDERS_CLIENT_ORDER_IDS_OFFSET + i * 8)
+            }),
+            referrer_rebates_accrued: r_u64(state, OPEN_ORDERS_REFERRER_REBATES_ACCRUED_OFFSET),
+        })
+    }
+}
+
+impl OpenOrdersData {
+    /// the base and quote totals, free and locked
+    pub fn totals(&self) -> OpenOrdersTotals {
+        OpenOrdersTotals {
+            base_total: self.base_token_total,
+            quote_total: self.quote_token_total,
+        }
+    }
+
+    /// the orders of the slots in use
+    pub fn resting_orders(&self) -> impl Iterator<Item = RestingOrder> + '_ {
+        (0..OPEN_ORDERS_SLOTS)
+            .filter(|slot| self.free_slot_bits & (1 << slot) == 0)
+            .map(|slot| RestingOrder {
+                slot,
+                order_id: self.orders[slot],
+                client_order_id: self.client_order_ids[slot],
+                is_bid: self.is_bid_bits & (1 << slot) != 0,
+            })
+    }
+}
+
+impl MarketData {
+    /// price in quote per base, decimals applied, of a price in quote lots per base lot
+    pub fn price_from_lots(&self, price_lots: u64, base_decimals: u8, quote_decimals: u8) -> f64 {
+        if self.base_lot_size == 0 {
+            return 0.0;
+        }
+        price_lots as f64 * self.quote_lot_size as f64 / self.base_lot_size as f64
+            * 10f64.powi(base_decimals as i32 - quote_decimals as i32)
+    }
+
+    /// size in base, decimals applied, of a quantity in base lots
+    pub fn size_from_lots(&self, quantity_lots: u64, base_decimals: u8) -> f64 {
+        quantity_lots as f64 * self.base_lot_size as f64 / 10f64.powi(base_decimals as i32)
+    }
+
+    /// the L2 levels of a bids or asks slab of the market, see `Slab::levels`
+    pub fn orderbook_levels(
+        &self,
+        slab: &Slab,
+        depth: usize,
+        base_decimals: u8,
+        quote_decimals: u8,
+    ) -> Vec<OrderbookLevel> {
+        slab.levels(depth)
+            .into_iter()
+            .map(|(price, quantity)| OrderbookLevel {
+                price: self.price_from_lots(price, base_decimals, quote_decimals),
+                size: self.size_from_lots(quantity, base_decimals),
+            })
+            .collect()
+    }
+}
+
+/// bids or asks critbit slab of a serum / OpenBook market
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slab {
+    pub account_flags: u64,
+    /// the orders of the slab by ascending key, so by ascending price
+    pub leaves: Vec<SlabLeaf>,
+}
+
+/// an order resting in a slab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlabLeaf {
+    /// order id, the price in lots in the upper 64 bits and a sequence number in the lower ones
+    pub key: u128,
+    /// open orders account of the order
+    pub owner: Pubkey,
+    pub owner_slot: u8,
+    /// size of the order in base lots
+    pub quantity: u64,
+    pub client_order_id: u64,
+}
+
+impl SlabLeaf {
+    /// price of the order in quote lots per base lot
+    pub fn price(&self) -> u64 {
+        (self.key >> 64) as u64
+    }
+}
+
+impl Slab {
+    /// parse a bids or asks slab, padding included, walking the critbit tree from its root
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::market::Slab;
+    /// assert!(Slab::parse(&[0u8; 100]).is_err());
+    /// ```
+    pub fn parse(data: &[u8]) -> Result<Slab, RaydiumError> {
+        let min_size = MARKET_HEAD_PADDING.len() + SLAB_NODES_OFFSET + MARKET_TAIL_PADDING.len();
+        if data.len() < min_size || !data.starts_with(MARKET_HEAD_PADDING) {
+            return Err(RaydiumError::Parse(format!(
+                "not a serum slab account: {} bytes",
+                data.len()
+            )));
+        }
+        let state = &data[MARKET_HEAD_PADDING.len()..data.len() - MARKET_TAIL_PADDING.len()];
+        let account_flags = r_u64(state, ACCOUNT_FLAGS_OFFSET);
+        if account_flags & ACCOUNT_FLAG_INITIALIZED == 0
+            || account_flags & (ACCOUNT_FLAG_BIDS | ACCOUNT_FLAG_ASKS) == 0
+        {
+            return Err(RaydiumError::Parse(format!(
+                "account flags {:#x} are not those of an initialized slab",
+                account_flags
+            )));
+        }
+        let nodes = &state[SLAB_NODES_OFFSET..];
+        let node_count = nodes.len() / SLAB_NODE_SIZE;
+        let node = |index: u32| {
+            let index = index as usize;
+            (index < node_count).then(|| &nodes[index * SLAB_NODE_SIZE..][..SLAB_NODE_SIZE])
+        };
+        let leaf_count = r_u64(state, SLAB_LEAF_COUNT_OFFSET) as usize;
+        let mut leaves = Vec::with_capacity(leaf_count.min(node_count));
+        if leaf_count > 0 {
+            // depth first, the lower child first, so the leaves come out by ascending key
+            let mut stack = vec![r_u32(state, SLAB_ROOT_NODE_OFFSET)];
+            let mut visited = 0;
+            while let Some(index) = stack.pop() {
+                visited += 1;
+                let data = node(index)
+                    .filter(|_| visited <= node_count)
+                    .ok_or_else(|| {
+                        RaydiumError::Parse(format!("slab node {} is out of bounds", index))
+                    })?;
+                match r_u32(data, 0) {
+                    SLAB_NODE_INNER => {
+                        stack.push(r_u32(data, SLAB_INNER_CHILDREN_OFFSET + 4));
+                        stack.push(r_u32(data, SLAB_INNER_CHILDREN_OFFSET));
+                    }
+                    SLAB_NODE_LEAF => leaves.push(SlabLeaf {
+                        key: r_u128(data, SLAB_LEAF_KEY_OFFSET),
+                        owner: r_pubkey(data, SLAB_LEAF_OWNER_OFFSET),
+                        owner_slot: r_u8(data, SLAB_LEAF_OWNER_SLOT_OFFSET),
+                        quantity: r_u64(data, SLAB_LEAF_QUANTITY_OFFSET),
+                        client_order_id: r_u64(data, SLAB_LEAF_CLIENT_ORDER_ID_OFFSET),
+                    }),
+                    tag => {
+                        return Err(RaydiumError::Parse(format!(
+                            "slab node {} has tag {} inside the tree",
+                            index, tag
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(Slab {
+            account_flags,
+            leaves,
+        })
+    }
+
+    /// whether the slab holds the bids of its market
+    pub fn is_bids(&self) -> bool {
+        self.account_flags & ACCOUNT_FLAG_BIDS != 0
+    }
+
+    /// the highest bid or the lowest ask, `None` when the book side is empty
+    pub fn best(&self) -> Option<&SlabLeaf> {
+        if self.is_bids() {
+            self.leaves.last()
+        } else {
+            self.leaves.first()
+        }
+    }
+
+    /// the orders summed by price in lots, best price first, at most `depth` levels
+    pub fn levels(&self, depth: usize) -> Vec<(u64, u64)> {
+        let ordered: Box<dyn Iterator<Item = &SlabLeaf>> = if self.is_bids() {
+            Box::new(self.leaves.iter().rev())
+        } else {
+            Box::new(self.leaves.iter())
+        };
+        let mut levels: Vec<(u64, u64)> = Vec::new();
+        for leaf in ordered {
+            match levels.last_mut() {
+                Some((price, quantity)) if *price == leaf.price() => {
+                    *quantity = quantity.saturating_add(leaf.quantity);
+                    continue;
+                }
+                _ => {}
+            }
+            if levels.len() == depth {
+                break;
+            }
+            levels.push((leaf.price(), leaf.quantity));
+        }
+        levels
+    }
+}
+
+/// a price level of an orderbook, decimals applied
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderbookLevel {
+    /// quote per base
+    pub price: f64,
+    /// base resting at the price
+    pub size: f64,
+}
+
+/// L2 orderbook of a serum / OpenBook market, each side best price first
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Orderbook {
+    pub bids: Vec<OrderbookLevel>,
+    pub asks: Vec<OrderbookLevel>,
+}
+
+/// parser of serum / OpenBook event queues
+pub struct EventQueue;
+
+/// a parsed event queue. `events` is the whole ring: the `count` events from `head` wait to be
+/// consumed, the slots before `head` keep the events consumed last
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventQueueData {
+    pub account_flags: u64,
+    pub head: u64,
+    pub count: u64,
+    /// sequence number of the next event pushed
+    pub seq_num: u64,
+    pub events: Vec<QueueEvent>,
+}
+
+/// an event of the queue, a fill or an order leaving the book. amounts are raw
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct QueueEvent {
+    pub flags: u8,
+    pub owner_slot: u8,
+    pub fee_tier: u8,
+    pub native_qty_released: u64,
+    pub native_qty_paid: u64,
+    pub native_fee_or_rebate: u64,
+    pub order_id: u128,
+    /// open orders account of the order
+    pub owner: Pubkey,
+    pub client_order_id: u64,
+}
+
+/// a fill of the event queue, price and size with decimals applied
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fill {
+    pub seq_num: u64,
+    pub is_bid: bool,
+    /// each match fills a maker and a taker, both appear
+    pub is_maker: bool,
+    /// quote per base, fees excluded
+    pub price: f64,
+    /// base traded
+    pub size: f64,
+    pub order_id: u128,
+    pub owner: Pubkey,
+}
+
+impl EventQueue {
+    /// parse an event queue, padding included
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::market::EventQueue;
+    /// assert!(EventQueue::parse(&[0u8; 100]).is_err());
+    /// ```
+    pub fn parse(data: &[u8]) -> Result<EventQueueData, RaydiumError> {
+        let min_size =
+            MARKET_HEAD_PADDING.len() + EVENT_QUEUE_EVENTS_OFFSET + MARKET_TAIL_PADDING.len();
+        if data.len() < min_size || !data.starts_with(MARKET_HEAD_PADDING) {
+            return Err(RaydiumError::Parse(format!(
+                "not a serum event queue account: {} bytes",
+                data.len()
+            )));
+        }
+        let state = &data[MARKET_HEAD_PADDING.len()..data.len() - MARKET_TAIL_PADDING.len()];
+        let account_flags = r_u64(state, ACCOUNT_FLAGS_OFFSET);
+        let expected = ACCOUNT_FLAG_INITIALIZED | ACCOUNT_FLAG_EVENT_QUEUE;
+        if account_flags & expected != expected {
+            return Err(RaydiumError::Parse(format!(
+                "account flags {:#x} are not those of an initialized event queue",
+                account_flags
+            )));
+        }
+        let events: Vec<QueueEvent> = state[EVENT_QUEUE_EVENTS_OFFSET..]
+            .chunks_exact(EVENT_SIZE)
+            .map(|event| QueueEvent {
+                flags: r_u8(event, EVENT_FLAGS_OFFSET),
+                owner_slot: r_u8(event, EVENT_OWNER_SLOT_OFFSET),
+                fee_tier: r_u8(event, EVENT_FEE_TIER_OFFSET),
+                native_qty_released: r_u64(event, EVENT_NATIVE_QTY_RELEASED_OFFSET),
+                native_qty_paid: r_u64(event, EVENT_NATIVE_QTY_PAID_OFFSET),
+                native_fee_or_rebate: r_u64(event, EVENT_NATIVE_FEE_OR_REBATE_OFFSET),
+                order_id: r_u128(event, EVENT_ORDER_ID_OFFSET),
+                owner: r_pubkey(event, EVENT_OWNER_OFFSET),
+                client_order_id: r_u64(event, EVENT_CLIENT_ORDER_ID_OFFSET),
+            })
+            .collect();
+        let head = r_u64(state, EVENT_QUEUE_HEAD_OFFSET);
+        let count = r_u64(state, EVENT_QUEUE_COUNT_OFFSET);
+        if events.is_empty() || head >= events.len() as u64 || count > events.len() as u64 {
+            return Err(RaydiumError::Parse(format!(
+                "event queue head {} and count {} do not fit {} events",
+                head,
+                count,
+                events.len()
+            )));
+        }
+        Ok(EventQueueData {
+            account_flags,
+            head,
+            count,
+            seq_num: r_u64(state, EVENT_QUEUE_SEQ_NUM_OFFSET),
+            events,
+        })
+    }
+}
+
+impl EventQueueData {
+    /// the events with their sequence numbers, newest first, walking back around the ring
+    /// from the last event pushed through the consumed ones. slots never written are skipped
+    pub fn recent_events(&self) -> impl Iterator<Item = (u64, &QueueEvent)> + '_ {
+        let capacity = self.events.len() as u64;
+        let newest = self.head + self.count + capacity - 1;
+        (0..capacity.min(self.seq_num))
+            .map(move |i| {
+                let event = &self.events[((newest - i) % capacity) as usize];
+                (self.seq_num - 1 - i, event)
+            })
+            .filter(|(_, event)| event.flags != 0)
+    }
+
+    /// the fills, newest first, see `recent_events`
+    pub fn recent_fills(
+        &self,
+        base_decimals: u8,
+        quote_decimals: u8,
+    ) -> impl Iterator<Item = Fill> + '_ {
+        self.recent_events()
+            .filter_map(move |(seq_num, event)| event.fill(seq_num, base_decimals, quote_decimals))
+    }
+}
+
+impl QueueEvent {
+    pub fn is_fill(&self) -> bool {
+        self.flags & EVENT_FLAG_FILL != 0
+    }
+
+    pub fn is_out(&self) -> bool {
+        self.flags & EVENT_FLAG_OUT != 0
+    }
+
+    pub fn is_bid(&self) -> bool {
+        self.flags & EVENT_FLAG_BID != 0
+    }
+
+    pub fn is_maker(&self) -> bool {
+        self.flags & EVENT_FLAG_MAKER != 0
+    }
+
+    /// the fill of a fill event, `None` for out events and empty fills.
+    /// a bid pays quote and is released base, an ask the other way around. takers pay the fee
+    /// on top of the quote, makers are credited a rebate, both are removed from the price
+    pub fn fill(&self, seq_num: u64, base_decimals: u8, quote_decimals: u8) -> Option<Fill> {
+        if !self.is_fill() {
+            return None;
+        }
+        let fee = self.native_fee_or_rebate;
+        let (base, quote) = match (self.is_bid(), self.is_maker()) {
+            (true, true) => (
+                self.native_qty_released,
+                self.native_qty_paid.saturating_add(fee),
+            ),
+            (true, false) => (
+                self.native_qty_released,
+                self.native_qty_paid.saturating_sub(fee),
+            ),
+            (false, true) => (
+                self.native_qty_paid,
+                self.native_qty_released.saturating_sub(fee),
+            ),
+            (false, false) => (
+                self.native_qty_paid,
+                self.native_qty_released.saturating_add(fee),
+            ),
+        };
+        if base == 0 {
+            return None;
+        }
+        let size = conver_balance(base as f64, base_decimals);
+        Some(Fill {
+            seq_num,
+            is_bid: self.is_bid(),
+            is_maker: self.is_maker(),
+            price: conver_balance(quote as f64, quote_decimals) / size,
+            size,
+            order_id: self.order_id,
+            owner: self.owner,
+        })
+    }
+}
+
+/// v4 pool price against the mid price of its OpenBook market
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Divergence {
+    /// quote per base from the effective reserves of the pool
+    pub amm_price: f64,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    /// `None` when either side of the book is empty
+    pub orderbook_mid: Option<f64>,
+    /// `(amm_price - orderbook_mid) / orderbook_mid` in basis points, positive when the pool
+    /// is more expensive than the book
+    pub divergence_bps: Option<f64>,
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// get the serum / OpenBook market of a v4 pool, its `market_id`
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let pool = raydium
+    ///     .get_liquidity_pool_v4("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2")
+    ///     .await?;
+    /// let market = raydium.get_market(&pool.market_id.to_string()).await?;
+    /// println!("bids {} asks {}", market.bids, market.asks);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_market(&self, market_id: &str) -> Result<MarketData, RaydiumError> {
+        let market_id = parse_address(market_id)?;
+        let account = self
+            .get_multiple_accounts_with_context(&[market_id])
+            .await?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(market_id))?;
+        OpenBookMarket::parse(&account.data)
+    }
+
+    /// compare the price of a v4 pool, from its effective reserves, against the mid price of
+    /// the best bid and ask of its OpenBook market
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let divergence = raydium
+    ///     .get_v4_price_divergence("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2")
+    ///     .await?;
+    /// match divergence.divergence_bps {
+    ///     Some(bps) => println!("amm {} is {:.1} bps off the book", divergence.amm_price, bps),
+    ///     None => println!("no orderbook price"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_v4_price_divergence(
+        &self,
+        pool_address: &str,
+    ) -> Result<Divergence, RaydiumError> {
+        let pool = self.get_liquidity_pool_v4(pool_address).await?;
+        self.v4_price_divergence_of(&pool).await
+    }
+
+    /// price divergence of already fetched pool data
+    pub(crate) async fn v4_price_divergence_of(
+        &self,
+        pool: &RaydiumLiquidityPoolData,
+    ) -> Result<Divergence, RaydiumError> {
+        let mut accounts = self
+            .get_multiple_accounts_with_context(&[
+                pool.base_vault,
+                pool.quote_vault,
+                pool.open_orders,
+                pool.market_id,
+            ])
+            .await?
+            .value;
+        let market = accounts
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(pool.market_id))?;
+        let market = OpenBookMarket::parse(&market.data)?;
+        let reserves = pool.reserves_from_accounts(&accounts)?;
+        let amm_price = pool.price_from_reserves(reserves.base, reserves.quote)?;
+        let books = self
+            .get_multiple_accounts_with_context(&[market.bids, market.asks])
+            .await?
+            .value;
+        let mut best = [None; 2];
+        for (i, address) in [market.bids, market.asks].iter().enumerate() {
+            let account = books
+                .get(i)
+                .and_then(Option::as_ref)
+                .ok_or(RaydiumError::AccountNotFound(*address))?;
+            best[i] = Slab::parse(&account.data)?.best().map(|leaf| {
+                market.price_from_lots(leaf.price(), pool.base_decimal, pool.quote_decimal)
+            });
+        }
+        let [best_bid, best_ask] = best;
+        let orderbook_mid = best_bid.zip(best_ask).map(|(bid, ask)| (bid + ask) / 2.0);
+        Ok(Divergence {
+            amm_price,
+            best_bid,
+            best_ask,
+            orderbook_mid,
+            divergence_bps: orderbook_mid.map(|mid| (amm_price - mid) / mid * 10_000.0),
+        })
+    }
+
+    /// get the L2 orderbook of a serum / OpenBook market, at most `depth` levels per side
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium, market_id: &str) -> Result<(), RaydiumError> {
+    /// let book = raydium.get_orderbook(market_id, 10).await?;
+    /// for level in &book.asks {
+    ///     println!("ask {} x {}", level.price, level.size);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_orderbook(
+        &self,
+        market_id: &str,
+        depth: usize,
+    ) -> Result<Orderbook, RaydiumError> {
+        let market = self.get_market(market_id).await?;
+        let books = self
+            .get_multiple_accounts_with_context(&[market.bids, market.asks])
+            .await?
+            .value;
+        let mints = self
+            .fetch_mint_infos(&[market.base_mint, market.quote_mint])
+            .await?;
+        let (base_decimals, quote_decimals) = (mints[0].decimals, mints[1].decimals);
+        let side = |i: usize, address: Pubkey| -> Result<Vec<OrderbookLevel>, RaydiumError> {
+            let account = books
+                .get(i)
+                .and_then(Option::as_ref)
+                .ok_or(RaydiumError::AccountNotFound(address))?;
+            let slab = Slab::parse(&account.data)?;
+            Ok(market.orderbook_levels(&slab, depth, base_decimals, quote_decimals))
+        };
+        Ok(Orderbook {
+            bids: side(0, market.bids)?,
+            asks: side(1, market.asks)?,
+        })
+    }
+
+    /// get a serum / OpenBook open orders account, such as the `open_orders` of a v4 pool
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let pool = raydium
+    ///     .get_liquidity_pool_v4("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2")
+    ///     .await?;
+    /// let open_orders = raydium.get_open_orders(&pool.open_orders.to_string()).await?;
+    /// println!("{} resting orders", open_orders.resting_orders().count());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_open_orders(&self, address: &str) -> Result<OpenOrdersData, RaydiumError> {
+        let address = parse_address(address)?;
+        let account = self
+            .get_multiple_accounts_with_context(&[address])
+            .await?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(address))?;
+        OpenOrders::parse(&account.data)
+    }
+
+    /// get the latest fills of a serum / OpenBook market from its event queue, newest first.
+    /// fills already consumed by the crank are included while the ring still holds them
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium, market_id: &str) -> Result<(), RaydiumError> {
+    /// let fills = raydium.get_recent_fills(market_id, 20).await?;
+    /// if let Some(last) = fills.iter().find(|fill| !fill.is_maker) {
+    ///     println!("last trade {} x {}", last.price, last.size);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_recent_fills(
+        &self,
+        market_id: &str,
+        limit: usize,
+    ) -> Result<Vec<Fill>, RaydiumError> {
+        let market = self.get_market(market_id).await?;
+        let queue = self
+            .get_multiple_accounts_with_context(&[market.event_queue])
+            .await?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(market.event_queue))?;
+        let queue = EventQueue::parse(&queue.data)?;
+        let mints = self
+            .fetch_mint_infos(&[market.base_mint, market.quote_mint])
+            .await?;
+        Ok(queue
+            .recent_fills(mints[0].decimals, mints[1].decimals)
+            .take(limit)
+            .collect())
+    }
+}
+
+/// MarketStateV2 account data with the given address, mints and vault signer nonce
+#[cfg(test)]
+pub(crate) fn market_data(
+    own_address: &Pubkey,
+    base_mint: &Pubkey,
+    quote_mint: &Pubkey,
+    vault_signer_nonce: u64,
+) -> Vec<u8> {
+    let mut data = vec![0u8; MARKET_STATE_V2_DATA_SIZE];
+    data[..5].copy_from_slice(MARKET_HEAD_PADDING);
+    data[MARKET_STATE_V2_DATA_SIZE - 7..].copy_from_slice(MARKET_TAIL_PADDING);
+    let state = &mut data[5..];
+    state[OWN_ADDRESS_OFFSET..OWN_ADDRESS_OFFSET + 32].copy_from_slice(own_address.as_ref());
+    state[VAULT_SIGNER_NONCE_OFFSET..VAULT_SIGNER_NONCE_OFFSET + 8]
+        .copy_from_slice(&vault_signer_nonce.to_le_bytes());
+    state[BASE_MINT_OFFSET..BASE_MINT_OFFSET + 32].copy_from_slice(base_mint.as_ref());
+    state[QUOTE_MINT_OFFSET..QUOTE_MINT_OFFSET + 32].copy_from_slice(quote_mint.as_ref());
+    data
+}
+
+/// open orders account data holding the given totals
+#[cfg(test)]
+pub(crate) fn open_orders_data(base_total: u64, quote_total: u64) -> Vec<u8> {
+    let mut data = vec![0u8; OPEN_ORDERS_DATA_SIZE];
+    data[..5].copy_from_slice(MARKET_HEAD_PADDING);
+    data[OPEN_ORDERS_DATA_SIZE - 7..].copy_from_slice(MARKET_TAIL_PADDING);
+    let state = &mut data[5..];
+    state[..8]
+        .copy_from_slice(&(ACCOUNT_FLAG_INITIALIZED | ACCOUNT_FLAG_OPEN_ORDERS).to_le_bytes());
+    state[OPEN_ORDERS_BASE_TOKEN_TOTAL_OFFSET..OPEN_ORDERS_BASE_TOKEN_TOTAL_OFFSET + 8]
+        .copy_from_slice(&base_total.to_le_bytes());
+    state[OPEN_ORDERS_QUOTE_TOKEN_TOTAL_OFFSET..OPEN_ORDERS_QUOTE_TOKEN_TOTAL_OFFSET + 8]
+        .copy_from_slice(&quote_total.to_le_bytes());
+    state[OPEN_ORDERS_FREE_SLOT_BITS_OFFSET..OPEN_ORDERS_FREE_SLOT_BITS_OFFSET + 16]
+        .copy_from_slice(&u128::MAX.to_le_bytes());
+    data
+}
+
+/// bids or asks slab holding orders at the given prices and quantities in lots,
+/// as a right-leaning critbit tree
+#[cfg(test)]
+pub(crate) fn slab_data(bids: bool, orders: &[(u64, u64)]) -> Vec<u8> {
+    let n = orders.len();
+    let node_count = (2 * n).max(1);
+    let mut state = vec![0u8; SLAB_NODES_OFFSET + node_count * SLAB_NODE_SIZE];
+    let side = if bids {
+        ACCOUNT_FLAG_BIDS
+    } else {
+        ACCOUNT_FLAG_ASKS
+    };
+    state[..8].copy_from_slice(&(ACCOUNT_FLAG_INITIALIZED | side).to_le_bytes());
+    let root = if n > 1 { n as u32 } else { 0 };
+    state[SLAB_ROOT_NODE_OFFSET..SLAB_ROOT_NODE_OFFSET + 4].copy_from_slice(&root.to_le_bytes());
+    state[SLAB_LEAF_COUNT_OFFSET..SLAB_LEAF_COUNT_OFFSET + 8]
+        .copy_from_slice(&(n as u64).to_le_bytes());
+    let mut sorted = orders.to_vec();
+    sorted.sort();
+    for (i, (price, quantity)) in sorted.iter().enumerate() {
+        let node = &mut state[SLAB_NODES_OFFSET + i * SLAB_NODE_SIZE..][..SLAB_NODE_SIZE];
+        node[..4].copy_from_slice(&SLAB_NODE_LEAF.to_le_bytes());
+        let key = ((*price as u128) << 64) | i as u128;
+        node[SLAB_LEAF_KEY_OFFSET..SLAB_LEAF_KEY_OFFSET + 16].copy_from_slice(&key.to_le_bytes());
+        node[SLAB_LEAF_QUANTITY_OFFSET..SLAB_LEAF_QUANTITY_OFFSET + 8]
+            .copy_from_slice(&quantity.to_le_bytes());
+    }
+    for j in 0..n.saturating_sub(1) {
+        let node = &mut state[SLAB_NODES_OFFSET + (n + j) * SLAB_NODE_SIZE..][..SLAB_NODE_SIZE];
+        node[..4].copy_from_slice(&SLAB_NODE_INNER.to_le_bytes());
+        let upper = if j == n - 2 { n - 1 } else { n + j + 1 };
+        node[SLAB_INNER_CHILDREN_OFFSET..SLAB_INNER_CHILDREN_OFFSET + 4]
+            .copy_from_slice(&(j as u32).to_le_bytes());
+        node[SLAB_INNER_CHILDREN_OFFSET + 4..SLAB_INNER_CHILDREN_OFFSET + 8]
+            .copy_from_slice(&(upper as u32).to_le_bytes());
+    }
+    [MARKET_HEAD_PADDING, &state, MARKET_TAIL_PADDING].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_market() {
+        let (own_address, base_mint, quote_mint) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut data = market_data(&own_address, &base_mint, &quote_mint, 3);
+        // absolute offsets of the official layout, the 5 bytes head padding included
+        data[5..13].copy_from_slice(&0x5bu64.to_le_bytes());
+        let (request_queue, bids) = (Pubkey::new_unique(), Pubkey::new_unique());
+        data[221..253].copy_from_slice(request_queue.as_ref());
+        data[285..317].copy_from_slice(bids.as_ref());
+        data[349..357].copy_from_slice(&100_000_000u64.to_le_bytes());
+        data[357..365].copy_from_slice(&100u64.to_le_bytes());
+        data[365..373].copy_from_slice(&22u64.to_le_bytes());
+        let market = OpenBookMarket::parse(&data).unwrap();
+        assert_eq!(market.account_flags, 0x5b);
+        assert_eq!(
+            (market.own_address, market.base_mint, market.quote_mint),
+            (own_address, base_mint, quote_mint)
+        );
+        assert_eq!((market.request_queue, market.bids), (request_queue, bids));
+        assert_eq!(market.vault_signer_nonce, 3);
+        assert_eq!(
+            (
+                market.base_lot_size,
+                market.quote_lot_size,
+                market.fee_rate_bps
+            ),
+            (100_000_000, 100, 22)
+        );
+        assert_eq!(market.authorities, None);
+        // the permissioned layout carries the authorities after the V2 state
+        let mut v3 = data[..MARKET_STATE_V2_DATA_SIZE - 7].to_vec();
+        let prune = Pubkey::new_unique();
+        v3.resize(MARKET_STATE_V3_DATA_SIZE - 7, 0);
+        v3[413..445].copy_from_slice(prune.as_ref());
+        v3.extend_from_slice(MARKET_TAIL_PADDING);
+        let market = OpenBookMarket::parse(&v3).unwrap();
+        assert_eq!(market.authorities.map(|a| a.prune), Some(prune));
+        assert!(OpenBookMarket::parse(&data[..381]).is_err());
+        data[0] = b'x';
+        assert!(OpenBookMarket::parse(&data).is_err());
+    }
+
+    #[test]
+    fn test_parse_open_orders() {
+        let mut data = open_orders_data(40, 2_000);
+        // absolute offsets of the official layout, the 5 bytes head padding included
+        let market = Pubkey::new_unique();
+        data[13..45].copy_from_slice(market.as_ref());
+        data[77..85].copy_from_slice(&10u64.to_le_bytes());
+        // slots 0 and 2 in use, slot 2 a bid
+        data[109..125].copy_from_slice(&(!0b101u128).to_le_bytes());
+        data[125..141].copy_from_slice(&0b100u128.to_le_bytes());
+        data[141 + 32..141 + 48].copy_from_slice(&77u128.to_le_bytes());
+        data[2189 + 16..2189 + 24].copy_from_slice(&9u64.to_le_bytes());
+        let open_orders = OpenOrders::parse(&data).unwrap();
+        assert_eq!(open_orders.market, market);
+        assert_eq!(
+            (open_orders.base_token_free, open_orders.totals()),
+            (
+                10,
+                OpenOrdersTotals {
+                    base_total: 40,
+                    quote_total: 2_000
+                }
+            )
+        );
+        let orders: Vec<_> = open_orders.resting_orders().collect();
+        assert_eq!(orders.len(), 2);
+        assert_eq!(
+            orders[1],
+            RestingOrder {
+                slot: 2,
+                order_id: 77,
+                client_order_id: 9,
+                is_bid: true
+            }
+        );
+        assert!(OpenOrders::parse(&data[..3000]).is_err());
+        data[5] = 1;
+        assert!(OpenOrders::parse(&data).is_err());
+    }
+
+    #[test]
+    fn test_parse_slab() {
+        let bids = Slab::parse(&slab_data(
+            true,
+            &[(1_499_000, 5), (1_498_000, 7), (1_497_000, 1)],
+        ))
+        .unwrap();
+        assert!(bids.is_bids());
+        let prices: Vec<_> = bids.leaves.iter().map(SlabLeaf::price).collect();
+        assert_eq!(prices, [1_497_000, 1_498_000, 1_499_000]);
+        assert_eq!(
+            bids.best().map(|l| (l.price(), l.quantity)),
+            Some((1_499_000, 5))
+        );
+        let asks = Slab::parse(&slab_data(false, &[(1_505_000, 2), (1_501_000, 3)])).unwrap();
+        assert_eq!(
+            asks.best().map(|l| (l.price(), l.quantity)),
+            Some((1_501_000, 3))
+        );
+        assert_eq!(Slab::parse(&slab_data(false, &[])).unwrap().best(), None);
+        // a child pointing outside of the slab
+        let mut data = slab_data(true, &[(1, 1), (2, 1)]);
+        let inner = 5 + SLAB_NODES_OFFSET + 2 * SLAB_NODE_SIZE + SLAB_INNER_CHILDREN_OFFSET;
+        data[inner..inner + 4].copy_from_slice(&99u32.to_le_bytes());
+        assert!(Slab::parse(&data).is_err());
+    }
+
+    #[test]
+    fn test_market_venue() {
+        for (program_id, venue) in [
+            (OPENBOOK_PROGRAM_ID, MarketVenue::OpenBookV1),
+            (DEVNET_OPENBOOK_PROGRAM_ID, MarketVenue::OpenBookV1),
+            (SERUM_V3_PROGRAM_ID, MarketVenue::SerumV3),
+            // serum v2, retired before the v4 AMM
+            (
+                Pubkey::from_str_const("EUqojwWA2rd19FZrzeBncJsm38Jm1hEhE3zsmX3bRc2o"),
+                MarketVenue::Unknown(Pubkey::from_str_const(
+                    "EUqojwWA2rd19FZrzeBncJsm38Jm1hEhE3zsmX3bRc2o",
+                )),
+            ),
+            (Pubkey::default(), MarketVenue::Unknown(Pubkey::default())),
+        ] {
+            assert_eq!(MarketVenue::from_program_id(&program_id), venue);
+            assert_eq!(
+                venue.is_supported(),
+                !matches!(venue, MarketVenue::Unknown(_))
+            );
+        }
+    }
+
+    #[test]
+    fn test_orderbook_levels() {
+        let mut data = market_data(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            0,
+        );
+        // 0.1 SOL base lots and 0.00001 USDC quote lots
+        data[349..357].copy_from_slice(&100_000_000u64.to_le_bytes());
+        data[357..365].copy_from_slice(&10u64.to_le_bytes());
+        let market = OpenBookMarket::parse(&data).unwrap();
+        let bids = Slab::parse(&slab_data(
+            true,
+            &[
+                (1_499_000, 5),
+                (1_498_000, 7),
+                (1_499_000, 3),
+                (1_497_000, 1),
+            ],
+        ))
+        .unwrap();
+        assert_eq!(bids.levels(2), [(1_499_000, 8), (1_498_000, 7)]);
+        let asks = Slab::parse(&slab_data(false, &[(1_505_000, 2), (1_501_000, 3)])).unwrap();
+        assert_eq!(asks.levels(10), [(1_501_000, 3), (1_505_000, 2)]);
+        let levels = market.orderbook_levels(&bids, 1, 9, 6);
+        assert_eq!(levels.len(), 1);
+        assert!((levels[0].price - 149.9).abs() < 1e-9);
+        assert!((levels[0].size - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_event_queue_recent_fills() {
+        // a ring of 4 events: the newest wrapped around to slot 0, slot 2 already consumed
+        let mut state = vec![0u8; EVENT_QUEUE_EVENTS_OFFSET + 4 * EVENT_SIZE];
+        let mut put = |offset: usize, bytes: &[u8]| {
+            state[offset..offset + bytes.len()].copy_from_slice(bytes)
+        };
+        put(
+            ACCOUNT_FLAGS_OFFSET,
+            &(ACCOUNT_FLAG_INITIALIZED | ACCOUNT_FLAG_EVENT_QUEUE).to_le_bytes(),
+        );
+        put(EVENT_QUEUE_HEAD_OFFSET, &3u64.to_le_bytes());
+        put(EVENT_QUEUE_COUNT_OFFSET, &2u64.to_le_bytes());
+        put(EVENT_QUEUE_SEQ_NUM_OFFSET, &10u64.to_le_bytes());
+        let event = |slot: usize| EVENT_QUEUE_EVENTS_OFFSET + slot * EVENT_SIZE;
+        // taker buys 2 SOL for 300 USDC plus a 0.12 USDC fee
+        put(event(0), &[EVENT_FLAG_FILL | EVENT_FLAG_BID]);
+        put(
+            event(0) + EVENT_NATIVE_QTY_RELEASED_OFFSET,
+            &2_000_000_000u64.to_le_bytes(),
+        );
+        put(
+            event(0) + EVENT_NATIVE_QTY_PAID_OFFSET,
+            &300_120_000u64.to_le_bytes(),
+        );
+        put(
+            event(0) + EVENT_NATIVE_FEE_OR_REBATE_OFFSET,
+            &120_000u64.to_le_bytes(),
+        );
+        put(event(3), &[EVENT_FLAG_OUT | EVENT_FLAG_BID]);
+        // maker sells 1 SOL at 149 USDC with a 0.03 USDC rebate
+        put(event(2), &[EVENT_FLAG_FILL | EVENT_FLAG_MAKER]);
+        put(
+            event(2) + EVENT_NATIVE_QTY_RELEASED_OFFSET,
+            &149_030_000u64.to_le_bytes(),
+        );
+        put(
+            event(2) + EVENT_NATIVE_QTY_PAID_OFFSET,
+            &1_000_000_000u64.to_le_bytes(),
+        );
+        put(
+            event(2) + EVENT_NATIVE_FEE_OR_REBATE_OFFSET,
+            &30_000u64.to_le_bytes(),
+        );
+        let queue = EventQueue::parse(&[MARKET_HEAD_PADDING, &state, MARKET_TAIL_PADDING].concat())
+            .unwrap();
+        assert_eq!(
+            queue
+                .recent_events()
+                .map(|(seq, event)| (seq, event.is_fill()))
+                .collect::<Vec<_>>(),
+            [(9, true), (8, false), (7, true)]
+        );
+        let fills: Vec<_> = queue.recent_fills(9, 6).collect();
+        assert_eq!(
+            fills
+                .iter()
+                .map(|f| (f.seq_num, f.is_bid, f.is_maker, f.price, f.size))
+                .collect::<Vec<_>>(),
+            [(9, true, false, 150.0, 2.0), (7, false, true, 149.0, 1.0)]
+        );
+        assert!(Slab::parse(&[MARKET_HEAD_PADDING, &state, MARKET_TAIL_PADDING].concat()).is_err());
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_v4_price_divergence() {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+
+        use crate::{
+            programs::TOKEN_PROGRAM_ID,
+            rpc::TOKEN_ACCOUNT_AMOUNT_OFFSET,
+            test_utils::{mock_raydium, multiple_accounts_response, ui_account},
+        };
+
+        let vault = |amount: u64| {
+            let mut data = vec![0u8; 165];
+            data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]
+                .copy_from_slice(&amount.to_le_bytes());
+            Some(ui_account(&TOKEN_PROGRAM_ID, data))
+        };
+        let account = |data: Vec<u8>| Some(ui_account(&Pubkey::new_unique(), data));
+        let pool = RaydiumLiquidityPoolData {
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            open_orders: Pubkey::new_unique(),
+            market_id: Pubkey::new_unique(),
+            base_decimal: 9,
+            quote_decimal: 6,
+            ..Default::default()
+        };
+        // 0.1 SOL base lots and 0.00001 USDC quote lots, 1_500_000 lots is 150 USDC
+        let mut market = market_data(
+            &pool.market_id,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            0,
+        );
+        market[349..357].copy_from_slice(&100_000_000u64.to_le_bytes());
+        market[357..365].copy_from_slice(&10u64.to_le_bytes());
+        // 100 SOL and 15_150 USDC in the vaults, 151.5
+        let pool_accounts = vec![
+            vault(100_000_000_000),
+            vault(15_150_000_000),
+            account(open_orders_data(0, 0)),
+            account(market),
+        ];
+        let bids = account(slab_data(true, &[(1_499_000, 5), (1_498_000, 7)]));
+        let mut mocks = MocksMap::default();
+        for asks in [&[(1_501_000, 3), (1_505_000, 2)][..], &[]] {
+            mocks.insert(
+                RpcRequest::GetMultipleAccounts,
+                multiple_accounts_response(pool_accounts.clone()),
+            );
+            mocks.insert(
+                RpcRequest::GetMultipleAccounts,
+                multiple_accounts_response(vec![bids.clone(), account(slab_data(false, asks))]),
+            );
+        }
+        let raydium = mock_raydium(mocks);
+        let divergence = raydium.v4_price_divergence_of(&pool).await.unwrap();
+        assert!((divergence.amm_price - 151.5).abs() < 1e-9);
+        assert!((divergence.best_bid.unwrap() - 149.9).abs() < 1e-9);
+        assert!((divergence.orderbook_mid.unwrap() - 150.0).abs() < 1e-9);
+        assert!((divergence.divergence_bps.unwrap() - 100.0).abs() < 1e-6);
+        // an empty side leaves the pool without an orderbook price
+        let divergence = raydium.v4_price_divergence_of(&pool).await.unwrap();
+        assert_eq!(
+            (
+                divergence.best_ask,
+                divergence.orderbook_mid,
+                divergence.divergence_bps
+            ),
+            (None, None, None)
+        );
+    }
+}
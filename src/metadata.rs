@@ -0,0 +1,370 @@
+//! token names, symbols and uris from the Metaplex metadata account of a mint,
+//! or from the metadata extension of a token-2022 mint
+
+#[cfg(feature = "rpc")]
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use crate::{
+    Raydium, error::RaydiumError, mints::MINT_DATA_SIZE, monitor::MAX_MULTIPLE_ACCOUNTS,
+    programs::TOKEN_2022_PROGRAM_ID, typs::PoolData,
+};
+use crate::{
+    mints::{EXTENSION_TOKEN_METADATA, mint_extensions},
+    programs::METAPLEX_METADATA_PROGRAM_ID,
+    reader::r_u32,
+};
+
+/// where the metadata of a mint was read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataSource {
+    /// the Metaplex metadata account of the mint
+    Metaplex,
+    /// the metadata extension of a token-2022 mint
+    Token2022,
+}
+
+/// name, symbol and uri of a token, trimmed of their padding.
+/// the uri usually points at a JSON document holding the logo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub source: MetadataSource,
+}
+
+/// metadata of a mint, or the typed absence of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetadataLookup {
+    Found(TokenMetadata),
+    /// the mint has neither a Metaplex metadata account nor a token-2022 metadata extension
+    NoMetadata(Pubkey),
+}
+
+impl MetadataLookup {
+    pub fn metadata(&self) -> Option<&TokenMetadata> {
+        match self {
+            MetadataLookup::Found(metadata) => Some(metadata),
+            MetadataLookup::NoMetadata(_) => None,
+        }
+    }
+
+    /// the symbol, or the mint address when the mint has no metadata
+    pub fn symbol_or_address(&self) -> String {
+        match self {
+            MetadataLookup::Found(metadata) => metadata.symbol.clone(),
+            MetadataLookup::NoMetadata(mint) => mint.to_string(),
+        }
+    }
+}
+
+/// address of the Metaplex metadata account of a mint
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            METAPLEX_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ],
+        &METAPLEX_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+impl TokenMetadata {
+    /// parse a Metaplex metadata account: a key byte, the update authority and the mint,
+    /// then the name, symbol and uri as length-prefixed strings padded with nulls
+    pub fn parse_metaplex(data: &[u8]) -> Result<TokenMetadata, String> {
+        let mint = Pubkey::new_from_array(
+            data.get(33..65)
+                .ok_or("metadata account is too short")?
+                .try_into()
+                .unwrap(),
+        );
+        let mut offset = 65;
+        let name = read_string(data, &mut offset)?;
+        let symbol = read_string(data, &mut offset)?;
+        let uri = read_string(data, &mut offset)?;
+        Ok(TokenMetadata {
+            mint,
+            name,
+            symbol,
+            uri,
+            source: MetadataSource::Metaplex,
+        })
+    }
+
+    /// parse the metadata extension of a token-2022 mint, `None` when the mint has none
+    pub fn parse_token_2022(mint: &Pubkey, data: &[u8]) -> Result<Option<TokenMetadata>, String> {
+        let Some((_, value)) = mint_extensions(data)?
+            .into_iter()
+            .find(|(extension, _)| *extension == EXTENSION_TOKEN_METADATA)
+        else {
+            return Ok(None);
+        };
+        // the update authority and the mint precede the strings
+        let mut offset = 64;
+        let name = read_string(value, &mut offset)?;
+        let symbol = read_string(value, &mut offset)?;
+        let uri = read_string(value, &mut offset)?;
+        Ok(Some(TokenMetadata {
+            mint: *mint,
+            name,
+            symbol,
+            uri,
+            source: MetadataSource::Token2022,
+        }))
+    }
+}
+
+/// read a u32 length-prefixed string, dropping the null padding of fixed-size fields
+fn read_string(data: &[u8], offset: &mut usize) -> Result<String, String> {
+    let length = r_u32(data, *offset) as usize;
+    let bytes = data
+        .get(*offset + 4..*offset + 4 + length)
+        .ok_or_else(|| format!("string at offset {} overruns the account", offset))?;
+    *offset += 4 + length;
+    Ok(String::from_utf8_lossy(bytes)
+        .trim_end_matches('\0')
+        .trim()
+        .to_string())
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// get the name, symbol and uri of a mint from its Metaplex metadata account,
+    /// falling back to the metadata extension of token-2022 mints
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::USDC_MINT};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// match raydium.fetch_token_metadata(&USDC_MINT).await?.metadata() {
+    ///     Some(metadata) => println!("{} ({})", metadata.name, metadata.symbol),
+    ///     None => println!("no metadata"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_token_metadata(
+        &self,
+        mint: &Pubkey,
+    ) -> Result<MetadataLookup, RaydiumError> {
+        Ok(self.fetch_token_metadatas(&[*mint]).await?.remove(0))
+    }
+
+    /// get the metadata of several mints, in order, fetching their metadata accounts
+    /// and mints in bulk
+    pub async fn fetch_token_metadatas(
+        &self,
+        mints: &[Pubkey],
+    ) -> Result<Vec<MetadataLookup>, RaydiumError> {
+        let mut lookups = Vec::with_capacity(mints.len());
+        for chunk in mints.chunks(MAX_MULTIPLE_ACCOUNTS / 2) {
+            let addresses: Vec<Pubkey> = chunk
+                .iter()
+                .flat_map(|mint| [metadata_address(mint), *mint])
+                .collect();
+            let accounts = self.get_multiple_accounts_with_context(&addresses).await?;
+            let mut accounts = accounts.value.into_iter();
+            for mint in chunk {
+                let metadata = accounts.next().flatten();
+                let mint_account = accounts.next().flatten();
+                lookups.push(parse_metadata_accounts(mint, metadata, mint_account)?);
+            }
+        }
+        Ok(lookups)
+    }
+}
+
+/// metadata from the Metaplex account when it exists, else from the token-2022 mint
+#[cfg(feature = "rpc")]
+fn parse_metadata_accounts(
+    mint: &Pubkey,
+    metadata: Option<Account>,
+    mint_account: Option<Account>,
+) -> Result<MetadataLookup, RaydiumError> {
+    if let Some(account) = metadata.filter(|a| a.owner == METAPLEX_METADATA_PROGRAM_ID) {
+        let metadata = TokenMetadata::parse_metaplex(&account.data).map_err(RaydiumError::Parse)?;
+        return Ok(MetadataLookup::Found(metadata));
+    }
+    if let Some(account) =
+        mint_account.filter(|a| a.owner == TOKEN_2022_PROGRAM_ID && a.data.len() > MINT_DATA_SIZE)
+    {
+        let metadata =
+            TokenMetadata::parse_token_2022(mint, &account.data).map_err(RaydiumError::Parse)?;
+        if let Some(metadata) = metadata {
+            return Ok(MetadataLookup::Found(metadata));
+        }
+    }
+    Ok(MetadataLookup::NoMetadata(*mint))
+}
+
+#[cfg(feature = "rpc")]
+impl PoolData {
+    /// symbols of the base and quote mints, a mint without metadata is shown by its address
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let pool = raydium.get_pool("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2").await?;
+    /// let (base, quote) = pool.resolve_symbols(&raydium).await?;
+    /// println!("{}/{}", base, quote);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn resolve_symbols(
+        &self,
+        raydium: &Raydium,
+    ) -> Result<(String, String), RaydiumError> {
+        let lookups = raydium
+            .fetch_token_metadatas(&[self.base_mint(), self.quote_mint()])
+            .await?;
+        Ok((
+            lookups[0].symbol_or_address(),
+            lookups[1].symbol_or_address(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mints::{MINT_ACCOUNT_TYPE_OFFSET, MINT_DATA_SIZE};
+
+    /// a length-prefixed string padded with nulls to `size`
+    fn padded(value: &str, size: usize) -> Vec<u8> {
+        let mut bytes = (size as u32).to_le_bytes().to_vec();
+        bytes.extend_from_slice(value.as_bytes());
+        bytes.resize(4 + size, 0);
+        bytes
+    }
+
+    fn metaplex_data(mint: &Pubkey, name: &str, symbol: &str) -> Vec<u8> {
+        let mut data = vec![4u8];
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(mint.as_ref());
+        data.extend(padded(name, 32));
+        data.extend(padded(symbol, 10));
+        data.extend(padded("https://example.com/token.json", 200));
+        // the remaining fields are not read
+        data.extend_from_slice(&[0u8; 100]);
+        data
+    }
+
+    /// a token-2022 mint with a transfer fee config entry before the metadata extension
+    fn token_2022_mint_data(mint: &Pubkey, symbol: &str) -> Vec<u8> {
+        let mut data = vec![0u8; MINT_ACCOUNT_TYPE_OFFSET];
+        data.push(1);
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&108u16.to_le_bytes());
+        data.extend_from_slice(&[0u8; 108]);
+        let mut value = vec![0u8; 32];
+        value.extend_from_slice(mint.as_ref());
+        for field in ["Token", symbol, "ipfs://token"] {
+            value.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            value.extend_from_slice(field.as_bytes());
+        }
+        value.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&EXTENSION_TOKEN_METADATA.to_le_bytes());
+        data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        data.extend(value);
+        data
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let mint = Pubkey::new_unique();
+        let metadata = TokenMetadata::parse_metaplex(&metaplex_data(&mint, "USD Coin", "USDC"));
+        let metadata = metadata.unwrap();
+        assert_eq!(metadata.mint, mint);
+        assert_eq!(
+            (metadata.name.as_str(), metadata.symbol.as_str()),
+            ("USD Coin", "USDC")
+        );
+        assert_eq!(metadata.uri, "https://example.com/token.json");
+        assert!(TokenMetadata::parse_metaplex(&[4u8; 80]).is_err());
+        let metadata = TokenMetadata::parse_token_2022(&mint, &token_2022_mint_data(&mint, "T22"));
+        let metadata = metadata.unwrap().unwrap();
+        assert_eq!(
+            (metadata.symbol.as_str(), metadata.source),
+            ("T22", MetadataSource::Token2022)
+        );
+        assert_eq!(
+            TokenMetadata::parse_token_2022(&mint, &[0u8; MINT_DATA_SIZE]),
+            Ok(None)
+        );
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_fetch_token_metadatas() -> Result<(), Box<dyn std::error::Error>> {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+
+        use crate::{
+            liquidity::cpmm::RaydiumLiquidityPoolCPMMData,
+            programs::TOKEN_PROGRAM_ID,
+            test_utils::{mock_raydium, multiple_accounts_response, ui_account},
+        };
+
+        let (usdc, token_2022, bare) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![
+                Some(ui_account(
+                    &METAPLEX_METADATA_PROGRAM_ID,
+                    metaplex_data(&usdc, "USD Coin", "USDC"),
+                )),
+                Some(ui_account(&TOKEN_PROGRAM_ID, vec![0u8; MINT_DATA_SIZE])),
+                None,
+                Some(ui_account(
+                    &TOKEN_2022_PROGRAM_ID,
+                    token_2022_mint_data(&token_2022, "T22"),
+                )),
+                None,
+                Some(ui_account(&TOKEN_PROGRAM_ID, vec![0u8; MINT_DATA_SIZE])),
+            ]),
+        );
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![
+                None,
+                Some(ui_account(
+                    &TOKEN_2022_PROGRAM_ID,
+                    token_2022_mint_data(&token_2022, "T22"),
+                )),
+                Some(ui_account(
+                    &METAPLEX_METADATA_PROGRAM_ID,
+                    metaplex_data(&usdc, "USD Coin", "USDC"),
+                )),
+                None,
+            ]),
+        );
+        let raydium = mock_raydium(mocks);
+        let lookups = raydium
+            .fetch_token_metadatas(&[usdc, token_2022, bare])
+            .await?;
+        assert_eq!(
+            lookups[0].metadata().unwrap().source,
+            MetadataSource::Metaplex
+        );
+        assert_eq!(lookups[1].symbol_or_address(), "T22");
+        assert_eq!(lookups[2], MetadataLookup::NoMetadata(bare));
+        let pool = PoolData::Cpmm(RaydiumLiquidityPoolCPMMData {
+            token_0_mint: token_2022,
+            token_1_mint: usdc,
+            ..Default::default()
+        });
+        let symbols = pool.resolve_symbols(&raydium).await?;
+        assert_eq!(symbols, ("T22".to_string(), "USDC".to_string()));
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+use std::{
+    fmt::Debug,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::{Raydium, error::RaydiumError, typs::PoolKind};
+
+/// rpc method behind a metrics observation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RpcCall {
+    GetAccountInfo,
+    GetMultipleAccounts,
+    GetProgramAccounts,
+    GetTransaction,
+    GetSignaturesForAddress,
+    GetSlot,
+    /// the vault balances of a pool, fetched by the pool price helpers
+    GetTokenBalances,
+    GetTokenLargestAccounts,
+    GetTokenAccountsByOwner,
+    GetLatestBlockhash,
+    SendTransaction,
+    GetSignatureStatuses,
+    IsBlockhashValid,
+    SimulateTransaction,
+}
+
+/// callbacks observing the rpc requests, parses and cache lookups of a `Raydium` handle,
+/// registered with `RaydiumConfig::with_metrics`. every method defaults to a no-op
+pub trait MetricsHook: Debug + Send + Sync {
+    /// an rpc request to one endpoint completed, failovers report one call per endpoint.
+    /// a missing account is reported as an error
+    fn on_rpc_call(&self, call: RpcCall, duration: Duration, result: Result<(), &RaydiumError>) {
+        let _ = (call, duration, result);
+    }
+
+    /// pool account data was parsed, `kind` is `None` when the data size matches no pool type
+    fn on_parse(
+        &self,
+        kind: Option<PoolKind>,
+        duration: Duration,
+        result: Result<(), &RaydiumError>,
+    ) {
+        let _ = (kind, duration, result);
+    }
+
+    /// a pool account, price or mint was looked up in the cache
+    fn on_cache(&self, hit: bool) {
+        let _ = hit;
+    }
+}
+
+/// totals observed by `CountingMetrics`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    pub rpc_calls: u64,
+    pub rpc_errors: u64,
+    /// time spent in rpc requests
+    pub rpc_time: Duration,
+    pub parses: u64,
+    pub parse_errors: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+}
+
+/// metrics hook keeping atomic counters
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{Raydium, config::RaydiumConfig, metrics::CountingMetrics};
+/// # use solana_network_client::{Mode, SolanaClient};
+/// # use std::sync::Arc;
+/// # async fn run() {
+/// let metrics = Arc::new(CountingMetrics::default());
+/// let config = RaydiumConfig::new().with_metrics(metrics.clone());
+/// let raydium = Raydium::new_with_config(Arc::new(SolanaClient::new(Mode::MAIN).unwrap()), config);
+/// let _ = raydium.get_pool("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2").await;
+/// println!("{:?}", metrics.snapshot());
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct CountingMetrics {
+    rpc_calls: AtomicU64,
+    rpc_errors: AtomicU64,
+    rpc_time_micros: AtomicU64,
+    parses: AtomicU64,
+    parse_errors: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+}
+
+impl CountingMetrics {
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            rpc_calls: self.rpc_calls.load(Ordering::Relaxed),
+            rpc_errors: self.rpc_errors.load(Ordering::Relaxed),
+            rpc_time: Duration::from_micros(self.rpc_time_micros.load(Ordering::Relaxed)),
+            parses: self.parses.load(Ordering::Relaxed),
+            parse_errors: self.parse_errors.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.cache_misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl MetricsHook for CountingMetrics {
+    fn on_rpc_call(&self, _: RpcCall, duration: Duration, result: Result<(), &RaydiumError>) {
+        self.rpc_calls.fetch_add(1, Ordering::Relaxed);
+        self.rpc_time_micros
+            .fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+        if result.is_err() {
+            self.rpc_errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn on_parse(&self, _: Option<PoolKind>, _: Duration, result: Result<(), &RaydiumError>) {
+        self.parses.fetch_add(1, Ordering::Relaxed);
+        if result.is_err() {
+            self.parse_errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn on_cache(&self, hit: bool) {
+        let counter = if hit {
+            &self.cache_hits
+        } else {
+            &self.cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Raydium {
+    /// run a pool parser, reporting its duration and outcome to the metrics hook
+    pub(crate) fn parse_with_metrics<T>(
+        &self,
+        kind: Option<PoolKind>,
+        parse: impl FnOnce() -> Result<T, RaydiumError>,
+    ) -> Result<T, RaydiumError> {
+        let Some(metrics) = &self.config.metrics else {
+            return parse();
+        };
+        let started = Instant::now();
+        let result = parse();
+        metrics.on_parse(kind, started.elapsed(), result.as_ref().map(|_| ()));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+    use crate::{
+        config::RaydiumConfig,
+        launchpad::LAUNCHPAD_POOL_STATE_DATA_SIZE,
+        programs::RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+        test_utils::{account_info_response, mock_raydium_with_config},
+    };
+
+    #[tokio::test]
+    async fn test_counting_metrics() -> Result<(), Box<dyn std::error::Error>> {
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            account_info_response(
+                &RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+                vec![0u8; LAUNCHPAD_POOL_STATE_DATA_SIZE],
+            ),
+        );
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            account_info_response(&RAYDIUM_LAUNCHPAD_PROGRAM_ID, vec![0u8; 10]),
+        );
+        let metrics = Arc::new(CountingMetrics::default());
+        let config = RaydiumConfig::new()
+            .with_cache_ttl(Duration::from_secs(60))
+            .with_metrics(metrics.clone());
+        let raydium = mock_raydium_with_config(mocks, config);
+        let address = Pubkey::new_unique();
+        // one fetch, then a cache hit, both parsed
+        raydium.get_pool(&address.to_string()).await?;
+        raydium
+            .get_liquidity_pool_launchpad(&address.to_string())
+            .await?;
+        raydium
+            .get_multiple_accounts_with_context(&[address])
+            .await?;
+        // data of no pool type, then a missing account
+        let unknown = raydium.get_pool(&Pubkey::new_unique().to_string()).await;
+        assert!(matches!(unknown, Err(RaydiumError::Parse(_))));
+        let missing = raydium.get_pool(&Pubkey::new_unique().to_string()).await;
+        assert!(matches!(missing, Err(RaydiumError::AccountNotFound(_))));
+        let snapshot = metrics.snapshot();
+        assert_eq!((snapshot.rpc_calls, snapshot.rpc_errors), (4, 1));
+        assert_eq!((snapshot.parses, snapshot.parse_errors), (3, 1));
+        assert_eq!((snapshot.cache_hits, snapshot.cache_misses), (1, 3));
+        Ok(())
+    }
+}
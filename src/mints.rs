@@ -0,0 +1,243 @@
+#[cfg(feature = "rpc")]
+use std::time::Duration;
+
+#[cfg(feature = "rpc")]
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use crate::{
+    Raydium, error::RaydiumError, monitor::MAX_MULTIPLE_ACCOUNTS, programs::TOKEN_PROGRAM_ID,
+};
+use crate::{
+    programs::TOKEN_2022_PROGRAM_ID,
+    reader::{conver_balance, r_bool, r_pubkey, r_u8, r_u16, r_u32, r_u64},
+};
+
+/// wrapped SOL mint
+pub const WSOL_MINT: Pubkey = Pubkey::from_str_const("So11111111111111111111111111111111111111112");
+/// USDC mint
+pub const USDC_MINT: Pubkey =
+    Pubkey::from_str_const("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+/// USDT mint
+pub const USDT_MINT: Pubkey =
+    Pubkey::from_str_const("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB");
+
+/// default quote preference used when pricing a token: USDC first, then WSOL
+pub const DEFAULT_QUOTE_PREFERENCE: [Pubkey; 2] = [USDC_MINT, WSOL_MINT];
+
+/// time mint infos are cached for, unless configured. decimals never change, the supply does
+#[cfg(feature = "rpc")]
+pub const DEFAULT_MINT_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// size of an SPL token mint, token-2022 mints with extensions are longer
+pub const MINT_DATA_SIZE: usize = 82;
+
+/// offset of the account type of a token-2022 mint with extensions,
+/// past the padding to the size of a token account
+pub const MINT_ACCOUNT_TYPE_OFFSET: usize = 165;
+/// account type of a token-2022 mint
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+/// token-2022 extension types read by the sdk
+pub const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+pub const EXTENSION_PERMANENT_DELEGATE: u16 = 12;
+pub const EXTENSION_TRANSFER_HOOK: u16 = 14;
+pub const EXTENSION_TOKEN_METADATA: u16 = 19;
+
+/// state of an SPL token or token-2022 mint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MintInfo {
+    pub decimals: u8,
+    pub supply: u64,
+    pub mint_authority: Option<Pubkey>,
+    pub freeze_authority: Option<Pubkey>,
+    /// token program owning the mint
+    pub token_program: Pubkey,
+}
+
+impl MintInfo {
+    /// parse the account data of a mint owned by `token_program`
+    pub fn parse(token_program: Pubkey, data: &[u8]) -> Result<MintInfo, String> {
+        if data.len() < MINT_DATA_SIZE {
+            return Err(format!(
+                "mint data size {} is below {}",
+                data.len(),
+                MINT_DATA_SIZE
+            ));
+        }
+        if !r_bool(data, 45) {
+            return Err("mint is not initialized".to_string());
+        }
+        Ok(MintInfo {
+            mint_authority: read_option_pubkey(data, 0),
+            supply: r_u64(data, 36),
+            decimals: r_u8(data, 44),
+            freeze_authority: read_option_pubkey(data, 46),
+            token_program,
+        })
+    }
+
+    /// supply as a decimal amount
+    pub fn ui_supply(&self) -> f64 {
+        conver_balance(self.supply as f64, self.decimals)
+    }
+
+    pub fn is_token_2022(&self) -> bool {
+        self.token_program == TOKEN_2022_PROGRAM_ID
+    }
+}
+
+/// type and value of each extension of a token-2022 mint, stored as type-length-value
+/// entries after the account type. empty for mints without extensions
+pub fn mint_extensions(data: &[u8]) -> Result<Vec<(u16, &[u8])>, String> {
+    let mut extensions = Vec::new();
+    if data.get(MINT_ACCOUNT_TYPE_OFFSET) != Some(&ACCOUNT_TYPE_MINT) {
+        return Ok(extensions);
+    }
+    let mut offset = MINT_ACCOUNT_TYPE_OFFSET + 1;
+    while offset + 4 <= data.len() {
+        let extension = r_u16(data, offset);
+        let length = r_u16(data, offset + 2) as usize;
+        let value = data
+            .get(offset + 4..offset + 4 + length)
+            .ok_or_else(|| format!("mint extension {} overruns the account", extension))?;
+        extensions.push((extension, value));
+        offset += 4 + length;
+    }
+    Ok(extensions)
+}
+
+/// read a `COption<Pubkey>`: a u32 tag followed by the key
+fn read_option_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    (r_u32(data, offset) == 1).then(|| r_pubkey(data, offset + 4))
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// get the decimals, supply and authorities of a mint.
+    /// mints are cached for `RaydiumConfig::mint_cache_ttl`
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::USDC_MINT};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let usdc = raydium.fetch_mint_info(&USDC_MINT).await?;
+    /// println!("{} decimals, supply {}", usdc.decimals, usdc.ui_supply());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_mint_info(&self, mint: &Pubkey) -> Result<MintInfo, RaydiumError> {
+        Ok(self.fetch_mint_infos(&[*mint]).await?.remove(0))
+    }
+
+    /// get several mints, in order, fetching the ones missing from the cache in bulk
+    pub async fn fetch_mint_infos(&self, mints: &[Pubkey]) -> Result<Vec<MintInfo>, RaydiumError> {
+        let mut infos = Vec::with_capacity(mints.len());
+        let mut missing = Vec::new();
+        for (i, mint) in mints.iter().enumerate() {
+            let info = self.mint_cache.cached(mint).await;
+            if info.is_none() {
+                missing.push(i);
+            }
+            infos.push(info);
+        }
+        for chunk in missing.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let addresses: Vec<Pubkey> = chunk.iter().map(|&i| mints[i]).collect();
+            let accounts = self.get_multiple_accounts_with_context(&addresses).await?;
+            for ((&i, address), account) in chunk.iter().zip(&addresses).zip(accounts.value) {
+                let info = parse_mint_account(address, account)?;
+                self.mint_cache.insert(*address, info).await;
+                infos[i] = Some(info);
+            }
+        }
+        Ok(infos.into_iter().flatten().collect())
+    }
+}
+
+/// parse a fetched mint, rejecting accounts owned by neither token program
+#[cfg(feature = "rpc")]
+fn parse_mint_account(
+    address: &Pubkey,
+    account: Option<Account>,
+) -> Result<MintInfo, RaydiumError> {
+    let account = account.ok_or(RaydiumError::AccountNotFound(*address))?;
+    if account.owner != TOKEN_PROGRAM_ID && account.owner != TOKEN_2022_PROGRAM_ID {
+        return Err(RaydiumError::WrongOwner {
+            address: *address,
+            expected: TOKEN_PROGRAM_ID,
+            owner: account.owner,
+        });
+    }
+    MintInfo::parse(account.owner, &account.data).map_err(RaydiumError::Parse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(feature = "rpc"))]
+    use crate::programs::TOKEN_PROGRAM_ID;
+
+    /// mint data with a mint authority and no freeze authority
+    fn mint_data(authority: &Pubkey, supply: u64, decimals: u8) -> Vec<u8> {
+        let mut data = vec![0u8; MINT_DATA_SIZE];
+        data[0] = 1;
+        data[4..36].copy_from_slice(authority.as_ref());
+        data[36..44].copy_from_slice(&supply.to_le_bytes());
+        data[44] = decimals;
+        data[45] = 1;
+        data
+    }
+
+    #[test]
+    fn test_parse_mint() {
+        let authority = Pubkey::new_unique();
+        let info = MintInfo::parse(TOKEN_PROGRAM_ID, &mint_data(&authority, 1_500_000, 6)).unwrap();
+        assert_eq!(info.mint_authority, Some(authority));
+        assert_eq!(info.freeze_authority, None);
+        assert_eq!((info.decimals, info.ui_supply()), (6, 1.5));
+        assert!(!info.is_token_2022());
+        assert!(MintInfo::parse(TOKEN_PROGRAM_ID, &[0u8; MINT_DATA_SIZE]).is_err());
+        assert!(MintInfo::parse(TOKEN_PROGRAM_ID, &[0u8; 10]).is_err());
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_fetch_mint_infos() -> Result<(), Box<dyn std::error::Error>> {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+
+        use crate::test_utils::{mock_raydium, multiple_accounts_response, ui_account};
+
+        let authority = Pubkey::new_unique();
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![Some(ui_account(
+                &TOKEN_2022_PROGRAM_ID,
+                mint_data(&authority, 10, 9),
+            ))]),
+        );
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![Some(ui_account(
+                &TOKEN_PROGRAM_ID,
+                mint_data(&authority, 20, 6),
+            ))]),
+        );
+        let raydium = mock_raydium(mocks);
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let info_a = raydium.fetch_mint_info(&a).await?;
+        assert!(info_a.is_token_2022());
+        // `a` is cached, only `b` is fetched
+        let infos = raydium.fetch_mint_infos(&[b, a]).await?;
+        assert_eq!(infos.iter().map(|i| i.supply).collect::<Vec<_>>(), [20, 10]);
+        assert_eq!(infos[1], info_a);
+        // the queue is empty, the account is missing
+        let missing = Pubkey::new_unique();
+        assert_eq!(
+            raydium.fetch_mint_info(&missing).await,
+            Err(RaydiumError::AccountNotFound(missing))
+        );
+        Ok(())
+    }
+}
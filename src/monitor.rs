@@ -0,0 +1,259 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use futures::{StreamExt, stream};
+use solana_sdk::pubkey::Pubkey;
+use tokio::{sync::mpsc, task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{
+    Raydium,
+    error::RaydiumError,
+    rpc::parse_address,
+    typs::{PoolData, PoolKind},
+};
+
+/// maximum number of accounts in a single getMultipleAccounts call
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+/// default number of batches fetched concurrently
+pub const DEFAULT_MONITOR_CONCURRENCY: usize = 4;
+/// number of pool updates buffered for a slow consumer
+const MONITOR_CHANNEL_SIZE: usize = 1024;
+
+/// registered pools, the type is known once a pool was fetched
+type PoolSet = Arc<Mutex<HashMap<Pubkey, Option<PoolKind>>>>;
+
+/// polls many pools concurrently and delivers their parsed state
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{Raydium, error::RaydiumError, monitor::PoolMonitor};
+/// # use std::time::Duration;
+/// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+/// let mut monitor = PoolMonitor::new(&raydium);
+/// monitor.add_pool("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2")?;
+/// let mut updates = monitor.run(Duration::from_secs(2));
+/// while let Some((address, pool)) = updates.recv().await {
+///     println!("{} {:?}", address, pool.kind());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct PoolMonitor {
+    raydium: Raydium,
+    pools: PoolSet,
+    concurrency: usize,
+    successes: Arc<AtomicU64>,
+    failures: Arc<AtomicU64>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl PoolMonitor {
+    pub fn new(raydium: &Raydium) -> Self {
+        Self {
+            raydium: raydium.clone(),
+            pools: Arc::new(Mutex::new(HashMap::new())),
+            concurrency: DEFAULT_MONITOR_CONCURRENCY,
+            successes: Arc::new(AtomicU64::new(0)),
+            failures: Arc::new(AtomicU64::new(0)),
+            task: None,
+        }
+    }
+
+    /// set the number of batches fetched concurrently
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// register a pool, also while the monitor is running
+    pub fn add_pool(&self, address: &str) -> Result<(), RaydiumError> {
+        let address = parse_address(address)?;
+        self.pools.lock().unwrap().entry(address).or_insert(None);
+        Ok(())
+    }
+
+    /// unregister a pool, returns whether it was registered
+    pub fn remove_pool(&self, address: &str) -> Result<bool, RaydiumError> {
+        let address = parse_address(address)?;
+        Ok(self.pools.lock().unwrap().remove(&address).is_some())
+    }
+
+    /// addresses of the registered pools
+    pub fn pools(&self) -> Vec<Pubkey> {
+        self.pools.lock().unwrap().keys().copied().collect()
+    }
+
+    /// number of pools refreshed successfully
+    pub fn successes(&self) -> u64 {
+        self.successes.load(Ordering::Relaxed)
+    }
+
+    /// number of pool refreshes that failed
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// refresh every registered pool on each `interval` tick
+    /// a second call replaces the running task and its channel
+    pub fn run(&mut self, interval: Duration) -> mpsc::Receiver<(Pubkey, PoolData)> {
+        let (sender, receiver) = mpsc::channel(MONITOR_CHANNEL_SIZE);
+        let refresher = Refresher {
+            raydium: self.raydium.clone(),
+            pools: self.pools.clone(),
+            concurrency: self.concurrency,
+            successes: self.successes.clone(),
+            failures: self.failures.clone(),
+            sender,
+        };
+        if let Some(task) = self.task.replace(tokio::spawn(refresher.run(interval))) {
+            task.abort();
+        }
+        receiver
+    }
+
+    /// stop the background task
+    pub fn stop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for PoolMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// state of the background refresh task
+struct Refresher {
+    raydium: Raydium,
+    pools: PoolSet,
+    concurrency: usize,
+    successes: Arc<AtomicU64>,
+    failures: Arc<AtomicU64>,
+    sender: mpsc::Sender<(Pubkey, PoolData)>,
+}
+
+impl Refresher {
+    async fn run(self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            tokio::select! {
+                _ = self.sender.closed() => break,
+                _ = ticker.tick() => {
+                    if !self.refresh().await {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// refresh every registered pool once, returns false once the receiver is dropped
+    async fn refresh(&self) -> bool {
+        let mut groups: HashMap<Option<PoolKind>, Vec<Pubkey>> = HashMap::new();
+        for (address, kind) in self.pools.lock().unwrap().iter() {
+            groups.entry(*kind).or_default().push(*address);
+        }
+        let batches: Vec<(Option<PoolKind>, Vec<Pubkey>)> = groups
+            .into_iter()
+            .flat_map(|(kind, addresses)| {
+                addresses
+                    .chunks(MAX_MULTIPLE_ACCOUNTS)
+                    .map(|chunk| (kind, chunk.to_vec()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let raydium = &self.raydium;
+        let mut results = stream::iter(batches)
+            .map(|(kind, addresses)| async move {
+                let accounts = raydium.get_multiple_accounts_with_context(&addresses).await;
+                (kind, addresses, accounts)
+            })
+            .buffer_unordered(self.concurrency);
+        while let Some((kind, addresses, accounts)) = results.next().await {
+            let accounts = match accounts {
+                Ok(accounts) => accounts.value,
+                Err(_) => {
+                    self.failures
+                        .fetch_add(addresses.len() as u64, Ordering::Relaxed);
+                    continue;
+                }
+            };
+            for (address, account) in addresses.into_iter().zip(accounts) {
+                match self.raydium.parse_pool_account(&address, kind, account) {
+                    Ok(pool) => {
+                        self.successes.fetch_add(1, Ordering::Relaxed);
+                        if let Some(kind) = self.pools.lock().unwrap().get_mut(&address) {
+                            *kind = Some(pool.kind());
+                        }
+                        if self.sender.send((address, pool)).await.is_err() {
+                            return false;
+                        }
+                    }
+                    Err(_) => {
+                        self.failures.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::PoolMonitor;
+    use crate::{
+        launchpad::LAUNCHPAD_POOL_STATE_DATA_SIZE,
+        programs::RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+        test_utils::{mock_raydium, multiple_accounts_response, ui_account},
+        typs::PoolKind,
+    };
+
+    #[tokio::test]
+    async fn test_pool_monitor() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = ui_account(
+            &RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+            vec![0u8; LAUNCHPAD_POOL_STATE_DATA_SIZE],
+        );
+        // one pool exists, the other one is missing
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![Some(pool), None]),
+        );
+        let raydium = mock_raydium(mocks);
+        let mut monitor = PoolMonitor::new(&raydium);
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        monitor.add_pool(&first.to_string())?;
+        monitor.add_pool(&second.to_string())?;
+        let mut updates = monitor.run(Duration::from_secs(60));
+        let (address, pool) = updates.recv().await.unwrap();
+        assert!(address == first || address == second);
+        assert_eq!(pool.kind(), PoolKind::Launchpad);
+        assert_eq!(monitor.successes(), 1);
+        while monitor.failures() < 1 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert!(monitor.remove_pool(&first.to_string())?);
+        assert_eq!(monitor.pools(), vec![second]);
+        // dropping the monitor stops the task and closes the channel
+        drop(monitor);
+        assert!(updates.recv().await.is_none());
+        Ok(())
+    }
+}
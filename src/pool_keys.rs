@@ -0,0 +1,302 @@
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use crate::{Raydium, rpc::parse_address, typs::PoolKind};
+use crate::{
+    error::RaydiumError,
+    liquidity::v4::RaydiumLiquidityPoolData,
+    market::{MarketData, MarketVenue, OpenBookMarket},
+};
+
+pub use crate::liquidity::v4::pda::{AMM_AUTHORITY_SEED, amm_authority};
+/// minimum size of a serum / OpenBook market account, "serum" padding included
+#[deprecated(
+    note = "market accounts are parsed by `market::OpenBookMarket`, use `MARKET_STATE_V2_DATA_SIZE`"
+)]
+pub const MARKET_MIN_DATA_SIZE: usize = 381;
+
+/// every account needed to build v4 swap instructions, the pool keys of the typescript sdk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolKeysV4 {
+    pub id: Pubkey,
+    pub program_id: Pubkey,
+    pub authority: Pubkey,
+    pub open_orders: Pubkey,
+    pub target_orders: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub withdraw_queue: Pubkey,
+    pub lp_vault: Pubkey,
+    pub market_program_id: Pubkey,
+    pub market_id: Pubkey,
+    pub market_authority: Pubkey,
+    pub market_base_vault: Pubkey,
+    pub market_quote_vault: Pubkey,
+    pub market_bids: Pubkey,
+    pub market_asks: Pubkey,
+    pub market_event_queue: Pubkey,
+}
+
+/// market accounts referenced by the pool keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarketKeys {
+    pub own_address: Pubkey,
+    pub vault_signer_nonce: u64,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub event_queue: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+}
+
+impl MarketKeys {
+    /// read the accounts of a serum / OpenBook market
+    pub fn parse(data: &[u8]) -> Result<MarketKeys, RaydiumError> {
+        OpenBookMarket::parse(data).map(MarketKeys::from)
+    }
+}
+
+impl From<MarketData> for MarketKeys {
+    fn from(market: MarketData) -> Self {
+        MarketKeys {
+            own_address: market.own_address,
+            vault_signer_nonce: market.vault_signer_nonce,
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            base_vault: market.base_vault,
+            quote_vault: market.quote_vault,
+            event_queue: market.event_queue,
+            bids: market.bids,
+            asks: market.asks,
+        }
+    }
+}
+
+/// the vault signer of a market, derived from its nonce
+pub fn market_vault_signer(
+    market_id: &Pubkey,
+    nonce: u64,
+    market_program_id: &Pubkey,
+) -> Result<Pubkey, RaydiumError> {
+    Pubkey::create_program_address(
+        &[market_id.as_ref(), &nonce.to_le_bytes()],
+        market_program_id,
+    )
+    .map_err(|e| RaydiumError::Parse(format!("market vault signer: {:?}", e)))
+}
+
+impl PoolKeysV4 {
+    /// assemble the pool keys from the parsed pool and market
+    pub fn new(
+        program_id: Pubkey,
+        id: Pubkey,
+        pool: &RaydiumLiquidityPoolData,
+        market: &MarketKeys,
+    ) -> Result<PoolKeysV4, RaydiumError> {
+        Ok(PoolKeysV4 {
+            id,
+            program_id,
+            authority: amm_authority(&program_id),
+            open_orders: pool.open_orders,
+            target_orders: pool.target_orders,
+            base_mint: pool.base_mint,
+            quote_mint: pool.quote_mint,
+            lp_mint: pool.lp_mint,
+            base_decimals: pool.base_decimal,
+            quote_decimals: pool.quote_decimal,
+            base_vault: pool.base_vault,
+            quote_vault: pool.quote_vault,
+            withdraw_queue: pool.withdraw_queue,
+            lp_vault: pool.lp_vault,
+            market_program_id: pool.market_program_id,
+            market_id: pool.market_id,
+            market_authority: market_vault_signer(
+                &pool.market_id,
+                market.vault_signer_nonce,
+                &pool.market_program_id,
+            )?,
+            market_base_vault: market.base_vault,
+            market_quote_vault: market.quote_vault,
+            market_bids: market.bids,
+            market_asks: market.asks,
+            market_event_queue: market.event_queue,
+        })
+    }
+
+    /// the program owning the market of the pool
+    pub fn market_venue(&self) -> MarketVenue {
+        MarketVenue::from_program_id(&self.market_program_id)
+    }
+
+    /// cross-check the keys against the pool and market they were built from:
+    /// the amm authority must match the pool nonce and the market must trade the pool mints
+    pub fn validate(
+        &self,
+        pool: &RaydiumLiquidityPoolData,
+        market: &MarketKeys,
+    ) -> Result<(), RaydiumError> {
+        let authority =
+            Pubkey::create_program_address(&[AMM_AUTHORITY_SEED, &[pool.nonce]], &self.program_id)
+                .map_err(|e| RaydiumError::Parse(format!("amm authority: {:?}", e)))?;
+        let checks = [
+            ("amm authority", authority, self.authority),
+            ("market address", self.market_id, market.own_address),
+            ("market base mint", self.base_mint, market.base_mint),
+            ("market quote mint", self.quote_mint, market.quote_mint),
+        ];
+        for (account, expected, found) in checks {
+            if expected != found {
+                return Err(RaydiumError::KeyMismatch {
+                    account: account.to_string(),
+                    expected,
+                    found,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// fetch a v4 pool and its market and assemble every account needed to swap against it
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let keys = raydium
+    ///     .get_pool_keys_v4("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2")
+    ///     .await?;
+    /// println!("authority {} market {}", keys.authority, keys.market_id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_pool_keys_v4(&self, pool_address: &str) -> Result<PoolKeysV4, RaydiumError> {
+        self.pool_keys_v4(pool_address, false).await
+    }
+
+    /// like `get_pool_keys_v4`, also checking the derived authority and the market
+    /// against the on-chain data
+    pub async fn get_pool_keys_v4_checked(
+        &self,
+        pool_address: &str,
+    ) -> Result<PoolKeysV4, RaydiumError> {
+        self.pool_keys_v4(pool_address, true).await
+    }
+
+    async fn pool_keys_v4(
+        &self,
+        pool_address: &str,
+        validate: bool,
+    ) -> Result<PoolKeysV4, RaydiumError> {
+        let pool = self.get_liquidity_pool_v4(pool_address).await?;
+        let id = parse_address(pool_address)?;
+        self.pool_keys_v4_of(id, &pool, validate).await
+    }
+
+    /// assemble the pool keys of already fetched pool data, fetching only its market
+    pub(crate) async fn pool_keys_v4_of(
+        &self,
+        id: Pubkey,
+        pool: &RaydiumLiquidityPoolData,
+        validate: bool,
+    ) -> Result<PoolKeysV4, RaydiumError> {
+        let market_account = self
+            .get_multiple_accounts_with_context(&[pool.market_id])
+            .await?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(pool.market_id))?;
+        if market_account.owner != pool.market_program_id {
+            return Err(RaydiumError::WrongOwner {
+                address: pool.market_id,
+                expected: pool.market_program_id,
+                owner: market_account.owner,
+            });
+        }
+        // swaps against a market the AMM does not trade on would only fail on chain
+        if let MarketVenue::Unknown(program_id) = pool.market_venue() {
+            return Err(RaydiumError::Unsupported(format!(
+                "market program {} is neither OpenBook nor serum v3",
+                program_id
+            )));
+        }
+        let market = MarketKeys::parse(&market_account.data)?;
+        let keys = PoolKeysV4::new(self.program_id(PoolKind::V4), id, pool, &market)?;
+        if validate {
+            keys.validate(pool, &market)?;
+        }
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+    use crate::{market::market_data, programs::RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID};
+
+    #[test]
+    fn test_amm_authority() {
+        assert_eq!(
+            amm_authority(&RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID).to_string(),
+            "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1"
+        );
+    }
+
+    #[test]
+    fn test_pool_keys_validate() {
+        let market_program_id = Pubkey::new_unique();
+        let (_, nonce) = Pubkey::find_program_address(
+            &[AMM_AUTHORITY_SEED],
+            &RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+        );
+        let mut pool = RaydiumLiquidityPoolData {
+            nonce,
+            base_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            market_id: Pubkey::new_unique(),
+            market_program_id,
+            ..Default::default()
+        };
+        // find a nonce giving a valid vault signer, as the market program does
+        let signer_nonce = (0u64..)
+            .find(|n| market_vault_signer(&pool.market_id, *n, &market_program_id).is_ok())
+            .unwrap();
+        let data = market_data(
+            &pool.market_id,
+            &pool.base_mint,
+            &pool.quote_mint,
+            signer_nonce,
+        );
+        let market = MarketKeys::parse(&data).unwrap();
+        let keys = PoolKeysV4::new(
+            RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+            Pubkey::new_unique(),
+            &pool,
+            &market,
+        )
+        .unwrap();
+        assert_eq!(keys.validate(&pool, &market), Ok(()));
+        // a pool pointing to a market of another pair
+        pool.quote_mint = Pubkey::new_unique();
+        let keys = PoolKeysV4 {
+            quote_mint: pool.quote_mint,
+            ..keys
+        };
+        assert!(matches!(
+            keys.validate(&pool, &market),
+            Err(RaydiumError::KeyMismatch { .. })
+        ));
+        assert!(MarketKeys::parse(&data[..100]).is_err());
+    }
+}
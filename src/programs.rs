@@ -0,0 +1,230 @@
+#[cfg(feature = "rpc")]
+use solana_network_client::Mode;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::typs::PoolKind;
+#[cfg(feature = "rpc")]
+use crate::{Raydium, error::RaydiumError};
+
+/// raydium liquidity pool v4 (AMM) program id
+pub const RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+/// raydium CPMM (constant product) program id
+pub const RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+/// raydium CLMM (concentrated liquidity) program id
+pub const RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+/// raydium launchpad program id
+pub const RAYDIUM_LAUNCHPAD_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj");
+/// raydium stable swap AMM program id
+pub const RAYDIUM_LIQUIDITY_POOL_STABLE_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("5quBtoiQqxF9Jv6KYKctB59NT3gtJD2Y65kdnB1Uev3h");
+
+/// OpenBook v1 program id, the serum v3 fork owning the markets of recent v4 pools
+pub const OPENBOOK_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX");
+/// serum v3 program id, no longer maintained, owning the markets of older v4 pools
+pub const SERUM_V3_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
+
+/// system program id
+pub const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::from_str_const("11111111111111111111111111111111");
+/// SPL token program id
+pub const TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+/// SPL token-2022 program id
+pub const TOKEN_2022_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+/// associated token account program id
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+/// rent sysvar id
+pub const SYSVAR_RENT_ID: Pubkey =
+    Pubkey::from_str_const("SysvarRent111111111111111111111111111111111");
+/// account receiving the fee for creating a raydium v4 pool
+pub const RAYDIUM_V4_CREATE_POOL_FEE_ACCOUNT: Pubkey =
+    Pubkey::from_str_const("7YttLkHDoNj9wyDur5pM1ejNaAvT9X4eqaYcHQqtj2G5");
+/// Metaplex token metadata program id
+pub const METAPLEX_METADATA_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+/// SPL memo program id, v2
+pub const MEMO_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// raydium liquidity pool v4 (AMM) devnet program id
+pub const DEVNET_RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("HWy1jotHpo6UqeQxx49dpYYdQB8wj9Qk9MdxwjLvDHB8");
+/// devnet account receiving the fee for creating a raydium v4 pool
+pub const DEVNET_RAYDIUM_V4_CREATE_POOL_FEE_ACCOUNT: Pubkey =
+    Pubkey::from_str_const("3XMrhbv989VxAMi3DErLV9eJht1pHppW5LbKxe9fkEFR");
+/// OpenBook v1 devnet program id
+pub const DEVNET_OPENBOOK_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("EoTcMgcDRTJVZDMZWBoU6rhYHZfkNTVEAfz3uUJRcYGj");
+/// raydium CPMM devnet program id
+pub const DEVNET_RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("CPMDWBwJDtYax9qW7AyRuVC19Cc4L4Vcy4n2BHAbHkCW");
+/// raydium CLMM devnet program id
+pub const DEVNET_RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("devi51mZmdwUJGU9hjN27vEz64Gps7uUefqxg27EAtH");
+/// raydium launchpad devnet program id
+pub const DEVNET_RAYDIUM_LAUNCHPAD_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("LanD8FpTBBvzZFXjTxsAoipkFsxPUCDB4qAqKxYDiNP");
+/// raydium stable swap AMM devnet program id
+pub const DEVNET_RAYDIUM_LIQUIDITY_POOL_STABLE_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("DDg4VmQaJV9ogWce7LpcjBA9bv22wRp5uaTPa5pGjijF");
+
+/// program ids of every pool type on one network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramRegistry {
+    pub v4: Pubkey,
+    pub cpmm: Pubkey,
+    pub clmm: Pubkey,
+    pub launchpad: Pubkey,
+    pub stable: Pubkey,
+}
+
+impl ProgramRegistry {
+    /// mainnet program ids
+    pub const MAINNET: ProgramRegistry = ProgramRegistry {
+        v4: RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+        cpmm: RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID,
+        clmm: RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+        launchpad: RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+        stable: RAYDIUM_LIQUIDITY_POOL_STABLE_PROGRAM_ID,
+    };
+    /// devnet program ids
+    pub const DEVNET: ProgramRegistry = ProgramRegistry {
+        v4: DEVNET_RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+        cpmm: DEVNET_RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID,
+        clmm: DEVNET_RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID,
+        launchpad: DEVNET_RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+        stable: DEVNET_RAYDIUM_LIQUIDITY_POOL_STABLE_PROGRAM_ID,
+    };
+
+    /// program ids of the network, raydium is not deployed on testnet so it uses the mainnet ids
+    #[cfg(feature = "rpc")]
+    pub fn for_mode(mode: Mode) -> ProgramRegistry {
+        match mode {
+            Mode::DEV => Self::DEVNET,
+            Mode::MAIN | Mode::TEST => Self::MAINNET,
+        }
+    }
+
+    /// program id owning pools of the given type
+    pub fn get(&self, kind: PoolKind) -> Pubkey {
+        match kind {
+            PoolKind::V4 => self.v4,
+            PoolKind::Cpmm => self.cpmm,
+            PoolKind::Clmm => self.clmm,
+            PoolKind::Launchpad => self.launchpad,
+            PoolKind::Stable => self.stable,
+        }
+    }
+}
+
+impl PoolKind {
+    /// mainnet program id owning pools of this type
+    pub fn program_id(&self) -> Pubkey {
+        ProgramRegistry::MAINNET.get(*self)
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// network of the client, taken from the config or detected from the rpc url
+    pub fn mode(&self) -> Mode {
+        if let Some(mode) = self.config.mode {
+            return mode;
+        }
+        if self.solana_client.get_current_mainnet_url().is_some() {
+            return Mode::MAIN;
+        }
+        let url = self.solana_client.client_arc().url();
+        if url.contains("devnet") {
+            Mode::DEV
+        } else if url.contains("testnet") {
+            Mode::TEST
+        } else {
+            Mode::MAIN
+        }
+    }
+
+    /// program id owning pools of the given type, honoring the overrides in the config
+    pub fn program_id(&self, kind: PoolKind) -> Pubkey {
+        match self.config.program_ids.get(&kind) {
+            Some(program_id) => *program_id,
+            None => ProgramRegistry::for_mode(self.mode()).get(kind),
+        }
+    }
+    /// reject a pool account not owned by the program of its type,
+    /// unless the owner check is disabled in the config
+    pub fn check_owner(
+        &self,
+        kind: PoolKind,
+        address: &Pubkey,
+        owner: &Pubkey,
+    ) -> Result<(), RaydiumError> {
+        let expected = self.program_id(kind);
+        if self.config.skip_owner_check || *owner == expected {
+            return Ok(());
+        }
+        Err(RaydiumError::WrongOwner {
+            address: *address,
+            expected,
+            owner: *owner,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "rpc"))]
+mod tests {
+    use std::sync::Arc;
+
+    use solana_network_client::{Mode, SolanaClient};
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+    use crate::config::RaydiumConfig;
+
+    #[test]
+    fn test_program_registry() {
+        let devnet = Raydium::new(Arc::new(SolanaClient::new(Mode::DEV).unwrap()));
+        assert_eq!(devnet.mode(), Mode::DEV);
+        assert_eq!(
+            devnet.program_id(PoolKind::Cpmm),
+            DEVNET_RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID
+        );
+        assert_eq!(
+            devnet.program_id(PoolKind::Clmm),
+            DEVNET_RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID
+        );
+        let mainnet = Raydium::new(Arc::new(SolanaClient::new(Mode::MAIN).unwrap()));
+        assert_eq!(mainnet.mode(), Mode::MAIN);
+        assert_eq!(
+            mainnet.program_id(PoolKind::V4),
+            RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID
+        );
+        // the configured mode wins over the detected one
+        let config = RaydiumConfig::new().with_mode(Mode::DEV);
+        let switched = Raydium::new_with_config(mainnet.solana_client.clone(), config);
+        assert_eq!(
+            switched.program_id(PoolKind::Launchpad),
+            DEVNET_RAYDIUM_LAUNCHPAD_PROGRAM_ID
+        );
+        // explicit overrides win over the network
+        let fork = Pubkey::new_unique();
+        let config = RaydiumConfig::new()
+            .with_mode(Mode::DEV)
+            .with_program_id(PoolKind::Cpmm, fork);
+        let forked = Raydium::new_with_config(mainnet.solana_client.clone(), config);
+        assert_eq!(forked.program_id(PoolKind::Cpmm), fork);
+        assert_eq!(
+            forked.program_id(PoolKind::Clmm),
+            DEVNET_RAYDIUM_LIQUIDITY_POOL_CLMM_PROGRAM_ID
+        );
+        let owner = forked.check_owner(PoolKind::Cpmm, &Pubkey::new_unique(), &fork);
+        assert_eq!(owner, Ok(()));
+    }
+}
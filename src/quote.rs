@@ -0,0 +1,560 @@
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::{
+    Raydium,
+    error::RaydiumError,
+    launchpad::{
+        LAUNCHPAD_FEE_RATE_DENOMINATOR, LAUNCHPAD_GLOBAL_CONFIG_CURVE_TYPE_OFFSET,
+        LAUNCHPAD_GLOBAL_CONFIG_TRADE_FEE_RATE_OFFSET, LaunchpadPoolData,
+    },
+    liquidity::{
+        clmm::{
+            RAYDIUM_CLMM_AMM_CONFIG_TRADE_FEE_RATE_OFFSET, RAYDIUM_CLMM_FEE_RATE_DENOMINATOR,
+            RaydiumLiquidityPoolCLMMData,
+        },
+        cpmm::{RAYDIUM_CPMM_AMM_CONFIG_TRADE_FEE_RATE_OFFSET, RAYDIUM_CPMM_FEE_RATE_DENOMINATOR},
+        stable::{self, DEFAULT_STABLE_AMP},
+    },
+    reader::r_u64,
+    rpc::TOKEN_ACCOUNT_AMOUNT_OFFSET,
+    typs::{MintSide, PoolData, PoolKind},
+};
+
+/// launchpad constant product bonding curve
+pub const LAUNCHPAD_CURVE_CONSTANT_PRODUCT: u8 = 0;
+/// launchpad fixed price bonding curve
+pub const LAUNCHPAD_CURVE_FIXED_PRICE: u8 = 1;
+
+/// quote of a swap against a single pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub pool_kind: PoolKind,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// trade fee, in units of `fee_mint`
+    pub fee: u64,
+    /// the input mint, except for launchpad sells where the fee is taken from the quote output
+    pub fee_mint: Pubkey,
+    /// execution price against the spot price before the swap, fees excluded
+    pub price_impact_bps: u32,
+}
+
+/// result of a swap against a curve, before it is tied to a pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveSwap {
+    pub amount_out: u64,
+    pub fee: u64,
+    pub price_impact_bps: u32,
+}
+
+/// fee of `amount` at `numerator / denominator`, rounded up like the programs do
+pub fn fee_amount(amount: u64, numerator: u64, denominator: u64) -> u64 {
+    if denominator == 0 {
+        return 0;
+    }
+    (amount as u128 * numerator as u128).div_ceil(denominator as u128) as u64
+}
+
+/// price impact in basis points of getting `amount_out` for `amount_in` at spot price
+/// `spot_out_per_in`
+fn price_impact_bps(amount_in: u64, amount_out: u64, spot_out_per_in: f64) -> u32 {
+    if amount_in == 0 || spot_out_per_in <= 0.0 {
+        return 0;
+    }
+    let execution = amount_out as f64 / amount_in as f64;
+    ((1.0 - execution / spot_out_per_in) * 10_000.0)
+        .round()
+        .clamp(0.0, 10_000.0) as u32
+}
+
+/// constant product swap with the fee taken from the input
+pub fn constant_product_swap(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> Result<CurveSwap, RaydiumError> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(RaydiumError::InsufficientLiquidity);
+    }
+    let fee = fee_amount(amount_in, fee_numerator, fee_denominator);
+    let amount_in_after_fee = amount_in - fee;
+    let amount_out = (reserve_out as u128 * amount_in_after_fee as u128
+        / (reserve_in as u128 + amount_in_after_fee as u128)) as u64;
+    Ok(CurveSwap {
+        amount_out,
+        fee,
+        price_impact_bps: price_impact_bps(
+            amount_in_after_fee,
+            amount_out,
+            reserve_out as f64 / reserve_in as f64,
+        ),
+    })
+}
+
+/// StableSwap swap with amplification coefficient `amp`, the fee taken from the input.
+/// an approximation of the stable program, whose output it will not match, see
+/// `stable::amount_out`
+pub fn stable_swap(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    amp: u64,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> Result<CurveSwap, RaydiumError> {
+    let fee = fee_amount(amount_in, fee_numerator, fee_denominator);
+    let amount_in_after_fee = amount_in - fee;
+    let amount_out = stable::amount_out(reserve_in, reserve_out, amount_in_after_fee, amp)
+        .ok_or(RaydiumError::InsufficientLiquidity)?;
+    let spot = stable::spot_price(reserve_in, reserve_out, amp).unwrap_or_default();
+    Ok(CurveSwap {
+        amount_out,
+        fee,
+        price_impact_bps: price_impact_bps(amount_in_after_fee, amount_out, spot),
+    })
+}
+
+/// concentrated liquidity swap that stays within the active liquidity,
+/// ticks are not crossed so large swaps are overestimated
+pub fn clmm_swap_in_range(
+    pool: &RaydiumLiquidityPoolCLMMData,
+    zero_for_one: bool,
+    amount_in: u64,
+    trade_fee_rate: u64,
+) -> Result<CurveSwap, RaydiumError> {
+    if pool.liquidity == 0 || pool.sqrt_price_x64 == 0 {
+        return Err(RaydiumError::InsufficientLiquidity);
+    }
+    let fee = fee_amount(amount_in, trade_fee_rate, RAYDIUM_CLMM_FEE_RATE_DENOMINATOR);
+    let amount = (amount_in - fee) as f64;
+    let liquidity = pool.liquidity as f64;
+    let sqrt_price = pool.sqrt_price_x64 as f64 / (1u128 << 64) as f64;
+    let (amount_out, spot) = if zero_for_one {
+        let next = liquidity * sqrt_price / (liquidity + amount * sqrt_price);
+        (liquidity * (sqrt_price - next), sqrt_price * sqrt_price)
+    } else {
+        let next = sqrt_price + amount / liquidity;
+        (
+            liquidity * (next - sqrt_price) / (sqrt_price * next),
+            1.0 / (sqrt_price * sqrt_price),
+        )
+    };
+    let amount_out = amount_out.max(0.0).floor() as u64;
+    Ok(CurveSwap {
+        amount_out,
+        fee,
+        price_impact_bps: price_impact_bps(amount_in - fee, amount_out, spot),
+    })
+}
+
+/// launchpad bonding curve swap, buying base with quote or selling base for quote.
+/// the trade fee is always taken in the quote token
+pub fn launchpad_swap(
+    pool: &LaunchpadPoolData,
+    buy: bool,
+    amount_in: u64,
+    curve_type: u8,
+    trade_fee_rate: u64,
+) -> Result<CurveSwap, RaydiumError> {
+    let (reserve_base, reserve_quote) = match curve_type {
+        LAUNCHPAD_CURVE_CONSTANT_PRODUCT => (
+            pool.virtual_base.saturating_sub(pool.real_base),
+            pool.virtual_quote.saturating_add(pool.real_quote),
+        ),
+        LAUNCHPAD_CURVE_FIXED_PRICE => (pool.virtual_base, pool.virtual_quote),
+        _ => {
+            return Err(RaydiumError::Unsupported(format!(
+                "launchpad curve type {}",
+                curve_type
+            )));
+        }
+    };
+    if reserve_base == 0 || reserve_quote == 0 {
+        return Err(RaydiumError::InsufficientLiquidity);
+    }
+    let swap = |reserve_in: u64, reserve_out: u64, amount: u64| -> u64 {
+        if curve_type == LAUNCHPAD_CURVE_FIXED_PRICE {
+            (amount as u128 * reserve_out as u128 / reserve_in as u128) as u64
+        } else {
+            (reserve_out as u128 * amount as u128 / (reserve_in as u128 + amount as u128)) as u64
+        }
+    };
+    if buy {
+        let fee = fee_amount(amount_in, trade_fee_rate, LAUNCHPAD_FEE_RATE_DENOMINATOR);
+        let amount_out = swap(reserve_quote, reserve_base, amount_in - fee);
+        Ok(CurveSwap {
+            amount_out,
+            fee,
+            price_impact_bps: price_impact_bps(
+                amount_in - fee,
+                amount_out,
+                reserve_base as f64 / reserve_quote as f64,
+            ),
+        })
+    } else {
+        let gross = swap(reserve_base, reserve_quote, amount_in);
+        let fee = fee_amount(gross, trade_fee_rate, LAUNCHPAD_FEE_RATE_DENOMINATOR);
+        Ok(CurveSwap {
+            amount_out: gross - fee,
+            fee,
+            price_impact_bps: price_impact_bps(
+                amount_in,
+                gross,
+                reserve_quote as f64 / reserve_base as f64,
+            ),
+        })
+    }
+}
+
+/// read the amount of a token account, a missing account holds nothing
+fn token_amount(account: &Option<Account>) -> u64 {
+    account
+        .as_ref()
+        .map(|a| r_u64(&a.data, TOKEN_ACCOUNT_AMOUNT_OFFSET))
+        .unwrap_or(0)
+}
+
+/// data of a config account required for a quote
+fn config_data<'a>(
+    account: &'a Option<Account>,
+    config: &Pubkey,
+) -> Result<&'a [u8], RaydiumError> {
+    account
+        .as_ref()
+        .map(|a| a.data.as_slice())
+        .ok_or(RaydiumError::AccountNotFound(*config))
+}
+
+fn read_u64_at(data: &[u8], offset: usize) -> Result<u64, RaydiumError> {
+    data.get(offset..offset + 8)
+        .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+        .ok_or_else(|| RaydiumError::Parse(format!("config account too short: {}", data.len())))
+}
+
+impl Raydium {
+    /// quote a swap of `amount_in` of `input_mint` against a pool of any type
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::WSOL_MINT};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let quote = raydium
+    ///     .quote_swap(
+    ///         "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2",
+    ///         &WSOL_MINT,
+    ///         1_000_000_000,
+    ///     )
+    ///     .await?;
+    /// println!("out {} impact {} bps", quote.amount_out, quote.price_impact_bps);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn quote_swap(
+        &self,
+        pool_address: &str,
+        input_mint: &Pubkey,
+        amount_in: u64,
+    ) -> Result<SwapQuote, RaydiumError> {
+        let pool = self.get_pool(pool_address).await?;
+        self.quote_swap_pool(&pool, input_mint, amount_in).await
+    }
+
+    /// quote a swap against already fetched pool data, fetching only the auxiliary state:
+    /// vault and open orders balances for v4 and stable pools, vault balances for CPMM,
+    /// the amm config for CPMM and CLMM, the global config for launchpad.
+    /// CLMM quotes stay within the active liquidity and do not cross ticks, `clmm::compute_swap`
+    /// walks the tick arrays instead. stable quotes use
+    /// the StableSwap invariant at `RaydiumConfig::stable_amp`, an approximation of the curve in
+    /// the model data account the program prices with: they will not match the on-chain output
+    pub async fn quote_swap_pool(
+        &self,
+        pool: &PoolData,
+        input_mint: &Pubkey,
+        amount_in: u64,
+    ) -> Result<SwapQuote, RaydiumError> {
+        let side = pool
+            .side_of(input_mint)
+            .ok_or(RaydiumError::MintNotInPool(*input_mint))?;
+        let base_in = side == MintSide::Base;
+        let output_mint = pool.other_mint(input_mint).unwrap();
+        let mut fee_mint = *input_mint;
+        let swap = match pool {
+            PoolData::V4(p) => {
+                let accounts = self
+                    .get_multiple_accounts_with_context(&[
+                        p.base_vault,
+                        p.quote_vault,
+                        p.open_orders,
+                    ])
+                    .await?
+                    .value;
+                let reserves = p.reserves_from_accounts(&accounts)?;
+                let (reserve_in, reserve_out) = if base_in {
+                    (reserves.base, reserves.quote)
+                } else {
+                    (reserves.quote, reserves.base)
+                };
+                constant_product_swap(
+                    reserve_in,
+                    reserve_out,
+                    amount_in,
+                    p.swap_fee_numerator,
+                    p.swap_fee_denominator,
+                )?
+            }
+            PoolData::Cpmm(p) => {
+                let accounts = self
+                    .get_multiple_accounts_with_context(&[
+                        p.token_0_vault,
+                        p.token_1_vault,
+                        p.amm_config,
+                    ])
+                    .await?
+                    .value;
+                let trade_fee_rate = read_u64_at(
+                    config_data(&accounts[2], &p.amm_config)?,
+                    RAYDIUM_CPMM_AMM_CONFIG_TRADE_FEE_RATE_OFFSET,
+                )?;
+                let (token_0, token_1) =
+                    pool.trading_reserves(token_amount(&accounts[0]), token_amount(&accounts[1]));
+                let (reserve_in, reserve_out) = if base_in {
+                    (token_0, token_1)
+                } else {
+                    (token_1, token_0)
+                };
+                constant_product_swap(
+                    reserve_in,
+                    reserve_out,
+                    amount_in,
+                    trade_fee_rate,
+                    RAYDIUM_CPMM_FEE_RATE_DENOMINATOR,
+                )?
+            }
+            PoolData::Clmm(p) => {
+                let accounts = self
+                    .get_multiple_accounts_with_context(&[p.amm_config])
+                    .await?
+                    .value;
+                let data = config_data(&accounts[0], &p.amm_config)?;
+                let offset = RAYDIUM_CLMM_AMM_CONFIG_TRADE_FEE_RATE_OFFSET;
+                let trade_fee_rate = data
+                    .get(offset..offset + 4)
+                    .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+                    .ok_or_else(|| {
+                        RaydiumError::Parse(format!("config account too short: {}", data.len()))
+                    })?;
+                clmm_swap_in_range(p, base_in, amount_in, trade_fee_rate as u64)?
+            }
+            PoolData::Launchpad(p) => {
+                let global_config = p.global_config;
+                let accounts = self
+                    .get_multiple_accounts_with_context(&[global_config])
+                    .await?
+                    .value;
+                let data = config_data(&accounts[0], &global_config)?;
+                let curve_type = *data
+                    .get(LAUNCHPAD_GLOBAL_CONFIG_CURVE_TYPE_OFFSET)
+                    .ok_or_else(|| {
+                        RaydiumError::Parse(format!("config account too short: {}", data.len()))
+                    })?;
+                let trade_fee_rate =
+                    read_u64_at(data, LAUNCHPAD_GLOBAL_CONFIG_TRADE_FEE_RATE_OFFSET)?;
+                if base_in {
+                    fee_mint = output_mint;
+                }
+                launchpad_swap(p, !base_in, amount_in, curve_type, trade_fee_rate)?
+            }
+            PoolData::Stable(p) => {
+                let accounts = self
+                    .get_multiple_accounts_with_context(&[
+                        p.base_vault,
+                        p.quote_vault,
+                        p.open_orders,
+                    ])
+                    .await?
+                    .value;
+                let reserves = p.reserves_from_accounts(&accounts)?;
+                let (reserve_in, reserve_out) = if base_in {
+                    (reserves.base, reserves.quote)
+                } else {
+                    (reserves.quote, reserves.base)
+                };
+                stable_swap(
+                    reserve_in,
+                    reserve_out,
+                    amount_in,
+                    self.config.stable_amp.unwrap_or(DEFAULT_STABLE_AMP),
+                    p.swap_fee_numerator,
+                    p.swap_fee_denominator,
+                )?
+            }
+        };
+        Ok(SwapQuote {
+            pool_kind: pool.kind(),
+            input_mint: *input_mint,
+            output_mint,
+            amount_in,
+            amount_out: swap.amount_out,
+            fee: swap.fee,
+            fee_mint,
+            price_impact_bps: swap.price_impact_bps,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+    use crate::{
+        liquidity::{stable::RaydiumLiquidityPoolStableData, v4::RaydiumLiquidityPoolData},
+        market::open_orders_data,
+        test_utils::{mock_raydium, multiple_accounts_response, ui_account},
+    };
+
+    #[test]
+    fn test_constant_product_swap() {
+        // 1_000_000 in at 0.25%: fee 2_500, 997_500 in,
+        // out = 2_000_000_000 * 997_500 / 1_000_997_500 = 1_993_011
+        let swap = constant_product_swap(1_000_000_000, 2_000_000_000, 1_000_000, 25, 10_000);
+        assert_eq!(
+            swap,
+            Ok(CurveSwap {
+                amount_out: 1_993_011,
+                fee: 2_500,
+                price_impact_bps: 10,
+            })
+        );
+        // a trade of 10% of the input reserve moves the price by ~9.1%
+        let swap = constant_product_swap(1_000_000, 1_000_000, 100_000, 0, 10_000).unwrap();
+        assert_eq!(swap.amount_out, 90_909);
+        assert_eq!(swap.price_impact_bps, 909);
+        assert_eq!(
+            constant_product_swap(0, 1, 1, 0, 1),
+            Err(RaydiumError::InsufficientLiquidity)
+        );
+    }
+
+    #[test]
+    fn test_launchpad_swap() {
+        let pool = LaunchpadPoolData {
+            virtual_base: 1_000_000,
+            virtual_quote: 1_000,
+            real_base: 0,
+            real_quote: 0,
+            ..Default::default()
+        };
+        // buy with 100 quote at 1%: fee 1, out = 1_000_000 * 99 / 1_099 = 90_081
+        let buy = launchpad_swap(&pool, true, 100, LAUNCHPAD_CURVE_CONSTANT_PRODUCT, 10_000);
+        assert_eq!(buy.map(|s| (s.amount_out, s.fee)), Ok((90_081, 1)));
+        // sell 100_000 base: gross = 1_000 * 100_000 / 1_100_000 = 90, fee 1
+        let sell = launchpad_swap(
+            &pool,
+            false,
+            100_000,
+            LAUNCHPAD_CURVE_CONSTANT_PRODUCT,
+            10_000,
+        );
+        assert_eq!(sell.map(|s| (s.amount_out, s.fee)), Ok((89, 1)));
+        assert!(matches!(
+            launchpad_swap(&pool, true, 100, 2, 0),
+            Err(RaydiumError::Unsupported(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_quote_swap_v4() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = PoolData::V4(RaydiumLiquidityPoolData {
+            base_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            swap_fee_numerator: 25,
+            swap_fee_denominator: 10_000,
+            ..Default::default()
+        });
+        let vault = |amount: u64| {
+            let mut data = vec![0u8; 165];
+            data[64..72].copy_from_slice(&amount.to_le_bytes());
+            Some(ui_account(&spl_token::ID, data))
+        };
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![
+                vault(1_000_000_000),
+                vault(2_000_000_000),
+                Some(ui_account(&Pubkey::new_unique(), open_orders_data(0, 0))),
+            ]),
+        );
+        let raydium = mock_raydium(mocks);
+        let quote = raydium
+            .quote_swap_pool(&pool, &pool.base_mint(), 1_000_000)
+            .await?;
+        assert_eq!(quote.output_mint, pool.quote_mint());
+        assert_eq!((quote.amount_out, quote.fee), (1_993_011, 2_500));
+        let missing = Pubkey::new_unique();
+        assert_eq!(
+            raydium.quote_swap_pool(&pool, &missing, 1).await,
+            Err(RaydiumError::MintNotInPool(missing))
+        );
+        Ok(())
+    }
+    #[tokio::test]
+    async fn test_quote_swap_stable() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = PoolData::Stable(RaydiumLiquidityPoolStableData {
+            base_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            swap_fee_numerator: 5,
+            swap_fee_denominator: 10_000,
+            ..Default::default()
+        });
+        let vault = |amount: u64| {
+            let mut data = vec![0u8; 165];
+            data[64..72].copy_from_slice(&amount.to_le_bytes());
+            Some(ui_account(&spl_token::ID, data))
+        };
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![
+                vault(1_000_000_000_000),
+                vault(1_000_000_000_000),
+                Some(ui_account(&Pubkey::new_unique(), open_orders_data(0, 0))),
+            ]),
+        );
+        let raydium = mock_raydium(mocks);
+        let quote = raydium
+            .quote_swap_pool(&pool, &pool.quote_mint(), 1_000_000_000)
+            .await?;
+        let expected = stable_swap(
+            1_000_000_000_000,
+            1_000_000_000_000,
+            1_000_000_000,
+            DEFAULT_STABLE_AMP,
+            5,
+            10_000,
+        )?;
+        assert_eq!(
+            (quote.amount_out, quote.fee),
+            (expected.amount_out, 500_000)
+        );
+        // the pegged curve pays more than constant product on the same reserves
+        let constant_product = constant_product_swap(
+            1_000_000_000_000,
+            1_000_000_000_000,
+            1_000_000_000,
+            5,
+            10_000,
+        )?;
+        assert!(quote.amount_out > constant_product.amount_out);
+        assert_eq!(quote.pool_kind, PoolKind::Stable);
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::Raydium;
+
+/// rpc request budget: a sustained rate and a burst allowed on top of it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub requests_per_second: f64,
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// whether the rate is a positive finite number, anything else cannot be waited on
+    pub fn is_limited(&self) -> bool {
+        self.requests_per_second.is_finite() && self.requests_per_second > 0.0
+    }
+}
+
+/// token bucket shared by a `Raydium` handle and its clones.
+/// requests past the budget reserve the next free slot and sleep until it, so they
+/// are spread at the configured rate in arrival order
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    /// available tokens, negative when requests are waiting, and the time they were counted at
+    bucket: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            bucket: Mutex::new((limit.burst.max(1) as f64, Instant::now())),
+        }
+    }
+
+    /// wait until a request may be sent, immediately when the rate sets no limit
+    pub async fn acquire(&self) {
+        if !self.limit.is_limited() {
+            return;
+        }
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+            let (tokens, counted_at) = &mut *bucket;
+            let now = Instant::now();
+            let refill =
+                now.duration_since(*counted_at).as_secs_f64() * self.limit.requests_per_second;
+            *tokens = (*tokens + refill).min(self.limit.burst.max(1) as f64) - 1.0;
+            *counted_at = now;
+            if *tokens >= 0.0 {
+                Duration::ZERO
+            } else {
+                Duration::from_secs_f64(-*tokens / self.limit.requests_per_second)
+            }
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl Raydium {
+    /// wait for the rate limiter before an rpc request or a subscription setup,
+    /// returns immediately when no rate limit is configured
+    pub(crate) async fn throttle(&self) {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use futures::future::join_all;
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::{RateLimit, RateLimiter};
+    use crate::{config::RaydiumConfig, test_utils::mock_raydium_with_config};
+
+    #[tokio::test]
+    async fn test_rate_limit() {
+        let config = RaydiumConfig::new().with_rate_limit(20.0, 2);
+        let raydium = mock_raydium_with_config(MocksMap::default(), config);
+        let start = Instant::now();
+        // clones share the budget: 2 requests pass at once, the other 4 wait 50ms each
+        let calls = (0..6).map(|_| {
+            let raydium = raydium.clone();
+            async move {
+                raydium
+                    .get_multiple_accounts_with_context(&[Pubkey::new_unique()])
+                    .await
+            }
+        });
+        let results = join_all(calls).await;
+        assert!(results.iter().all(|r| r.is_ok()));
+        assert!(start.elapsed() >= Duration::from_millis(190));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_without_limit() {
+        for rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(
+                RaydiumConfig::new().with_rate_limit(rate, 2).rate_limit,
+                None
+            );
+            // a limiter built from such a rate lets every request through instead of panicking
+            let limiter = RateLimiter::new(RateLimit {
+                requests_per_second: rate,
+                burst: 2,
+            });
+            for _ in 0..4 {
+                limiter.acquire().await;
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! little endian readers for account data, reading past the end yields the default value
+
+use solana_sdk::pubkey::Pubkey;
+
+pub fn r_u8(data: &[u8], offset: usize) -> u8 {
+    data.get(offset).copied().unwrap_or(0)
+}
+
+pub fn r_u16(data: &[u8], offset: usize) -> u16 {
+    read(data, offset).map_or(0, u16::from_le_bytes)
+}
+
+pub fn r_u32(data: &[u8], offset: usize) -> u32 {
+    read(data, offset).map_or(0, u32::from_le_bytes)
+}
+
+pub fn r_u64(data: &[u8], offset: usize) -> u64 {
+    read(data, offset).map_or(0, u64::from_le_bytes)
+}
+
+pub fn r_u128(data: &[u8], offset: usize) -> u128 {
+    read(data, offset).map_or(0, u128::from_le_bytes)
+}
+
+pub fn r_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    read(data, offset).map_or_else(Pubkey::default, Pubkey::new_from_array)
+}
+
+pub fn r_bool(data: &[u8], offset: usize) -> bool {
+    r_u8(data, offset) != 0
+}
+
+/// convert a raw token amount to a decimal amount
+pub fn conver_balance(balance: f64, decimal: u8) -> f64 {
+    balance / 10f64.powi(decimal as i32)
+}
+
+/// digits of `value` grouped by thousands, `1_234_567`
+pub fn group_digits(value: u128) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::with_capacity(digits.len() * 4 / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push('_');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// write one `name: value` line per field of `$data`, each preceded by a newline so the
+/// caller's header stays the first line. fields after `grouped:` go through `group_digits`
+macro_rules! write_fields {
+    ($f:expr, $data:expr, $($field:ident),+ $(,)?) => {
+        $(write!($f, concat!("\n", stringify!($field), ": {}"), $data.$field)?;)+
+    };
+    ($f:expr, $data:expr, grouped: $($field:ident),+ $(,)?) => {
+        $(write!(
+            $f,
+            concat!("\n", stringify!($field), ": {}"),
+            $crate::reader::group_digits($data.$field as u128)
+        )?;)+
+    };
+}
+
+pub(crate) use write_fields;
+
+fn read<const N: usize>(data: &[u8], offset: usize) -> Option<[u8; N]> {
+    data.get(offset..offset.checked_add(N)?)?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_digits() {
+        assert_eq!(group_digits(0), "0");
+        assert_eq!(group_digits(999), "999");
+        assert_eq!(group_digits(1_000), "1_000");
+        assert_eq!(
+            group_digits(u128::MAX),
+            "340_282_366_920_938_463_463_374_607_431_768_211_455"
+        );
+    }
+
+    #[test]
+    fn test_reader() {
+        let data = [1u8, 0, 0, 0, 0, 0, 0, 0, 2];
+        assert_eq!(r_u64(&data, 0), 1);
+        assert_eq!(r_u8(&data, 8), 2);
+        // reads past the end
+        assert_eq!(r_u64(&data, 2), 0);
+        assert_eq!(r_u64(&data, usize::MAX), 0);
+        assert_eq!(r_pubkey(&data, 0), Pubkey::default());
+        assert_eq!(conver_balance(1_500_000.0, 6), 1.5);
+    }
+}
@@ -0,0 +1,416 @@
+use std::{collections::HashMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use crate::{Raydium, metrics::RpcCall, monitor::MAX_MULTIPLE_ACCOUNTS};
+use crate::{
+    error::RaydiumError,
+    typs::{PoolData, PoolKind},
+};
+
+/// a pool known to the registry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    #[serde(with = "pubkey_string")]
+    pub address: Pubkey,
+    pub kind: PoolKind,
+    #[serde(with = "pubkey_string")]
+    pub base_mint: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub quote_mint: Pubkey,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    /// slot the pool was last observed at
+    pub last_seen_slot: u64,
+}
+
+impl RegistryEntry {
+    pub fn from_pool(address: Pubkey, pool: &PoolData, slot: u64) -> Self {
+        Self {
+            address,
+            kind: pool.kind(),
+            base_mint: pool.base_mint(),
+            quote_mint: pool.quote_mint(),
+            base_decimals: pool.base_decimals(),
+            quote_decimals: pool.quote_decimals(),
+            last_seen_slot: slot,
+        }
+    }
+
+    pub fn has_mint(&self, mint: &Pubkey) -> bool {
+        self.base_mint == *mint || self.quote_mint == *mint
+    }
+}
+
+/// discovered pools persisted between runs, serialized to JSON as a list of entries
+/// Example
+/// ```rust,no_run
+/// # use raydium_sdk::{error::RaydiumError, mints::WSOL_MINT, registry::PoolRegistry};
+/// # fn run() -> Result<(), RaydiumError> {
+/// let registry = PoolRegistry::load("pools.json").unwrap_or_default();
+/// for pool in registry.pools_for_mint(&WSOL_MINT) {
+///     println!("{} {:?} seen at {}", pool.address, pool.kind, pool.last_seen_slot);
+/// }
+/// registry.save("pools.json")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<RegistryEntry>", into = "Vec<RegistryEntry>")]
+pub struct PoolRegistry {
+    entries: HashMap<Pubkey, RegistryEntry>,
+}
+
+impl From<Vec<RegistryEntry>> for PoolRegistry {
+    fn from(entries: Vec<RegistryEntry>) -> Self {
+        let mut registry = PoolRegistry::new();
+        for entry in entries {
+            registry.insert(entry);
+        }
+        registry
+    }
+}
+
+impl From<PoolRegistry> for Vec<RegistryEntry> {
+    fn from(registry: PoolRegistry) -> Self {
+        let mut entries: Vec<RegistryEntry> = registry.entries.into_values().collect();
+        entries.sort_by_key(|entry| entry.address);
+        entries
+    }
+}
+
+impl PoolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add or replace an entry, keeping the most recently seen one
+    pub fn insert(&mut self, entry: RegistryEntry) {
+        match self.entries.get(&entry.address) {
+            Some(known) if known.last_seen_slot > entry.last_seen_slot => {}
+            _ => {
+                self.entries.insert(entry.address, entry);
+            }
+        }
+    }
+
+    /// add or replace the entry of a pool observed at `slot`
+    pub fn insert_pool(&mut self, address: Pubkey, pool: &PoolData, slot: u64) {
+        self.insert(RegistryEntry::from_pool(address, pool, slot));
+    }
+
+    pub fn remove(&mut self, address: &Pubkey) -> Option<RegistryEntry> {
+        self.entries.remove(address)
+    }
+
+    pub fn get(&self, address: &Pubkey) -> Option<&RegistryEntry> {
+        self.entries.get(address)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &RegistryEntry> {
+        self.entries.values()
+    }
+
+    /// pools holding the mint on either side
+    pub fn pools_for_mint(&self, mint: &Pubkey) -> Vec<&RegistryEntry> {
+        self.entries()
+            .filter(|entry| entry.has_mint(mint))
+            .collect()
+    }
+
+    /// pools trading `mint_a` against `mint_b`, in either order
+    pub fn pools_for_pair(&self, mint_a: &Pubkey, mint_b: &Pubkey) -> Vec<&RegistryEntry> {
+        self.entries()
+            .filter(|entry| entry.has_mint(mint_a) && entry.has_mint(mint_b))
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Result<String, RaydiumError> {
+        serde_json::to_string_pretty(self).map_err(|e| RaydiumError::Parse(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, RaydiumError> {
+        serde_json::from_str(json).map_err(|e| RaydiumError::Parse(e.to_string()))
+    }
+
+    /// write the registry to a JSON file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RaydiumError> {
+        std::fs::write(path, self.to_json()?).map_err(|e| RaydiumError::Io(e.to_string()))
+    }
+
+    /// read a registry from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RaydiumError> {
+        let json = std::fs::read_to_string(path).map_err(|e| RaydiumError::Io(e.to_string()))?;
+        Self::from_json(&json)
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// get the v4, CPMM and CLMM pools containing the mint, refreshing the pools the registry
+    /// knows instead of running discovery. discovery runs, and fills the registry, when the
+    /// registry knows no live pool of the mint. pools that no longer exist or no longer parse
+    /// as their recorded type are evicted
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::WSOL_MINT, registry::PoolRegistry};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let mut registry = PoolRegistry::load("pools.json").unwrap_or_default();
+    /// let pools = raydium
+    ///     .get_liquidity_pools_by_mint_with_registry(&WSOL_MINT, &mut registry)
+    ///     .await?;
+    /// println!("{} pools", pools.len());
+    /// registry.save("pools.json")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_liquidity_pools_by_mint_with_registry(
+        &self,
+        mint: &Pubkey,
+        registry: &mut PoolRegistry,
+    ) -> Result<Vec<(Pubkey, PoolData)>, RaydiumError> {
+        let known: Vec<RegistryEntry> = registry
+            .pools_for_mint(mint)
+            .into_iter()
+            .filter(|entry| entry.kind != PoolKind::Launchpad)
+            .cloned()
+            .collect();
+        let pools = self.refresh_registry_entries(&known, registry).await?;
+        if !pools.is_empty() {
+            return Ok(pools);
+        }
+        let pools = self.get_liquidity_pools_by_mint(mint).await?;
+        self.register_discovered(&pools, registry).await?;
+        Ok(pools)
+    }
+
+    /// get the v4, CPMM and CLMM pools trading `mint_a` against `mint_b`, consulting the
+    /// registry first like `get_liquidity_pools_by_mint_with_registry`
+    pub async fn get_liquidity_pools_by_pair_with_registry(
+        &self,
+        mint_a: &Pubkey,
+        mint_b: &Pubkey,
+        registry: &mut PoolRegistry,
+    ) -> Result<Vec<(Pubkey, PoolData)>, RaydiumError> {
+        let known: Vec<RegistryEntry> = registry
+            .pools_for_pair(mint_a, mint_b)
+            .into_iter()
+            .filter(|entry| entry.kind != PoolKind::Launchpad)
+            .cloned()
+            .collect();
+        let pools = self.refresh_registry_entries(&known, registry).await?;
+        if !pools.is_empty() {
+            return Ok(pools);
+        }
+        let pools = self.get_liquidity_pools_by_pair(mint_a, mint_b).await?;
+        self.register_discovered(&pools, registry).await?;
+        Ok(pools)
+    }
+
+    /// fetch the pools of the entries, updating the live ones and evicting the stale ones
+    async fn refresh_registry_entries(
+        &self,
+        entries: &[RegistryEntry],
+        registry: &mut PoolRegistry,
+    ) -> Result<Vec<(Pubkey, PoolData)>, RaydiumError> {
+        let mut pools = Vec::new();
+        for chunk in entries.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let addresses: Vec<Pubkey> = chunk.iter().map(|entry| entry.address).collect();
+            let accounts = self.get_multiple_accounts_with_context(&addresses).await?;
+            for (entry, account) in chunk.iter().zip(accounts.value) {
+                match self.parse_pool_account(&entry.address, Some(entry.kind), account) {
+                    Ok(pool) => {
+                        registry.insert_pool(entry.address, &pool, accounts.slot);
+                        pools.push((entry.address, pool));
+                    }
+                    Err(e) if e.is_retryable() => return Err(e),
+                    Err(_) => {
+                        registry.remove(&entry.address);
+                    }
+                }
+            }
+        }
+        Ok(pools)
+    }
+
+    /// record discovered pools at the current slot
+    async fn register_discovered(
+        &self,
+        pools: &[(Pubkey, PoolData)],
+        registry: &mut PoolRegistry,
+    ) -> Result<(), RaydiumError> {
+        if pools.is_empty() {
+            return Ok(());
+        }
+        let slot = self
+            .with_rpc(RpcCall::GetSlot, None, |client| async move {
+                client
+                    .get_slot()
+                    .await
+                    .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))
+            })
+            .await?;
+        for (address, pool) in pools {
+            registry.insert_pool(*address, pool, slot);
+        }
+        Ok(())
+    }
+}
+
+/// serialize pubkeys as base58 strings
+pub(crate) mod pubkey_string {
+    use std::str::FromStr;
+
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Pubkey::from_str(&s).map_err(D::Error::custom)
+    }
+}
+
+pub(crate) mod option_pubkey_string {
+    use std::str::FromStr;
+
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(
+        pubkey: &Option<Pubkey>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match pubkey {
+            Some(pubkey) => serializer.collect_str(pubkey),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Pubkey>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| Pubkey::from_str(&s).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(kind: PoolKind, base_mint: Pubkey, quote_mint: Pubkey, slot: u64) -> RegistryEntry {
+        RegistryEntry {
+            address: Pubkey::new_unique(),
+            kind,
+            base_mint,
+            quote_mint,
+            base_decimals: 9,
+            quote_decimals: 6,
+            last_seen_slot: slot,
+        }
+    }
+
+    #[test]
+    fn test_registry_queries() {
+        let (sol, usdc, ray) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut registry = PoolRegistry::new();
+        let sol_usdc = entry(PoolKind::V4, sol, usdc, 10);
+        registry.insert(sol_usdc.clone());
+        registry.insert(entry(PoolKind::Cpmm, ray, sol, 10));
+        registry.insert(entry(PoolKind::Clmm, ray, usdc, 10));
+        assert_eq!(registry.pools_for_mint(&sol).len(), 2);
+        assert_eq!(registry.pools_for_pair(&usdc, &sol), vec![&sol_usdc]);
+        // an older observation does not replace a newer one
+        registry.insert(RegistryEntry {
+            last_seen_slot: 5,
+            ..sol_usdc.clone()
+        });
+        assert_eq!(registry.get(&sol_usdc.address), Some(&sol_usdc));
+        registry.remove(&sol_usdc.address);
+        assert!(registry.pools_for_pair(&sol, &usdc).is_empty());
+    }
+
+    #[test]
+    fn test_registry_json() -> Result<(), RaydiumError> {
+        let mut registry = PoolRegistry::new();
+        let pool = entry(
+            PoolKind::Cpmm,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            42,
+        );
+        registry.insert(pool.clone());
+        let json = registry.to_json()?;
+        assert!(json.contains(&pool.address.to_string()));
+        assert_eq!(PoolRegistry::from_json(&json)?, registry);
+        assert!(PoolRegistry::from_json("[{\"address\": \"not a pubkey\"}]").is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_registry_lookup() -> Result<(), Box<dyn std::error::Error>> {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+
+        use crate::{
+            liquidity::cpmm::RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE,
+            programs::RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID,
+            test_utils::{mock_raydium, multiple_accounts_response, ui_account},
+        };
+
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![Some(ui_account(
+                &RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID,
+                vec![0u8; RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE],
+            ))]),
+        );
+        for _ in 0..6 {
+            mocks.insert(RpcRequest::GetProgramAccounts, serde_json::json!([]));
+        }
+        let raydium = mock_raydium(mocks);
+        let known = entry(
+            PoolKind::Cpmm,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            0,
+        );
+        let mut registry = PoolRegistry::new();
+        registry.insert(known.clone());
+        // the known pool is refreshed without discovery
+        let pools = raydium
+            .get_liquidity_pools_by_mint_with_registry(&known.base_mint, &mut registry)
+            .await?;
+        assert_eq!(pools.len(), 1);
+        let refreshed = registry.get(&known.address).cloned().unwrap();
+        assert_eq!(refreshed.last_seen_slot, 1);
+        // the pool no longer exists: it is evicted and discovery finds nothing
+        let pools = raydium
+            .get_liquidity_pools_by_mint_with_registry(&refreshed.base_mint, &mut registry)
+            .await?;
+        assert!(pools.is_empty());
+        assert!(registry.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures::{StreamExt, stream};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    Raydium,
+    error::RaydiumError,
+    mints::{USDC_MINT, WSOL_MINT},
+    monitor::MAX_MULTIPLE_ACCOUNTS,
+    quote::SwapQuote,
+    typs::PoolData,
+};
+
+/// number of pools quoted concurrently while routing
+pub const ROUTING_CONCURRENCY: usize = 8;
+
+/// maximum number of pools quoted for each leg of a route
+pub const MAX_ROUTE_POOLS_PER_LEG: usize = 5;
+/// intermediate mints tried when none are given
+pub const DEFAULT_ROUTE_INTERMEDIATES: [Pubkey; 2] = [WSOL_MINT, USDC_MINT];
+
+/// a pool able to fill a trade, with its quote
+#[derive(Debug, Clone)]
+pub struct PoolCandidate {
+    pub address: Pubkey,
+    pub pool: PoolData,
+    pub quote: SwapQuote,
+}
+
+/// a two-hop route through an intermediate mint
+#[derive(Debug, Clone)]
+pub struct RouteQuote {
+    pub intermediate: Pubkey,
+    /// input mint to intermediate mint
+    pub first: PoolCandidate,
+    /// intermediate mint to output mint
+    pub second: PoolCandidate,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// combined price impact of both legs
+    pub price_impact_bps: u32,
+}
+
+/// combined price impact of two consecutive trades
+pub fn combine_price_impact_bps(first: u32, second: u32) -> u32 {
+    let kept = (10_000 - first.min(10_000)) as u64 * (10_000 - second.min(10_000)) as u64;
+    (10_000 - kept / 10_000) as u32
+}
+
+/// current unix time in seconds
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// sort candidates by output amount, best first
+pub fn rank_candidates(candidates: &mut [PoolCandidate]) {
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.quote.amount_out));
+}
+
+impl Raydium {
+    /// quote a trade on every tradable v4, CPMM and CLMM pool of the pair,
+    /// returning the candidates sorted by output amount so the next one can be tried
+    /// when a transaction against the first one fails.
+    /// pools that fail to quote are left out
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::{USDC_MINT, WSOL_MINT}};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let candidates = raydium
+    ///     .best_pool_for_pair(&WSOL_MINT, &USDC_MINT, 1_000_000_000, &WSOL_MINT)
+    ///     .await?;
+    /// let best = &candidates[0];
+    /// println!("{} {:?} out {}", best.address, best.pool.kind(), best.quote.amount_out);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn best_pool_for_pair(
+        &self,
+        mint_a: &Pubkey,
+        mint_b: &Pubkey,
+        amount_in: u64,
+        input_mint: &Pubkey,
+    ) -> Result<Vec<PoolCandidate>, RaydiumError> {
+        if input_mint != mint_a && input_mint != mint_b {
+            return Err(RaydiumError::MintNotInPool(*input_mint));
+        }
+        let pools = self.get_liquidity_pools_by_pair(mint_a, mint_b).await?;
+        let candidates = self
+            .quote_candidates(tradable_pools(pools), input_mint, amount_in)
+            .await;
+        if candidates.is_empty() {
+            return Err(RaydiumError::PoolNotFound(format!(
+                "no tradable pool for {} / {}",
+                mint_a, mint_b
+            )));
+        }
+        Ok(candidates)
+    }
+
+    /// quote a two-hop trade from `input_mint` to `output_mint` through each intermediate mint,
+    /// using the best pool of each leg, and return the route with the highest output.
+    /// WSOL and USDC are tried when no intermediate is given.
+    /// at most `MAX_ROUTE_POOLS_PER_LEG` pools, the deepest on the input side, are quoted per leg
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::USDC_MINT};
+    /// # use solana_sdk::pubkey::Pubkey;
+    /// # async fn run(raydium: Raydium, token: Pubkey) -> Result<(), RaydiumError> {
+    /// let route = raydium.quote_route(&token, &USDC_MINT, 1_000_000, &[]).await?;
+    /// println!("via {} out {}", route.intermediate, route.amount_out);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn quote_route(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        amount_in: u64,
+        intermediates: &[Pubkey],
+    ) -> Result<RouteQuote, RaydiumError> {
+        let intermediates = if intermediates.is_empty() {
+            &DEFAULT_ROUTE_INTERMEDIATES[..]
+        } else {
+            intermediates
+        };
+        let mut best: Option<RouteQuote> = None;
+        for intermediate in intermediates {
+            if intermediate == input_mint || intermediate == output_mint {
+                continue;
+            }
+            let first = self.best_leg(input_mint, intermediate, amount_in).await?;
+            let Some(first) = first else { continue };
+            let second = self
+                .best_leg(intermediate, output_mint, first.quote.amount_out)
+                .await?;
+            let Some(second) = second else { continue };
+            if best
+                .as_ref()
+                .is_some_and(|b| b.amount_out >= second.quote.amount_out)
+            {
+                continue;
+            }
+            best = Some(RouteQuote {
+                intermediate: *intermediate,
+                amount_in,
+                amount_out: second.quote.amount_out,
+                price_impact_bps: combine_price_impact_bps(
+                    first.quote.price_impact_bps,
+                    second.quote.price_impact_bps,
+                ),
+                first,
+                second,
+            });
+        }
+        best.ok_or_else(|| {
+            RaydiumError::NoRoute(format!(
+                "no two-hop route from {} to {}",
+                input_mint, output_mint
+            ))
+        })
+    }
+
+    /// best pool for one leg of a route, `None` when the pair has no tradable pool
+    async fn best_leg(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        amount_in: u64,
+    ) -> Result<Option<PoolCandidate>, RaydiumError> {
+        let pools = self
+            .get_liquidity_pools_by_pair(input_mint, output_mint)
+            .await?;
+        let pools = self
+            .deepest_pools(tradable_pools(pools), input_mint, MAX_ROUTE_POOLS_PER_LEG)
+            .await?;
+        Ok(self
+            .quote_candidates(pools, input_mint, amount_in)
+            .await
+            .into_iter()
+            .next())
+    }
+
+    /// keep the `limit` pools holding the most of `mint`
+    async fn deepest_pools(
+        &self,
+        pools: Vec<(Pubkey, PoolData)>,
+        mint: &Pubkey,
+        limit: usize,
+    ) -> Result<Vec<(Pubkey, PoolData)>, RaydiumError> {
+        if pools.len() <= limit {
+            return Ok(pools);
+        }
+        let vaults: Vec<Pubkey> = pools
+            .iter()
+            .map(|(_, pool)| pool.vault_of(mint).unwrap_or_default())
+            .collect();
+        let mut depths = Vec::with_capacity(vaults.len());
+        for chunk in vaults.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            depths.extend(self.get_token_account_amounts(chunk).await?);
+        }
+        let mut pools: Vec<((Pubkey, PoolData), u64)> = pools.into_iter().zip(depths).collect();
+        pools.sort_by_key(|(_, depth)| std::cmp::Reverse(*depth));
+        Ok(pools
+            .into_iter()
+            .take(limit)
+            .map(|(pool, _)| pool)
+            .collect())
+    }
+
+    /// quote a trade on each pool, dropping the pools that fail, best first
+    pub(crate) async fn quote_candidates(
+        &self,
+        pools: Vec<(Pubkey, PoolData)>,
+        input_mint: &Pubkey,
+        amount_in: u64,
+    ) -> Vec<PoolCandidate> {
+        let mut candidates: Vec<PoolCandidate> = stream::iter(pools)
+            .map(|(address, pool)| async move {
+                self.quote_swap_pool(&pool, input_mint, amount_in)
+                    .await
+                    .ok()
+                    .map(|quote| PoolCandidate {
+                        address,
+                        pool,
+                        quote,
+                    })
+            })
+            .buffer_unordered(ROUTING_CONCURRENCY)
+            .filter_map(|candidate| async move { candidate })
+            .collect()
+            .await;
+        rank_candidates(&mut candidates);
+        candidates
+    }
+}
+
+/// keep the pools accepting swaps now
+pub(crate) fn tradable_pools(pools: Vec<(Pubkey, PoolData)>) -> Vec<(Pubkey, PoolData)> {
+    let now = unix_now();
+    pools
+        .into_iter()
+        .filter(|(_, pool)| pool.is_tradable(now))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+    use crate::{
+        liquidity::{
+            clmm::RaydiumLiquidityPoolCLMMData, cpmm::RaydiumLiquidityPoolCPMMData,
+            v4::RaydiumLiquidityPoolData,
+        },
+        typs::{CLMM_STATUS_SWAP_DISABLED, PoolKind, V4_STATUS_INITIALIZED},
+    };
+
+    #[test]
+    fn test_combine_price_impact_bps() {
+        assert_eq!(combine_price_impact_bps(0, 0), 0);
+        // 1% then 2% keeps 0.99 * 0.98 = 97.02% of the value
+        assert_eq!(combine_price_impact_bps(100, 200), 298);
+        assert_eq!(combine_price_impact_bps(10_000, 50), 10_000);
+    }
+
+    #[test]
+    fn test_tradable_pools() {
+        let pools = vec![
+            (
+                Pubkey::new_unique(),
+                PoolData::V4(RaydiumLiquidityPoolData {
+                    status: V4_STATUS_INITIALIZED,
+                    ..Default::default()
+                }),
+            ),
+            // v4 disabled
+            (
+                Pubkey::new_unique(),
+                PoolData::V4(RaydiumLiquidityPoolData {
+                    status: 2,
+                    ..Default::default()
+                }),
+            ),
+            // CPMM opening in the future
+            (
+                Pubkey::new_unique(),
+                PoolData::Cpmm(RaydiumLiquidityPoolCPMMData {
+                    open_time: u64::MAX,
+                    ..Default::default()
+                }),
+            ),
+            // CLMM with swaps disabled
+            (
+                Pubkey::new_unique(),
+                PoolData::Clmm(RaydiumLiquidityPoolCLMMData {
+                    status: CLMM_STATUS_SWAP_DISABLED,
+                    ..Default::default()
+                }),
+            ),
+            (
+                Pubkey::new_unique(),
+                PoolData::Clmm(RaydiumLiquidityPoolCLMMData::default()),
+            ),
+        ];
+        let kinds: Vec<PoolKind> = tradable_pools(pools)
+            .iter()
+            .map(|(_, pool)| pool.kind())
+            .collect();
+        assert_eq!(kinds, vec![PoolKind::V4, PoolKind::Clmm]);
+    }
+}
@@ -0,0 +1,393 @@
+use std::{future::Future, str::FromStr, time::Duration};
+
+use solana_account_decoder::{UiAccountData, UiAccountEncoding};
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_request::TokenAccountsFilter,
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::{
+    Raydium,
+    error::RaydiumError,
+    market::OpenOrders,
+    metrics::RpcCall,
+    monitor::MAX_MULTIPLE_ACCOUNTS,
+    reader::r_u64,
+    typs::{Contextual, PoolData, PoolKind},
+};
+
+/// offset of the amount field in an SPL token account
+pub(crate) const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+/// parse a base58 address
+pub(crate) fn parse_address(address: &str) -> Result<Pubkey, RaydiumError> {
+    Pubkey::from_str(address).map_err(|e| RaydiumError::InvalidAddress(format!("{:?}", e)))
+}
+
+impl Raydium {
+    /// run an rpc future under `timeout`, falling back to the configured timeout,
+    /// once the rate limiter lets it through
+    pub(crate) async fn with_timeout<T, F>(
+        &self,
+        timeout: Option<Duration>,
+        fut: F,
+    ) -> Result<T, RaydiumError>
+    where
+        F: Future<Output = Result<T, RaydiumError>>,
+    {
+        self.throttle().await;
+        match timeout.or(self.config.timeout) {
+            Some(timeout) => tokio::time::timeout(timeout, fut)
+                .await
+                .map_err(|_| RaydiumError::Timeout(timeout))?,
+            None => fut.await,
+        }
+    }
+
+    /// get the data of a pool account together with the slot it was observed at,
+    /// rejecting accounts not owned by the program of the pool type.
+    /// with `kind` unset the type is detected from the data size
+    pub(crate) async fn get_pool_account_data_with_context(
+        &self,
+        address: &str,
+        kind: Option<PoolKind>,
+        timeout: Option<Duration>,
+    ) -> Result<Contextual<Vec<u8>>, RaydiumError> {
+        let address = parse_address(address)?;
+        let fetch = self.with_rpc(RpcCall::GetAccountInfo, timeout, |client| async move {
+            let response = client
+                .get_account_with_commitment(&address, client.commitment())
+                .await
+                .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))?;
+            let account = response
+                .value
+                .ok_or(RaydiumError::AccountNotFound(address))?;
+            Ok(Contextual::new(response.context.slot, account))
+        });
+        let account = match &self.cache {
+            Some(cache) => cache.accounts.get_or_fetch(address, fetch).await?,
+            None => fetch.await?,
+        };
+        if let Some(kind) = kind.or_else(|| PoolKind::from_data_len(account.value.data.len())) {
+            self.check_owner(kind, &address, &account.value.owner)?;
+        }
+        Ok(account.map(|account| account.data))
+    }
+
+    /// get several accounts from a single RPC call together with the slot they were observed at
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(accounts = addresses.len()))
+    )]
+    pub async fn get_multiple_accounts_with_context(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Contextual<Vec<Option<Account>>>, RaydiumError> {
+        let response = self
+            .with_rpc(RpcCall::GetMultipleAccounts, None, |client| async move {
+                client
+                    .get_multiple_accounts_with_commitment(addresses, client.commitment())
+                    .await
+                    .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))
+            })
+            .await?;
+        Ok(Contextual::new(response.context.slot, response.value))
+    }
+
+    /// parse a fetched pool account, detecting the type when `kind` is unset,
+    /// and reject it when it is not owned by the program of its type
+    pub(crate) fn parse_pool_account(
+        &self,
+        address: &Pubkey,
+        kind: Option<PoolKind>,
+        account: Option<Account>,
+    ) -> Result<PoolData, RaydiumError> {
+        let account = account.ok_or(RaydiumError::AccountNotFound(*address))?;
+        let pool = self.parse_with_metrics(
+            kind.or_else(|| PoolKind::from_data_len(account.data.len())),
+            || {
+                match kind {
+                    Some(kind) => PoolData::parse(kind, &account.data),
+                    None => PoolData::parse_detect(&account.data),
+                }
+                .map_err(RaydiumError::Parse)
+            },
+        )?;
+        self.check_owner(pool.kind(), address, &account.owner)?;
+        Ok(pool)
+    }
+
+    /// get the raw amounts of SPL token accounts in one call, missing accounts count as 0
+    pub(crate) async fn get_token_account_amounts(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<u64>, RaydiumError> {
+        Ok(self
+            .get_token_account_amounts_with_context(addresses)
+            .await?
+            .value)
+    }
+
+    /// get the raw amounts of SPL token accounts in one call together with the slot,
+    /// missing accounts count as 0
+    pub(crate) async fn get_token_account_amounts_with_context(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Contextual<Vec<u64>>, RaydiumError> {
+        let accounts = self.get_multiple_accounts_with_context(addresses).await?;
+        Ok(accounts.map(|accounts| {
+            accounts
+                .iter()
+                .map(|account| {
+                    account
+                        .as_ref()
+                        .map(|a| r_u64(&a.data, TOKEN_ACCOUNT_AMOUNT_OFFSET))
+                        .unwrap_or(0)
+                })
+                .collect()
+        }))
+    }
+
+    /// get in bulk the amounts `PoolData::spot_price_with_amp` prices each pool off: the vault
+    /// balances of v4 and CPMM pools, the vault balances plus the open orders totals of stable
+    /// pools like their quotes, and zeros for CLMM and launchpad pools which price off their
+    /// state. missing vaults count as 0, a missing open orders account fails its pool alone.
+    /// the slot is the oldest one the accounts were observed at, `None` when none was fetched
+    pub(crate) async fn get_spot_amounts(
+        &self,
+        pools: &[&PoolData],
+    ) -> Result<(Option<u64>, Vec<Result<(u64, u64), RaydiumError>>), RaydiumError> {
+        let accounts_of = |pool: &PoolData| match pool {
+            PoolData::Stable(p) => vec![p.base_vault, p.quote_vault, p.open_orders],
+            pool if pool.is_priced_from_vaults() => vec![pool.base_vault(), pool.quote_vault()],
+            _ => Vec::new(),
+        };
+        let addresses: Vec<Pubkey> = pools.iter().flat_map(|pool| accounts_of(pool)).collect();
+        let mut slot = None;
+        let mut accounts = Vec::with_capacity(addresses.len());
+        for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let fetched = self.get_multiple_accounts_with_context(chunk).await?;
+            slot = Some(slot.map_or(fetched.slot, |slot: u64| slot.min(fetched.slot)));
+            accounts.extend(fetched.value);
+        }
+        let mut accounts = accounts.into_iter();
+        let amounts = pools
+            .iter()
+            .map(|pool| {
+                let fetched: Vec<Option<Account>> =
+                    accounts.by_ref().take(accounts_of(pool).len()).collect();
+                let amount = |i: usize| {
+                    fetched
+                        .get(i)
+                        .and_then(Option::as_ref)
+                        .map_or(0, |account| {
+                            r_u64(&account.data, TOKEN_ACCOUNT_AMOUNT_OFFSET)
+                        })
+                };
+                match pool {
+                    PoolData::Stable(p) => {
+                        let data = fetched
+                            .get(2)
+                            .and_then(Option::as_ref)
+                            .map(|account| account.data.as_slice())
+                            .ok_or(RaydiumError::AccountNotFound(p.open_orders))?;
+                        let totals = OpenOrders::parse(data)?.totals();
+                        Ok((
+                            amount(0).saturating_add(totals.base_total),
+                            amount(1).saturating_add(totals.quote_total),
+                        ))
+                    }
+                    _ => Ok((amount(0), amount(1))),
+                }
+            })
+            .collect();
+        Ok((slot, amounts))
+    }
+
+    /// get the raw balance of `owner` in `mint`, summed over all its token accounts of the mint.
+    /// an owner without any account has a balance of 0
+    pub(crate) async fn get_owner_token_balance(
+        &self,
+        owner: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<u64, RaydiumError> {
+        let accounts = self
+            .with_rpc(
+                RpcCall::GetTokenAccountsByOwner,
+                None,
+                |client| async move {
+                    client
+                        .get_token_accounts_by_owner(owner, TokenAccountsFilter::Mint(*mint))
+                        .await
+                        .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))
+                },
+            )
+            .await?;
+        accounts.iter().try_fold(0u64, |total, keyed| {
+            // the rpc answers in jsonParsed, falling back to binary for accounts it cannot parse
+            let amount = match &keyed.account.data {
+                UiAccountData::Json(parsed) => parsed.parsed["info"]["tokenAmount"]["amount"]
+                    .as_str()
+                    .and_then(|amount| amount.parse::<u64>().ok()),
+                data => data
+                    .decode()
+                    .filter(|data| data.len() >= TOKEN_ACCOUNT_AMOUNT_OFFSET + 8)
+                    .map(|data| r_u64(&data, TOKEN_ACCOUNT_AMOUNT_OFFSET)),
+            }
+            .ok_or_else(|| {
+                RaydiumError::Parse(format!("token account {} has no amount", keyed.pubkey))
+            })?;
+            Ok(total.saturating_add(amount))
+        })
+    }
+
+    /// get the accounts of a program with the given size and pubkeys at the given offsets
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%program, data_size))
+    )]
+    pub(crate) async fn get_program_accounts_filtered(
+        &self,
+        program: &Pubkey,
+        data_size: usize,
+        pubkey_filters: &[(usize, Pubkey)],
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>, RaydiumError> {
+        let mut filters = vec![RpcFilterType::DataSize(data_size as u64)];
+        filters.extend(pubkey_filters.iter().map(|(offset, pubkey)| {
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(*offset, pubkey.as_ref()))
+        }));
+        self.get_program_accounts_with_filters(program, filters)
+            .await
+    }
+
+    /// get the accounts of a program matching every filter
+    pub(crate) async fn get_program_accounts_with_filters(
+        &self,
+        program: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>, RaydiumError> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = self
+            .with_rpc(RpcCall::GetProgramAccounts, None, |client| {
+                let config = config.clone();
+                async move {
+                    client
+                        .get_program_ui_accounts_with_config(program, config)
+                        .await
+                        .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))
+                }
+            })
+            .await?;
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(address, account)| account.data.decode().map(|data| (address, data)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_network_client::SolanaClient;
+
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::{
+        Raydium,
+        config::RaydiumConfig,
+        error::RaydiumError,
+        liquidity::cpmm::RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE,
+        programs::RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID,
+        test_utils::{account_info_response, mock_raydium, mock_raydium_with_config},
+    };
+    use std::{sync::Arc, time::Duration};
+
+    #[tokio::test]
+    async fn test_timeout() -> Result<(), Box<dyn std::error::Error>> {
+        let solana_client = SolanaClient::new(solana_network_client::Mode::MAIN).unwrap();
+        let config = RaydiumConfig::new().with_timeout(Duration::from_millis(10));
+        let raydium = Raydium::new_with_config(Arc::new(solana_client), config);
+        let never = std::future::pending::<Result<(), RaydiumError>>();
+        assert_eq!(
+            raydium.with_timeout(None, never).await,
+            Err(RaydiumError::Timeout(Duration::from_millis(10)))
+        );
+        // the per-call timeout overrides the configured one
+        let never = std::future::pending::<Result<(), RaydiumError>>();
+        assert_eq!(
+            raydium
+                .with_timeout(Some(Duration::from_millis(1)), never)
+                .await,
+            Err(RaydiumError::Timeout(Duration::from_millis(1)))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_owner_check() -> Result<(), Box<dyn std::error::Error>> {
+        let data = vec![0u8; RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE];
+        let forged = Pubkey::new_unique();
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            account_info_response(&RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID, data.clone()),
+        );
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            account_info_response(&forged, data.clone()),
+        );
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            account_info_response(&forged, data.clone()),
+        );
+        let raydium = mock_raydium(mocks);
+        let address = Pubkey::new_unique();
+        assert!(
+            raydium
+                .get_liquidity_pool_cpmm(&address.to_string())
+                .await
+                .is_ok()
+        );
+        // right size, wrong owner
+        assert_eq!(
+            raydium
+                .get_liquidity_pool_cpmm(&address.to_string())
+                .await
+                .err(),
+            Some(RaydiumError::WrongOwner {
+                address,
+                expected: RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID,
+                owner: forged,
+            })
+        );
+        assert!(matches!(
+            raydium.get_pool(&address.to_string()).await,
+            Err(RaydiumError::WrongOwner { .. })
+        ));
+        // forks opt out of the owner check
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            account_info_response(&forged, data),
+        );
+        let config = RaydiumConfig::new().with_skip_owner_check(true);
+        let raydium = mock_raydium_with_config(mocks, config);
+        assert!(
+            raydium
+                .get_liquidity_pool_cpmm(&address.to_string())
+                .await
+                .is_ok()
+        );
+        Ok(())
+    }
+}
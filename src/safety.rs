@@ -0,0 +1,503 @@
+//! authority and token-2022 extension checks run before trading a token
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "rpc")]
+use crate::{
+    Raydium, error::RaydiumError, metrics::RpcCall, monitor::MAX_MULTIPLE_ACCOUNTS,
+    programs::TOKEN_PROGRAM_ID, typs::PoolData,
+};
+use crate::{
+    mints::{
+        EXTENSION_PERMANENT_DELEGATE, EXTENSION_TRANSFER_FEE_CONFIG, EXTENSION_TRANSFER_HOOK,
+        MintInfo, mint_extensions,
+    },
+    programs::TOKEN_2022_PROGRAM_ID,
+    reader::{r_pubkey, r_u16, r_u64},
+    registry::{option_pubkey_string, pubkey_string},
+};
+
+/// transfer fee of a token-2022 mint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferFee {
+    /// fee in basis points of the transferred amount
+    pub basis_points: u16,
+    /// cap on the fee of one transfer, in raw token units
+    pub maximum_fee: u64,
+    /// epoch the fee applies from
+    pub epoch: u64,
+}
+
+/// what the authorities and extensions of a mint allow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenSafetyReport {
+    #[serde(with = "pubkey_string")]
+    pub mint: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub token_program: Pubkey,
+    /// authority able to mint new tokens
+    #[serde(with = "option_pubkey_string")]
+    pub mint_authority: Option<Pubkey>,
+    /// authority able to freeze token accounts
+    #[serde(with = "option_pubkey_string")]
+    pub freeze_authority: Option<Pubkey>,
+    /// program invoked on every transfer
+    #[serde(with = "option_pubkey_string")]
+    pub transfer_hook_program: Option<Pubkey>,
+    /// delegate able to transfer or burn from any token account
+    #[serde(with = "option_pubkey_string")]
+    pub permanent_delegate: Option<Pubkey>,
+    /// the newest transfer fee
+    pub transfer_fee: Option<TransferFee>,
+}
+
+impl TokenSafetyReport {
+    /// report on a parsed mint and its account data, reading the extensions of token-2022 mints
+    pub fn new(mint: Pubkey, info: &MintInfo, data: &[u8]) -> Result<TokenSafetyReport, String> {
+        let mut report = TokenSafetyReport {
+            mint,
+            token_program: info.token_program,
+            mint_authority: info.mint_authority,
+            freeze_authority: info.freeze_authority,
+            transfer_hook_program: None,
+            permanent_delegate: None,
+            transfer_fee: None,
+        };
+        if !info.is_token_2022() {
+            return Ok(report);
+        }
+        for (extension, value) in mint_extensions(data)? {
+            match extension {
+                // the transfer fee config ends with the older and the newer fee
+                EXTENSION_TRANSFER_FEE_CONFIG => {
+                    report.transfer_fee = Some(TransferFee {
+                        epoch: r_u64(value, 90),
+                        maximum_fee: r_u64(value, 98),
+                        basis_points: r_u16(value, 106),
+                    })
+                }
+                EXTENSION_PERMANENT_DELEGATE => {
+                    report.permanent_delegate = non_zero_pubkey(value, 0)
+                }
+                // the hook authority precedes the program
+                EXTENSION_TRANSFER_HOOK => {
+                    report.transfer_hook_program = non_zero_pubkey(value, 32)
+                }
+                _ => {}
+            }
+        }
+        Ok(report)
+    }
+
+    /// the supply can still grow
+    pub fn is_mintable(&self) -> bool {
+        self.mint_authority.is_some()
+    }
+
+    /// token accounts can be frozen
+    pub fn is_freezable(&self) -> bool {
+        self.freeze_authority.is_some()
+    }
+
+    pub fn is_token_2022(&self) -> bool {
+        self.token_program == TOKEN_2022_PROGRAM_ID
+    }
+
+    pub fn has_transfer_hook(&self) -> bool {
+        self.transfer_hook_program.is_some()
+    }
+
+    pub fn has_permanent_delegate(&self) -> bool {
+        self.permanent_delegate.is_some()
+    }
+
+    /// transfers pay a non-zero fee
+    pub fn has_transfer_fee(&self) -> bool {
+        self.transfer_fee.is_some_and(|fee| fee.basis_points > 0)
+    }
+
+    /// none of the checks above is raised
+    pub fn is_safe(&self) -> bool {
+        !(self.is_mintable()
+            || self.is_freezable()
+            || self.has_transfer_hook()
+            || self.has_permanent_delegate()
+            || self.has_transfer_fee())
+    }
+}
+
+/// how much of the LP supply of a v4 pool was burned and how concentrated the rest is
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LpBurnReport {
+    #[serde(with = "pubkey_string")]
+    pub lp_mint: Pubkey,
+    /// current supply of the LP mint, raw
+    pub lp_mint_supply: u64,
+    /// LP supply the pool accounts for, raw. burning shrinks the mint supply but not this
+    pub lp_reserve: u64,
+    /// share of the LP reserve no longer in the mint supply, in percent
+    pub burned_pct: f64,
+    /// share of the mint supply held by the largest LP token account, in percent,
+    /// `None` when it was not queried or nothing is left
+    pub largest_holder_pct: Option<f64>,
+}
+
+impl LpBurnReport {
+    /// report on an LP mint from its supply, the `lp_reserve` of the pool and the balance of
+    /// the largest LP token account. the reserve stands for the minted supply the way explorers
+    /// infer it, so the burned share is the part of it missing from the mint supply
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::safety::LpBurnReport;
+    /// # use solana_sdk::pubkey::Pubkey;
+    /// let report = LpBurnReport::new(Pubkey::new_unique(), 1_000, 250, Some(200));
+    /// assert_eq!((report.burned_pct, report.largest_holder_pct), (75.0, Some(80.0)));
+    /// ```
+    pub fn new(
+        lp_mint: Pubkey,
+        lp_reserve: u64,
+        lp_mint_supply: u64,
+        largest_holder: Option<u64>,
+    ) -> LpBurnReport {
+        let burned_pct = if lp_reserve == 0 {
+            0.0
+        } else {
+            lp_reserve.saturating_sub(lp_mint_supply) as f64 / lp_reserve as f64 * 100.0
+        };
+        LpBurnReport {
+            lp_mint,
+            lp_mint_supply,
+            lp_reserve,
+            burned_pct,
+            largest_holder_pct: largest_holder
+                .filter(|_| lp_mint_supply > 0)
+                .map(|amount| amount as f64 / lp_mint_supply as f64 * 100.0),
+        }
+    }
+}
+
+/// an `OptionalNonZeroPubkey`, where the default key means none
+fn non_zero_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    let pubkey = r_pubkey(data, offset);
+    (pubkey != Pubkey::default()).then_some(pubkey)
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// report the authorities, token program and risky token-2022 extensions of a mint
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # use solana_sdk::pubkey::Pubkey;
+    /// # async fn run(raydium: Raydium, mint: Pubkey) -> Result<(), RaydiumError> {
+    /// let report = raydium.check_token_safety(&mint).await?;
+    /// if report.is_freezable() || report.is_mintable() || report.has_transfer_hook() {
+    ///     println!("skipping {}", report.mint);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn check_token_safety(
+        &self,
+        mint: &Pubkey,
+    ) -> Result<TokenSafetyReport, RaydiumError> {
+        Ok(self.check_tokens_safety(&[*mint]).await?.remove(0))
+    }
+
+    /// safety reports of several mints, in order, fetched in bulk
+    pub async fn check_tokens_safety(
+        &self,
+        mints: &[Pubkey],
+    ) -> Result<Vec<TokenSafetyReport>, RaydiumError> {
+        let mut reports = Vec::with_capacity(mints.len());
+        for chunk in mints.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let accounts = self.get_multiple_accounts_with_context(chunk).await?;
+            for (mint, account) in chunk.iter().zip(accounts.value) {
+                let account = account.ok_or(RaydiumError::AccountNotFound(*mint))?;
+                if account.owner != TOKEN_PROGRAM_ID && account.owner != TOKEN_2022_PROGRAM_ID {
+                    return Err(RaydiumError::WrongOwner {
+                        address: *mint,
+                        expected: TOKEN_PROGRAM_ID,
+                        owner: account.owner,
+                    });
+                }
+                let report = MintInfo::parse(account.owner, &account.data)
+                    .and_then(|info| TokenSafetyReport::new(*mint, &info, &account.data))
+                    .map_err(RaydiumError::Parse)?;
+                reports.push(report);
+            }
+        }
+        Ok(reports)
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// report how much of the LP supply of a v4 pool was burned, comparing the LP mint supply
+    /// with the `lp_reserve` of the pool, and how much of the rest the largest holder owns
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let report = raydium
+    ///     .check_lp_burn_v4("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2")
+    ///     .await?;
+    /// println!("{}", serde_json::to_string(&report).unwrap());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn check_lp_burn_v4(&self, pool_address: &str) -> Result<LpBurnReport, RaydiumError> {
+        let pool = self.get_liquidity_pool_v4(pool_address).await?;
+        let lp_mint = &pool.lp_mint;
+        let account = self
+            .get_multiple_accounts_with_context(&[*lp_mint])
+            .await?
+            .value
+            .pop()
+            .flatten()
+            .ok_or(RaydiumError::AccountNotFound(*lp_mint))?;
+        let supply = MintInfo::parse(account.owner, &account.data)
+            .map_err(RaydiumError::Parse)?
+            .supply;
+        let largest = self
+            .with_rpc(
+                RpcCall::GetTokenLargestAccounts,
+                None,
+                |client| async move {
+                    client
+                        .get_token_largest_accounts(lp_mint)
+                        .await
+                        .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))
+                },
+            )
+            .await?;
+        let largest_holder = largest
+            .first()
+            .map(|holder| holder.amount.amount.parse::<u64>())
+            .transpose()
+            .map_err(|e| RaydiumError::Parse(e.to_string()))?;
+        Ok(LpBurnReport::new(
+            *lp_mint,
+            pool.lp_reserve,
+            supply,
+            largest_holder,
+        ))
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl PoolData {
+    /// safety reports of the base and quote mints
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let pool = raydium.get_pool("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2").await?;
+    /// let (base, _) = pool.safety_report(&raydium).await?;
+    /// println!("{}", serde_json::to_string(&base).unwrap());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn safety_report(
+        &self,
+        raydium: &Raydium,
+    ) -> Result<(TokenSafetyReport, TokenSafetyReport), RaydiumError> {
+        let mut reports = raydium
+            .check_tokens_safety(&[self.base_mint(), self.quote_mint()])
+            .await?;
+        let quote = reports.remove(1);
+        Ok((reports.remove(0), quote))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mints::{MINT_ACCOUNT_TYPE_OFFSET, MINT_DATA_SIZE};
+
+    /// an initialized mint with a freeze authority and the given extensions
+    fn mint_data(freeze_authority: &Pubkey, extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0u8; MINT_DATA_SIZE];
+        data[44] = 6;
+        data[45] = 1;
+        data[46] = 1;
+        data[50..82].copy_from_slice(freeze_authority.as_ref());
+        if !extensions.is_empty() {
+            data.resize(MINT_ACCOUNT_TYPE_OFFSET, 0);
+            data.push(1);
+        }
+        for (extension, value) in extensions {
+            data.extend_from_slice(&extension.to_le_bytes());
+            data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+        data
+    }
+
+    fn transfer_fee_config(basis_points: u16) -> Vec<u8> {
+        let mut value = vec![0u8; 108];
+        value[98..106].copy_from_slice(&5_000u64.to_le_bytes());
+        value[106..108].copy_from_slice(&basis_points.to_le_bytes());
+        value
+    }
+
+    #[test]
+    fn test_token_safety_report() {
+        let (freeze, delegate, hook) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut hook_value = vec![0u8; 64];
+        hook_value[32..].copy_from_slice(hook.as_ref());
+        let data = mint_data(
+            &freeze,
+            &[
+                (EXTENSION_TRANSFER_FEE_CONFIG, transfer_fee_config(150)),
+                (EXTENSION_PERMANENT_DELEGATE, delegate.to_bytes().to_vec()),
+                (EXTENSION_TRANSFER_HOOK, hook_value),
+            ],
+        );
+        let mint = Pubkey::new_unique();
+        let info = MintInfo::parse(TOKEN_2022_PROGRAM_ID, &data).unwrap();
+        let report = TokenSafetyReport::new(mint, &info, &data).unwrap();
+        assert!(!report.is_mintable() && report.is_freezable() && report.is_token_2022());
+        assert_eq!(report.permanent_delegate, Some(delegate));
+        assert_eq!(report.transfer_hook_program, Some(hook));
+        assert_eq!(
+            report
+                .transfer_fee
+                .map(|fee| (fee.basis_points, fee.maximum_fee)),
+            Some((150, 5_000))
+        );
+        assert!(!report.is_safe());
+        // the report round-trips through JSON with keys as strings
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["freeze_authority"], freeze.to_string());
+        assert_eq!(json["mint_authority"], serde_json::Value::Null);
+        assert_eq!(
+            serde_json::from_value::<TokenSafetyReport>(json).unwrap(),
+            report
+        );
+        // an empty hook program and a zero fee raise nothing
+        let data = mint_data(
+            &freeze,
+            &[
+                (EXTENSION_TRANSFER_FEE_CONFIG, transfer_fee_config(0)),
+                (EXTENSION_TRANSFER_HOOK, vec![0u8; 64]),
+            ],
+        );
+        let info = MintInfo::parse(TOKEN_2022_PROGRAM_ID, &data).unwrap();
+        let report = TokenSafetyReport::new(mint, &info, &data).unwrap();
+        assert!(!report.has_transfer_hook() && !report.has_transfer_fee());
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_check_lp_burn_v4() -> Result<(), Box<dyn std::error::Error>> {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+
+        use crate::{
+            liquidity::v4::RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE,
+            programs::RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID,
+            test_utils::{
+                account_info_response, mock_raydium, multiple_accounts_response, ui_account,
+            },
+        };
+
+        let lp_mint = Pubkey::new_unique();
+        let mut pool = vec![0u8; RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE];
+        pool[464..496].copy_from_slice(lp_mint.as_ref());
+        pool[720..728].copy_from_slice(&4_000_000u64.to_le_bytes());
+        // 3 of the 4 LP minted were burned
+        let mut mint = mint_data(&Pubkey::default(), &[]);
+        mint[36..44].copy_from_slice(&1_000_000u64.to_le_bytes());
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            account_info_response(&RAYDIUM_LIQUIDITY_POOL_V4_PROGRAM_ID, pool),
+        );
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![Some(ui_account(&TOKEN_PROGRAM_ID, mint))]),
+        );
+        mocks.insert(
+            RpcRequest::GetTokenLargestAccounts,
+            serde_json::json!({
+                "context": { "slot": 1 },
+                "value": [{
+                    "address": Pubkey::new_unique().to_string(),
+                    "amount": "900000",
+                    "decimals": 6,
+                    "uiAmount": 0.9,
+                    "uiAmountString": "0.9",
+                }],
+            }),
+        );
+        let report = mock_raydium(mocks)
+            .check_lp_burn_v4(&Pubkey::new_unique().to_string())
+            .await?;
+        assert_eq!(
+            report,
+            LpBurnReport {
+                lp_mint,
+                lp_mint_supply: 1_000_000,
+                lp_reserve: 4_000_000,
+                burned_pct: 75.0,
+                largest_holder_pct: Some(90.0),
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(report)?["lp_mint"],
+            lp_mint.to_string()
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_pool_safety_report() -> Result<(), Box<dyn std::error::Error>> {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+
+        use crate::{
+            liquidity::cpmm::RaydiumLiquidityPoolCPMMData,
+            test_utils::{mock_raydium, multiple_accounts_response, ui_account},
+        };
+
+        let freeze = Pubkey::new_unique();
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![
+                Some(ui_account(&TOKEN_PROGRAM_ID, mint_data(&freeze, &[]))),
+                Some(ui_account(
+                    &TOKEN_2022_PROGRAM_ID,
+                    mint_data(
+                        &freeze,
+                        &[(EXTENSION_TRANSFER_FEE_CONFIG, transfer_fee_config(25))],
+                    ),
+                )),
+            ]),
+        );
+        let raydium = mock_raydium(mocks);
+        let pool = PoolData::Cpmm(RaydiumLiquidityPoolCPMMData {
+            token_0_mint: Pubkey::new_unique(),
+            token_1_mint: Pubkey::new_unique(),
+            ..Default::default()
+        });
+        let (base, quote) = pool.safety_report(&raydium).await?;
+        assert_eq!(base.mint, pool.base_mint());
+        assert!(base.is_freezable() && !base.is_token_2022());
+        assert!(quote.has_transfer_fee());
+        // the queue is empty, the mint is missing
+        let missing = Pubkey::new_unique();
+        assert_eq!(
+            raydium.check_token_safety(&missing).await,
+            Err(RaydiumError::AccountNotFound(missing))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,711 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    str::FromStr,
+    sync::atomic::Ordering,
+    time::Duration,
+};
+
+use futures::{Stream, StreamExt, future, stream};
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::{
+    nonblocking::pubsub_client::PubsubClient,
+    rpc_config::{
+        CommitmentConfig, RpcAccountInfoConfig, RpcTransactionConfig, RpcTransactionLogsConfig,
+        RpcTransactionLogsFilter, UiTransactionEncoding,
+    },
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use tokio::sync::mpsc;
+
+use crate::{
+    Raydium,
+    error::RaydiumError,
+    liquidity::v4::pda::{DerivedPoolAccounts, derive_pool_accounts},
+    metrics::RpcCall,
+    rpc::parse_address,
+    typs::{Contextual, PoolData, PoolKind},
+};
+
+/// number of pool updates buffered for a slow consumer
+const SUBSCRIPTION_CHANNEL_SIZE: usize = 64;
+/// number of recently seen pools remembered to drop duplicate events
+const NEW_POOL_DEDUP_SIZE: usize = 10_000;
+/// first delay before reconnecting a dropped websocket, doubled up to the maximum
+const RECONNECT_DELAY: Duration = Duration::from_millis(500);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// v4 `initialize2` instruction tag
+pub const V4_INITIALIZE2_TAG: u8 = 1;
+/// index of the market in the accounts of `initialize2`
+const V4_INITIALIZE2_MARKET_INDEX: usize = 16;
+/// anchor discriminator of the CPMM and launchpad `initialize` instructions
+pub const INITIALIZE_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+/// anchor discriminator of the CLMM `create_pool` instruction
+pub const CREATE_POOL_DISCRIMINATOR: [u8; 8] = [233, 146, 209, 142, 207, 104, 64, 188];
+
+/// an item of a pool subscription
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum PoolEvent {
+    /// the pool state at the slot of the notification
+    Update(Contextual<PoolData>),
+    /// the websocket was re-established after a disconnect, updates of the last
+    /// `gap_slots` slots may have been missed. the refetched state follows
+    Reconnected { gap_slots: u64 },
+}
+
+/// a pool created on chain
+#[derive(Debug, Clone)]
+pub struct NewPoolEvent {
+    pub kind: PoolKind,
+    pub pool: Pubkey,
+    /// v4 coin mint, CPMM/CLMM token 0, launchpad base mint
+    pub base_mint: Pubkey,
+    /// v4 pc mint, CPMM/CLMM token 1, launchpad quote mint
+    pub quote_mint: Pubkey,
+    pub creator: Pubkey,
+    pub signature: Signature,
+    pub slot: u64,
+    /// pool state fetched once after creation, when requested and available
+    pub state: Option<PoolData>,
+    /// v4 only, the accounts the program derives from the market of the pool. pools created
+    /// with the seeded `initialize2` have `pool == derived.amm_id`
+    pub derived: Option<DerivedPoolAccounts>,
+}
+
+/// accounts of a pool creation instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NewPoolAccounts {
+    pub pool: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub creator: Pubkey,
+    /// serum / OpenBook market of a v4 pool
+    pub market: Option<Pubkey>,
+}
+
+/// recognize a pool creation instruction of the given type and extract its accounts
+/// (pool, base mint, quote mint, creator indexes in the instruction accounts)
+pub fn parse_new_pool_instruction(
+    kind: PoolKind,
+    accounts: &[Pubkey],
+    data: &[u8],
+) -> Option<NewPoolAccounts> {
+    let [pool, base_mint, quote_mint, creator] = match kind {
+        PoolKind::V4 if data.first() == Some(&V4_INITIALIZE2_TAG) => [4, 8, 9, 17],
+        PoolKind::Cpmm if data.starts_with(&INITIALIZE_DISCRIMINATOR) => [3, 4, 5, 0],
+        PoolKind::Clmm if data.starts_with(&CREATE_POOL_DISCRIMINATOR) => [2, 3, 4, 0],
+        PoolKind::Launchpad if data.starts_with(&INITIALIZE_DISCRIMINATOR) => [5, 6, 7, 1],
+        _ => return None,
+    };
+    Some(NewPoolAccounts {
+        pool: *accounts.get(pool)?,
+        base_mint: *accounts.get(base_mint)?,
+        quote_mint: *accounts.get(quote_mint)?,
+        creator: *accounts.get(creator)?,
+        market: match kind {
+            PoolKind::V4 => Some(*accounts.get(V4_INITIALIZE2_MARKET_INDEX)?),
+            _ => None,
+        },
+    })
+}
+
+/// whether the program logs announce a pool creation of the given type
+pub fn logs_announce_new_pool(kind: PoolKind, logs: &[String]) -> bool {
+    logs.iter().any(|log| match kind {
+        PoolKind::V4 => log.starts_with("Program log: initialize2"),
+        PoolKind::Cpmm | PoolKind::Launchpad => log == "Program log: Instruction: Initialize",
+        PoolKind::Clmm => log == "Program log: Instruction: CreatePool",
+        PoolKind::Stable => false,
+    })
+}
+
+/// a bounded set of recently seen keys
+struct RecentSet {
+    order: VecDeque<Pubkey>,
+    keys: HashSet<Pubkey>,
+}
+
+impl RecentSet {
+    fn new() -> Self {
+        Self {
+            order: VecDeque::new(),
+            keys: HashSet::new(),
+        }
+    }
+
+    /// remember the key, returns false if it was already seen
+    fn insert(&mut self, key: Pubkey) -> bool {
+        if !self.keys.insert(key) {
+            return false;
+        }
+        self.order.push_back(key);
+        if self.order.len() > NEW_POOL_DEDUP_SIZE
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.keys.remove(&oldest);
+        }
+        true
+    }
+}
+
+/// exponential reconnection delay, bounded in consecutive attempts when configured
+struct Backoff {
+    delay: Duration,
+    attempts: u32,
+    max_attempts: Option<u32>,
+}
+
+impl Backoff {
+    fn new(max_attempts: Option<u32>) -> Self {
+        Self {
+            delay: RECONNECT_DELAY,
+            attempts: 0,
+            max_attempts,
+        }
+    }
+
+    /// delay before the next attempt, `None` once the attempts are exhausted
+    fn next_delay(&mut self) -> Option<Duration> {
+        if self.max_attempts.is_some_and(|max| self.attempts >= max) {
+            return None;
+        }
+        self.attempts += 1;
+        let delay = self.delay;
+        self.delay = (self.delay * 2).min(MAX_RECONNECT_DELAY);
+        Some(delay)
+    }
+
+    fn reset(&mut self) {
+        self.delay = RECONNECT_DELAY;
+        self.attempts = 0;
+    }
+}
+
+impl Raydium {
+    /// subscribe to the parsed state of a pool of any type
+    /// the stream yields the current state first, then every on-chain update.
+    /// a dropped websocket is reconnected and the state refetched, see `subscribe_pool_events`
+    /// to be told about reconnections. dropping the stream unsubscribes and closes the websocket.
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # use futures::StreamExt;
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let mut updates = Box::pin(
+    ///     raydium
+    ///         .subscribe_pool("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2")
+    ///         .await?,
+    /// );
+    /// while let Some(pool) = updates.next().await {
+    ///     println!("{:?}", pool.map(|p| p.kind()));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subscribe_pool(
+        &self,
+        address: &str,
+    ) -> Result<impl Stream<Item = Result<PoolData, RaydiumError>> + Send + 'static, RaydiumError>
+    {
+        let events = self.subscribe_pool_events(address).await?;
+        Ok(events.filter_map(|event| {
+            future::ready(match event {
+                Ok(PoolEvent::Update(pool)) => Some(Ok(pool.value)),
+                Ok(PoolEvent::Reconnected { .. }) => None,
+                Err(e) => Some(Err(e)),
+            })
+        }))
+    }
+
+    /// subscribe to the parsed state of a pool of any type, with the slot of every update.
+    /// when the websocket drops it is resubscribed with exponential backoff, up to
+    /// `RaydiumConfig::max_reconnects` consecutive attempts, then a `Reconnected` marker
+    /// and the refetched state are yielded. the stream ends with an error once the
+    /// attempts are exhausted
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, subscription::PoolEvent};
+    /// # use futures::StreamExt;
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let mut events = Box::pin(
+    ///     raydium
+    ///         .subscribe_pool_events("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2")
+    ///         .await?,
+    /// );
+    /// while let Some(event) = events.next().await {
+    ///     match event? {
+    ///         PoolEvent::Update(pool) => println!("{} {:?}", pool.slot, pool.value.kind()),
+    ///         PoolEvent::Reconnected { gap_slots } => println!("missed up to {} slots", gap_slots),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subscribe_pool_events(
+        &self,
+        address: &str,
+    ) -> Result<impl Stream<Item = Result<PoolEvent, RaydiumError>> + Send + 'static, RaydiumError>
+    {
+        let pubkey = parse_address(address)?;
+        let initial = self.get_pool_with_context(address).await?;
+        let kind = initial.value.kind();
+        let slot = initial.slot;
+        let (sender, receiver) = mpsc::channel(SUBSCRIPTION_CHANNEL_SIZE);
+        // the channel is empty, so this cannot wait
+        let _ = sender.send(Ok(PoolEvent::Update(initial))).await;
+        tokio::spawn(forward_pool_events(
+            self.clone(),
+            pubkey,
+            kind,
+            slot,
+            sender,
+        ));
+        Ok(stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|item| (item, receiver))
+        }))
+    }
+
+    /// number of websocket subscriptions re-established after a disconnect,
+    /// counted across every clone of the handle
+    pub fn subscription_reconnects(&self) -> u64 {
+        self.reconnects.load(Ordering::Relaxed)
+    }
+
+    /// subscribe to the creation of new pools of the given types (all types when empty).
+    /// the websocket is reconnected when dropped, up to `RaydiumConfig::max_reconnects`
+    /// consecutive attempts, and pools seen twice are reported once
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, typs::PoolKind};
+    /// # use futures::StreamExt;
+    /// # async fn run(raydium: Raydium) {
+    /// let mut pools = Box::pin(raydium.subscribe_new_pools(&[PoolKind::Cpmm, PoolKind::Launchpad]));
+    /// while let Some(event) = pools.next().await {
+    ///     println!("{:?} {} {}/{}", event.kind, event.pool, event.base_mint, event.quote_mint);
+    /// }
+    /// # }
+    /// ```
+    pub fn subscribe_new_pools(
+        &self,
+        kinds: &[PoolKind],
+    ) -> impl Stream<Item = NewPoolEvent> + Send + 'static {
+        self.new_pool_stream(kinds, false)
+    }
+
+    /// subscribe to the creation of new pools, fetching the state of each new pool once
+    pub fn subscribe_new_pools_with_state(
+        &self,
+        kinds: &[PoolKind],
+    ) -> impl Stream<Item = NewPoolEvent> + Send + 'static {
+        self.new_pool_stream(kinds, true)
+    }
+
+    fn new_pool_stream(
+        &self,
+        kinds: &[PoolKind],
+        fetch_state: bool,
+    ) -> impl Stream<Item = NewPoolEvent> + Send + 'static {
+        let kinds = if kinds.is_empty() {
+            &[
+                PoolKind::V4,
+                PoolKind::Cpmm,
+                PoolKind::Clmm,
+                PoolKind::Launchpad,
+            ][..]
+        } else {
+            kinds
+        };
+        let programs = kinds
+            .iter()
+            .map(|kind| (*kind, self.program_id(*kind)))
+            .collect();
+        let (sender, receiver) = mpsc::channel(SUBSCRIPTION_CHANNEL_SIZE);
+        tokio::spawn(forward_new_pools(
+            self.clone(),
+            programs,
+            fetch_state,
+            sender,
+        ));
+        stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|item| (item, receiver))
+        })
+    }
+
+    /// find the pool creations of the given program in a transaction
+    async fn get_new_pools_in_transaction(
+        &self,
+        kind: PoolKind,
+        program: &Pubkey,
+        signature: &Signature,
+    ) -> Result<(u64, Vec<NewPoolAccounts>), RaydiumError> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let transaction = self
+            .with_rpc(RpcCall::GetTransaction, None, |client| async move {
+                client
+                    .get_transaction_with_config(signature, config)
+                    .await
+                    .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))
+            })
+            .await?;
+        let decoded = transaction
+            .transaction
+            .transaction
+            .decode()
+            .ok_or_else(|| RaydiumError::Parse("undecodable transaction".to_string()))?;
+        let message = &decoded.message;
+        let mut keys: Vec<Pubkey> = message.static_account_keys().to_vec();
+        if let Some(loaded) = transaction
+            .transaction
+            .meta
+            .and_then(|meta| meta.loaded_addresses.ok_or(()).ok())
+        {
+            for address in loaded.writable.iter().chain(loaded.readonly.iter()) {
+                keys.push(parse_address(address)?);
+            }
+        }
+        let pools = message
+            .instructions()
+            .iter()
+            .filter(|ix| keys.get(ix.program_id_index as usize) == Some(program))
+            .filter_map(|ix| {
+                let accounts: Option<Vec<Pubkey>> = ix
+                    .accounts
+                    .iter()
+                    .map(|i| keys.get(*i as usize).copied())
+                    .collect();
+                parse_new_pool_instruction(kind, &accounts?, &ix.data)
+            })
+            .collect();
+        Ok((transaction.slot, pools))
+    }
+
+    /// websocket endpoint used by subscriptions
+    pub fn ws_url(&self) -> String {
+        match &self.config.ws_url {
+            Some(ws_url) => ws_url.clone(),
+            None => http_to_ws_url(&self.solana_client.client_arc().url()),
+        }
+    }
+}
+
+/// derive the websocket endpoint from an http rpc endpoint
+pub fn http_to_ws_url(url: &str) -> String {
+    if let Some(rest) = url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        url.to_string()
+    }
+}
+
+/// decode an account notification into pool data of the given type
+pub fn decode_pool_update(kind: PoolKind, account: &UiAccount) -> Result<PoolData, RaydiumError> {
+    let data = account
+        .data
+        .decode()
+        .ok_or_else(|| RaydiumError::Parse("undecodable account notification".to_string()))?;
+    PoolData::parse(kind, &data).map_err(RaydiumError::Parse)
+}
+
+/// forward account notifications until the consumer drops the stream.
+/// a dropped websocket is resubscribed with backoff, then the state is refetched and
+/// announced by a `Reconnected` marker
+async fn forward_pool_events(
+    raydium: Raydium,
+    pubkey: Pubkey,
+    kind: PoolKind,
+    mut last_slot: u64,
+    sender: mpsc::Sender<Result<PoolEvent, RaydiumError>>,
+) {
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64Zstd),
+        ..RpcAccountInfoConfig::default()
+    };
+    let mut backoff = Backoff::new(raydium.config.max_reconnects);
+    let mut reconnecting = false;
+    loop {
+        if reconnecting {
+            let Some(delay) = backoff.next_delay() else {
+                let _ = sender
+                    .send(Err(RaydiumError::Rpc(
+                        "websocket reconnection attempts exhausted".to_string(),
+                    )))
+                    .await;
+                return;
+            };
+            tokio::select! {
+                _ = sender.closed() => return,
+                _ = tokio::time::sleep(delay) => {}
+            }
+        }
+        raydium.throttle().await;
+        let Ok(client) = PubsubClient::new(&raydium.ws_url()).await else {
+            reconnecting = true;
+            continue;
+        };
+        raydium.throttle().await;
+        let Ok((mut updates, unsubscribe)) = client
+            .account_subscribe(&pubkey, Some(config.clone()))
+            .await
+        else {
+            reconnecting = true;
+            continue;
+        };
+        let mut closed = false;
+        if reconnecting {
+            // notifications sent while disconnected are lost, the current state replaces them
+            raydium.invalidate(&pubkey);
+            let Ok(state) = raydium.get_pool_with_context(&pubkey.to_string()).await else {
+                continue;
+            };
+            raydium.reconnects.fetch_add(1, Ordering::Relaxed);
+            let gap_slots = state.slot.saturating_sub(last_slot);
+            last_slot = last_slot.max(state.slot);
+            closed = sender
+                .send(Ok(PoolEvent::Reconnected { gap_slots }))
+                .await
+                .is_err()
+                || sender.send(Ok(PoolEvent::Update(state))).await.is_err();
+        }
+        backoff.reset();
+        while !closed {
+            tokio::select! {
+                _ = sender.closed() => closed = true,
+                update = updates.next() => match update {
+                    Some(update) => {
+                        let slot = update.context.slot;
+                        last_slot = last_slot.max(slot);
+                        let event = raydium
+                            .parse_with_metrics(Some(kind), || decode_pool_update(kind, &update.value))
+                            .map(|pool| PoolEvent::Update(Contextual::new(slot, pool)));
+                        closed = sender.send(event).await.is_err();
+                    }
+                    None => break,
+                },
+            }
+        }
+        drop(updates);
+        unsubscribe().await;
+        let _ = client.shutdown().await;
+        if closed {
+            return;
+        }
+        reconnecting = true;
+    }
+}
+
+/// forward pool creations until the consumer drops the stream, reconnecting on failures
+async fn forward_new_pools(
+    raydium: Raydium,
+    programs: Vec<(PoolKind, Pubkey)>,
+    fetch_state: bool,
+    sender: mpsc::Sender<NewPoolEvent>,
+) {
+    let mut seen = RecentSet::new();
+    let mut backoff = Backoff::new(raydium.config.max_reconnects);
+    let mut connected = false;
+    while !sender.is_closed() {
+        raydium.throttle().await;
+        if let Ok(client) = PubsubClient::new(&raydium.ws_url()).await {
+            let mut subscriptions = Vec::new();
+            let mut unsubscribes = Vec::new();
+            for (kind, program) in &programs {
+                let filter = RpcTransactionLogsFilter::Mentions(vec![program.to_string()]);
+                let config = RpcTransactionLogsConfig {
+                    commitment: Some(CommitmentConfig::confirmed()),
+                };
+                raydium.throttle().await;
+                if let Ok((logs, unsubscribe)) = client.logs_subscribe(filter, config).await {
+                    subscriptions.push(logs.map(move |logs| (*kind, *program, logs)).boxed());
+                    unsubscribes.push(unsubscribe);
+                }
+            }
+            if !subscriptions.is_empty() {
+                if connected {
+                    raydium.reconnects.fetch_add(1, Ordering::Relaxed);
+                }
+                connected = true;
+                backoff.reset();
+            }
+            let mut logs = stream::select_all(subscriptions);
+            loop {
+                let (kind, program, response) = tokio::select! {
+                    _ = sender.closed() => break,
+                    logs = logs.next() => match logs {
+                        Some(logs) => logs,
+                        None => break,
+                    },
+                };
+                let logs = response.value;
+                if logs.err.is_some() || !logs_announce_new_pool(kind, &logs.logs) {
+                    continue;
+                }
+                let Ok(signature) = Signature::from_str(&logs.signature) else {
+                    continue;
+                };
+                let Ok((slot, pools)) = raydium
+                    .get_new_pools_in_transaction(kind, &program, &signature)
+                    .await
+                else {
+                    continue;
+                };
+                for pool in pools {
+                    if !seen.insert(pool.pool) {
+                        continue;
+                    }
+                    let state = if fetch_state {
+                        raydium.get_pool(&pool.pool.to_string()).await.ok()
+                    } else {
+                        None
+                    };
+                    let event = NewPoolEvent {
+                        kind,
+                        pool: pool.pool,
+                        base_mint: pool.base_mint,
+                        quote_mint: pool.quote_mint,
+                        creator: pool.creator,
+                        signature,
+                        slot,
+                        state,
+                        derived: pool
+                            .market
+                            .map(|market| derive_pool_accounts(&market, &program)),
+                    };
+                    if sender.send(event).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            drop(logs);
+            for unsubscribe in unsubscribes {
+                unsubscribe().await;
+            }
+            let _ = client.shutdown().await;
+        }
+        let Some(delay) = backoff.next_delay() else {
+            break;
+        };
+        tokio::select! {
+            _ = sender.closed() => break,
+            _ = tokio::time::sleep(delay) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_account_decoder::{UiAccountEncoding, encode_ui_account};
+    use solana_sdk::{account::Account, pubkey::Pubkey};
+
+    use super::*;
+    use crate::liquidity::cpmm::RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE;
+
+    fn notification(data: Vec<u8>, encoding: UiAccountEncoding) -> UiAccount {
+        let account = Account {
+            lamports: 1,
+            data,
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        };
+        encode_ui_account(&Pubkey::new_unique(), &account, encoding, None, None)
+    }
+
+    #[test]
+    fn test_decode_pool_update() {
+        let data = vec![0u8; RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE];
+        for encoding in [UiAccountEncoding::Base64, UiAccountEncoding::Base64Zstd] {
+            let pool = decode_pool_update(PoolKind::Cpmm, &notification(data.clone(), encoding));
+            assert_eq!(pool.map(|p| p.kind()), Ok(PoolKind::Cpmm));
+        }
+        // a data length change yields an error item instead of a panic
+        let resized = notification(vec![0u8; 700], UiAccountEncoding::Base64Zstd);
+        assert!(matches!(
+            decode_pool_update(PoolKind::Cpmm, &resized),
+            Err(RaydiumError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_http_to_ws_url() {
+        assert_eq!(
+            http_to_ws_url("https://api.mainnet-beta.solana.com"),
+            "wss://api.mainnet-beta.solana.com"
+        );
+        assert_eq!(
+            http_to_ws_url("http://127.0.0.1:8899"),
+            "ws://127.0.0.1:8899"
+        );
+    }
+
+    #[test]
+    fn test_parse_new_pool_instruction() {
+        let accounts: Vec<Pubkey> = (0..21).map(|_| Pubkey::new_unique()).collect();
+        let mut data = CREATE_POOL_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[0u8; 24]);
+        assert_eq!(
+            parse_new_pool_instruction(PoolKind::Clmm, &accounts, &data),
+            Some(NewPoolAccounts {
+                pool: accounts[2],
+                base_mint: accounts[3],
+                quote_mint: accounts[4],
+                creator: accounts[0],
+                market: None,
+            })
+        );
+        // a CPMM swap is not a pool creation
+        assert_eq!(
+            parse_new_pool_instruction(PoolKind::Cpmm, &accounts, &[143, 190, 90, 218]),
+            None
+        );
+        let v4 = parse_new_pool_instruction(PoolKind::V4, &accounts, &[V4_INITIALIZE2_TAG, 254]);
+        assert_eq!(
+            v4.map(|p| (p.pool, p.creator, p.market)),
+            Some((accounts[4], accounts[17], Some(accounts[16])))
+        );
+        // too few accounts
+        assert_eq!(
+            parse_new_pool_instruction(PoolKind::V4, &accounts[..10], &[V4_INITIALIZE2_TAG]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_logs_announce_new_pool() {
+        let logs = vec![
+            "Program log: Instruction: InitializeAccount3".to_string(),
+            "Program log: Instruction: Initialize".to_string(),
+        ];
+        assert!(logs_announce_new_pool(PoolKind::Cpmm, &logs));
+        assert!(!logs_announce_new_pool(PoolKind::Cpmm, &logs[..1]));
+        assert!(!logs_announce_new_pool(PoolKind::Clmm, &logs));
+    }
+
+    #[test]
+    fn test_recent_set() {
+        let mut seen = RecentSet::new();
+        let pool = Pubkey::new_unique();
+        assert!(seen.insert(pool));
+        assert!(!seen.insert(pool));
+    }
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = Backoff::new(Some(2));
+        assert_eq!(backoff.next_delay(), Some(RECONNECT_DELAY));
+        assert_eq!(backoff.next_delay(), Some(RECONNECT_DELAY * 2));
+        assert_eq!(backoff.next_delay(), None);
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Some(RECONNECT_DELAY));
+        // unbounded attempts are capped at the maximum delay
+        let mut backoff = Backoff::new(None);
+        let delays: Vec<_> = (0..20).filter_map(|_| backoff.next_delay()).collect();
+        assert_eq!(delays.last(), Some(&MAX_RECONNECT_DELAY));
+    }
+}
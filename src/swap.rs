@@ -0,0 +1,315 @@
+//! swap transactions built from a quote against any pool type
+
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, transaction::Transaction};
+
+use crate::{
+    Raydium,
+    error::RaydiumError,
+    launchpad::instructions::swap_exact_in,
+    liquidity::{cpmm::instructions::swap_base_input, v4::instructions::swap_base_in},
+    quote::SwapQuote,
+    routing::unix_now,
+    rpc::parse_address,
+    token_accounts::associated_token_address,
+    typs::{MintSide, PoolData, PoolKind},
+    wsol::{WsolStrategy, wsol_legs},
+};
+
+/// an unsigned swap transaction with the quote it was built from
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuiltSwap {
+    /// paid by the user, to be signed with a recent blockhash
+    pub transaction: Transaction,
+    /// the instructions of the transaction, for `Raydium::send_transaction`
+    pub instructions: Vec<Instruction>,
+    pub quote: SwapQuote,
+    /// the output below which the swap fails
+    pub min_amount_out: u64,
+}
+
+/// the quoted output reduced by `slippage_bps`, rounded down
+pub fn min_amount_out(amount_out: u64, slippage_bps: u16) -> u64 {
+    let kept = 10_000 - slippage_bps.min(10_000) as u128;
+    (amount_out as u128 * kept / 10_000) as u64
+}
+
+impl Raydium {
+    /// quote a swap of `amount_in` of `input_mint` and build the unsigned transaction executing
+    /// it for `user`, failing on chain when the output falls below the quote minus `slippage_bps`.
+    /// the output associated token account is created when missing and WSOL is wrapped and
+    /// unwrapped around the swap in an ephemeral account. the input account must hold the amount.
+    /// v4, CPMM and launchpad pools are supported, CLMM swaps need tick arrays
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::WSOL_MINT};
+    /// # use solana_sdk::{hash::Hash, signature::Keypair, signer::Signer};
+    /// # async fn run(raydium: Raydium, user: Keypair, blockhash: Hash) -> Result<(), RaydiumError> {
+    /// let mut built = raydium
+    ///     .build_swap_transaction(
+    ///         "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2",
+    ///         &WSOL_MINT,
+    ///         1_000_000_000,
+    ///         50,
+    ///         &user.pubkey(),
+    ///     )
+    ///     .await?;
+    /// println!("out {} at least {}", built.quote.amount_out, built.min_amount_out);
+    /// built.transaction.sign(&[&user], blockhash);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn build_swap_transaction(
+        &self,
+        pool_address: &str,
+        input_mint: &Pubkey,
+        amount_in: u64,
+        slippage_bps: u16,
+        user: &Pubkey,
+    ) -> Result<BuiltSwap, RaydiumError> {
+        let pool_id = parse_address(pool_address)?;
+        let pool = self.get_pool(pool_address).await?;
+        let side = pool
+            .side_of(input_mint)
+            .ok_or(RaydiumError::MintNotInPool(*input_mint))?;
+        if !pool.is_tradable(unix_now()) {
+            return Err(RaydiumError::PoolNotTradable(pool_id));
+        }
+        // CLMM pools do not record their token programs and are not supported below
+        let Some((base_program, quote_program)) = pool.token_programs() else {
+            return Err(RaydiumError::Unsupported(
+                "swap instructions of CLMM pools".to_string(),
+            ));
+        };
+        let quote = self.quote_swap_pool(&pool, input_mint, amount_in).await?;
+        let min_amount_out = min_amount_out(quote.amount_out, slippage_bps);
+        if min_amount_out == 0 {
+            return Err(RaydiumError::AmountTooSmall(amount_in));
+        }
+        let output_mint = quote.output_mint;
+        let (input_program, output_program) = match side {
+            MintSide::Base => (base_program, quote_program),
+            MintSide::Quote => (quote_program, base_program),
+        };
+        let legs = wsol_legs(
+            user,
+            input_mint,
+            &output_mint,
+            amount_in,
+            &WsolStrategy::ephemeral(),
+        )?;
+        let user_input = legs
+            .input_account
+            .unwrap_or_else(|| associated_token_address(user, input_mint, &input_program));
+        let mut instructions = Vec::new();
+        let user_output = match legs.output_account {
+            Some(account) => account,
+            None => {
+                let resolved = self
+                    .resolve_token_accounts(user, &[output_mint], &[output_program])
+                    .await?;
+                instructions.extend(resolved.create_instructions);
+                resolved.accounts[0]
+            }
+        };
+        let swap = match &pool {
+            PoolData::V4(p) => {
+                let keys = self.pool_keys_v4_of(pool_id, p, false).await?;
+                swap_base_in(
+                    &keys,
+                    user,
+                    &user_input,
+                    &user_output,
+                    amount_in,
+                    min_amount_out,
+                )
+            }
+            PoolData::Cpmm(p) => swap_base_input(
+                &self.program_id(PoolKind::Cpmm),
+                &pool_id,
+                p,
+                user,
+                input_mint,
+                &user_input,
+                &user_output,
+                amount_in,
+                min_amount_out,
+            )
+            .ok_or(RaydiumError::MintNotInPool(*input_mint))?,
+            PoolData::Launchpad(p) => {
+                let buy = side == MintSide::Quote;
+                let (user_base, user_quote) = if buy {
+                    (user_output, user_input)
+                } else {
+                    (user_input, user_output)
+                };
+                swap_exact_in(
+                    &self.program_id(PoolKind::Launchpad),
+                    &pool_id,
+                    p,
+                    user,
+                    &user_base,
+                    &user_quote,
+                    buy,
+                    amount_in,
+                    min_amount_out,
+                )
+            }
+            PoolData::Clmm(_) => {
+                return Err(RaydiumError::Unsupported(
+                    "swap instructions of CLMM pools".to_string(),
+                ));
+            }
+            PoolData::Stable(_) => {
+                return Err(RaydiumError::Unsupported(
+                    "swap instructions of stable pools".to_string(),
+                ));
+            }
+        };
+        instructions.extend(legs.wrap(vec![swap]));
+        Ok(BuiltSwap {
+            transaction: Transaction::new_with_payer(&instructions, Some(user)),
+            instructions,
+            quote,
+            min_amount_out,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+
+    use super::*;
+    use crate::{
+        liquidity::cpmm::{
+            RAYDIUM_CPMM_AMM_CONFIG_TRADE_FEE_RATE_OFFSET, RAYDIUM_CPMM_FEE_RATE_DENOMINATOR,
+            RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE, instructions::SWAP_BASE_INPUT_DISCRIMINATOR,
+        },
+        mints::WSOL_MINT,
+        programs::{RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID, TOKEN_PROGRAM_ID},
+        quote::constant_product_swap,
+        test_utils::{account_info_response, mock_raydium, multiple_accounts_response, ui_account},
+    };
+
+    /// CPMM pool of WSOL (token 0) against `mint` (token 1), both owned by the token program
+    fn cpmm_pool(mint: &Pubkey, open_time: u64) -> Vec<u8> {
+        let mut data = vec![0u8; RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE];
+        let mut key = |offset: usize, key: &Pubkey| {
+            data[offset..offset + 32].copy_from_slice(key.as_ref());
+        };
+        // amm config, creator, vaults and lp mint precede the mints, the programs and the observation
+        for offset in [8, 72, 104, 296] {
+            key(offset, &Pubkey::new_unique());
+        }
+        key(168, &WSOL_MINT);
+        key(200, mint);
+        key(232, &TOKEN_PROGRAM_ID);
+        key(264, &TOKEN_PROGRAM_ID);
+        data[331] = 9;
+        data[332] = 6;
+        data[376..384].copy_from_slice(&open_time.to_le_bytes());
+        data
+    }
+
+    /// getMultipleAccounts response of the vaults and amm config read by the quote
+    fn quote_accounts() -> serde_json::Value {
+        let vault = |amount: u64| {
+            let mut data = vec![0u8; 165];
+            data[64..72].copy_from_slice(&amount.to_le_bytes());
+            Some(ui_account(&TOKEN_PROGRAM_ID, data))
+        };
+        let mut config = vec![0u8; 236];
+        let offset = RAYDIUM_CPMM_AMM_CONFIG_TRADE_FEE_RATE_OFFSET;
+        config[offset..offset + 8].copy_from_slice(&2_500u64.to_le_bytes());
+        multiple_accounts_response(vec![
+            vault(1_000_000_000_000),
+            vault(150_000_000_000),
+            Some(ui_account(&RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID, config)),
+        ])
+    }
+
+    #[test]
+    fn test_min_amount_out() {
+        assert_eq!(min_amount_out(1_000_000, 50), 995_000);
+        assert_eq!(min_amount_out(199, 100), 197);
+        assert_eq!(min_amount_out(u64::MAX, 0), u64::MAX);
+        assert_eq!(min_amount_out(1_000, 20_000), 0);
+    }
+
+    #[tokio::test]
+    async fn test_build_swap_transaction() -> Result<(), Box<dyn std::error::Error>> {
+        let (mint, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let pool_address = Pubkey::new_unique().to_string();
+        let mut mocks = MocksMap::default();
+        let pool =
+            account_info_response(&RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID, cpmm_pool(&mint, 0));
+        // selling WSOL: the pool, the quote accounts, then the missing output account
+        mocks.insert(RpcRequest::GetAccountInfo, pool.clone());
+        mocks.insert(RpcRequest::GetMultipleAccounts, quote_accounts());
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![None]),
+        );
+        // an unknown input mint, then a swap so small nothing comes out
+        mocks.insert(RpcRequest::GetAccountInfo, pool.clone());
+        mocks.insert(RpcRequest::GetAccountInfo, pool);
+        mocks.insert(RpcRequest::GetMultipleAccounts, quote_accounts());
+        // a pool opening in the future
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            account_info_response(
+                &RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID,
+                cpmm_pool(&mint, u64::MAX),
+            ),
+        );
+        let raydium = mock_raydium(mocks);
+
+        let built = raydium
+            .build_swap_transaction(&pool_address, &WSOL_MINT, 1_000_000_000, 100, &user)
+            .await?;
+        let expected = constant_product_swap(
+            1_000_000_000_000,
+            150_000_000_000,
+            1_000_000_000,
+            2_500,
+            RAYDIUM_CPMM_FEE_RATE_DENOMINATOR,
+        )?;
+        assert_eq!(built.quote.amount_out, expected.amount_out);
+        assert_eq!(built.min_amount_out, expected.amount_out * 99 / 100);
+        // create the output account, wrap into an ephemeral account, swap, unwrap
+        let programs: Vec<Pubkey> = built.instructions.iter().map(|i| i.program_id).collect();
+        assert_eq!(programs.len(), 5);
+        assert_eq!(programs[3], RAYDIUM_LIQUIDITY_POOL_CPMM_PROGRAM_ID);
+        let swap = &built.instructions[3];
+        assert_eq!(&swap.data[..8], SWAP_BASE_INPUT_DISCRIMINATOR);
+        assert_eq!(swap.data[16..], built.min_amount_out.to_le_bytes());
+        assert_eq!(
+            swap.accounts[5].pubkey,
+            associated_token_address(&user, &mint, &TOKEN_PROGRAM_ID)
+        );
+        assert_eq!(built.transaction.message.account_keys[0], user);
+        assert_eq!(built.transaction.signatures.len(), 1);
+
+        let missing = Pubkey::new_unique();
+        assert_eq!(
+            raydium
+                .build_swap_transaction(&pool_address, &missing, 1_000, 100, &user)
+                .await,
+            Err(RaydiumError::MintNotInPool(missing))
+        );
+        assert_eq!(
+            raydium
+                .build_swap_transaction(&pool_address, &WSOL_MINT, 1, 100, &user)
+                .await,
+            Err(RaydiumError::AmountTooSmall(1))
+        );
+        assert_eq!(
+            raydium
+                .build_swap_transaction(&pool_address, &WSOL_MINT, 1_000, 100, &user)
+                .await,
+            Err(RaydiumError::PoolNotTradable(pool_address.parse()?))
+        );
+        Ok(())
+    }
+}
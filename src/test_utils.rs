@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use solana_account_decoder::{UiAccount, UiAccountEncoding, encode_ui_account};
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::UiTransactionEncoding,
+    rpc_response::{Response, RpcResponseContext},
+};
+use solana_network_client::SolanaClient;
+use solana_rpc_client::mock_sender::MocksMap;
+use solana_sdk::{account::Account, pubkey::Pubkey, transaction::Transaction};
+
+use crate::{Raydium, config::RaydiumConfig};
+
+/// raydium backed by a mock rpc client answering with the queued responses
+pub(crate) fn mock_raydium(mocks: MocksMap) -> Raydium {
+    mock_raydium_with_config(mocks, RaydiumConfig::default())
+}
+
+pub(crate) fn mock_raydium_with_config(mocks: MocksMap, config: RaydiumConfig) -> Raydium {
+    let mut solana_client = SolanaClient::new(solana_network_client::Mode::MAIN).unwrap();
+    solana_client.client = Some(Arc::new(RpcClient::new_mock_with_mocks_map(
+        "succeeds", mocks,
+    )));
+    Raydium::new_with_config(Arc::new(solana_client), config)
+}
+
+/// account as returned by the rpc
+pub(crate) fn ui_account(owner: &Pubkey, data: Vec<u8>) -> UiAccount {
+    let account = Account {
+        lamports: 1,
+        data,
+        owner: *owner,
+        executable: false,
+        rent_epoch: 0,
+    };
+    encode_ui_account(
+        &Pubkey::new_unique(),
+        &account,
+        UiAccountEncoding::Base64,
+        None,
+        None,
+    )
+}
+
+/// getAccountInfo response
+pub(crate) fn account_info_response(owner: &Pubkey, data: Vec<u8>) -> serde_json::Value {
+    serde_json::to_value(Response {
+        context: RpcResponseContext::new(1),
+        value: ui_account(owner, data),
+    })
+    .unwrap()
+}
+
+/// getMultipleAccounts response
+pub(crate) fn multiple_accounts_response(accounts: Vec<Option<UiAccount>>) -> serde_json::Value {
+    serde_json::to_value(Response {
+        context: RpcResponseContext::new(1),
+        value: accounts,
+    })
+    .unwrap()
+}
+
+/// getTransaction response of `transaction` in base64, confirmed in `slot` at a block time
+/// derived from the slot
+#[allow(deprecated)]
+pub(crate) fn transaction_response(
+    slot: u64,
+    transaction: &Transaction,
+    meta: serde_json::Value,
+) -> serde_json::Value {
+    use solana_transaction_status::Encodable;
+    serde_json::json!({
+        "slot": slot,
+        "blockTime": 1_700_000_000 + slot as i64,
+        "transaction": transaction.encode(UiTransactionEncoding::Base64),
+        "meta": meta,
+    })
+}
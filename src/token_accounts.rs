@@ -0,0 +1,198 @@
+//! associated token accounts of the pool mints
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::programs::{ASSOCIATED_TOKEN_PROGRAM_ID, SYSTEM_PROGRAM_ID};
+#[cfg(feature = "rpc")]
+use crate::{Raydium, error::RaydiumError, monitor::MAX_MULTIPLE_ACCOUNTS, typs::PoolData};
+
+/// associated token account of `owner` for `mint`, `token_program` being the program owning the mint
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// instruction creating the associated token account of `owner` for `mint` unless it exists,
+/// paid by `payer`
+pub fn create_associated_token_account_idempotent(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        &[1],
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(owner, mint, token_program), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+    )
+}
+
+/// associated token accounts of an owner and the instructions creating the missing ones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenAccounts {
+    /// one account per mint, in order
+    pub accounts: Vec<Pubkey>,
+    /// idempotent creation of the accounts that do not exist, paid by the owner
+    pub create_instructions: Vec<Instruction>,
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// derive the associated token accounts of `owner` for `mints`, each mint owned by the token
+    /// program at the same index of `token_programs`, and build the instructions creating the
+    /// accounts that do not exist yet. a mint listed twice is created once
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::{USDC_MINT, WSOL_MINT}, programs::TOKEN_PROGRAM_ID};
+    /// # use solana_sdk::pubkey::Pubkey;
+    /// # async fn run(raydium: Raydium, owner: Pubkey) -> Result<(), RaydiumError> {
+    /// let resolved = raydium
+    ///     .resolve_token_accounts(&owner, &[WSOL_MINT, USDC_MINT], &[TOKEN_PROGRAM_ID; 2])
+    ///     .await?;
+    /// println!("{} accounts to create", resolved.create_instructions.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn resolve_token_accounts(
+        &self,
+        owner: &Pubkey,
+        mints: &[Pubkey],
+        token_programs: &[Pubkey],
+    ) -> Result<TokenAccounts, RaydiumError> {
+        assert_eq!(
+            mints.len(),
+            token_programs.len(),
+            "one token program is required per mint"
+        );
+        let accounts: Vec<Pubkey> = mints
+            .iter()
+            .zip(token_programs)
+            .map(|(mint, program)| associated_token_address(owner, mint, program))
+            .collect();
+        let mut exists = Vec::with_capacity(accounts.len());
+        for chunk in accounts.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let fetched = self.get_multiple_accounts_with_context(chunk).await?;
+            exists.extend(fetched.value.iter().map(Option::is_some));
+        }
+        let mut create_instructions = Vec::new();
+        for (i, ((mint, program), account)) in
+            mints.iter().zip(token_programs).zip(&accounts).enumerate()
+        {
+            if !exists[i] && !accounts[..i].contains(account) {
+                create_instructions.push(create_associated_token_account_idempotent(
+                    owner, owner, mint, program,
+                ));
+            }
+        }
+        Ok(TokenAccounts {
+            accounts,
+            create_instructions,
+        })
+    }
+
+    /// resolve the associated token accounts of `owner` for the base and quote mints of a pool.
+    /// the token programs come from the pool state, or from the mints for CLMM pools
+    pub async fn resolve_pool_token_accounts(
+        &self,
+        owner: &Pubkey,
+        pool: &PoolData,
+    ) -> Result<TokenAccounts, RaydiumError> {
+        let mints = [pool.base_mint(), pool.quote_mint()];
+        let (base_program, quote_program) = match pool.token_programs() {
+            Some(programs) => programs,
+            None => {
+                let infos = self.fetch_mint_infos(&mints).await?;
+                (infos[0].token_program, infos[1].token_program)
+            }
+        };
+        self.resolve_token_accounts(owner, &mints, &[base_program, quote_program])
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mints::USDC_MINT,
+        programs::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID},
+    };
+
+    #[test]
+    fn test_associated_token_address() {
+        let owner = Pubkey::new_unique();
+        let ata = associated_token_address(&owner, &USDC_MINT, &TOKEN_PROGRAM_ID);
+        assert!(!ata.is_on_curve());
+        // token-2022 mints derive a different account
+        assert_ne!(
+            associated_token_address(&owner, &USDC_MINT, &TOKEN_2022_PROGRAM_ID),
+            ata
+        );
+        let create = create_associated_token_account_idempotent(
+            &owner,
+            &owner,
+            &USDC_MINT,
+            &TOKEN_PROGRAM_ID,
+        );
+        assert_eq!(
+            (create.data.as_slice(), create.accounts[1].pubkey),
+            (&[1u8][..], ata)
+        );
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_resolve_token_accounts() -> Result<(), Box<dyn std::error::Error>> {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+
+        use crate::test_utils::{mock_raydium, multiple_accounts_response, ui_account};
+
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![
+                Some(ui_account(&TOKEN_PROGRAM_ID, vec![0u8; 165])),
+                None,
+                None,
+            ]),
+        );
+        let raydium = mock_raydium(mocks);
+        let (owner, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let resolved = raydium
+            .resolve_token_accounts(
+                &owner,
+                &[USDC_MINT, mint, mint],
+                &[
+                    TOKEN_PROGRAM_ID,
+                    TOKEN_2022_PROGRAM_ID,
+                    TOKEN_2022_PROGRAM_ID,
+                ],
+            )
+            .await?;
+        assert_eq!(
+            resolved.accounts[1],
+            associated_token_address(&owner, &mint, &TOKEN_2022_PROGRAM_ID)
+        );
+        // the existing account is skipped and the repeated mint is created once
+        assert_eq!(resolved.create_instructions.len(), 1);
+        assert_eq!(
+            resolved.create_instructions[0].accounts[5].pubkey,
+            TOKEN_2022_PROGRAM_ID
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+//! tracing events that compile to nothing without the `tracing` feature
+
+/// emit a debug event when the `tracing` feature is enabled
+#[cfg(feature = "rpc")]
+macro_rules! trace_debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+/// emit a warn event when the `tracing` feature is enabled
+macro_rules! trace_warn {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::warn!($($arg)*);
+    };
+}
+
+/// record the pool type on the current span once it is known
+#[cfg(feature = "rpc")]
+macro_rules! trace_kind {
+    ($kind:expr) => {
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("kind", tracing::field::debug($kind));
+    };
+}
+
+pub(crate) use trace_warn;
+#[cfg(feature = "rpc")]
+pub(crate) use {trace_debug, trace_kind};
+
+#[cfg(all(test, feature = "tracing", feature = "rpc"))]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_sdk::pubkey::Pubkey;
+    use tracing::{
+        Event, Metadata, Subscriber,
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+    };
+
+    use crate::{
+        config::RaydiumConfig,
+        launchpad::LAUNCHPAD_POOL_STATE_DATA_SIZE,
+        programs::RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+        test_utils::{account_info_response, mock_raydium_with_config},
+        typs::PoolData,
+    };
+
+    /// subscriber keeping the names of the spans and the messages of the events
+    #[derive(Clone, Default)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<String>>>,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    struct Message(String);
+
+    impl Visit for Message {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "message" {
+                self.0 = format!("{:?}", value);
+            }
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(span.metadata().name().to_string());
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut message = Message(String::new());
+            event.record(&mut message);
+            self.events.lock().unwrap().push(message.0);
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[tokio::test]
+    async fn test_tracing() -> Result<(), Box<dyn std::error::Error>> {
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            account_info_response(
+                &RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+                vec![0u8; LAUNCHPAD_POOL_STATE_DATA_SIZE],
+            ),
+        );
+        let config = RaydiumConfig::new().with_cache_ttl(Duration::from_secs(60));
+        let raydium = mock_raydium_with_config(mocks, config);
+        let address = Pubkey::new_unique().to_string();
+        raydium.get_pool(&address).await?;
+        raydium.get_pool(&address).await?;
+        assert!(PoolData::parse_detect(&[0u8; 3]).is_err());
+        let spans = recorder.spans.lock().unwrap();
+        assert_eq!(spans.as_slice(), ["get_pool_with_context"; 2]);
+        let events = recorder.events.lock().unwrap();
+        assert_eq!(
+            events.as_slice(),
+            [
+                "cache miss",
+                "rpc request",
+                "cache hit",
+                "unknown raydium pool data size"
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,509 @@
+//! compute budget instructions and transaction submission
+
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+#[cfg(feature = "rpc")]
+use {
+    crate::{
+        Raydium, error::RaydiumError, metrics::RpcCall, reader::r_u64,
+        rpc::TOKEN_ACCOUNT_AMOUNT_OFFSET, token_accounts::associated_token_address,
+    },
+    solana_account_decoder::UiAccountEncoding,
+    solana_client::rpc_config::{
+        RpcSendTransactionConfig, RpcSimulateTransactionAccountsConfig,
+        RpcSimulateTransactionConfig,
+    },
+    solana_sdk::account::Account,
+    solana_sdk::{
+        hash::Hash,
+        signature::Signature,
+        signer::Signer,
+        transaction::{Transaction, TransactionError},
+    },
+    solana_transaction_status::TransactionConfirmationStatus,
+    std::time::Duration,
+};
+
+/// compute budget program id
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ComputeBudget111111111111111111111111111111");
+
+/// times a transaction is re-signed with a fresh blockhash after its blockhash expired, unless set
+pub const DEFAULT_BLOCKHASH_RETRIES: u32 = 3;
+
+/// time between two signature status checks while confirming
+#[cfg(feature = "rpc")]
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// instruction capping the compute units of the transaction
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![2u8];
+    data.extend_from_slice(&units.to_le_bytes());
+    Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM_ID, &data, vec![])
+}
+
+/// instruction setting the priority fee, in micro-lamports per compute unit
+pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+    let mut data = vec![3u8];
+    data.extend_from_slice(&micro_lamports.to_le_bytes());
+    Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM_ID, &data, vec![])
+}
+
+/// how `Raydium::send_transaction` builds, submits and confirms a transaction
+#[cfg(feature = "rpc")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SendOptions {
+    /// compute unit limit, the runtime default when `None`
+    pub cu_limit: Option<u32>,
+    /// priority fee in micro-lamports per compute unit, none when `None`
+    pub priority_fee_microlamports: Option<u64>,
+    pub skip_preflight: bool,
+    /// times the rpc node rebroadcasts the transaction, the node default when `None`
+    pub max_retries: Option<usize>,
+    /// level to wait for, the transaction is only submitted when `None`
+    pub confirmation: Option<TransactionConfirmationStatus>,
+    /// times the transaction is re-signed with a fresh blockhash after its blockhash expired
+    pub blockhash_retries: u32,
+    /// outcome the transaction is simulated against before each submission, not simulated when `None`
+    pub expected_outcome: Option<ExpectedOutcome>,
+}
+
+#[cfg(feature = "rpc")]
+impl Default for SendOptions {
+    fn default() -> Self {
+        Self {
+            cu_limit: None,
+            priority_fee_microlamports: None,
+            skip_preflight: false,
+            max_retries: None,
+            confirmation: None,
+            blockhash_retries: DEFAULT_BLOCKHASH_RETRIES,
+            expected_outcome: None,
+        }
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl SendOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// cap the compute units of the transaction
+    pub fn with_cu_limit(mut self, units: u32) -> Self {
+        self.cu_limit = Some(units);
+        self
+    }
+
+    /// pay a priority fee of `micro_lamports` per compute unit
+    pub fn with_priority_fee(mut self, micro_lamports: u64) -> Self {
+        self.priority_fee_microlamports = Some(micro_lamports);
+        self
+    }
+
+    pub fn with_skip_preflight(mut self, skip: bool) -> Self {
+        self.skip_preflight = skip;
+        self
+    }
+
+    /// set the times the rpc node rebroadcasts the transaction
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// wait until the transaction reaches `confirmation`
+    pub fn with_confirmation(mut self, confirmation: TransactionConfirmationStatus) -> Self {
+        self.confirmation = Some(confirmation);
+        self
+    }
+
+    /// set the times the transaction is re-signed after its blockhash expired
+    pub fn with_blockhash_retries(mut self, retries: u32) -> Self {
+        self.blockhash_retries = retries;
+        self
+    }
+
+    /// simulate the transaction before submitting it and abort unless it yields `expected`
+    pub fn with_simulation(mut self, expected: ExpectedOutcome) -> Self {
+        self.expected_outcome = Some(expected);
+        self
+    }
+
+    /// the compute budget instructions to prepend
+    pub fn compute_budget_instructions(&self) -> Vec<Instruction> {
+        self.cu_limit
+            .map(set_compute_unit_limit)
+            .into_iter()
+            .chain(self.priority_fee_microlamports.map(set_compute_unit_price))
+            .collect()
+    }
+}
+
+/// minimum amount of `mint` the fee payer must receive
+#[cfg(feature = "rpc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpectedOutcome {
+    pub mint: Pubkey,
+    pub min_amount_out: u64,
+}
+
+/// result of a successful simulation
+#[cfg(feature = "rpc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationOutcome {
+    /// increase of the fee payer's associated token account of the expected mint
+    pub amount_out: u64,
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+}
+
+/// a submitted transaction
+#[cfg(feature = "rpc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SentTransaction {
+    pub signature: Signature,
+    /// slot the transaction was confirmed in, `None` when confirmation was not requested
+    pub slot: Option<u64>,
+}
+
+#[cfg(feature = "rpc")]
+impl Raydium {
+    /// sign and submit `instructions` with the compute budget of `opts` prepended,
+    /// then wait for the requested confirmation. a transaction whose blockhash expired
+    /// before it landed is re-signed with a fresh blockhash up to `opts.blockhash_retries` times
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, transaction::SendOptions};
+    /// # use solana_sdk::{instruction::Instruction, signature::Keypair, signer::Signer};
+    /// # use solana_transaction_status::TransactionConfirmationStatus;
+    /// # async fn run(raydium: Raydium, payer: Keypair, instructions: Vec<Instruction>) -> Result<(), RaydiumError> {
+    /// let opts = SendOptions::new()
+    ///     .with_cu_limit(200_000)
+    ///     .with_priority_fee(10_000)
+    ///     .with_confirmation(TransactionConfirmationStatus::Confirmed);
+    /// let sent = raydium
+    ///     .send_transaction(&instructions, &payer.pubkey(), &[&payer], &opts)
+    ///     .await?;
+    /// println!("{} landed in slot {:?}", sent.signature, sent.slot);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_transaction(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        signers: &[&dyn Signer],
+        opts: &SendOptions,
+    ) -> Result<SentTransaction, RaydiumError> {
+        let mut all_instructions = opts.compute_budget_instructions();
+        all_instructions.extend_from_slice(instructions);
+        let mut attempt = 0;
+        loop {
+            let blockhash = self.get_latest_blockhash().await?;
+            let mut transaction = Transaction::new_with_payer(&all_instructions, Some(payer));
+            transaction
+                .try_sign(signers, blockhash)
+                .map_err(|e| RaydiumError::Signing(e.to_string()))?;
+            if let Some(expected) = &opts.expected_outcome {
+                self.simulate_and_check(&transaction, expected).await?;
+            }
+            let result = match self.submit(&transaction, opts).await {
+                Ok(signature) => match &opts.confirmation {
+                    Some(confirmation) => self
+                        .confirm(&signature, &blockhash, confirmation)
+                        .await
+                        .map(|slot| SentTransaction {
+                            signature,
+                            slot: Some(slot),
+                        }),
+                    None => Ok(SentTransaction {
+                        signature,
+                        slot: None,
+                    }),
+                },
+                Err(e) => Err(e),
+            };
+            match result {
+                Err(RaydiumError::BlockhashExpired) if attempt < opts.blockhash_retries => {
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// simulate a transaction and check that its fee payer receives at least
+    /// `expected.min_amount_out` of `expected.mint` in its associated token account.
+    /// fails with `SimulationFailed` when the simulation errors and with `SlippageExceeded`
+    /// when the output is short. signatures are not verified
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError, mints::USDC_MINT, transaction::ExpectedOutcome};
+    /// # use solana_sdk::transaction::Transaction;
+    /// # async fn run(raydium: Raydium, transaction: Transaction) -> Result<(), RaydiumError> {
+    /// let expected = ExpectedOutcome { mint: USDC_MINT, min_amount_out: 1_000_000 };
+    /// let outcome = raydium.simulate_and_check(&transaction, &expected).await?;
+    /// println!("{} out, {:?} compute units", outcome.amount_out, outcome.units_consumed);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn simulate_and_check(
+        &self,
+        transaction: &Transaction,
+        expected: &ExpectedOutcome,
+    ) -> Result<SimulationOutcome, RaydiumError> {
+        let owner = transaction
+            .message
+            .account_keys
+            .first()
+            .ok_or_else(|| RaydiumError::TransactionFailed("no fee payer".to_string()))?;
+        let token_program = self.fetch_mint_info(&expected.mint).await?.token_program;
+        let token_account = associated_token_address(owner, &expected.mint, &token_program);
+        let before = self.get_token_account_amounts(&[token_account]).await?[0];
+        let config = RpcSimulateTransactionConfig {
+            accounts: Some(RpcSimulateTransactionAccountsConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                addresses: vec![token_account.to_string()],
+            }),
+            ..Default::default()
+        };
+        let simulation = self
+            .with_rpc(RpcCall::SimulateTransaction, None, |client| {
+                let config = config.clone();
+                async move {
+                    client
+                        .simulate_transaction_with_config(transaction, config)
+                        .await
+                        .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))
+                }
+            })
+            .await?
+            .value;
+        let logs = simulation.logs.unwrap_or_default();
+        if let Some(error) = simulation.err {
+            return Err(RaydiumError::SimulationFailed {
+                error: format!("{:?}", error),
+                logs,
+            });
+        }
+        let after = simulation
+            .accounts
+            .and_then(|accounts| accounts.into_iter().next().flatten())
+            .and_then(|account| account.decode::<Account>())
+            .map_or(0, |account| {
+                r_u64(&account.data, TOKEN_ACCOUNT_AMOUNT_OFFSET)
+            });
+        let amount_out = after.saturating_sub(before);
+        if amount_out < expected.min_amount_out {
+            return Err(RaydiumError::SlippageExceeded {
+                min_amount_out: expected.min_amount_out,
+                amount_out,
+            });
+        }
+        Ok(SimulationOutcome {
+            amount_out,
+            units_consumed: simulation.units_consumed,
+            logs,
+        })
+    }
+
+    async fn get_latest_blockhash(&self) -> Result<Hash, RaydiumError> {
+        self.with_rpc(RpcCall::GetLatestBlockhash, None, |client| async move {
+            client
+                .get_latest_blockhash()
+                .await
+                .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))
+        })
+        .await
+    }
+
+    async fn submit(
+        &self,
+        transaction: &Transaction,
+        opts: &SendOptions,
+    ) -> Result<Signature, RaydiumError> {
+        let config = RpcSendTransactionConfig {
+            skip_preflight: opts.skip_preflight,
+            max_retries: opts.max_retries,
+            ..Default::default()
+        };
+        self.with_rpc(RpcCall::SendTransaction, None, |client| async move {
+            client
+                .send_transaction_with_config(transaction, config)
+                .await
+                .map_err(|e| match e.get_transaction_error() {
+                    Some(TransactionError::BlockhashNotFound) => RaydiumError::BlockhashExpired,
+                    Some(error) => RaydiumError::TransactionFailed(error.to_string()),
+                    None => RaydiumError::Rpc(format!("{:?}", e)),
+                })
+        })
+        .await
+    }
+
+    /// wait until the transaction reaches `confirmation`, returning its slot.
+    /// fails with `BlockhashExpired` once the blockhash can no longer land it
+    async fn confirm(
+        &self,
+        signature: &Signature,
+        blockhash: &Hash,
+        confirmation: &TransactionConfirmationStatus,
+    ) -> Result<u64, RaydiumError> {
+        loop {
+            let statuses = self
+                .with_rpc(RpcCall::GetSignatureStatuses, None, |client| async move {
+                    client
+                        .get_signature_statuses(&[*signature])
+                        .await
+                        .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))
+                })
+                .await?;
+            if let Some(Some(status)) = statuses.value.first() {
+                if let Some(error) = &status.err {
+                    return Err(RaydiumError::TransactionFailed(error.to_string()));
+                }
+                let reached = status
+                    .confirmation_status
+                    .as_ref()
+                    .is_some_and(|status| rank(status) >= rank(confirmation));
+                if reached {
+                    return Ok(status.slot);
+                }
+            } else {
+                let valid = self
+                    .with_rpc(RpcCall::IsBlockhashValid, None, |client| async move {
+                        client
+                            .is_blockhash_valid(blockhash, client.commitment())
+                            .await
+                            .map_err(|e| RaydiumError::Rpc(format!("{:?}", e)))
+                    })
+                    .await?;
+                if !valid {
+                    return Err(RaydiumError::BlockhashExpired);
+                }
+            }
+            tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// order of the confirmation levels
+#[cfg(feature = "rpc")]
+fn rank(status: &TransactionConfirmationStatus) -> u8 {
+    match status {
+        TransactionConfirmationStatus::Processed => 0,
+        TransactionConfirmationStatus::Confirmed => 1,
+        TransactionConfirmationStatus::Finalized => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_budget_instructions() {
+        let limit = set_compute_unit_limit(200_000);
+        assert_eq!(limit.program_id, COMPUTE_BUDGET_PROGRAM_ID);
+        assert_eq!(limit.data, [2, 0x40, 0x0d, 0x03, 0]);
+        let price = set_compute_unit_price(1);
+        assert_eq!(price.data, [3, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(limit.accounts.is_empty() && price.accounts.is_empty());
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_send_transaction() -> Result<(), Box<dyn std::error::Error>> {
+        use solana_rpc_client::mock_sender::MocksMap;
+        use solana_sdk::signature::Keypair;
+
+        use crate::test_utils::mock_raydium;
+
+        let raydium = mock_raydium(MocksMap::default());
+        let payer = Keypair::new();
+        let opts = SendOptions::new()
+            .with_cu_limit(100_000)
+            .with_priority_fee(5)
+            .with_confirmation(TransactionConfirmationStatus::Confirmed);
+        assert_eq!(opts.compute_budget_instructions().len(), 2);
+        // the mock answers every signature status as finalized at slot 1
+        let sent = raydium
+            .send_transaction(&[], &payer.pubkey(), &[&payer], &opts)
+            .await?;
+        assert_eq!(sent.slot, Some(1));
+        let sent = raydium
+            .send_transaction(&[], &payer.pubkey(), &[&payer], &SendOptions::new())
+            .await?;
+        assert_eq!(sent.slot, None);
+        // the payer must sign
+        let other = Keypair::new();
+        assert!(matches!(
+            raydium
+                .send_transaction(&[], &payer.pubkey(), &[&other], &opts)
+                .await,
+            Err(RaydiumError::Signing(_))
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_simulate_and_check() -> Result<(), Box<dyn std::error::Error>> {
+        use solana_client::rpc_request::RpcRequest;
+        use solana_rpc_client::mock_sender::MocksMap;
+
+        use crate::{
+            mints::MINT_DATA_SIZE,
+            programs::TOKEN_PROGRAM_ID,
+            test_utils::{mock_raydium, multiple_accounts_response, ui_account},
+        };
+
+        let token_account = |amount: u64| {
+            let mut data = vec![0u8; 165];
+            data[64..72].copy_from_slice(&amount.to_le_bytes());
+            ui_account(&TOKEN_PROGRAM_ID, data)
+        };
+        let mut mint = vec![0u8; MINT_DATA_SIZE];
+        mint[45] = 1;
+        let mut mocks = MocksMap::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![Some(ui_account(&TOKEN_PROGRAM_ID, mint))]),
+        );
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            multiple_accounts_response(vec![Some(token_account(100))]),
+        );
+        mocks.insert(
+            RpcRequest::SimulateTransaction,
+            serde_json::json!({
+                "context": { "slot": 1 },
+                "value": {
+                    "err": null,
+                    "logs": ["Program log: swap"],
+                    "accounts": [token_account(350)],
+                    "unitsConsumed": 1234,
+                },
+            }),
+        );
+        let raydium = mock_raydium(mocks);
+        let payer = Pubkey::new_unique();
+        let transaction = Transaction::new_with_payer(&[], Some(&payer));
+        let expected = ExpectedOutcome {
+            mint: Pubkey::new_unique(),
+            min_amount_out: 200,
+        };
+        let outcome = raydium.simulate_and_check(&transaction, &expected).await?;
+        assert_eq!(outcome.amount_out, 250);
+        assert_eq!(outcome.units_consumed, Some(1234));
+        assert_eq!(outcome.logs, ["Program log: swap"]);
+        // the default simulation returns no account, nothing was received
+        assert_eq!(
+            raydium.simulate_and_check(&transaction, &expected).await,
+            Err(RaydiumError::SlippageExceeded {
+                min_amount_out: 200,
+                amount_out: 0
+            })
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,511 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    error::RaydiumError,
+    launchpad::{LAUNCHPAD_POOL_STATE_DATA_SIZE, LaunchpadPool, LaunchpadPoolData, PoolStatus},
+    liquidity::{
+        clmm::{
+            RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE, RaydiumLiquidityPoolCLMM,
+            RaydiumLiquidityPoolCLMMData,
+        },
+        cpmm::{
+            RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE, RaydiumLiquidityPoolCPMM,
+            RaydiumLiquidityPoolCPMMData,
+        },
+        stable::{
+            self, DEFAULT_STABLE_AMP, RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE,
+            RaydiumLiquidityPoolStable, RaydiumLiquidityPoolStableData,
+        },
+        v4::{
+            RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE, RaydiumLiquidityPoolData, RaydiumLiquidityPoolV4,
+            V4PoolStatus,
+        },
+    },
+    programs::TOKEN_PROGRAM_ID,
+    trace::trace_warn,
+};
+
+/// a value together with the slot at which it was observed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contextual<T> {
+    pub slot: u64,
+    pub value: T,
+}
+
+impl<T> Contextual<T> {
+    pub fn new(slot: u64, value: T) -> Self {
+        Self { slot, value }
+    }
+
+    /// transform the value, keeping the slot
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Contextual<U> {
+        Contextual {
+            slot: self.slot,
+            value: f(self.value),
+        }
+    }
+}
+
+/// a pool parsed from the known prefix of an account that may be longer than its layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lenient<T> {
+    pub value: T,
+    /// bytes after the known layout, fields appended by a program upgrade or indexer padding
+    pub trailing_bytes: usize,
+}
+
+impl<T> Lenient<T> {
+    /// parse the first `size` bytes of `data` with a strict parser, accepting longer data
+    pub(crate) fn parse<F>(data: &[u8], size: usize, parse: F) -> Result<Lenient<T>, String>
+    where
+        F: FnOnce(&[u8]) -> Result<T, String>,
+    {
+        let Some(prefix) = data.get(..size) else {
+            trace_warn!(expected = size, actual = data.len(), "pool data too short");
+            return Err(format!(
+                "pool data too short. Expected at least {}, got {}",
+                size,
+                data.len()
+            ));
+        };
+        Ok(Lenient {
+            value: parse(prefix)?,
+            trailing_bytes: data.len() - size,
+        })
+    }
+}
+
+/// price of each pool of a batch, a failing pool does not fail the others
+pub type PoolPrices = Vec<(Pubkey, Result<f64, RaydiumError>)>;
+
+/// raydium pool type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PoolKind {
+    V4,
+    Cpmm,
+    Clmm,
+    Launchpad,
+    Stable,
+}
+
+impl PoolKind {
+    /// detect the pool type from the account data size
+    pub fn from_data_len(len: usize) -> Option<PoolKind> {
+        match len {
+            RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE => Some(PoolKind::V4),
+            RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE => Some(PoolKind::Cpmm),
+            RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE => Some(PoolKind::Clmm),
+            LAUNCHPAD_POOL_STATE_DATA_SIZE => Some(PoolKind::Launchpad),
+            RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE => Some(PoolKind::Stable),
+            _ => None,
+        }
+    }
+
+    /// size of the account data of this pool type
+    pub fn data_len(&self) -> usize {
+        match self {
+            PoolKind::V4 => RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE,
+            PoolKind::Cpmm => RAYDIUM_LIQUIDITY_POOL_CPMM_DATA_SIZE,
+            PoolKind::Clmm => RAYDIUM_LIQUIDITY_POOL_CLMM_DATA_SIZE,
+            PoolKind::Launchpad => LAUNCHPAD_POOL_STATE_DATA_SIZE,
+            PoolKind::Stable => RAYDIUM_LIQUIDITY_POOL_STABLE_DATA_SIZE,
+        }
+    }
+}
+
+/// v4 pool status accepting every instruction
+pub const V4_STATUS_INITIALIZED: u8 = 1;
+/// v4 pool status accepting swaps only
+pub const V4_STATUS_SWAP_ONLY: u8 = 6;
+/// v4 pool status accepting swaps once the pool open time is reached
+pub const V4_STATUS_WAITING_TRADE: u8 = 7;
+/// CPMM status bit disabling swaps
+pub const CPMM_STATUS_SWAP_DISABLED: u8 = 1 << 2;
+/// CLMM status bit disabling swaps
+pub const CLMM_STATUS_SWAP_DISABLED: u8 = 1 << 4;
+
+/// which side of a pool a mint sits on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MintSide {
+    /// base side (v4 base, CPMM/CLMM token 0, launchpad base)
+    Base,
+    /// quote side (v4 quote, CPMM/CLMM token 1, launchpad quote)
+    Quote,
+}
+
+/// price of one unit of `mint` denominated in `denominated_in`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Price {
+    pub mint: Pubkey,
+    pub denominated_in: Pubkey,
+    pub value: f64,
+}
+
+impl Price {
+    /// the same price seen from the other mint, infinite when the value is zero
+    pub fn invert(&self) -> Price {
+        Price {
+            mint: self.denominated_in,
+            denominated_in: self.mint,
+            value: 1.0 / self.value,
+        }
+    }
+}
+
+/// parsed pool data of any raydium pool type
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum PoolData {
+    V4(RaydiumLiquidityPoolData),
+    Cpmm(RaydiumLiquidityPoolCPMMData),
+    Clmm(RaydiumLiquidityPoolCLMMData),
+    Launchpad(LaunchpadPoolData),
+    Stable(RaydiumLiquidityPoolStableData),
+}
+
+impl PoolData {
+    /// parse account data as a pool of the given type
+    pub fn parse(kind: PoolKind, data: &[u8]) -> Result<PoolData, String> {
+        match kind {
+            PoolKind::V4 => RaydiumLiquidityPoolV4::get_liquidity_pool_info(data).map(PoolData::V4),
+            PoolKind::Cpmm => {
+                RaydiumLiquidityPoolCPMM::get_liquidity_pool_info(data).map(PoolData::Cpmm)
+            }
+            PoolKind::Clmm => RaydiumLiquidityPoolCLMM::get_liquidity_pool_info(data)
+                .map(PoolData::Clmm)
+                .map_err(|e| e.to_string()),
+            PoolKind::Launchpad => {
+                LaunchpadPool::get_liquidity_pool_info(data).map(PoolData::Launchpad)
+            }
+            PoolKind::Stable => {
+                RaydiumLiquidityPoolStable::get_liquidity_pool_info(data).map(PoolData::Stable)
+            }
+        }
+    }
+
+    /// parse account data as a pool of the given type, accepting data longer than the layout
+    /// and reporting the bytes past it. `parse` stays strict about the size
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::{liquidity::v4::RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE, typs::{PoolData, PoolKind}};
+    /// // an indexer returning the account with 8 bytes of padding
+    /// let data = vec![0u8; RAYDIUM_LIQUIDITY_POOL_V4_DATA_SIZE + 8];
+    /// assert!(PoolData::parse(PoolKind::V4, &data).is_err());
+    /// let pool = PoolData::parse_lenient(PoolKind::V4, &data).unwrap();
+    /// assert_eq!((pool.value.kind(), pool.trailing_bytes), (PoolKind::V4, 8));
+    /// ```
+    pub fn parse_lenient(kind: PoolKind, data: &[u8]) -> Result<Lenient<PoolData>, String> {
+        Lenient::parse(data, kind.data_len(), |prefix| Self::parse(kind, prefix))
+    }
+
+    /// parse account data as a pool, detecting the type from the data size
+    pub fn parse_detect(data: &[u8]) -> Result<PoolData, String> {
+        let Some(kind) = PoolKind::from_data_len(data.len()) else {
+            trace_warn!(actual = data.len(), "unknown raydium pool data size");
+            return Err(format!("unknown raydium pool data size: {}", data.len()));
+        };
+        Self::parse(kind, data)
+    }
+
+    pub fn kind(&self) -> PoolKind {
+        match self {
+            PoolData::V4(_) => PoolKind::V4,
+            PoolData::Cpmm(_) => PoolKind::Cpmm,
+            PoolData::Clmm(_) => PoolKind::Clmm,
+            PoolData::Launchpad(_) => PoolKind::Launchpad,
+            PoolData::Stable(_) => PoolKind::Stable,
+        }
+    }
+
+    pub fn base_mint(&self) -> Pubkey {
+        match self {
+            PoolData::V4(p) => p.base_mint,
+            PoolData::Cpmm(p) => p.token_0_mint,
+            PoolData::Clmm(p) => p.token_mint_0,
+            PoolData::Launchpad(p) => p.base_mint,
+            PoolData::Stable(p) => p.base_mint,
+        }
+    }
+
+    pub fn quote_mint(&self) -> Pubkey {
+        match self {
+            PoolData::V4(p) => p.quote_mint,
+            PoolData::Cpmm(p) => p.token_1_mint,
+            PoolData::Clmm(p) => p.token_mint_1,
+            PoolData::Launchpad(p) => p.quote_mint,
+            PoolData::Stable(p) => p.quote_mint,
+        }
+    }
+
+    pub fn base_vault(&self) -> Pubkey {
+        match self {
+            PoolData::V4(p) => p.base_vault,
+            PoolData::Cpmm(p) => p.token_0_vault,
+            PoolData::Clmm(p) => p.token_vault_0,
+            PoolData::Launchpad(p) => p.base_vault,
+            PoolData::Stable(p) => p.base_vault,
+        }
+    }
+
+    pub fn quote_vault(&self) -> Pubkey {
+        match self {
+            PoolData::V4(p) => p.quote_vault,
+            PoolData::Cpmm(p) => p.token_1_vault,
+            PoolData::Clmm(p) => p.token_vault_1,
+            PoolData::Launchpad(p) => p.quote_vault,
+            PoolData::Stable(p) => p.quote_vault,
+        }
+    }
+
+    pub fn base_decimals(&self) -> u8 {
+        match self {
+            PoolData::V4(p) => p.base_decimal,
+            PoolData::Cpmm(p) => p.mint_0_decimals,
+            PoolData::Clmm(p) => p.mint_decimals_0,
+            PoolData::Launchpad(p) => p.base_decimals,
+            PoolData::Stable(p) => p.base_decimal,
+        }
+    }
+
+    pub fn quote_decimals(&self) -> u8 {
+        match self {
+            PoolData::V4(p) => p.quote_decimal,
+            PoolData::Cpmm(p) => p.mint_1_decimals,
+            PoolData::Clmm(p) => p.mint_decimals_1,
+            PoolData::Launchpad(p) => p.quote_decimals,
+            PoolData::Stable(p) => p.quote_decimal,
+        }
+    }
+
+    /// side of the pool the mint sits on, `None` if the mint is not in the pool
+    pub fn side_of(&self, mint: &Pubkey) -> Option<MintSide> {
+        if self.base_mint() == *mint {
+            Some(MintSide::Base)
+        } else if self.quote_mint() == *mint {
+            Some(MintSide::Quote)
+        } else {
+            None
+        }
+    }
+
+    /// the mint paired against `mint` in this pool
+    pub fn other_mint(&self, mint: &Pubkey) -> Option<Pubkey> {
+        match self.side_of(mint)? {
+            MintSide::Base => Some(self.quote_mint()),
+            MintSide::Quote => Some(self.base_mint()),
+        }
+    }
+
+    /// vault balances less the amounts owed to the protocol, which cannot be traded against
+    pub fn trading_reserves(&self, base_vault_amount: u64, quote_vault_amount: u64) -> (u64, u64) {
+        match self {
+            PoolData::V4(p) => (
+                base_vault_amount.saturating_sub(p.base_need_take_pnl),
+                quote_vault_amount.saturating_sub(p.quote_need_take_pnl),
+            ),
+            PoolData::Stable(p) => (
+                base_vault_amount.saturating_sub(p.base_need_take_pnl),
+                quote_vault_amount.saturating_sub(p.quote_need_take_pnl),
+            ),
+            PoolData::Cpmm(p) => (
+                base_vault_amount.saturating_sub(
+                    p.protocol_fees_token_0
+                        .saturating_add(p.fund_fees_token_0)
+                        .saturating_add(p.creator_fees_token_0),
+                ),
+                quote_vault_amount.saturating_sub(
+                    p.protocol_fees_token_1
+                        .saturating_add(p.fund_fees_token_1)
+                        .saturating_add(p.creator_fees_token_1),
+                ),
+            ),
+            PoolData::Clmm(_) | PoolData::Launchpad(_) => (base_vault_amount, quote_vault_amount),
+        }
+    }
+
+    /// whether `spot_price` reads the vault balances, CLMM and launchpad prices come from the
+    /// pool state alone
+    #[cfg(feature = "rpc")]
+    pub(crate) fn is_priced_from_vaults(&self) -> bool {
+        matches!(
+            self,
+            PoolData::V4(_) | PoolData::Cpmm(_) | PoolData::Stable(_)
+        )
+    }
+
+    /// spot price of the base token in quote token, decimals applied.
+    /// v4 and CPMM prices come from the trading reserves, CLMM from the sqrt price,
+    /// launchpad from the curve and stable pools from the StableSwap curve at
+    /// `DEFAULT_STABLE_AMP`, see `spot_price_with_amp`. `None` when the pool is empty
+    pub fn spot_price(&self, base_vault_amount: u64, quote_vault_amount: u64) -> Option<f64> {
+        self.spot_price_with_amp(base_vault_amount, quote_vault_amount, DEFAULT_STABLE_AMP)
+    }
+
+    /// `spot_price` with stable pools priced on the StableSwap curve at `stable_amp`.
+    /// stable pools trade against their open orders too, so pass the vault balances plus the
+    /// open orders totals to price them off the reserves their quotes use. the program prices
+    /// off the curve in its model data account instead, the price is an approximation that
+    /// does not match the on-chain one
+    pub fn spot_price_with_amp(
+        &self,
+        base_vault_amount: u64,
+        quote_vault_amount: u64,
+        stable_amp: u64,
+    ) -> Option<f64> {
+        let decimals = 10f64.powi(self.base_decimals() as i32 - self.quote_decimals() as i32);
+        let raw = match self {
+            PoolData::V4(_) | PoolData::Cpmm(_) => {
+                let (base, quote) = self.trading_reserves(base_vault_amount, quote_vault_amount);
+                if base == 0 {
+                    return None;
+                }
+                quote as f64 / base as f64
+            }
+            PoolData::Clmm(p) => {
+                let sqrt_price = p.sqrt_price_x64 as f64 / (1u128 << 64) as f64;
+                sqrt_price * sqrt_price
+            }
+            PoolData::Launchpad(p) => p.get_price(),
+            PoolData::Stable(_) => {
+                let (base, quote) = self.trading_reserves(base_vault_amount, quote_vault_amount);
+                stable::spot_price(base, quote, stable_amp)?
+            }
+        };
+        if raw > 0.0 {
+            Some(raw * decimals)
+        } else {
+            None
+        }
+    }
+
+    /// whether the pool accepts swaps at unix time `now`:
+    /// v4 pools must be initialized, swap-only or past their open time while waiting for trade,
+    /// CPMM and CLMM pools must not have swaps disabled nor open in the future,
+    /// launchpad pools must still be fundraising on the curve,
+    /// stable pools must be initialized or swap-only and past their open time
+    pub fn is_tradable(&self, now: u64) -> bool {
+        match self {
+            PoolData::V4(p) => match p.status_flags() {
+                V4PoolStatus::WaitingTrade => p.is_open(now),
+                status => status.swap_enabled(),
+            },
+            PoolData::Cpmm(p) => p.status & CPMM_STATUS_SWAP_DISABLED == 0 && p.open_time <= now,
+            PoolData::Clmm(p) => p.status & CLMM_STATUS_SWAP_DISABLED == 0 && p.open_time <= now,
+            PoolData::Launchpad(p) => p.status == PoolStatus::Fund,
+            PoolData::Stable(p) => {
+                matches!(p.status, V4_STATUS_INITIALIZED | V4_STATUS_SWAP_ONLY)
+                    && p.pool_open_time <= now
+            }
+        }
+    }
+
+    /// token programs of the base and quote mints, `None` for CLMM pools which do not record them
+    pub fn token_programs(&self) -> Option<(Pubkey, Pubkey)> {
+        match self {
+            PoolData::V4(_) | PoolData::Stable(_) => Some((TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID)),
+            PoolData::Cpmm(p) => Some((p.token_0_program, p.token_1_program)),
+            PoolData::Clmm(_) => None,
+            PoolData::Launchpad(p) => Some((
+                p.token_program_flag.base_token_program.program_id(),
+                p.token_program_flag.quote_token_program.program_id(),
+            )),
+        }
+    }
+
+    /// vault holding `mint`
+    pub fn vault_of(&self, mint: &Pubkey) -> Option<Pubkey> {
+        match self.side_of(mint)? {
+            MintSide::Base => Some(self.base_vault()),
+            MintSide::Quote => Some(self.quote_vault()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::liquidity::clmm::RAYDIUM_CLMM_POOL_STATE_DISCRIMINATOR;
+
+    #[test]
+    fn test_price_invert() {
+        let price = Price {
+            mint: Pubkey::new_unique(),
+            denominated_in: Pubkey::new_unique(),
+            value: 250.0,
+        };
+        let inverted = price.invert();
+        assert_eq!(
+            (inverted.mint, inverted.denominated_in, inverted.value),
+            (price.denominated_in, price.mint, 0.004)
+        );
+        assert_eq!(inverted.invert(), price);
+    }
+
+    #[test]
+    fn test_side_of() {
+        let base = Pubkey::new_unique();
+        let quote = Pubkey::new_unique();
+        let pool = PoolData::Cpmm(RaydiumLiquidityPoolCPMMData {
+            token_0_mint: base,
+            token_1_mint: quote,
+            ..Default::default()
+        });
+        assert_eq!(pool.kind(), PoolKind::Cpmm);
+        assert_eq!(pool.side_of(&base), Some(MintSide::Base));
+        assert_eq!(pool.side_of(&quote), Some(MintSide::Quote));
+        assert_eq!(pool.side_of(&Pubkey::new_unique()), None);
+        assert_eq!(pool.other_mint(&base), Some(quote));
+        assert_eq!(pool.other_mint(&quote), Some(base));
+    }
+
+    #[test]
+    fn test_trading_reserves_cpmm() {
+        let pool = PoolData::Cpmm(RaydiumLiquidityPoolCPMMData {
+            protocol_fees_token_0: 100,
+            fund_fees_token_0: 20,
+            creator_fees_token_0: 3,
+            protocol_fees_token_1: 1,
+            fund_fees_token_1: 2,
+            ..Default::default()
+        });
+        assert_eq!(pool.trading_reserves(1_000, 1_000), (877, 997));
+        // fees read from a corrupt account overflowing a u64 empty the vault instead of panicking
+        let pool = PoolData::Cpmm(RaydiumLiquidityPoolCPMMData {
+            protocol_fees_token_0: u64::MAX,
+            fund_fees_token_0: 1,
+            fund_fees_token_1: u64::MAX,
+            creator_fees_token_1: u64::MAX,
+            ..Default::default()
+        });
+        assert_eq!(pool.trading_reserves(1_000, u64::MAX), (0, 0));
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        for kind in [
+            PoolKind::V4,
+            PoolKind::Cpmm,
+            PoolKind::Clmm,
+            PoolKind::Launchpad,
+            PoolKind::Stable,
+        ] {
+            let size = kind.data_len();
+            assert_eq!(PoolKind::from_data_len(size), Some(kind));
+            let mut padded = vec![0u8; size + 8];
+            if kind == PoolKind::Clmm {
+                padded[..8].copy_from_slice(&RAYDIUM_CLMM_POOL_STATE_DISCRIMINATOR);
+            }
+            assert!(PoolData::parse(kind, &padded).is_err());
+            let pool = PoolData::parse_lenient(kind, &padded).unwrap();
+            assert_eq!((pool.value.kind(), pool.trailing_bytes), (kind, 8));
+            assert!(PoolData::parse_lenient(kind, &padded[..size - 1]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_contextual_map_keeps_slot() {
+        let v = Contextual::new(42, vec![1u64, 2]).map(|v| (v[0], v[1]));
+        assert_eq!(v, Contextual::new(42, (1, 2)));
+    }
+}
@@ -0,0 +1,213 @@
+//! trading volume and fees between two snapshots of the cumulative swap counters of a pool
+
+use std::time::Duration;
+
+use crate::{
+    error::RaydiumError,
+    liquidity::{clmm::RaydiumLiquidityPoolCLMMData, v4::RaydiumLiquidityPoolData},
+    reader::conver_balance,
+};
+
+/// cumulative swap amounts of a pool, raw. base and quote are token 0 and token 1 of CLMM pools
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SwapTotals {
+    pub base_in: u128,
+    pub quote_in: u128,
+    pub base_out: u128,
+    pub quote_out: u128,
+}
+
+/// pools recording their cumulative swap amounts in the pool account
+pub trait SwapCounters {
+    fn swap_totals(&self) -> SwapTotals;
+    /// decimals of the base and quote mints
+    fn decimals(&self) -> (u8, u8);
+    /// swap fee charged on the input as numerator and denominator, `None` when the pool account
+    /// does not record it
+    fn swap_fee_rate(&self) -> Option<(u64, u64)>;
+}
+
+impl SwapCounters for RaydiumLiquidityPoolData {
+    fn swap_totals(&self) -> SwapTotals {
+        SwapTotals {
+            base_in: self.swap_base_in_amount,
+            quote_in: self.swap_quote_in_amount,
+            base_out: self.swap_base_out_amount,
+            quote_out: self.swap_quote_out_amount,
+        }
+    }
+
+    fn decimals(&self) -> (u8, u8) {
+        (self.base_decimal, self.quote_decimal)
+    }
+
+    fn swap_fee_rate(&self) -> Option<(u64, u64)> {
+        Some((self.swap_fee_numerator, self.swap_fee_denominator))
+    }
+}
+
+impl SwapCounters for RaydiumLiquidityPoolCLMMData {
+    fn swap_totals(&self) -> SwapTotals {
+        SwapTotals {
+            base_in: self.swap_in_amount_token_0,
+            quote_in: self.swap_in_amount_token_1,
+            base_out: self.swap_out_amount_token_0,
+            quote_out: self.swap_out_amount_token_1,
+        }
+    }
+
+    fn decimals(&self) -> (u8, u8) {
+        (self.mint_decimals_0, self.mint_decimals_1)
+    }
+
+    /// the fee rate of a CLMM pool is in its amm config, see `VolumeDelta::with_fee_rate`
+    fn swap_fee_rate(&self) -> Option<(u64, u64)> {
+        None
+    }
+}
+
+/// swap counters of a pool at a unix time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VolumeSnapshot {
+    pub totals: SwapTotals,
+    pub timestamp: u64,
+    pub base_decimal: u8,
+    pub quote_decimal: u8,
+    pub swap_fee_rate: Option<(u64, u64)>,
+}
+
+/// swaps between two snapshots, amounts raw
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolumeDelta {
+    pub base_in: u128,
+    pub quote_in: u128,
+    pub base_out: u128,
+    pub quote_out: u128,
+    /// quote paid in and out, each swap counted once, decimals applied
+    pub quote_volume_ui: f64,
+    pub elapsed: Duration,
+    /// fees charged on the base paid in, `None` without a fee rate
+    pub base_fees: Option<u128>,
+    /// fees charged on the quote paid in, `None` without a fee rate
+    pub quote_fees: Option<u128>,
+}
+
+impl VolumeSnapshot {
+    /// snapshot the counters of `pool` read at unix time `timestamp`
+    pub fn new<P: SwapCounters>(pool: &P, timestamp: u64) -> VolumeSnapshot {
+        let (base_decimal, quote_decimal) = pool.decimals();
+        VolumeSnapshot {
+            totals: pool.swap_totals(),
+            timestamp,
+            base_decimal,
+            quote_decimal,
+            swap_fee_rate: pool.swap_fee_rate(),
+        }
+    }
+
+    /// swaps since `earlier`, a snapshot of the same pool. errors when a counter decreased,
+    /// which happens when the pool is reset or migrated
+    /// Example
+    /// ```rust
+    /// # use raydium_sdk::{liquidity::v4::RaydiumLiquidityPoolData, volume::VolumeSnapshot};
+    /// let mut pool = RaydiumLiquidityPoolData {
+    ///     quote_decimal: 6,
+    ///     swap_fee_numerator: 25,
+    ///     swap_fee_denominator: 10_000,
+    ///     ..Default::default()
+    /// };
+    /// let earlier = VolumeSnapshot::new(&pool, 1_700_000_000);
+    /// pool.swap_quote_in_amount += 1_000_000_000;
+    /// pool.swap_quote_out_amount += 500_000_000;
+    /// let delta = VolumeSnapshot::new(&pool, 1_700_086_400).volume_since(&earlier).unwrap();
+    /// assert_eq!((delta.quote_volume_ui, delta.quote_fees), (1_500.0, Some(2_500_000)));
+    /// ```
+    pub fn volume_since(&self, earlier: &VolumeSnapshot) -> Result<VolumeDelta, RaydiumError> {
+        let delta = |later: u128, earlier: u128| {
+            later
+                .checked_sub(earlier)
+                .ok_or(RaydiumError::CountersReset)
+        };
+        let (now, then) = (&self.totals, &earlier.totals);
+        let base_in = delta(now.base_in, then.base_in)?;
+        let quote_in = delta(now.quote_in, then.quote_in)?;
+        let base_out = delta(now.base_out, then.base_out)?;
+        let quote_out = delta(now.quote_out, then.quote_out)?;
+        let delta = VolumeDelta {
+            base_in,
+            quote_in,
+            base_out,
+            quote_out,
+            quote_volume_ui: conver_balance((quote_in + quote_out) as f64, self.quote_decimal),
+            elapsed: Duration::from_secs(self.timestamp.saturating_sub(earlier.timestamp)),
+            base_fees: None,
+            quote_fees: None,
+        };
+        Ok(match self.swap_fee_rate {
+            Some((numerator, denominator)) => delta.with_fee_rate(numerator, denominator),
+            None => delta,
+        })
+    }
+}
+
+impl VolumeDelta {
+    /// the fees of the window at a fee rate charged on the input, for pools whose account
+    /// does not record the rate
+    pub fn with_fee_rate(mut self, numerator: u64, denominator: u64) -> VolumeDelta {
+        let fees = |amount_in: u128| {
+            (denominator != 0).then(|| amount_in * numerator as u128 / denominator as u128)
+        };
+        self.base_fees = fees(self.base_in);
+        self.quote_fees = fees(self.quote_in);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::liquidity::clmm::RAYDIUM_CLMM_FEE_RATE_DENOMINATOR;
+
+    #[test]
+    fn test_volume_since() {
+        let mut pool = RaydiumLiquidityPoolCLMMData {
+            mint_decimals_0: 9,
+            mint_decimals_1: 6,
+            swap_in_amount_token_0: u64::MAX as u128 * 3,
+            swap_out_amount_token_1: 1,
+            ..Default::default()
+        };
+        let earlier = VolumeSnapshot::new(&pool, 1_000);
+        // 2 SOL sold for 300 USDC, 150 USDC spent on 1 SOL, across the u64 boundary
+        pool.swap_in_amount_token_0 += 2_000_000_000;
+        pool.swap_out_amount_token_1 += 300_000_000;
+        pool.swap_in_amount_token_1 += 150_000_000;
+        pool.swap_out_amount_token_0 += 1_000_000_000;
+        let later = VolumeSnapshot::new(&pool, 4_600);
+        let delta = later.volume_since(&earlier).unwrap();
+        assert_eq!(
+            (
+                delta.base_in,
+                delta.quote_out,
+                delta.quote_in,
+                delta.base_out
+            ),
+            (2_000_000_000, 300_000_000, 150_000_000, 1_000_000_000)
+        );
+        assert_eq!(delta.quote_volume_ui, 450.0);
+        assert_eq!(delta.elapsed, Duration::from_secs(3_600));
+        // the CLMM fee rate comes from the amm config
+        assert_eq!(delta.base_fees, None);
+        let delta = delta.with_fee_rate(2_500, RAYDIUM_CLMM_FEE_RATE_DENOMINATOR);
+        assert_eq!(
+            (delta.base_fees, delta.quote_fees),
+            (Some(5_000_000), Some(375_000))
+        );
+        // a migrated pool starts its counters over
+        assert_eq!(
+            VolumeSnapshot::new(&RaydiumLiquidityPoolCLMMData::default(), 8_200)
+                .volume_since(&later),
+            Err(RaydiumError::CountersReset)
+        );
+    }
+}
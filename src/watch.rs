@@ -0,0 +1,192 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use futures::{StreamExt, stream};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{nonblocking::pubsub_client::PubsubClient, rpc_config::RpcAccountInfoConfig};
+use tokio::{
+    sync::watch,
+    task::JoinHandle,
+    time::{MissedTickBehavior, interval_at},
+};
+
+use crate::{
+    Raydium, error::RaydiumError, rpc::parse_address, subscription::decode_pool_update,
+    typs::PoolData,
+};
+
+impl Raydium {
+    /// watch the price of a pool of any type
+    /// the price is refreshed on every `interval` tick and on every pool account update.
+    /// transient errors keep the last good price, the task stops once the receiver is dropped.
+    /// Example
+    /// ```rust,no_run
+    /// # use raydium_sdk::{Raydium, error::RaydiumError};
+    /// # use std::time::Duration;
+    /// # async fn run(raydium: Raydium) -> Result<(), RaydiumError> {
+    /// let (price, _task) = raydium
+    ///     .watch_price(
+    ///         "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2",
+    ///         Duration::from_secs(5),
+    ///     )
+    ///     .await?;
+    /// println!("price {}", *price.borrow());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn watch_price(
+        &self,
+        address: &str,
+        interval: Duration,
+    ) -> Result<(watch::Receiver<f64>, JoinHandle<()>), RaydiumError> {
+        let (receiver, task, _) = self.watch_price_with_errors(address, interval).await?;
+        Ok((receiver, task))
+    }
+
+    /// watch the price of a pool of any type, also returning the number of failed refreshes
+    pub async fn watch_price_with_errors(
+        &self,
+        address: &str,
+        interval: Duration,
+    ) -> Result<(watch::Receiver<f64>, JoinHandle<()>, Arc<AtomicU64>), RaydiumError> {
+        parse_address(address)?;
+        let pool = self.get_pool(address).await?;
+        let price = self.get_pool_price(&pool).await?;
+        let (sender, receiver) = watch::channel(price);
+        let errors = Arc::new(AtomicU64::new(0));
+        let raydium = self.clone();
+        let task = tokio::spawn(refresh_price(
+            raydium,
+            address.to_string(),
+            pool,
+            interval,
+            sender,
+            errors.clone(),
+        ));
+        Ok((receiver, task, errors))
+    }
+}
+
+/// refresh the price until every receiver is dropped
+async fn refresh_price(
+    raydium: Raydium,
+    address: String,
+    pool: PoolData,
+    interval: Duration,
+    sender: watch::Sender<f64>,
+    errors: Arc<AtomicU64>,
+) {
+    let kind = pool.kind();
+    let pubkey = match parse_address(&address) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return,
+    };
+    // pool account updates are best effort, the interval keeps the price fresh without them
+    raydium.throttle().await;
+    let client = PubsubClient::new(&raydium.ws_url()).await.ok();
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64Zstd),
+        ..RpcAccountInfoConfig::default()
+    };
+    let subscription = match &client {
+        Some(client) => {
+            raydium.throttle().await;
+            client.account_subscribe(&pubkey, Some(config)).await.ok()
+        }
+        None => None,
+    };
+    let (mut updates, unsubscribe) = subscription.map_or_else(
+        || {
+            errors.fetch_add(1, Ordering::Relaxed);
+            (stream::pending().boxed(), None)
+        },
+        |(updates, unsubscribe)| (updates, Some(unsubscribe)),
+    );
+    let mut ticker = interval_at(tokio::time::Instant::now() + interval, interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    loop {
+        let pool = tokio::select! {
+            _ = sender.closed() => break,
+            _ = ticker.tick() => raydium.get_pool(&address).await,
+            update = updates.next() => match update {
+                Some(update) => decode_pool_update(kind, &update.value),
+                None => {
+                    updates = stream::pending().boxed();
+                    continue;
+                }
+            },
+        };
+        let price = match pool {
+            Ok(pool) => raydium.get_pool_price(&pool).await,
+            Err(e) => Err(e),
+        };
+        match price {
+            Ok(price) => {
+                if sender.send(price).is_err() {
+                    break;
+                }
+            }
+            Err(_) => {
+                errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+    drop(updates);
+    if let Some(unsubscribe) = unsubscribe {
+        unsubscribe().await;
+    }
+    if let Some(client) = client {
+        let _ = client.shutdown().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::atomic::Ordering, time::Duration};
+
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::{
+        launchpad::LAUNCHPAD_POOL_STATE_DATA_SIZE,
+        programs::RAYDIUM_LAUNCHPAD_PROGRAM_ID,
+        test_utils::{account_info_response, mock_raydium},
+    };
+
+    /// account info response of a launchpad pool with the given virtual reserves
+    fn launchpad_response(virtual_base: u64, virtual_quote: u64) -> serde_json::Value {
+        let mut data = vec![0u8; LAUNCHPAD_POOL_STATE_DATA_SIZE];
+        data[37..45].copy_from_slice(&virtual_base.to_le_bytes());
+        data[45..53].copy_from_slice(&virtual_quote.to_le_bytes());
+        account_info_response(&RAYDIUM_LAUNCHPAD_PROGRAM_ID, data)
+    }
+
+    #[tokio::test]
+    async fn test_watch_price() -> Result<(), Box<dyn std::error::Error>> {
+        let mut mocks = MocksMap::default();
+        mocks.insert(RpcRequest::GetAccountInfo, launchpad_response(100, 200));
+        mocks.insert(RpcRequest::GetAccountInfo, launchpad_response(100, 300));
+        let raydium = mock_raydium(mocks);
+        let address = Pubkey::new_unique().to_string();
+        let (mut price, task, errors) = raydium
+            .watch_price_with_errors(&address, Duration::from_millis(10))
+            .await?;
+        assert_eq!(*price.borrow_and_update(), 2.0);
+        price.changed().await?;
+        assert_eq!(*price.borrow_and_update(), 3.0);
+        // the mock now reports the account as missing, the last good price is kept
+        while errors.load(Ordering::Relaxed) < 3 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(*price.borrow(), 3.0);
+        drop(price);
+        tokio::time::timeout(Duration::from_secs(1), task).await??;
+        Ok(())
+    }
+}
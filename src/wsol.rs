@@ -0,0 +1,269 @@
+//! wrapping native SOL around swaps whose input or output mint is WSOL
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    rent::Rent,
+};
+
+use crate::{
+    error::RaydiumError,
+    mints::WSOL_MINT,
+    programs::{SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID},
+    token_accounts::{associated_token_address, create_associated_token_account_idempotent},
+};
+
+/// size of an SPL token account
+pub const TOKEN_ACCOUNT_DATA_SIZE: usize = 165;
+
+/// where the WSOL of a swap is held
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WsolStrategy {
+    /// a fresh account derived from the owner and the seed, closed after the swap
+    Ephemeral { seed: String },
+    /// the owner's associated WSOL account, created if missing and closed after the swap,
+    /// which also unwraps any WSOL it held before
+    Ata,
+}
+
+impl WsolStrategy {
+    /// ephemeral account with a seed derived from the current time
+    pub fn ephemeral() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos());
+        WsolStrategy::Ephemeral {
+            seed: format!("wsol{:x}", nanos),
+        }
+    }
+}
+
+/// wrap and unwrap legs of a swap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WsolLegs {
+    /// WSOL account to swap from, `None` when the input mint is not WSOL
+    pub input_account: Option<Pubkey>,
+    /// WSOL account to swap into, `None` when the output mint is not WSOL
+    pub output_account: Option<Pubkey>,
+    /// instructions to run before the swap
+    pub setup: Vec<Instruction>,
+    /// instructions to run after the swap, closing the WSOL account back to SOL
+    pub cleanup: Vec<Instruction>,
+    /// lamports the owner must hold for the legs, the rent is refunded when the account is closed
+    pub lamports: u64,
+}
+
+impl WsolLegs {
+    /// the swap instructions with the setup before and the cleanup after
+    pub fn wrap(&self, swap: Vec<Instruction>) -> Vec<Instruction> {
+        self.setup
+            .iter()
+            .cloned()
+            .chain(swap)
+            .chain(self.cleanup.iter().cloned())
+            .collect()
+    }
+}
+
+/// rent-exempt balance of a token account
+pub fn token_account_rent() -> u64 {
+    Rent::default().minimum_balance(TOKEN_ACCOUNT_DATA_SIZE)
+}
+
+/// build the legs wrapping `amount_in` lamports when `input_mint` is WSOL and unwrapping
+/// the output when `output_mint` is WSOL. no legs are built when neither mint is WSOL
+/// Example
+/// ```rust
+/// # use raydium_sdk::{error::RaydiumError, mints::{USDC_MINT, WSOL_MINT}, wsol::{WsolStrategy, wsol_legs}};
+/// # use solana_sdk::pubkey::Pubkey;
+/// # fn run(owner: Pubkey) -> Result<(), RaydiumError> {
+/// let legs = wsol_legs(&owner, &WSOL_MINT, &USDC_MINT, 1_000_000_000, &WsolStrategy::ephemeral())?;
+/// let source = legs.input_account.unwrap();
+/// // build the swap from `source`, then
+/// let instructions = legs.wrap(vec![]);
+/// println!("{} instructions, {} lamports", instructions.len(), legs.lamports);
+/// # Ok(())
+/// # }
+/// ```
+pub fn wsol_legs(
+    owner: &Pubkey,
+    input_mint: &Pubkey,
+    output_mint: &Pubkey,
+    amount_in: u64,
+    strategy: &WsolStrategy,
+) -> Result<WsolLegs, RaydiumError> {
+    let mut legs = WsolLegs {
+        input_account: None,
+        output_account: None,
+        setup: Vec::new(),
+        cleanup: Vec::new(),
+        lamports: 0,
+    };
+    let amount = if *input_mint == WSOL_MINT {
+        amount_in
+    } else if *output_mint == WSOL_MINT {
+        0
+    } else {
+        return Ok(legs);
+    };
+    let rent = token_account_rent();
+    let account = match strategy {
+        WsolStrategy::Ephemeral { seed } => {
+            let account = Pubkey::create_with_seed(owner, seed, &TOKEN_PROGRAM_ID)
+                .map_err(|e| RaydiumError::Unsupported(format!("wsol account seed: {}", e)))?;
+            legs.setup.push(create_account_with_seed(
+                owner,
+                &account,
+                seed,
+                rent + amount,
+                TOKEN_ACCOUNT_DATA_SIZE as u64,
+                &TOKEN_PROGRAM_ID,
+            ));
+            // a native account initialized with lamports above the rent holds them as WSOL
+            legs.setup
+                .push(initialize_account3(&account, &WSOL_MINT, owner));
+            account
+        }
+        WsolStrategy::Ata => {
+            let account = associated_token_address(owner, &WSOL_MINT, &TOKEN_PROGRAM_ID);
+            legs.setup.push(create_associated_token_account_idempotent(
+                owner,
+                owner,
+                &WSOL_MINT,
+                &TOKEN_PROGRAM_ID,
+            ));
+            if amount > 0 {
+                legs.setup.push(transfer(owner, &account, amount));
+                legs.setup.push(sync_native(&account));
+            }
+            account
+        }
+    };
+    legs.cleanup.push(close_account(&account, owner, owner));
+    legs.lamports = rent + amount;
+    if *input_mint == WSOL_MINT {
+        legs.input_account = Some(account);
+    } else {
+        legs.output_account = Some(account);
+    }
+    Ok(legs)
+}
+
+/// system instruction creating `account` at the address derived from `base` and `seed`
+fn create_account_with_seed(
+    base: &Pubkey,
+    account: &Pubkey,
+    seed: &str,
+    lamports: u64,
+    space: u64,
+    owner: &Pubkey,
+) -> Instruction {
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(base.as_ref());
+    data.extend_from_slice(&(seed.len() as u64).to_le_bytes());
+    data.extend_from_slice(seed.as_bytes());
+    data.extend_from_slice(&lamports.to_le_bytes());
+    data.extend_from_slice(&space.to_le_bytes());
+    data.extend_from_slice(owner.as_ref());
+    Instruction::new_with_bytes(
+        SYSTEM_PROGRAM_ID,
+        &data,
+        vec![
+            AccountMeta::new(*base, true),
+            AccountMeta::new(*account, false),
+            AccountMeta::new_readonly(*base, true),
+        ],
+    )
+}
+
+fn transfer(from: &Pubkey, to: &Pubkey, lamports: u64) -> Instruction {
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    Instruction::new_with_bytes(
+        SYSTEM_PROGRAM_ID,
+        &data,
+        vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+    )
+}
+
+fn initialize_account3(account: &Pubkey, mint: &Pubkey, owner: &Pubkey) -> Instruction {
+    let mut data = vec![18u8];
+    data.extend_from_slice(owner.as_ref());
+    Instruction::new_with_bytes(
+        TOKEN_PROGRAM_ID,
+        &data,
+        vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new_readonly(*mint, false),
+        ],
+    )
+}
+
+fn sync_native(account: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        TOKEN_PROGRAM_ID,
+        &[17],
+        vec![AccountMeta::new(*account, false)],
+    )
+}
+
+fn close_account(account: &Pubkey, destination: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        TOKEN_PROGRAM_ID,
+        &[9],
+        vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mints::USDC_MINT;
+
+    #[test]
+    fn test_wsol_legs() -> Result<(), RaydiumError> {
+        let owner = Pubkey::new_unique();
+        assert_eq!(token_account_rent(), 2_039_280);
+        // wrapping into an ephemeral account funds it with the rent and the amount at creation
+        let strategy = WsolStrategy::Ephemeral {
+            seed: "seed".to_string(),
+        };
+        let legs = wsol_legs(&owner, &WSOL_MINT, &USDC_MINT, 1_000, &strategy)?;
+        let account = Pubkey::create_with_seed(&owner, "seed", &TOKEN_PROGRAM_ID).unwrap();
+        assert_eq!(
+            (legs.input_account, legs.output_account),
+            (Some(account), None)
+        );
+        assert_eq!(legs.lamports, 2_040_280);
+        assert_eq!(legs.setup.len(), 2);
+        assert_eq!(&legs.setup[0].data[44..48], b"seed");
+        assert_eq!(legs.setup[0].data[48..56], 2_040_280u64.to_le_bytes());
+        assert_eq!(legs.cleanup[0].data, [9]);
+        let instructions = legs.wrap(vec![Instruction::new_with_bytes(owner, &[], vec![])]);
+        assert_eq!(instructions.len(), 4);
+        assert_eq!(instructions[2].program_id, owner);
+        // receiving into the ata only creates it before the swap
+        let legs = wsol_legs(&owner, &USDC_MINT, &WSOL_MINT, 1_000, &WsolStrategy::Ata)?;
+        assert_eq!(
+            legs.output_account,
+            Some(associated_token_address(
+                &owner,
+                &WSOL_MINT,
+                &TOKEN_PROGRAM_ID
+            ))
+        );
+        assert_eq!((legs.setup.len(), legs.lamports), (1, 2_039_280));
+        let legs = wsol_legs(&owner, &WSOL_MINT, &USDC_MINT, 1_000, &WsolStrategy::Ata)?;
+        assert_eq!(legs.setup[1].data, [2, 0, 0, 0, 0xe8, 3, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(legs.setup[2].data, [17]);
+        let legs = wsol_legs(&owner, &USDC_MINT, &Pubkey::new_unique(), 1_000, &strategy)?;
+        assert!(legs.setup.is_empty() && legs.cleanup.is_empty());
+        Ok(())
+    }
+}